@@ -0,0 +1,275 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Typed async client of EDB's debug JSON-RPC API.
+//!
+//! [`EdbClient`] sends requests to an engine server over HTTP. Its typed methods come from
+//! the [`EdbApi`] trait, generated from the API definition of [`edb_common::api`], so the
+//! parameters and results of each call have the types the server expects and returns.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use edb_client::{EdbApi, EdbClient};
+//!
+//! # async fn example() -> Result<(), edb_client::ApiError> {
+//! let client = EdbClient::builder().auth_token("secret").build("http://127.0.0.1:3000")?;
+//!
+//! let count = client.get_snapshot_count().await?;
+//! let info = client.get_snapshot_info(count - 1).await?;
+//! println!("Last snapshot: {info:?}");
+//!
+//! // Address another session hosted by the server
+//! let session = client.create_session(Default::default()).await?;
+//! let other = client.with_session(session.session_id);
+//! println!("{} snapshots", other.get_snapshot_count().await?);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::debug;
+
+pub use edb_common::api::{error_codes, method, ApiError, EdbApi};
+
+/// Default timeout of requests
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Client of an engine server's debug JSON-RPC API
+#[derive(Debug, Clone)]
+pub struct EdbClient {
+    /// HTTP client sending the requests
+    http: reqwest::Client,
+    /// URL of the JSON-RPC endpoint
+    url: String,
+    /// Bearer token required by the server, if any
+    auth_token: Option<String>,
+    /// Hosted session addressed by the requests, if not the server's own
+    session_id: Option<String>,
+    /// Id of the next request, shared by the clones of the client
+    next_id: Arc<AtomicU64>,
+}
+
+/// Builder of an [`EdbClient`]
+#[derive(Debug, Default)]
+pub struct EdbClientBuilder {
+    request_timeout: Option<Duration>,
+    auth_token: Option<String>,
+    session_id: Option<String>,
+}
+
+impl EdbClientBuilder {
+    /// Set the timeout of requests (30 seconds by default)
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Authenticate with a bearer token
+    pub fn auth_token(mut self, token: impl Into<String>) -> Self {
+        self.auth_token = Some(token.into());
+        self
+    }
+
+    /// Address a session hosted by the server instead of its own
+    pub fn session(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// Build a client of the server at `url`
+    pub fn build(self, url: &str) -> Result<EdbClient, ApiError> {
+        let http = reqwest::Client::builder()
+            .timeout(self.request_timeout.unwrap_or(DEFAULT_REQUEST_TIMEOUT))
+            .build()
+            .map_err(|e| ApiError::Transport(e.into()))?;
+
+        Ok(EdbClient {
+            http,
+            url: url.trim_end_matches('/').to_string(),
+            auth_token: self.auth_token,
+            session_id: self.session_id,
+            next_id: Arc::new(AtomicU64::new(1)),
+        })
+    }
+}
+
+/// A JSON-RPC request, with the EDB `session_id` extension
+#[derive(Serialize)]
+struct Request<'a> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'a str,
+    params: Vec<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<&'a str>,
+}
+
+/// A JSON-RPC response
+#[derive(Deserialize)]
+struct Response {
+    #[serde(default)]
+    result: Value,
+    error: Option<ResponseError>,
+}
+
+/// The error of a JSON-RPC response
+#[derive(Deserialize)]
+struct ResponseError {
+    code: i32,
+    message: String,
+    data: Option<Value>,
+}
+
+impl EdbClient {
+    /// Create a client of the server at `url`, with the default settings
+    pub fn new(url: &str) -> Result<Self, ApiError> {
+        Self::builder().build(url)
+    }
+
+    /// Create a builder, to configure the client
+    pub fn builder() -> EdbClientBuilder {
+        EdbClientBuilder::default()
+    }
+
+    /// Get the URL of the server
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Get a client of a session hosted by the same server
+    pub fn with_session(&self, session_id: impl Into<String>) -> Self {
+        Self { session_id: Some(session_id.into()), ..self.clone() }
+    }
+
+    /// Check the health of the server, which does not require authentication
+    pub async fn health(&self) -> Result<Value, ApiError> {
+        let response = self
+            .http
+            .get(format!("{}/health", self.url))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| ApiError::Transport(e.into()))?;
+        response.json().await.map_err(|e| ApiError::Transport(e.into()))
+    }
+}
+
+impl EdbApi for EdbClient {
+    async fn call_raw(&self, method: &'static str, params: Vec<Value>) -> Result<Value, ApiError> {
+        let request = Request {
+            jsonrpc: "2.0",
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            method,
+            params,
+            session_id: self.session_id.as_deref(),
+        };
+        debug!("Calling {} on {}", method, self.url);
+
+        let mut builder = self.http.post(&self.url).json(&request);
+        if let Some(token) = &self.auth_token {
+            builder = builder.bearer_auth(token);
+        }
+
+        let response: Response = builder
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| ApiError::Transport(e.into()))?
+            .json()
+            .await
+            .map_err(|e| ApiError::Transport(e.into()))?;
+
+        match response.error {
+            Some(error) => {
+                Err(ApiError::Rpc { code: error.code, message: error.message, data: error.data })
+            }
+            None => Ok(response.result),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    /// Serve a single HTTP request with `body`, returning the request received
+    async fn serve_once(body: &'static str) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            // Read until the whole JSON body, which ends the request, is received
+            while !request.ends_with(b"}") {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (url, server)
+    }
+
+    #[tokio::test]
+    async fn test_typed_call() {
+        let (url, server) = serve_once(r#"{"jsonrpc":"2.0","id":1,"result":42}"#).await;
+        let client = EdbClient::builder().auth_token("secret").build(&url).unwrap();
+
+        let count = client.with_session("s1").get_snapshot_count().await.unwrap();
+        assert_eq!(count, 42);
+
+        let request = server.await.unwrap();
+        assert!(request.to_lowercase().contains("authorization: bearer secret"));
+        let body: Value = serde_json::from_str(&request[request.find('{').unwrap()..]).unwrap();
+        assert_eq!(body["method"], method::GET_SNAPSHOT_COUNT);
+        assert_eq!(body["params"], serde_json::json!([]));
+        assert_eq!(body["session_id"], "s1");
+    }
+
+    #[tokio::test]
+    async fn test_rpc_error() {
+        let (url, _server) = serve_once(
+            r#"{"jsonrpc":"2.0","id":1,"error":{"code":-33007,"message":"Session not found"}}"#,
+        )
+        .await;
+        let client = EdbClient::new(&url).unwrap();
+
+        match client.close_session("s1").await {
+            Err(ApiError::Rpc { code, .. }) => assert_eq!(code, error_codes::SESSION_NOT_FOUND),
+            other => panic!("expected an RPC error, got {other:?}"),
+        }
+    }
+}
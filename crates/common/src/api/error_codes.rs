@@ -0,0 +1,62 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Error codes of the debug JSON-RPC API.
+
+macro_rules! error_codes {
+    ($($(#[doc = $doc:literal])* $name:ident = $code:literal;)*) => {
+        $(
+            $(#[doc = $doc])*
+            pub const $name: i32 = $code;
+        )*
+
+        /// Every error code, as its name, code and description
+        pub const ALL: &[(&str, i32, &str)] =
+            &[$((stringify!($name), $code, concat!($($doc),*)),)*];
+    };
+}
+
+error_codes! {
+    // Standard JSON-RPC 2.0 error codes
+
+    /// Parse error - Invalid JSON was received by the server
+    PARSE_ERROR = -32700;
+    /// Invalid request - The JSON sent is not a valid request object
+    INVALID_REQUEST = -32600;
+    /// Method not found - The method does not exist or is not available
+    METHOD_NOT_FOUND = -32601;
+    /// Invalid params - Invalid method parameter(s)
+    INVALID_PARAMS = -32602;
+    /// Internal error - Internal JSON-RPC error
+    INTERNAL_ERROR = -32603;
+
+    // EDB-specific error codes (starting from -33000)
+
+    /// Snapshot index is out of bounds
+    SNAPSHOT_OUT_OF_BOUNDS = -33001;
+    /// Invalid or malformed address
+    INVALID_ADDRESS = -33002;
+    /// Trace entry not found for the given ID
+    TRACE_ENTRY_NOT_FOUND = -33003;
+    /// Contract code not found at the given address
+    CODE_NOT_FOUND = -33004;
+    /// User-defined snapshot ID not found
+    USID_NOT_FOUND = -33005;
+    /// Expression evaluation failed
+    EVAL_FAILED = -33006;
+    /// Debugging session not found (never created, closed or evicted)
+    SESSION_NOT_FOUND = -33007;
+}
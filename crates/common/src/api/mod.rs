@@ -0,0 +1,563 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Definition of the debug JSON-RPC API served by the engine.
+//!
+//! Every `edb_*` method is defined once below, with its name, documentation, positional
+//! parameters and result type. From this definition are generated:
+//!
+//! - the method names, in [`method`], used by the engine to dispatch requests
+//! - the method descriptions ([`method_specs`]), and from them the OpenRPC document of
+//!   the API ([`openrpc_document`]), served by the engine as `edb_rpcDiscover`
+//! - the param decoders, in [`params`], used by the engine to decode the positional
+//!   parameters of each method into their typed values
+//! - the typed client trait [`EdbApi`], whose methods encode the parameters and decode
+//!   the result of each call. Transports only implement [`EdbApi::call_raw`] (see the
+//!   `edb-client` crate).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use edb_common::api::EdbApi;
+//!
+//! let count = client.get_snapshot_count().await?;
+//! let value = client.eval_on_snapshot(count - 1, "balanceOf[msg.sender]", None).await?;
+//! ```
+
+pub mod error_codes;
+mod openrpc;
+mod schema;
+
+pub use openrpc::*;
+pub(crate) use schema::remote;
+pub use schema::*;
+
+use std::{collections::HashMap, future::Future};
+
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::{Address, Bytes, TxHash, U256};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::types::{
+    Breakpoint, BreakpointHit, CallableAbiInfo, Code, EdbSolValue, EvalReport, HistoryScope,
+    LogpointEntry, NestedCallTimeline, SearchDirection, SessionInfo, SnapshotFilter, SnapshotInfo,
+    StateEdit, Trace, ValueHistory, ValueLocation, ValueOrigin, Watchpoint, WatchpointHit,
+    WhatIfResult,
+};
+
+/// Error of a typed API call
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    /// The server answered with a JSON-RPC error, see [`error_codes`]
+    #[error("RPC error {code}: {message}")]
+    Rpc {
+        /// Error code
+        code: i32,
+        /// Error message
+        message: String,
+        /// Additional error data
+        data: Option<Value>,
+    },
+    /// The request could not be sent, or the response could not be read
+    #[error("Transport error: {0}")]
+    Transport(#[source] Box<dyn std::error::Error + Send + Sync>),
+    /// A parameter could not be encoded
+    #[error("Failed to encode the params of {method}: {source}")]
+    Encode {
+        /// Method called
+        method: &'static str,
+        /// Encoding error
+        #[source]
+        source: serde_json::Error,
+    },
+    /// The result does not have the type of the method's result
+    #[error("Failed to decode the result of {method}: {source}")]
+    Decode {
+        /// Method called
+        method: &'static str,
+        /// Decoding error
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+/// Encode a positional parameter of a method call
+fn encode_param<T: Serialize>(method: &'static str, param: T) -> Result<Value, ApiError> {
+    serde_json::to_value(param).map_err(|source| ApiError::Encode { method, source })
+}
+
+/// The parameters of a method call do not match the parameters of the method
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid params for {method}: {message}")]
+pub struct ParamsError {
+    /// Method called
+    pub method: &'static str,
+    /// What is wrong with the params
+    pub message: String,
+}
+
+/// A type usable as a parameter of an API method, decoded by the server into its owned form
+pub trait ApiParam {
+    /// Type the parameter is decoded into
+    type Owned: DeserializeOwned;
+}
+
+impl<T: ApiParam + ?Sized> ApiParam for &T {
+    type Owned = T::Owned;
+}
+
+impl<T: ApiParam> ApiParam for Option<T> {
+    type Owned = Option<T::Owned>;
+}
+
+impl<T: ApiParam> ApiParam for [T] {
+    type Owned = Vec<T::Owned>;
+}
+
+impl ApiParam for str {
+    type Owned = String;
+}
+
+/// Implement [`ApiParam`] for types decoded as themselves
+macro_rules! owned_params {
+    ($($ty:ty,)*) => {
+        $(
+            impl ApiParam for $ty {
+                type Owned = Self;
+            }
+        )*
+    };
+}
+
+owned_params! {
+    bool,
+    usize,
+    Address,
+    TxHash,
+    U256,
+    Breakpoint,
+    HistoryScope,
+    SearchDirection,
+    SnapshotFilter,
+    StateEdit,
+    ValueLocation,
+    Watchpoint,
+}
+
+/// Positional parameters of a method call; absent params are the same as no params
+fn param_values<'a>(
+    method: &'static str,
+    params: Option<&'a Value>,
+    count: usize,
+) -> Result<&'a [Value], ParamsError> {
+    let values = match params {
+        None | Some(Value::Null) => &[],
+        Some(Value::Array(values)) => values.as_slice(),
+        Some(_) => {
+            return Err(ParamsError { method, message: "expected an array of params".to_string() })
+        }
+    };
+    if values.len() > count {
+        return Err(ParamsError {
+            method,
+            message: format!("expected at most {count} params, got {}", values.len()),
+        });
+    }
+    Ok(values)
+}
+
+/// Decode a positional parameter of a method call; a missing parameter decodes as `null`
+fn decode_param<T: DeserializeOwned>(
+    method: &'static str,
+    name: &str,
+    value: Option<&Value>,
+) -> Result<T, ParamsError> {
+    T::deserialize(value.unwrap_or(&Value::Null))
+        .map_err(|e| ParamsError { method, message: format!("`{name}`: {e}") })
+}
+
+/// Whether a method kind reports its progress through `edb_progress` notifications
+macro_rules! is_long_running {
+    (method) => {
+        false
+    };
+    (long_running) => {
+        true
+    };
+}
+
+/// Generate the method names, descriptions and typed client trait from the definition of
+/// the methods. Methods are either `method`s or `long_running` methods, which report their
+/// progress through `edb_progress` notifications.
+macro_rules! edb_api {
+    ($(
+        $(#[doc = $doc:literal])*
+        $kind:ident $const_name:ident = $name:literal;
+        fn $fn_name:ident($($param:ident: $param_ty:ty),* $(,)?) -> $result:ty;
+    )*) => {
+        /// Names of the API methods
+        pub mod method {
+            $(
+                #[doc = concat!("`", $name, "`")]
+                pub const $const_name: &str = $name;
+            )*
+        }
+
+        /// Whether a method reports its progress through `edb_progress` notifications
+        pub fn is_long_running(method: &str) -> bool {
+            match method {
+                $($name => is_long_running!($kind),)*
+                _ => false,
+            }
+        }
+
+        /// Descriptions of the API methods
+        pub fn method_specs() -> Vec<MethodSpec> {
+            vec![$(
+                MethodSpec {
+                    name: $name,
+                    docs: {
+                        let lines: &[&str] = &[$($doc),*];
+                        lines.iter().map(|line| line.trim()).collect::<Vec<_>>().join("\n")
+                    },
+                    params: vec![$(
+                        ParamSpec {
+                            name: stringify!($param),
+                            required: !<$param_ty as ApiSchema>::OPTIONAL,
+                            schema: <$param_ty as ApiSchema>::schema(),
+                        }
+                    ),*],
+                    result: <$result as ApiSchema>::schema(),
+                    long_running: is_long_running!($kind),
+                },
+            )*]
+        }
+
+        /// Decoders of the positional parameters of the API methods, into a tuple of the
+        /// typed parameters of each method
+        pub mod params {
+            use super::*;
+
+            $(
+                #[doc = concat!("Decode the params of `", $name, "`")]
+                #[allow(unused_variables, unused_mut, unused_assignments)]
+                pub fn $fn_name(
+                    params: Option<&Value>,
+                ) -> Result<($(<$param_ty as ApiParam>::Owned,)*), ParamsError> {
+                    let names: &[&str] = &[$(stringify!($param)),*];
+                    let values = param_values($name, params, names.len())?;
+                    let mut index = 0;
+                    Ok(($({
+                        let value = decode_param::<<$param_ty as ApiParam>::Owned>(
+                            $name,
+                            names[index],
+                            values.get(index),
+                        )?;
+                        index += 1;
+                        value
+                    },)*))
+                }
+            )*
+        }
+
+        /// Typed access to the debug JSON-RPC API.
+        ///
+        /// Implementors provide the transport with [`EdbApi::call_raw`], and get a typed
+        /// method for each API method.
+        pub trait EdbApi: Sync {
+            /// Call a method with positional params, returning its raw result
+            fn call_raw(
+                &self,
+                method: &'static str,
+                params: Vec<Value>,
+            ) -> impl Future<Output = Result<Value, ApiError>> + Send;
+
+            $(
+                $(#[doc = $doc])*
+                #[doc = ""]
+                #[doc = concat!("Calls `", $name, "`.")]
+                fn $fn_name(
+                    &self,
+                    $($param: $param_ty),*
+                ) -> impl Future<Output = Result<$result, ApiError>> + Send {
+                    async move {
+                        let params = vec![$(encode_param($name, $param)?),*];
+                        let value = self.call_raw($name, params).await?;
+                        serde_json::from_value(value)
+                            .map_err(|source| ApiError::Decode { method: $name, source })
+                    }
+                }
+            )*
+        }
+    };
+}
+
+edb_api! {
+    // Artifacts
+
+    /// Get the code of the contract executing at a snapshot
+    method GET_CODE = "edb_getCode";
+    fn get_code(snapshot_id: usize) -> Code;
+
+    /// Get the code of the contract deployed at an address
+    method GET_CODE_BY_ADDRESS = "edb_getCodeByAddress";
+    fn get_code_by_address(address: Address) -> Code;
+
+    /// Get the constructor arguments of the contract deployed at an address
+    method GET_CONSTRUCTOR_ARGS = "edb_getConstructorArgs";
+    fn get_constructor_args(address: Address) -> Option<Bytes>;
+
+    // Breakpoints
+
+    /// Find the nearest snapshot at which any of the breakpoints is hit
+    long_running FIND_BREAKPOINT_HIT = "edb_findBreakpointHit";
+    fn find_breakpoint_hit(
+        breakpoints: &[Breakpoint],
+        snapshot_id: usize,
+        direction: SearchDirection,
+    ) -> Option<BreakpointHit>;
+
+    /// Collect the messages logged by the logpoints over the whole timeline
+    long_running COLLECT_LOGPOINTS = "edb_collectLogpoints";
+    fn collect_logpoints(logpoints: &[Breakpoint]) -> Vec<LogpointEntry>;
+
+    /// Collect the snapshots at which each breakpoint is hit over the whole timeline
+    long_running COUNT_BREAKPOINT_HITS = "edb_countBreakpointHits";
+    fn count_breakpoint_hits(breakpoints: &[Breakpoint]) -> Vec<Vec<usize>>;
+
+    // Expression evaluation
+
+    /// Evaluate an expression on a snapshot
+    ///
+    /// The expression is evaluated in the given frame of the call stack (see
+    /// `edb_getCallStack`), the snapshot's own frame by default.
+    method EVAL_ON_SNAPSHOT = "edb_evalOnSnapshot";
+    fn eval_on_snapshot(
+        snapshot_id: usize,
+        expr: &str,
+        frame: Option<usize>,
+    ) -> Result<EdbSolValue, String>;
+
+    /// Evaluate an expression on a snapshot, reporting the contract calls it makes
+    method EVAL_ON_SNAPSHOT_WITH_CALLS = "edb_evalOnSnapshotWithCalls";
+    fn eval_on_snapshot_with_calls(
+        snapshot_id: usize,
+        expr: &str,
+        frame: Option<usize>,
+    ) -> EvalReport;
+
+    /// Collect every change of an expression's value over a call or a range of snapshots
+    long_running GET_VALUE_HISTORY = "edb_getValueHistory";
+    fn get_value_history(expr: &str, scope: HistoryScope) -> Result<ValueHistory, String>;
+
+    /// Find every snapshot at which a boolean expression holds
+    ///
+    /// Snapshots are first narrowed down by the filter, if any.
+    long_running FIND_SNAPSHOTS = "edb_findSnapshots";
+    fn find_snapshots(
+        expr: &str,
+        filter: Option<&SnapshotFilter>,
+    ) -> Result<Vec<usize>, String>;
+
+    // Nested calls
+
+    /// Debug a call made by an expression on a snapshot as a nested timeline
+    ///
+    /// The call is picked by its index among the calls the expression makes, the first
+    /// one by default.
+    long_running STEP_INTO_CALL = "edb_stepIntoCall";
+    fn step_into_call(
+        snapshot_id: usize,
+        expr: &str,
+        call_index: Option<usize>,
+    ) -> Result<NestedCallTimeline, String>;
+
+    // Navigation
+
+    /// Get the snapshot of the next function call after a snapshot
+    method GET_NEXT_CALL = "edb_getNextCall";
+    fn get_next_call(snapshot_id: usize) -> usize;
+
+    /// Get the snapshot of the previous function call before a snapshot
+    method GET_PREV_CALL = "edb_getPrevCall";
+    fn get_prev_call(snapshot_id: usize) -> usize;
+
+    /// Find the next change of a watched storage slot or state variable after a snapshot
+    method GET_NEXT_WATCHPOINT_HIT = "edb_getNextWatchpointHit";
+    fn get_next_watchpoint_hit(
+        snapshot_id: usize,
+        watchpoint: &Watchpoint,
+    ) -> Result<Option<WatchpointHit>, String>;
+
+    /// Find the previous change of a watched storage slot or state variable before a snapshot
+    method GET_PREV_WATCHPOINT_HIT = "edb_getPrevWatchpointHit";
+    fn get_prev_watchpoint_hit(
+        snapshot_id: usize,
+        watchpoint: &Watchpoint,
+    ) -> Result<Option<WatchpointHit>, String>;
+
+    // Data-flow tracing
+
+    /// Trace a value on a snapshot back to the instruction that produced it
+    method TRACE_VALUE_ORIGIN = "edb_traceValueOrigin";
+    fn trace_value_origin(
+        snapshot_id: usize,
+        location: ValueLocation,
+    ) -> Result<ValueOrigin, String>;
+
+    // Resolution
+
+    /// Get the ABI of the contract deployed at an address, or of its recompiled version
+    method GET_CONTRACT_ABI = "edb_getContractABI";
+    fn get_contract_abi(address: Address, recompiled: bool) -> Option<JsonAbi>;
+
+    /// Get the callable ABI entries of the contract deployed at an address
+    method GET_CALLABLE_ABI = "edb_getCallableABI";
+    fn get_callable_abi(address: Address) -> Vec<CallableAbiInfo>;
+
+    // Sessions
+
+    /// Prepare a transaction and host it as a new session
+    long_running CREATE_SESSION = "edb_createSession";
+    fn create_session(tx_hash: TxHash) -> SessionInfo;
+
+    /// List the hosted sessions, starting with the server's own session
+    method LIST_SESSIONS = "edb_listSessions";
+    fn list_sessions() -> Vec<SessionInfo>;
+
+    /// Close a hosted session
+    method CLOSE_SESSION = "edb_closeSession";
+    fn close_session(session_id: &str) -> SessionInfo;
+
+    // Snapshots
+
+    /// Get the total number of snapshots
+    method GET_SNAPSHOT_COUNT = "edb_getSnapshotCount";
+    fn get_snapshot_count() -> usize;
+
+    /// Get the details of a snapshot
+    method GET_SNAPSHOT_INFO = "edb_getSnapshotInfo";
+    fn get_snapshot_info(snapshot_id: usize) -> SnapshotInfo;
+
+    /// Get the call stack at a snapshot, as the snapshots of its frames (innermost first)
+    method GET_CALL_STACK = "edb_getCallStack";
+    fn get_call_stack(snapshot_id: usize) -> Vec<usize>;
+
+    // Storage
+
+    /// Read a storage slot of the contract executing at a snapshot
+    method GET_STORAGE = "edb_getStorage";
+    fn get_storage(snapshot_id: usize, slot: U256) -> U256;
+
+    /// Get the storage slots changed by the transaction up to a snapshot, with their
+    /// original and current values
+    method GET_STORAGE_DIFF = "edb_getStorageDiff";
+    fn get_storage_diff(snapshot_id: usize) -> HashMap<U256, (U256, U256)>;
+
+    // Trace
+
+    /// Get the execution trace of the transaction
+    method GET_TRACE = "edb_getTrace";
+    fn get_trace() -> Trace;
+
+    // What-if analysis
+
+    /// Apply state edits at a snapshot and re-execute the rest of the transaction
+    long_running WHAT_IF = "edb_whatIf";
+    fn what_if(snapshot_id: usize, edits: &[StateEdit]) -> WhatIfResult;
+
+    /// Replay the transaction with the patched source code of a contract
+    long_running REPLAY_WITH_PATCHED_SOURCE = "edb_replayWithPatchedSource";
+    fn replay_with_patched_source(address: Address, path: &str, source: &str) -> WhatIfResult;
+
+    // Discovery
+
+    /// Get the OpenRPC document describing this API
+    method RPC_DISCOVER = "edb_rpcDiscover";
+    fn rpc_discover() -> Value;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    /// Records the calls made through the typed methods, answering with a canned result
+    struct RecordingApi {
+        calls: Mutex<Vec<(&'static str, Vec<Value>)>>,
+        result: Value,
+    }
+
+    impl EdbApi for RecordingApi {
+        async fn call_raw(
+            &self,
+            method: &'static str,
+            params: Vec<Value>,
+        ) -> Result<Value, ApiError> {
+            self.calls.lock().unwrap().push((method, params));
+            Ok(self.result.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_typed_calls() {
+        let api = RecordingApi { calls: Mutex::new(Vec::new()), result: json!({ "Ok": [1, 2] }) };
+
+        let matches = api.find_snapshots("x > 1", None).await.unwrap();
+        assert_eq!(matches, Ok(vec![1, 2]));
+        assert!(api.get_snapshot_count().await.is_err());
+
+        let calls = api.calls.lock().unwrap();
+        assert_eq!(calls[0], (method::FIND_SNAPSHOTS, vec![json!("x > 1"), Value::Null]));
+        assert_eq!(calls[1], (method::GET_SNAPSHOT_COUNT, vec![]));
+    }
+
+    #[test]
+    fn test_decode_params() {
+        let (expr, filter) = params::find_snapshots(Some(&json!(["x > 1"]))).unwrap();
+        assert_eq!((expr.as_str(), filter), ("x > 1", None));
+
+        let (snapshot_id, expr, frame) =
+            params::eval_on_snapshot(Some(&json!([3, "a", null]))).unwrap();
+        assert_eq!((snapshot_id, expr.as_str(), frame), (3, "a", None));
+
+        params::get_trace(None).unwrap();
+        assert!(params::get_trace(Some(&json!([1]))).is_err());
+        assert!(params::get_code(Some(&json!({ "snapshot_id": 1 }))).is_err());
+
+        let error = params::get_storage(Some(&json!([1]))).unwrap_err();
+        assert_eq!(error.method, method::GET_STORAGE);
+        assert!(error.message.starts_with("`slot`"), "{}", error.message);
+    }
+
+    #[test]
+    fn test_method_specs() {
+        let specs = method_specs();
+        let what_if = specs.iter().find(|spec| spec.name == method::WHAT_IF).unwrap();
+        assert!(what_if.long_running && is_long_running(method::WHAT_IF));
+        assert!(!is_long_running(method::GET_TRACE));
+        assert_eq!(
+            what_if.docs.lines().next(),
+            Some("Apply state edits at a snapshot and re-execute the rest of the transaction")
+        );
+        assert_eq!(
+            what_if.params.iter().map(|p| p.name).collect::<Vec<_>>(),
+            ["snapshot_id", "edits"]
+        );
+    }
+}
@@ -0,0 +1,220 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! OpenRPC description of the debug JSON-RPC API.
+//!
+//! The document is generated from the method definitions of [`super`], and served by the
+//! engine as the result of `edb_rpcDiscover`.
+
+use serde_json::{json, Map, Value};
+
+use super::{error_codes, method_specs, schema::component_schemas};
+
+/// Version of the OpenRPC specification the document follows
+pub const OPENRPC_VERSION: &str = "1.3.2";
+
+/// Description of an API method
+#[derive(Debug, Clone)]
+pub struct MethodSpec {
+    /// Name of the method, e.g. `edb_getSnapshotInfo`
+    pub name: &'static str,
+    /// Documentation of the method, its first line being a summary
+    pub docs: String,
+    /// Parameters of the method, by position
+    pub params: Vec<ParamSpec>,
+    /// JSON schema of the result
+    pub result: Value,
+    /// Whether the method reports its progress through `edb_progress` notifications
+    pub long_running: bool,
+}
+
+/// Description of a positional parameter of an API method
+#[derive(Debug, Clone)]
+pub struct ParamSpec {
+    /// Name of the parameter
+    pub name: &'static str,
+    /// Whether the parameter must be given (optional parameters may be `null` or omitted)
+    pub required: bool,
+    /// JSON schema of the parameter
+    pub schema: Value,
+}
+
+impl MethodSpec {
+    /// Describe the method as an OpenRPC method object
+    fn to_openrpc(&self) -> Value {
+        let (summary, description) = match self.docs.split_once('\n') {
+            Some((summary, description)) => (summary, description.trim()),
+            None => (self.docs.as_str(), ""),
+        };
+        let params: Vec<Value> = self
+            .params
+            .iter()
+            .map(|param| {
+                json!({ "name": param.name, "required": param.required, "schema": param.schema })
+            })
+            .collect();
+
+        let mut method = json!({
+            "name": self.name,
+            "summary": summary,
+            "paramStructure": "by-position",
+            "params": params,
+            "result": { "name": "result", "schema": self.result },
+        });
+        if !description.is_empty() {
+            method["description"] = json!(description);
+        }
+        if self.long_running {
+            method["x-long-running"] = json!(true);
+        }
+        method
+    }
+}
+
+/// Generate the OpenRPC document of the API, for a server of the given version
+pub fn openrpc_document(version: &str) -> Value {
+    let methods: Vec<Value> = method_specs().iter().map(MethodSpec::to_openrpc).collect();
+
+    let mut errors = Map::new();
+    for (name, code, description) in error_codes::ALL {
+        errors.insert(name.to_string(), json!({ "code": code, "message": description.trim() }));
+    }
+
+    json!({
+        "openrpc": OPENRPC_VERSION,
+        "info": {
+            "title": "EDB debug JSON-RPC API",
+            "description": "Inspect and navigate the execution of a transaction prepared by EDB. \
+                Requests may carry a top-level `session_id` member to address a hosted session.",
+            "version": version,
+        },
+        "methods": methods,
+        "components": {
+            "schemas": component_schemas(),
+            "errors": errors,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_openrpc_document() {
+        let document = openrpc_document("0.0.1");
+        let methods = document["methods"].as_array().unwrap();
+        assert_eq!(methods.len(), method_specs().len());
+
+        // Method names are unique, and every referenced schema is a component
+        let names: HashSet<_> = methods.iter().map(|m| m["name"].as_str().unwrap()).collect();
+        assert_eq!(names.len(), methods.len());
+        let text = document.to_string();
+        for reference in text.split("\"#/components/schemas/").skip(1) {
+            let name = &reference[..reference.find('"').unwrap()];
+            assert!(document["components"]["schemas"].get(name).is_some(), "missing {name}");
+        }
+
+        let eval = methods.iter().find(|m| m["name"] == "edb_evalOnSnapshot").unwrap();
+        let params: Vec<_> = eval["params"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| (p["name"].as_str().unwrap(), p["required"].as_bool().unwrap()))
+            .collect();
+        assert_eq!(params, vec![("snapshot_id", true), ("expr", true), ("frame", false)]);
+        assert_eq!(document["components"]["errors"]["SESSION_NOT_FOUND"]["code"], -33007);
+    }
+
+    /// Names of the properties described by a schema and the schemas it references
+    fn property_names(schema: &Value, components: &Value, names: &mut HashSet<String>) {
+        match schema {
+            Value::Object(map) => {
+                if let Some(reference) = map.get("$ref").and_then(Value::as_str) {
+                    let name = reference.trim_start_matches("#/components/schemas/");
+                    if names.insert(format!("$ref:{name}")) {
+                        property_names(&components[name], components, names);
+                    }
+                }
+                if let Some(Value::Object(properties)) = map.get("properties") {
+                    names.extend(properties.keys().cloned());
+                }
+                map.values().for_each(|value| property_names(value, components, names));
+            }
+            Value::Array(items) => {
+                items.iter().for_each(|item| property_names(item, components, names))
+            }
+            _ => {}
+        }
+    }
+
+    /// Keys of the objects in a serialized value
+    fn serialized_keys(value: &Value, keys: &mut HashSet<String>) {
+        match value {
+            Value::Object(map) => {
+                keys.extend(map.keys().cloned());
+                map.values().for_each(|value| serialized_keys(value, keys));
+            }
+            Value::Array(items) => items.iter().for_each(|item| serialized_keys(item, keys)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_component_schemas_follow_serde() {
+        use crate::types::{
+            Breakpoint, BreakpointLocation, SnapshotFilter, SnapshotKind, StateEdit,
+        };
+        use alloy_primitives::{Address, U256};
+
+        let components = Value::Object(component_schemas());
+        let check = |name: &str, value: Value| {
+            let mut names = HashSet::new();
+            property_names(&components[name], &components, &mut names);
+            let mut keys = HashSet::new();
+            serialized_keys(&value, &mut keys);
+            for key in keys {
+                assert!(names.contains(&key), "{name} does not describe field {key}");
+            }
+        };
+
+        let breakpoint = Breakpoint {
+            loc: Some(BreakpointLocation::Opcode { bytecode_address: Address::ZERO, pc: 1 }),
+            condition: Some("x > 1".to_string()),
+            log: Some("x = {x}".to_string()),
+            ignore_count: 2,
+            temporary: true,
+        };
+        check("Breakpoint", serde_json::to_value(breakpoint).unwrap());
+        let filter = SnapshotFilter {
+            address: Some(Address::ZERO),
+            function: Some(1),
+            path: Some("src/A.sol".into()),
+            kind: Some(SnapshotKind::Hook),
+        };
+        check("SnapshotFilter", serde_json::to_value(filter).unwrap());
+        let edit =
+            StateEdit::Storage { address: Address::ZERO, slot: U256::ZERO, value: U256::ZERO };
+        check("StateEdit", serde_json::to_value(edit).unwrap());
+
+        // Fields carry their documentation
+        assert_eq!(
+            components["Breakpoint"]["properties"]["temporary"]["description"],
+            "Whether the breakpoint is deleted once it stops execution (`tbreak`)."
+        );
+    }
+}
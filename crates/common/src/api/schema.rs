@@ -0,0 +1,349 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! JSON schemas of the parameters and results of the debug JSON-RPC API.
+//!
+//! Primitive types are described inline. The structured types of [`crate::types`] derive
+//! [`JsonSchema`], and are referenced by name from the `components` of the OpenRPC
+//! document, where their full schemas are generated. External types without a
+//! [`JsonSchema`] implementation are described by the schemas of [`remote`], which follow
+//! their serde encoding.
+
+use std::collections::HashMap;
+
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::{Address, Bytes, B256, U256};
+use schemars::{generate::SchemaSettings, JsonSchema, SchemaGenerator};
+use serde_json::{json, Map, Value};
+
+use crate::types::{
+    Breakpoint, BreakpointHit, CallableAbiInfo, Code, EdbSolValue, EvalReport, HistoryScope,
+    LogpointEntry, NestedCallTimeline, SearchDirection, SessionInfo, SnapshotFilter, SnapshotInfo,
+    StateEdit, Trace, ValueHistory, ValueLocation, ValueOrigin, Watchpoint, WatchpointHit,
+    WhatIfResult,
+};
+
+/// A type with a JSON schema, usable as a parameter or result of an API method
+pub trait ApiSchema {
+    /// Whether a parameter of this type may be omitted (or `null`)
+    const OPTIONAL: bool = false;
+
+    /// JSON schema of the type
+    fn schema() -> Value;
+}
+
+impl<T: ApiSchema + ?Sized> ApiSchema for &T {
+    const OPTIONAL: bool = T::OPTIONAL;
+
+    fn schema() -> Value {
+        T::schema()
+    }
+}
+
+impl<T: ApiSchema> ApiSchema for Option<T> {
+    const OPTIONAL: bool = true;
+
+    fn schema() -> Value {
+        json!({ "oneOf": [T::schema(), { "type": "null" }] })
+    }
+}
+
+impl<T: ApiSchema> ApiSchema for [T] {
+    fn schema() -> Value {
+        json!({ "type": "array", "items": T::schema() })
+    }
+}
+
+impl<T: ApiSchema> ApiSchema for Vec<T> {
+    fn schema() -> Value {
+        <[T]>::schema()
+    }
+}
+
+impl<K, V: ApiSchema> ApiSchema for HashMap<K, V> {
+    fn schema() -> Value {
+        json!({ "type": "object", "additionalProperties": V::schema() })
+    }
+}
+
+impl<A: ApiSchema, B: ApiSchema> ApiSchema for (A, B) {
+    fn schema() -> Value {
+        json!({
+            "type": "array",
+            "prefixItems": [A::schema(), B::schema()],
+            "minItems": 2,
+            "maxItems": 2,
+        })
+    }
+}
+
+/// Results that fail with a message are encoded as `{"Ok": value}` or `{"Err": message}`
+impl<T: ApiSchema> ApiSchema for Result<T, String> {
+    fn schema() -> Value {
+        json!({
+            "oneOf": [
+                { "type": "object", "properties": { "Ok": T::schema() }, "required": ["Ok"] },
+                { "type": "object", "properties": { "Err": { "type": "string" } }, "required": ["Err"] },
+            ]
+        })
+    }
+}
+
+/// Inline schema of a type, without definitions
+fn inline_schema<T: JsonSchema>() -> Value {
+    T::json_schema(&mut SchemaGenerator::default()).to_value()
+}
+
+/// Implement [`ApiSchema`] for types with an inline schema
+macro_rules! inline_schemas {
+    ($($ty:ty => $schema:expr,)*) => {
+        $(
+            impl ApiSchema for $ty {
+                fn schema() -> Value {
+                    $schema
+                }
+            }
+        )*
+    };
+}
+
+inline_schemas! {
+    bool => json!({ "type": "boolean" }),
+    usize => json!({ "type": "integer", "minimum": 0 }),
+    str => json!({ "type": "string" }),
+    String => json!({ "type": "string" }),
+    Value => json!({}),
+    Address => inline_schema::<remote::Address>(),
+    B256 => inline_schema::<remote::B256>(),
+    U256 => inline_schema::<remote::U256>(),
+    Bytes => inline_schema::<remote::Bytes>(),
+}
+
+/// Implement [`ApiSchema`] for structured types, referencing their components. Types
+/// without a [`JsonSchema`] implementation name the [`remote`] type describing them.
+macro_rules! named_schemas {
+    ($($ty:ident $(=> $remote:ty)?,)*) => {
+        $(
+            impl ApiSchema for $ty {
+                fn schema() -> Value {
+                    let name = <named_schemas!(@schema $ty $(, $remote)?) as JsonSchema>::schema_name();
+                    json!({ "$ref": format!("#/components/schemas/{name}") })
+                }
+            }
+        )*
+
+        /// Schemas of the structured types, and of the types they contain, as the
+        /// `components` of an OpenRPC document
+        pub fn component_schemas() -> Map<String, Value> {
+            let mut generator = SchemaSettings::draft2020_12()
+                .with(|settings| settings.definitions_path = "/components/schemas".into())
+                .into_generator();
+            $(
+                generator.subschema_for::<named_schemas!(@schema $ty $(, $remote)?)>();
+            )*
+            generator.take_definitions(true)
+        }
+    };
+    (@schema $ty:ty) => { $ty };
+    (@schema $ty:ty, $remote:ty) => { $remote };
+}
+
+named_schemas! {
+    Breakpoint,
+    BreakpointHit,
+    CallableAbiInfo,
+    Code,
+    EdbSolValue,
+    EvalReport,
+    HistoryScope,
+    JsonAbi => remote::JsonAbi,
+    LogpointEntry,
+    NestedCallTimeline,
+    SearchDirection,
+    SessionInfo,
+    SnapshotFilter,
+    SnapshotInfo,
+    StateEdit,
+    Trace,
+    ValueHistory,
+    ValueLocation,
+    ValueOrigin,
+    Watchpoint,
+    WatchpointHit,
+    WhatIfResult,
+}
+
+/// JSON schemas of the external types contained in the API types, following their serde
+/// encoding. Fields of these types are annotated with `#[schemars(with = "...")]`.
+pub(crate) mod remote {
+    use std::borrow::Cow;
+
+    use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+    /// Define a type standing for an external type in schemas
+    macro_rules! remote_schemas {
+        ($($(#[doc = $doc:literal])* $name:ident, inline: $inline:literal => $schema:tt,)*) => {
+            $(
+                $(#[doc = $doc])*
+                pub(crate) struct $name;
+
+                impl JsonSchema for $name {
+                    fn inline_schema() -> bool {
+                        $inline
+                    }
+
+                    fn schema_name() -> Cow<'static, str> {
+                        stringify!($name).into()
+                    }
+
+                    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                        json_schema!($schema)
+                    }
+                }
+            )*
+        };
+    }
+
+    remote_schemas! {
+        /// [`alloy_primitives::Address`]
+        Address, inline: true => {
+            "type": "string",
+            "pattern": "^0x[0-9a-fA-F]{40}$",
+        },
+        /// [`alloy_primitives::B256`]
+        B256, inline: true => {
+            "type": "string",
+            "pattern": "^0x[0-9a-fA-F]{64}$",
+        },
+        /// [`alloy_primitives::FixedBytes<24>`], a Solidity function pointer
+        B192, inline: true => {
+            "type": "string",
+            "pattern": "^0x[0-9a-fA-F]{48}$",
+        },
+        /// [`alloy_primitives::Selector`]
+        Selector, inline: true => {
+            "type": "string",
+            "pattern": "^0x[0-9a-fA-F]{8}$",
+        },
+        /// [`alloy_primitives::U256`]
+        U256, inline: true => {
+            "type": "string",
+            "pattern": "^0x[0-9a-fA-F]{1,64}$",
+        },
+        /// [`alloy_primitives::I256`], in decimal
+        I256, inline: true => {
+            "type": "string",
+            "pattern": "^-?[0-9]+$",
+        },
+        /// [`alloy_primitives::Bytes`]
+        Bytes, inline: true => {
+            "type": "string",
+            "pattern": "^0x([0-9a-fA-F]{2})*$",
+        },
+        /// [`revm::interpreter::CallScheme`]
+        CallScheme, inline: false => {
+            "description": "Kind of a message call",
+            "enum": ["Call", "CallCode", "DelegateCall", "StaticCall"],
+        },
+        /// [`revm::context::CreateScheme`]
+        CreateScheme, inline: false => {
+            "description": "Kind of a contract creation",
+            "oneOf": [
+                { "const": "Create" },
+                {
+                    "type": "object",
+                    "properties": {
+                        "Create2": {
+                            "type": "object",
+                            "properties": { "salt": { "type": "string" } },
+                            "required": ["salt"],
+                        },
+                    },
+                    "required": ["Create2"],
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "Custom": {
+                            "type": "object",
+                            "properties": { "address": { "type": "string" } },
+                            "required": ["address"],
+                        },
+                    },
+                    "required": ["Custom"],
+                },
+            ],
+        },
+        /// [`revm::interpreter::InstructionResult`]
+        InstructionResult, inline: false => {
+            "description": "How the execution of a frame ended, e.g. `Stop`, `Return`, `Revert` or `OutOfGas`",
+            "type": "string",
+        },
+        /// [`revm::state::TransientStorage`]
+        TransientStorage, inline: false => {
+            "description": "Transient storage, by address and slot",
+            "type": "object",
+        },
+        /// [`alloy_primitives::LogData`]
+        LogData, inline: false => {
+            "description": "Topics and data of an event",
+            "type": "object",
+            "properties": {
+                "topics": { "type": "array", "items": { "type": "string" } },
+                "data": { "type": "string" },
+            },
+            "required": ["topics", "data"],
+        },
+        /// [`alloy_primitives::Log`]
+        Log, inline: false => {
+            "description": "An event emitted by a contract",
+            "type": "object",
+            "properties": {
+                "address": { "type": "string" },
+                "topics": { "type": "array", "items": { "type": "string" } },
+                "data": { "type": "string" },
+            },
+            "required": ["address", "topics", "data"],
+        },
+        /// [`alloy_json_abi::Function`]
+        Function, inline: false => {
+            "description": "JSON ABI of a function",
+            "type": "object",
+            "properties": {
+                "type": { "const": "function" },
+                "name": { "type": "string" },
+                "inputs": { "type": "array", "items": { "type": "object" } },
+                "outputs": { "type": "array", "items": { "type": "object" } },
+                "stateMutability": { "enum": ["pure", "view", "nonpayable", "payable"] },
+            },
+            "required": ["type", "name", "inputs", "outputs", "stateMutability"],
+        },
+        /// [`alloy_json_abi::JsonAbi`]
+        JsonAbi, inline: false => {
+            "description": "JSON ABI of a contract",
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "type": {
+                        "enum": ["constructor", "fallback", "receive", "function", "event", "error"],
+                    },
+                },
+                "required": ["type"],
+            },
+        },
+    }
+}
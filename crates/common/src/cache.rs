@@ -67,6 +67,12 @@ pub trait CachePath {
     fn compiler_chain_cache_dir(&self, chain_id: impl Into<Chain>) -> Option<PathBuf> {
         Some(self.compiler_cache_dir()?.join(chain_id.into().to_string()))
     }
+
+    /// Returns the path to edb's debug session cache dir, where breakpoints and watch
+    /// expressions are kept across sessions: `<cache_root>/session`.
+    fn session_cache_dir(&self) -> Option<PathBuf> {
+        Some(self.edb_cache_dir()?.join("session"))
+    }
 }
 
 /// Cache path for edb.
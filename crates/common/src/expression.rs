@@ -18,3 +18,15 @@
 pub fn normalize_expression(expr: &str) -> String {
     expr.split_whitespace().collect::<Vec<_>>().join(" ")
 }
+
+/// Pseudo-functions of the evaluator that change the session overlay rather than read
+/// state. Evaluating them has side effects on later evaluations.
+pub const OVERLAY_FUNCTIONS: &[&str] =
+    &["edb_sstore", "edb_deal", "edb_etch", "edb_warp", "edb_roll", "edb_prank", "edb_reset"];
+
+/// Whether an expression may change the session overlay, i.e., mentions any of the
+/// [`OVERLAY_FUNCTIONS`]
+pub fn mutates_overlay(expr: &str) -> bool {
+    expr.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .any(|word| OVERLAY_FUNCTIONS.contains(&word))
+}
@@ -24,6 +24,8 @@
 /// Common types used throughout the EDB ecosystem including execution traces, snapshots, and code representations
 pub mod types;
 
+/// Definition of the debug JSON-RPC API, with its OpenRPC description and typed client trait
+pub mod api;
 /// Caching utilities for storing and retrieving RPC responses to optimize performance
 pub mod cache;
 /// Execution context management for EDB, including environment setup and configuration
@@ -19,14 +19,17 @@ use std::{borrow::Borrow, fmt};
 use alloy_json_abi::Function;
 use alloy_primitives::Address;
 use foundry_compilers::artifacts::Contract;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+
+use crate::api::remote;
 use tracing::error;
 
 /// Magic flag to identify state variables in the instrumented code.
 pub static EDB_STATE_VAR_FLAG: &str = "_edb_state_var_";
 
 /// The type of an callable ABI entry from outside the contract.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum AbiEntryTy {
     /// A function that can be called.
     Function,
@@ -35,7 +38,7 @@ pub enum AbiEntryTy {
 }
 
 /// Information about a callable entry in an ABI.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub struct CallableAbiEntry {
     /// The name of the callable entry.
     pub name: String,
@@ -46,11 +49,12 @@ pub struct CallableAbiEntry {
     /// The output types of the callable entry.
     pub outputs: Vec<String>,
     /// The actually function abi
+    #[schemars(with = "remote::Function")]
     pub abi: Function,
 }
 
 /// Contract type in terms of proxy pattern.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum ContractTy {
     /// A normal contract.
     Normal,
@@ -61,9 +65,10 @@ pub enum ContractTy {
 }
 
 /// Information about all callable ABI entries of a contract.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub struct CallableAbiInfo {
     /// The address of the contract.
+    #[schemars(with = "remote::Address")]
     pub address: Address,
     /// The type of the address (normal, proxy, implementation).
     pub contract_ty: ContractTy,
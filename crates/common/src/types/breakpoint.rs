@@ -16,20 +16,39 @@
 
 use std::{fmt::Display, path::PathBuf, str::FromStr};
 
-use alloy_primitives::Address;
+use alloy_primitives::{keccak256, Address, Selector, B256};
 use eyre::{bail, eyre, Error, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::api::remote;
+
 use crate::normalize_expression;
 
 /// Represents a breakpoint in the debugger with optional location and condition.
 /// A breakpoint can be set at specific code locations and optionally have conditions that must be met to trigger.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+///
+/// A breakpoint with a log format is a logpoint: instead of stopping execution, it logs a
+/// message each time it is hit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
 pub struct Breakpoint {
     /// The location where the breakpoint is set (source code or opcode).
     pub loc: Option<BreakpointLocation>,
     /// Optional condition expression that must evaluate to true for the breakpoint to trigger.
     pub condition: Option<String>,
+    /// Optional log format with `{expr}` placeholders, making the breakpoint a logpoint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log: Option<String>,
+    /// Number of hits (in timeline order) to ignore before the breakpoint stops.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub ignore_count: usize,
+    /// Whether the breakpoint is deleted once it stops execution (`tbreak`).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub temporary: bool,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
 }
 
 impl Display for Breakpoint {
@@ -39,13 +58,19 @@ impl Display for Breakpoint {
         }
         if let Some(cond) = &self.condition {
             if self.loc.is_some() {
-                write!(f, " if {cond}")
+                write!(f, " if {cond}")?;
             } else {
-                write!(f, "if {cond}")
+                write!(f, "if {cond}")?;
             }
-        } else {
-            Ok(())
         }
+        if let Some(log) = &self.log {
+            if self.loc.is_some() || self.condition.is_some() {
+                write!(f, " log {log}")?;
+            } else {
+                write!(f, "log {log}")?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -53,16 +78,36 @@ impl FromStr for Breakpoint {
     type Err = Error;
 
     /// Parses a breakpoint from a string.
-    /// Format: `[@<location>] [if <condition>]`
+    /// Format: `[@<location>] [if <condition>] [log <format>]`
     /// Examples:
     /// - `@0x1234:42` - Breakpoint at opcode
     /// - `@0x1234:src/main.rs:100` - Breakpoint at source location
     /// - `if x > 10` - Data-watching breakpoint
     /// - `@0x1234:42 if balance == 0` - Breakpoint with condition
+    /// - `@0x1234:src/main.rs:100 log i = {i}` - Logpoint at source location
     fn from_str(s: &str) -> Result<Self> {
         let trimmed = s.trim();
         if trimmed.is_empty() {
-            return Ok(Self { loc: None, condition: None });
+            return Ok(Self::default());
+        }
+
+        // The log format comes last and may contain anything, so split it off first
+        let (trimmed, log) = if let Some(format) = trimmed.strip_prefix("log ") {
+            ("", Some(format.trim()))
+        } else if let Some(log_pos) = find_top_level(trimmed, " log ") {
+            (trimmed[..log_pos].trim(), Some(trimmed[log_pos + 5..].trim()))
+        } else {
+            (trimmed, None)
+        };
+        let log = match log {
+            Some(format) => {
+                parse_log_format(format)?;
+                Some(format.to_string())
+            }
+            None => None,
+        };
+        if trimmed.is_empty() {
+            return Ok(Self { log, ..Default::default() });
         }
 
         let mut loc = None;
@@ -96,17 +141,20 @@ impl FromStr for Breakpoint {
             bail!("Invalid breakpoint format. Expected [@<location>] [if <condition>], got: {s}");
         }
 
-        Ok(Self { loc, condition })
+        Ok(Self { loc, condition, log, ..Default::default() })
     }
 }
 
 /// Specifies the location of a breakpoint, either in source code or at a specific opcode.
-/// Breakpoints can be placed at source code lines or at specific program counter positions.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// Breakpoints can be placed at source code lines or at specific program counter positions,
+/// or at symbolic locations (catchpoints) such as function entries, event emissions, calls,
+/// contract creations, self-destructs and reverts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
 pub enum BreakpointLocation {
     /// A breakpoint in source code at a specific file and character range.
     Source {
         /// The address of the bytecode contract.
+        #[schemars(with = "remote::Address")]
         bytecode_address: Address,
         /// Path to the source file.
         file_path: PathBuf,
@@ -116,10 +164,43 @@ pub enum BreakpointLocation {
     /// A breakpoint at a specific opcode position.
     Opcode {
         /// The address of the bytecode contract.
+        #[schemars(with = "remote::Address")]
         bytecode_address: Address,
         /// Program counter (PC) position in the bytecode.
         pc: usize,
     },
+    /// The entry of a function, by name (qualified with its contract) and/or signature.
+    Function {
+        /// Name of the contract defining the function.
+        contract: Option<String>,
+        /// Name of the function.
+        name: String,
+        /// Canonical parameter types, if given as a signature.
+        params: Option<Vec<String>>,
+    },
+    /// The emission of an event.
+    Event {
+        /// Canonical event signature, if the event is given by signature.
+        signature: Option<String>,
+        /// The first topic of the event (the hash of its signature).
+        #[schemars(with = "remote::B256")]
+        topic0: B256,
+    },
+    /// The entry of an external call, optionally to a given address and/or selector.
+    Call {
+        /// The called address.
+        #[schemars(with = "Option<remote::Address>")]
+        target: Option<Address>,
+        /// The selector of the called function.
+        #[schemars(with = "Option<remote::Selector>")]
+        selector: Option<Selector>,
+    },
+    /// The entry of a contract creation (CREATE / CREATE2).
+    Create,
+    /// The execution of a SELFDESTRUCT.
+    SelfDestruct,
+    /// The end of any reverting call, right before the revert.
+    Revert,
 }
 
 impl FromStr for BreakpointLocation {
@@ -128,7 +209,49 @@ impl FromStr for BreakpointLocation {
     /// Parses a breakpoint location from a string in the format:
     /// - `<addr>:<pc>` for opcode breakpoints
     /// - `<addr>:<path>:<line>` for source breakpoints
+    /// - `<Contract>.<function>`, `<function>(<types>)` or `<Contract>.<function>(<types>)`
+    ///   for function entries
+    /// - `event:<signature>` or `event:<topic0>` for event emissions
+    /// - `call`, `call:<addr>`, `call:<selector>` or `call:<addr>:<selector>` for external
+    ///   calls, where the selector may also be given as a function signature
+    /// - `create`, `selfdestruct` and `revert`
     fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "create" => return Ok(Self::Create),
+            "selfdestruct" => return Ok(Self::SelfDestruct),
+            "revert" => return Ok(Self::Revert),
+            "call" => return Ok(Self::Call { target: None, selector: None }),
+            _ => {}
+        }
+
+        if let Some(event) = s.strip_prefix("event:") {
+            return if event.starts_with("0x") && !event.contains('(') {
+                let topic0 = event.parse::<B256>().map_err(|e| eyre!("Invalid topic: {e}"))?;
+                Ok(Self::Event { signature: None, topic0 })
+            } else {
+                let (name, params) = parse_signature(event)?;
+                let signature = format!("{name}({})", params.join(","));
+                Ok(Self::Event { topic0: keccak256(&signature), signature: Some(signature) })
+            };
+        }
+
+        if let Some(call) = s.strip_prefix("call:") {
+            let (target, selector) = match call.split_once(':') {
+                Some((target, selector)) => (Some(target), Some(selector)),
+                None if call.contains('(') || call.len() == 10 => (None, Some(call)),
+                None => (Some(call), None),
+            };
+            let target = target
+                .map(|target| target.parse::<Address>().map_err(|e| eyre!("Invalid address: {e}")))
+                .transpose()?;
+            let selector = selector.map(parse_selector).transpose()?;
+            return Ok(Self::Call { target, selector });
+        }
+
+        if !s.contains(':') {
+            return parse_function_location(s);
+        }
+
         let parts: Vec<&str> = s.split(':').collect();
 
         if parts.len() == 2 {
@@ -166,40 +289,287 @@ impl BreakpointLocation {
     }
 
     /// Returns the bytecode address associated with this breakpoint location.
-    /// Works for both Source and Opcode variants.
-    pub fn bytecode_address(&self) -> Address {
+    /// Only Source and Opcode locations have one; symbolic locations may match any code.
+    pub fn bytecode_address(&self) -> Option<Address> {
         match self {
-            Self::Source { bytecode_address, .. } => *bytecode_address,
-            Self::Opcode { bytecode_address, .. } => *bytecode_address,
+            Self::Source { bytecode_address, .. } => Some(*bytecode_address),
+            Self::Opcode { bytecode_address, .. } => Some(*bytecode_address),
+            _ => None,
         }
     }
 
+    /// Whether the location is symbolic, i.e., not tied to a position in some bytecode.
+    pub fn is_symbolic(&self) -> bool {
+        self.bytecode_address().is_none()
+    }
+
     /// Formats the breakpoint location as a string.
     pub fn display(&self, addr_label: Option<String>) -> String {
-        let bytecode_address = self.bytecode_address();
-        let addr_str = addr_label.unwrap_or_else(|| format!("{bytecode_address}"));
+        let addr_str = || match (addr_label, self.bytecode_address()) {
+            (Some(label), _) => label,
+            (None, Some(bytecode_address)) => format!("{bytecode_address}"),
+            (None, None) => String::new(),
+        };
 
         match self {
             Self::Opcode { pc, .. } => {
-                format!("{addr_str}:{pc}")
+                format!("{}:{pc}", addr_str())
             }
             Self::Source { file_path, line_number, .. } => {
-                format!("{addr_str}:{}:{line_number}", file_path.display())
+                format!("{}:{}:{line_number}", addr_str(), file_path.display())
+            }
+            Self::Function { contract, name, params } => {
+                let mut s = String::new();
+                if let Some(contract) = contract {
+                    s.push_str(&format!("{contract}."));
+                }
+                s.push_str(name);
+                if let Some(params) = params {
+                    s.push_str(&format!("({})", params.join(",")));
+                }
+                s
+            }
+            Self::Event { signature: Some(signature), .. } => format!("event:{signature}"),
+            Self::Event { signature: None, topic0 } => format!("event:{topic0}"),
+            Self::Call { target, selector } => {
+                let mut s = "call".to_string();
+                if let Some(target) = target {
+                    s.push_str(&format!(":{target}"));
+                }
+                if let Some(selector) = selector {
+                    s.push_str(&format!(":{selector}"));
+                }
+                s
             }
+            Self::Create => "create".to_string(),
+            Self::SelfDestruct => "selfdestruct".to_string(),
+            Self::Revert => "revert".to_string(),
         }
     }
 }
 
+/// Parses a function location: `<Contract>.<function>`, `<function>(<types>)` or
+/// `<Contract>.<function>(<types>)`.
+fn parse_function_location(s: &str) -> Result<BreakpointLocation> {
+    let (qualified_name, params) = match s.find('(') {
+        Some(pos) => (s[..pos].trim().to_string(), Some(parse_signature(s)?.1)),
+        None => (s.to_string(), None),
+    };
+
+    let (contract, name) = match qualified_name.split_once('.') {
+        Some((contract, name)) => (Some(contract.to_string()), name.to_string()),
+        None => (None, qualified_name),
+    };
+    if !contract.as_deref().is_none_or(is_identifier) || !is_identifier(&name) {
+        bail!("Invalid function location: {s}");
+    }
+    if contract.is_none() && params.is_none() {
+        bail!("Ambiguous function location '{s}', expected <Contract>.{name} or {name}(<types>)");
+    }
+
+    Ok(BreakpointLocation::Function { contract, name, params })
+}
+
+/// Parses a function or event signature `<name>(<types>)` into its name and canonical
+/// parameter types. The name may be qualified with a contract (`<Contract>.<name>`).
+pub fn parse_signature(signature: &str) -> Result<(String, Vec<String>)> {
+    let signature: String = signature.split_whitespace().collect();
+    let (Some(open), true) = (signature.find('('), signature.ends_with(')')) else {
+        bail!("Invalid signature: {signature}, expected <name>(<types>)");
+    };
+
+    let name = &signature[..open];
+    let name = name.rsplit_once('.').map_or(name, |(_, name)| name);
+    if !is_identifier(name) {
+        bail!("Invalid signature: {signature}, expected <name>(<types>)");
+    }
+
+    // Split the parameters at top-level commas, keeping tuples intact
+    let inner = &signature[open + 1..signature.len() - 1];
+    let mut params = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| eyre!("Unbalanced parentheses in signature: {signature}"))?
+            }
+            ',' if depth == 0 => {
+                params.push(inner[start..i].to_string());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        bail!("Unbalanced parentheses in signature: {signature}");
+    }
+    if !inner.is_empty() {
+        params.push(inner[start..].to_string());
+    }
+    if params.iter().any(|param| param.is_empty()) {
+        bail!("Empty parameter type in signature: {signature}");
+    }
+
+    Ok((name.to_string(), params))
+}
+
+/// Parses a selector, given either as 4 hex bytes or as a function signature.
+fn parse_selector(s: &str) -> Result<Selector> {
+    if s.contains('(') {
+        let (name, params) = parse_signature(s)?;
+        let signature = format!("{name}({})", params.join(","));
+        Ok(Selector::from_slice(&keccak256(signature)[..4]))
+    } else {
+        s.parse::<Selector>().map_err(|e| eyre!("Invalid selector: {e}"))
+    }
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
 impl Breakpoint {
     /// Creates a new breakpoint with the given location and optional condition.
     pub fn new(loc: Option<BreakpointLocation>, condition: Option<String>) -> Self {
-        Self { loc, condition }
+        Self { loc, condition, ..Default::default() }
     }
 
     /// Update the condition of the breakpoint.
     pub fn set_condition(&mut self, condition: &str) {
         self.condition = Some(normalize_expression(condition));
     }
+
+    /// Whether the breakpoint is a logpoint, which logs a message instead of stopping.
+    pub fn is_logpoint(&self) -> bool {
+        self.log.is_some()
+    }
+}
+
+/// Finds the first occurrence of a keyword outside string literals and parentheses or
+/// brackets, e.g., the `log` of a logpoint rather than a `log` within its condition.
+fn find_top_level(s: &str, keyword: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (pos, c) in s.char_indices() {
+        if let Some(q) = quote {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                _ if c == q => quote = None,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ if depth == 0 && s[pos..].starts_with(keyword) => return Some(pos),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// A piece of a logpoint format string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogSegment {
+    /// Literal text
+    Text(String),
+    /// An expression whose value is substituted
+    Expr(String),
+}
+
+/// Parses a logpoint format string into literal text and `{expr}` placeholders.
+/// Literal braces are written as `{{` and `}}`.
+pub fn parse_log_format(format: &str) -> Result<Vec<LogSegment>> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut expr = String::new();
+                let mut depth = 0usize;
+                loop {
+                    match chars.next() {
+                        Some('}') if depth == 0 => break,
+                        Some(c) => {
+                            match c {
+                                '{' => depth += 1,
+                                '}' => depth -= 1,
+                                _ => {}
+                            }
+                            expr.push(c);
+                        }
+                        None => bail!("Unclosed placeholder in log format: {format}"),
+                    }
+                }
+                let expr = normalize_expression(expr.trim());
+                if expr.is_empty() {
+                    bail!("Empty placeholder in log format: {format}");
+                }
+                if !text.is_empty() {
+                    segments.push(LogSegment::Text(std::mem::take(&mut text)));
+                }
+                segments.push(LogSegment::Expr(expr));
+            }
+            '}' => bail!("Unmatched '}}' in log format: {format}"),
+            c => text.push(c),
+        }
+    }
+
+    if !text.is_empty() {
+        segments.push(LogSegment::Text(text));
+    }
+    Ok(segments)
+}
+
+/// A message logged by a logpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+pub struct LogpointEntry {
+    /// The snapshot at which the logpoint is hit
+    pub snapshot_id: usize,
+    /// Index (into the collected logpoint list) of the logpoint hit
+    pub logpoint: usize,
+    /// The message, with placeholders replaced by their values
+    pub message: String,
+}
+
+/// Direction in which the snapshot timeline is searched
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchDirection {
+    /// Towards later snapshots (continue)
+    Forward,
+    /// Towards earlier snapshots (reverse-continue)
+    Backward,
+}
+
+/// The nearest snapshot at which any of a set of breakpoints is hit.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+pub struct BreakpointHit {
+    /// The snapshot at which execution stops
+    pub snapshot_id: usize,
+    /// Indices (into the searched breakpoint list) of all breakpoints hit at this snapshot
+    pub breakpoints: Vec<usize>,
 }
 
 #[cfg(test)]
@@ -410,7 +780,7 @@ mod tests {
 
         // Test for Opcode variant
         let loc = BreakpointLocation::Opcode { bytecode_address: addr, pc: 42 };
-        assert_eq!(loc.bytecode_address(), addr);
+        assert_eq!(loc.bytecode_address(), Some(addr));
 
         // Test for Source variant
         let loc = BreakpointLocation::Source {
@@ -418,7 +788,10 @@ mod tests {
             file_path: PathBuf::from("test.rs"),
             line_number: 10,
         };
-        assert_eq!(loc.bytecode_address(), addr);
+        assert_eq!(loc.bytecode_address(), Some(addr));
+
+        // Symbolic locations have no bytecode address
+        assert_eq!(BreakpointLocation::Revert.bytecode_address(), None);
     }
 
     #[test]
@@ -458,4 +831,156 @@ mod tests {
         assert!(!set.insert(bp2)); // Should return false as it's a duplicate
         assert!(set.insert(bp3)); // Should return true as it's different
     }
+
+    #[test]
+    fn test_breakpoint_from_str_logpoint() {
+        let bp = Breakpoint::from_str(
+            "@0x1234567890123456789012345678901234567890:src/main.rs:100 if $ i > 2 log i = {i}",
+        )
+        .unwrap();
+        assert!(bp.is_logpoint());
+        assert_eq!(bp.condition, Some("i > 2".to_string()));
+        assert_eq!(bp.log, Some("i = {i}".to_string()));
+        assert_eq!(
+            bp.to_string(),
+            "@0x1234567890123456789012345678901234567890:src/main.rs:100 if i > 2 log i = {i}"
+        );
+
+        // A log keyword within the condition does not start the format
+        let bp =
+            Breakpoint::from_str(r#"if $name == " log " && f(a log b) log name = {name}"#).unwrap();
+        assert_eq!(bp.condition, Some(r#"name == " log " && f(a log b)"#.to_string()));
+        assert_eq!(bp.log, Some("name = {name}".to_string()));
+
+        let bp = Breakpoint::from_str("log {x}").unwrap();
+        assert!(bp.loc.is_none() && bp.condition.is_none());
+        assert_eq!(bp.log, Some("{x}".to_string()));
+
+        // Invalid format
+        assert!(
+            Breakpoint::from_str("@0x1234567890123456789012345678901234567890:42 log {x").is_err()
+        );
+    }
+
+    #[test]
+    fn test_breakpoint_location_from_str_symbolic() {
+        let loc = BreakpointLocation::from_str("Vault.withdraw").unwrap();
+        assert_eq!(
+            loc,
+            BreakpointLocation::Function {
+                contract: Some("Vault".to_string()),
+                name: "withdraw".to_string(),
+                params: None,
+            }
+        );
+        assert!(loc.is_symbolic());
+
+        let loc = BreakpointLocation::from_str("withdraw(uint256, (address,bool)[])").unwrap();
+        assert_eq!(
+            loc,
+            BreakpointLocation::Function {
+                contract: None,
+                name: "withdraw".to_string(),
+                params: Some(vec!["uint256".to_string(), "(address,bool)[]".to_string()]),
+            }
+        );
+
+        let loc = BreakpointLocation::from_str("event:Transfer(address,address,uint256)").unwrap();
+        let topic0 = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+            .parse::<B256>()
+            .unwrap();
+        assert!(matches!(loc, BreakpointLocation::Event { topic0: t, .. } if t == topic0));
+        assert_eq!(
+            BreakpointLocation::from_str(&format!("event:{topic0}")).unwrap(),
+            BreakpointLocation::Event { signature: None, topic0 }
+        );
+
+        let loc = BreakpointLocation::from_str(
+            "call:0x1234567890123456789012345678901234567890:transfer(address,uint256)",
+        )
+        .unwrap();
+        assert_eq!(
+            loc,
+            BreakpointLocation::Call {
+                target: Some(address!("1234567890123456789012345678901234567890")),
+                selector: Some("0xa9059cbb".parse().unwrap()),
+            }
+        );
+        assert_eq!(
+            BreakpointLocation::from_str("call:0xa9059cbb").unwrap(),
+            BreakpointLocation::Call {
+                target: None,
+                selector: Some("0xa9059cbb".parse().unwrap())
+            }
+        );
+
+        assert_eq!(BreakpointLocation::from_str("create").unwrap(), BreakpointLocation::Create);
+        assert_eq!(
+            BreakpointLocation::from_str("selfdestruct").unwrap(),
+            BreakpointLocation::SelfDestruct
+        );
+        assert_eq!(BreakpointLocation::from_str("revert").unwrap(), BreakpointLocation::Revert);
+
+        // A bare function name is ambiguous
+        assert!(BreakpointLocation::from_str("withdraw").is_err());
+        assert!(BreakpointLocation::from_str("Vault.withdraw(uint256").is_err());
+    }
+
+    #[test]
+    fn test_breakpoint_location_symbolic_roundtrip() {
+        for original in [
+            "Vault.withdraw(uint256)",
+            "event:Transfer(address,address,uint256)",
+            "call:0x1234567890123456789012345678901234567890:0xa9059cbb",
+            "call",
+            "revert",
+        ] {
+            let loc = BreakpointLocation::from_str(original).unwrap();
+            assert_eq!(loc.display(None), original);
+        }
+
+        let bp = Breakpoint::from_str("@Vault.withdraw if $ amount > 0").unwrap();
+        assert_eq!(bp.to_string(), "@Vault.withdraw if amount > 0");
+    }
+
+    #[test]
+    fn test_parse_log_format() {
+        assert_eq!(
+            parse_log_format("a[{i}] = {a[i]} {{ok}}").unwrap(),
+            vec![
+                LogSegment::Text("a[".to_string()),
+                LogSegment::Expr("i".to_string()),
+                LogSegment::Text("] = ".to_string()),
+                LogSegment::Expr("a[i]".to_string()),
+                LogSegment::Text(" {ok}".to_string()),
+            ]
+        );
+        assert!(parse_log_format("{}").is_err());
+        assert!(parse_log_format("x}").is_err());
+        assert!(parse_log_format("{x").is_err());
+    }
+
+    #[test]
+    fn test_breakpoint_serde_counts() {
+        // Default counts are omitted, keeping older clients compatible
+        let mut bp =
+            Breakpoint::from_str("@0x1234567890123456789012345678901234567890:42").unwrap();
+        let value = serde_json::to_value(&bp).unwrap();
+        assert!(value.get("ignore_count").is_none() && value.get("temporary").is_none());
+
+        bp.ignore_count = 3;
+        bp.temporary = true;
+        let value = serde_json::to_value(&bp).unwrap();
+        assert_eq!(value["ignore_count"], 3);
+        assert_eq!(serde_json::from_value::<Breakpoint>(value).unwrap(), bp);
+    }
+
+    #[test]
+    fn test_search_direction_serde() {
+        assert_eq!(serde_json::to_value(SearchDirection::Forward).unwrap(), "forward");
+        assert_eq!(
+            serde_json::from_value::<SearchDirection>("backward".into()).unwrap(),
+            SearchDirection::Backward
+        );
+    }
 }
@@ -18,10 +18,13 @@ use std::{collections::HashMap, path::PathBuf};
 
 use alloy_primitives::Address;
 use derive_more::From;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::api::remote;
+
 /// Represents code information in either opcode or source format for debugging analysis
-#[derive(Debug, Clone, Serialize, Deserialize, From)]
+#[derive(Debug, Clone, Serialize, Deserialize, From, JsonSchema)]
 pub enum Code {
     /// Opcode-level code representation with disassembled bytecode
     Opcode(#[from] OpcodeInfo),
@@ -40,18 +43,20 @@ impl Code {
 }
 
 /// Information about disassembled bytecode with opcode mappings for debugging at the EVM level
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct OpcodeInfo {
     /// The address where the actual bytecode is stored (may differ from address in proxy patterns)
+    #[schemars(with = "remote::Address")]
     pub bytecode_address: Address,
     /// Mapping from program counter to disassembled opcode strings for step-by-step debugging
     pub codes: HashMap<usize, String>, // pc -> opcode
 }
 
 /// Information about original Solidity source code for high-level debugging with source mappings
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct SourceInfo {
     /// The address where the actual bytecode is stored (may differ from address in proxy patterns)
+    #[schemars(with = "remote::Address")]
     pub bytecode_address: Address,
     /// Mapping from source file paths to their content for source-level debugging
     pub sources: HashMap<PathBuf, String>, // file -> source
@@ -0,0 +1,185 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Calls made into contracts while evaluating an expression
+
+use std::{fmt, path::PathBuf};
+
+use alloy_dyn_abi::{DynSolType, DynSolValue};
+use alloy_primitives::{hex, Address, Bytes, Log, U256};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::api::remote;
+
+use crate::types::EdbSolValue;
+
+/// Selector of the standard `Error(string)` revert
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Selector of the standard `Panic(uint256)` revert
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Options of a call made from an expression, e.g. `f{from: owner, value: 1 ether, gas: 100000}()`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct CallOptions {
+    /// Sender of the call (an EDB pseudo-option), defaulting to the sender of the transaction
+    #[schemars(with = "Option<remote::Address>")]
+    pub from: Option<Address>,
+    /// Value sent with the call, defaulting to zero
+    #[schemars(with = "Option<remote::U256>")]
+    pub value: Option<U256>,
+    /// Gas limit of the call, defaulting to unlimited
+    pub gas: Option<u64>,
+}
+
+impl CallOptions {
+    /// Returns whether no option is set
+    pub fn is_empty(&self) -> bool {
+        self.from.is_none() && self.value.is_none() && self.gas.is_none()
+    }
+}
+
+impl fmt::Display for CallOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut options = Vec::new();
+        if let Some(from) = self.from {
+            options.push(format!("from: {from}"));
+        }
+        if let Some(value) = self.value {
+            options.push(format!("value: {value}"));
+        }
+        if let Some(gas) = self.gas {
+            options.push(format!("gas: {gas}"));
+        }
+        write!(f, "{{{}}}", options.join(", "))
+    }
+}
+
+/// Outcome of a call made into a contract while evaluating an expression
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct EvalCall {
+    /// Address that was called
+    #[schemars(with = "remote::Address")]
+    pub to: Address,
+    /// Signature of the called function, e.g. `withdraw(uint256)`
+    pub function: String,
+    /// Call data of the call
+    #[schemars(with = "remote::Bytes")]
+    pub input: Bytes,
+    /// Options the call was made with
+    pub options: CallOptions,
+    /// Whether the call succeeded
+    pub success: bool,
+    /// Decoded revert (or halt) reason of a failed call
+    pub revert_reason: Option<String>,
+    /// Gas used by the call
+    pub gas_used: u64,
+    /// Events emitted by the call
+    #[schemars(with = "Vec<remote::Log>")]
+    pub logs: Vec<Log>,
+}
+
+/// Result of evaluating an expression, together with the calls it made into contracts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct EvalReport {
+    /// Value of the expression, or the error that stopped its evaluation
+    pub value: Result<EdbSolValue, String>,
+    /// Calls made during the evaluation, in execution order
+    pub calls: Vec<EvalCall>,
+}
+
+/// A call made while evaluating an expression, re-executed as a nested timeline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct NestedCallTimeline {
+    /// Port of the debug server serving the nested timeline
+    pub port: u16,
+    /// Unix domain socket of the debug server, instead of a port (then 0), when the
+    /// server it was derived from listens on one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub socket: Option<PathBuf>,
+    /// The call debugged by the nested timeline
+    pub call: EvalCall,
+}
+
+/// Decode the revert reason of a failed call into a human-readable string.
+///
+/// Standard `Error(string)` and `Panic(uint256)` reverts are decoded; any other
+/// output (e.g. a custom error) is shown as hex.
+pub fn decode_revert_reason(output: &[u8]) -> String {
+    if output.is_empty() {
+        return "(empty revert)".to_string();
+    }
+
+    if let Some(data) = output.strip_prefix(&ERROR_SELECTOR) {
+        if let Ok(DynSolValue::String(reason)) = DynSolType::String.abi_decode(data) {
+            return format!("\"{reason}\"");
+        }
+    }
+
+    if let Some(data) = output.strip_prefix(&PANIC_SELECTOR) {
+        if let Ok(DynSolValue::Uint(panic_code, _)) = DynSolType::Uint(256).abi_decode(data) {
+            let panic_reason = match u64::try_from(panic_code).unwrap_or(u64::MAX) {
+                0x01 => "assertion failed",
+                0x11 => "arithmetic overflow/underflow",
+                0x12 => "division by zero",
+                0x21 => "enum conversion error",
+                0x22 => "invalid storage byte array access",
+                0x31 => "pop() on empty array",
+                0x32 => "array index out of bounds",
+                0x41 => "memory allocation overflow",
+                0x51 => "zero initialization of invalid type",
+                _ => "unknown panic",
+            };
+            return format!("Panic({panic_code}: {panic_reason})");
+        }
+    }
+
+    if output.len() <= 32 {
+        format!("0x{}", hex::encode(output))
+    } else {
+        format!("0x{}...({} bytes)", hex::encode(&output[..8]), output.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_revert_reason() {
+        let mut output = ERROR_SELECTOR.to_vec();
+        output.extend(DynSolValue::String("not owner".to_string()).abi_encode());
+        assert_eq!(decode_revert_reason(&output), "\"not owner\"");
+
+        let mut output = PANIC_SELECTOR.to_vec();
+        output.extend(DynSolValue::Uint(U256::from(0x11), 256).abi_encode());
+        assert_eq!(decode_revert_reason(&output), "Panic(17: arithmetic overflow/underflow)");
+
+        assert_eq!(decode_revert_reason(&[]), "(empty revert)");
+        assert_eq!(decode_revert_reason(&[0xde, 0xad]), "0xdead");
+    }
+
+    #[test]
+    fn test_call_options_display() {
+        let options =
+            CallOptions { value: Some(U256::from(5)), gas: Some(1000), ..Default::default() };
+        assert!(!options.is_empty());
+        assert_eq!(options.to_string(), "{value: 5, gas: 1000}");
+        assert!(CallOptions::default().is_empty());
+    }
+}
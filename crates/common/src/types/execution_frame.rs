@@ -20,6 +20,7 @@
 
 use std::fmt;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Execution frame identifier for tracking nested call contexts
@@ -27,7 +28,9 @@ use serde::{Deserialize, Serialize};
 /// A frame ID is a tuple (trace_entry_id, re_entry_count) where:
 /// - `trace_entry_id`: Unique identifier for the trace entry
 /// - `re_entry_count`: Number of times this frame has been re-entered
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
 pub struct ExecutionFrameId(pub usize, pub usize);
 
 impl fmt::Display for ExecutionFrameId {
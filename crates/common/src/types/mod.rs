@@ -26,11 +26,32 @@ pub use code::*;
 mod snapshot;
 pub use snapshot::*;
 
+mod session;
+pub use session::*;
+
+mod snapshot_filter;
+pub use snapshot_filter::*;
+
+mod state_edit;
+pub use state_edit::*;
+
 mod trace;
 pub use trace::*;
 
+mod eval_call;
+pub use eval_call::*;
+
 mod execution_frame;
 pub use execution_frame::*;
 
 mod sol_value;
 pub use sol_value::*;
+
+mod value_history;
+pub use value_history::*;
+
+mod value_origin;
+pub use value_origin::*;
+
+mod watchpoint;
+pub use watchpoint::*;
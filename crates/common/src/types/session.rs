@@ -0,0 +1,49 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Types describing the debugging sessions hosted by an engine server
+
+use std::path::PathBuf;
+
+use alloy_primitives::TxHash;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::api::remote;
+
+/// Id of the session of the transaction the engine server was started for
+pub const DEFAULT_SESSION_ID: &str = "default";
+
+/// Environment variable handing the token of a debug server to its clients (e.g., `edb-tui`)
+pub const RPC_TOKEN_ENV: &str = "EDB_RPC_TOKEN";
+
+/// A debugging session hosted by an engine server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SessionInfo {
+    /// Id to address the session with, through the `session_id` member of requests
+    pub session_id: String,
+    /// Transaction debugged in the session
+    #[schemars(with = "remote::B256")]
+    pub tx_hash: TxHash,
+    /// Port of the debug server of the session
+    pub port: u16,
+    /// Unix domain socket of the debug server, instead of a port (then 0), when the
+    /// hosting server listens on one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub socket: Option<PathBuf>,
+    /// Time since the session last served a request, in milliseconds
+    pub idle_ms: u64,
+}
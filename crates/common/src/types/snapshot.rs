@@ -19,12 +19,15 @@ use std::{collections::HashMap, path::PathBuf, sync::Arc};
 use alloy_primitives::{Address, Bytes, U256};
 use derive_more::From;
 use revm::state::TransientStorage;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::api::remote;
+
 use crate::types::{EdbSolValue, ExecutionFrameId};
 
 /// Complete snapshot information capturing EVM state at a specific execution point for debugging navigation
-#[derive(Debug, Clone, Serialize, Deserialize, From)]
+#[derive(Debug, Clone, Serialize, Deserialize, From, JsonSchema)]
 pub struct SnapshotInfo {
     /// Unique snapshot identifier for debugging navigation
     pub id: usize,
@@ -37,13 +40,15 @@ pub struct SnapshotInfo {
     /// Detailed snapshot information varying by debugging mode (opcode vs source)
     pub detail: SnapshotInfoDetail,
     /// Target contract address for this execution step
+    #[schemars(with = "remote::Address")]
     pub target_address: Address,
     /// Address where the actual bytecode is stored (may differ from target in proxy patterns)
+    #[schemars(with = "remote::Address")]
     pub bytecode_address: Address,
 }
 
 /// Snapshot detail information varying by debugging mode for different levels of analysis
-#[derive(Debug, Clone, Serialize, Deserialize, From)]
+#[derive(Debug, Clone, Serialize, Deserialize, From, JsonSchema)]
 pub enum SnapshotInfoDetail {
     /// Low-level opcode debugging with EVM state (program counter, stack, memory)
     Opcode(#[from] OpcodeSnapshotInfoDetail),
@@ -111,7 +116,7 @@ impl SnapshotInfo {
 }
 
 /// Source-level debugging snapshot with variable states and source location mapping
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HookSnapshotInfoDetail {
     /// Unique snapshot identifier for debugging navigation
     pub id: usize,
@@ -130,7 +135,7 @@ pub struct HookSnapshotInfoDetail {
 }
 
 /// Low-level opcode debugging snapshot with complete EVM state for instruction-level analysis
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OpcodeSnapshotInfoDetail {
     /// Unique snapshot identifier for debugging navigation
     pub id: usize,
@@ -143,9 +148,12 @@ pub struct OpcodeSnapshotInfoDetail {
     /// EVM memory state at this execution point (shared via Arc when unchanged for efficiency)
     pub memory: Vec<u8>,
     /// EVM stack state with all values (always cloned as most opcodes modify it)
+    #[schemars(with = "Vec<remote::U256>")]
     pub stack: Vec<U256>,
     /// Call data for this execution context (shared via Arc within same call frame)
+    #[schemars(with = "remote::Bytes")]
     pub calldata: Bytes,
     /// Transient storage state for EIP-1153 temporary storage operations
+    #[schemars(with = "remote::TransientStorage")]
     pub transient_storage: TransientStorage,
 }
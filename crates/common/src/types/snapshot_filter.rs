@@ -0,0 +1,109 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Filters narrowing down the snapshots searched by a snapshot query
+
+use std::{fmt, path::PathBuf};
+
+use alloy_primitives::Address;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::api::remote;
+
+/// The kind of a snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub enum SnapshotKind {
+    /// Opcode snapshots of uninstrumented contracts
+    Opcode,
+    /// Source-level (hooked) snapshots of instrumented contracts
+    Hook,
+}
+
+impl fmt::Display for SnapshotKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Opcode => write!(f, "opcode"),
+            Self::Hook => write!(f, "hook"),
+        }
+    }
+}
+
+/// Conditions a snapshot has to satisfy to be searched. Unset conditions match any snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SnapshotFilter {
+    /// Target or bytecode address of the snapshot
+    #[schemars(with = "Option<remote::Address>")]
+    pub address: Option<Address>,
+    /// Function (UFID) containing the step of a hook snapshot. UFIDs are only unique
+    /// within the code they are analyzed from, so the function is looked for in the code
+    /// at `address` (the bytecode address), which must be set as well.
+    pub function: Option<u64>,
+    /// Source file containing the step of a hook snapshot, matched as a path suffix
+    pub path: Option<PathBuf>,
+    /// Kind of the snapshot
+    pub kind: Option<SnapshotKind>,
+}
+
+impl SnapshotFilter {
+    /// Returns whether the filter requires source-level information, which only hook
+    /// snapshots carry.
+    pub fn needs_source(&self) -> bool {
+        self.function.is_some() || self.path.is_some()
+    }
+}
+
+impl fmt::Display for SnapshotFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut conditions = Vec::new();
+        if let Some(address) = self.address {
+            conditions.push(format!("address {address}"));
+        }
+        if let Some(function) = self.function {
+            conditions.push(format!("function #{function}"));
+        }
+        if let Some(path) = &self.path {
+            conditions.push(format!("file {}", path.display()));
+        }
+        if let Some(kind) = self.kind {
+            conditions.push(format!("{kind} snapshots"));
+        }
+
+        if conditions.is_empty() {
+            write!(f, "all snapshots")
+        } else {
+            write!(f, "{}", conditions.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_filter_deserialize_partial() {
+        let filter: SnapshotFilter = serde_json::from_str(r#"{"kind":"Hook"}"#).unwrap();
+        assert_eq!(filter, SnapshotFilter { kind: Some(SnapshotKind::Hook), ..Default::default() });
+        assert!(!filter.needs_source());
+        assert_eq!(filter.to_string(), "hook snapshots");
+
+        let filter: SnapshotFilter = serde_json::from_str("{}").unwrap();
+        assert_eq!(filter, SnapshotFilter::default());
+        assert_eq!(filter.to_string(), "all snapshots");
+    }
+}
@@ -19,8 +19,11 @@ use std::ops::{Deref, DerefMut};
 use alloy_dyn_abi::DynSolValue;
 use alloy_primitives::hex;
 use alloy_primitives::{Address, FixedBytes, I256, U256};
+use schemars::{JsonSchema, Schema, SchemaGenerator};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::api::remote;
+
 /// Wrapper around DynSolValue with custom serialization for EDB debugging and analysis
 #[derive(Debug, Clone, PartialEq)]
 pub struct EdbSolValue(pub DynSolValue);
@@ -52,21 +55,34 @@ impl DerefMut for EdbSolValue {
 }
 
 /// Serializable representation of DynSolValue for JSON and other formats with complete type information preservation
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "value")]
+#[schemars(rename = "EdbSolValue", description = "A Solidity value, tagged with its type")]
 enum SerializedDynSolValue {
     /// Boolean value
     Bool(bool),
     /// Signed integer with bit size specification
-    Int { value: I256, bits: usize },
+    Int {
+        #[schemars(with = "remote::I256")]
+        value: I256,
+        bits: usize,
+    },
     /// Unsigned integer with bit size specification
-    Uint { value: U256, bits: usize },
+    Uint {
+        #[schemars(with = "remote::U256")]
+        value: U256,
+        bits: usize,
+    },
     /// Fixed-size bytes with size specification
-    FixedBytes { value: FixedBytes<32>, size: usize },
+    FixedBytes {
+        #[schemars(with = "remote::B256")]
+        value: FixedBytes<32>,
+        size: usize,
+    },
     /// Ethereum address (20 bytes)
-    Address(Address),
+    Address(#[schemars(with = "remote::Address")] Address),
     /// Function selector (24 bytes)
-    Function(FixedBytes<24>),
+    Function(#[schemars(with = "remote::B192")] FixedBytes<24>),
     /// Dynamic bytes array
     Bytes(Vec<u8>),
     /// String value
@@ -155,6 +171,20 @@ impl<'de> Deserialize<'de> for EdbSolValue {
     }
 }
 
+impl JsonSchema for EdbSolValue {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        SerializedDynSolValue::schema_name()
+    }
+
+    fn schema_id() -> std::borrow::Cow<'static, str> {
+        SerializedDynSolValue::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        SerializedDynSolValue::json_schema(generator)
+    }
+}
+
 /// Trait for formatting Solidity values into human-readable strings.
 pub trait SolValueFormatter {
     /// Formats a Solidity value into a human-readable string.
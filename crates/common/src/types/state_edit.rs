@@ -0,0 +1,291 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! State edits for what-if re-execution, and the comparison of the derived timeline
+//! against the original one
+
+use std::{fmt, path::PathBuf, str::FromStr};
+
+use alloy_primitives::{Address, Bytes, U256};
+use eyre::{bail, eyre, Error, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::api::remote;
+
+use crate::types::CallResult;
+
+/// A modification of the execution state at a snapshot, after which the rest of the
+/// transaction is re-executed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub enum StateEdit {
+    /// Overwrite a persistent storage slot
+    Storage {
+        /// Address owning the storage
+        #[schemars(with = "remote::Address")]
+        address: Address,
+        /// Storage slot
+        #[schemars(with = "remote::U256")]
+        slot: U256,
+        /// New value of the slot
+        #[schemars(with = "remote::U256")]
+        value: U256,
+    },
+    /// Overwrite a transient storage slot (EIP-1153)
+    TransientStorage {
+        /// Address owning the transient storage
+        #[schemars(with = "remote::Address")]
+        address: Address,
+        /// Transient storage slot
+        #[schemars(with = "remote::U256")]
+        slot: U256,
+        /// New value of the slot
+        #[schemars(with = "remote::U256")]
+        value: U256,
+    },
+    /// Overwrite the balance of an account
+    Balance {
+        /// Address of the account
+        #[schemars(with = "remote::Address")]
+        address: Address,
+        /// New balance in wei
+        #[schemars(with = "remote::U256")]
+        balance: U256,
+    },
+    /// Replace the runtime bytecode of an account
+    Code {
+        /// Address of the account
+        #[schemars(with = "remote::Address")]
+        address: Address,
+        /// New runtime bytecode
+        #[schemars(with = "remote::Bytes")]
+        code: Bytes,
+    },
+    /// Replace the calldata of the current call frame
+    Calldata {
+        /// New calldata
+        #[schemars(with = "remote::Bytes")]
+        data: Bytes,
+    },
+    /// Overwrite a stack item (only at opcode snapshots)
+    Stack {
+        /// Depth of the item, 0 being the top of the stack
+        depth: usize,
+        /// New value of the item
+        #[schemars(with = "remote::U256")]
+        value: U256,
+    },
+    /// Overwrite a memory range, expanding the memory if needed (only at opcode snapshots)
+    Memory {
+        /// Memory offset
+        offset: usize,
+        /// Bytes to write at the offset
+        #[schemars(with = "remote::Bytes")]
+        data: Bytes,
+    },
+    /// Overwrite a local variable of the function executing at a hook snapshot. Value
+    /// types are located on the stack, and `bytes` and `string` in memory, so the edit
+    /// is applied as a stack or memory edit.
+    Local {
+        /// Name of the variable
+        name: String,
+        /// New value, parsed as the type of the variable
+        value: String,
+    },
+}
+
+impl StateEdit {
+    /// Whether the edit modifies the interpreter of the current frame (i.e., its stack or
+    /// memory) rather than the world state.
+    pub fn is_interpreter_edit(&self) -> bool {
+        matches!(self, Self::Stack { .. } | Self::Memory { .. })
+    }
+}
+
+impl fmt::Display for StateEdit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Storage { address, slot, value } => {
+                write!(f, "storage[{address}][{slot:#x}]={value:#x}")
+            }
+            Self::TransientStorage { address, slot, value } => {
+                write!(f, "tstorage[{address}][{slot:#x}]={value:#x}")
+            }
+            Self::Balance { address, balance } => write!(f, "balance[{address}]={balance}"),
+            Self::Code { address, code } => write!(f, "code[{address}]={code}"),
+            Self::Calldata { data } => write!(f, "calldata={data}"),
+            Self::Stack { depth, value } => write!(f, "stack[{depth}]={value:#x}"),
+            Self::Memory { offset, data } => write!(f, "memory[{offset:#x}]={data}"),
+            Self::Local { name, value } => write!(f, "{name}={value}"),
+        }
+    }
+}
+
+impl FromStr for StateEdit {
+    type Err = Error;
+
+    /// Parses a state edit from a string.
+    /// Format:
+    /// - `storage[<addr>][<slot>]=<value>` for persistent storage slots
+    /// - `tstorage[<addr>][<slot>]=<value>` for transient storage slots
+    /// - `balance[<addr>]=<wei>` for balances
+    /// - `code[<addr>]=<hex>` for runtime bytecode
+    /// - `calldata=<hex>` for the calldata of the current frame
+    /// - `stack[<depth>]=<value>` for stack items
+    /// - `memory[<offset>]=<hex>` for memory ranges
+    /// - `<name>=<value>` for local variables
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((target, value)) = s.trim().split_once('=') else {
+            bail!("Invalid state edit format. Expected: <target>=<value>, got: {s}");
+        };
+        let (target, value) = (target.trim(), value.trim());
+
+        let address = |arg: &str| -> Result<Address> {
+            arg.parse::<Address>().map_err(|e| eyre!("Invalid address {arg}: {e}"))
+        };
+        let number = |arg: &str| -> Result<U256> {
+            arg.parse::<U256>().map_err(|e| eyre!("Invalid number {arg}: {e}"))
+        };
+        let index = |arg: &str| -> Result<usize> {
+            usize::try_from(number(arg)?).map_err(|_| eyre!("Number {arg} is too large"))
+        };
+        let bytes = |arg: &str| -> Result<Bytes> {
+            arg.parse::<Bytes>().map_err(|e| eyre!("Invalid hex data {arg}: {e}"))
+        };
+        let slot_of = |rest: &str| -> Result<(Address, U256)> {
+            let Some((addr, slot)) = rest.strip_suffix(']').and_then(|rest| rest.split_once("]["))
+            else {
+                bail!("Invalid slot format. Expected: [<addr>][<slot>], got: {target}");
+            };
+            Ok((address(addr)?, number(slot)?))
+        };
+        let arg_of = |rest: &str| -> Result<String> {
+            let Some(arg) = rest.strip_suffix(']') else {
+                bail!("Invalid state edit target: {target}");
+            };
+            Ok(arg.to_string())
+        };
+
+        if let Some(rest) = target.strip_prefix("storage[") {
+            let (address, slot) = slot_of(rest)?;
+            Ok(Self::Storage { address, slot, value: number(value)? })
+        } else if let Some(rest) = target.strip_prefix("tstorage[") {
+            let (address, slot) = slot_of(rest)?;
+            Ok(Self::TransientStorage { address, slot, value: number(value)? })
+        } else if let Some(rest) = target.strip_prefix("balance[") {
+            Ok(Self::Balance { address: address(&arg_of(rest)?)?, balance: number(value)? })
+        } else if let Some(rest) = target.strip_prefix("code[") {
+            Ok(Self::Code { address: address(&arg_of(rest)?)?, code: bytes(value)? })
+        } else if target == "calldata" {
+            Ok(Self::Calldata { data: bytes(value)? })
+        } else if let Some(rest) = target.strip_prefix("stack[") {
+            Ok(Self::Stack { depth: index(&arg_of(rest)?)?, value: number(value)? })
+        } else if let Some(rest) = target.strip_prefix("memory[") {
+            Ok(Self::Memory { offset: index(&arg_of(rest)?)?, data: bytes(value)? })
+        } else if target.starts_with(|c: char| c.is_alphabetic() || c == '_')
+            && target.chars().all(|c| c.is_alphanumeric() || c == '_')
+        {
+            Ok(Self::Local { name: target.to_string(), value: value.to_string() })
+        } else {
+            bail!(
+                "Invalid state edit target. Expected: storage[<addr>][<slot>], tstorage[<addr>][<slot>], balance[<addr>], code[<addr>], calldata, stack[<depth>], memory[<offset>] or a local variable, got: {target}"
+            )
+        }
+    }
+}
+
+/// Difference of a storage slot between the original and a derived timeline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct StorageChange {
+    /// Address owning the storage
+    #[schemars(with = "remote::Address")]
+    pub address: Address,
+    /// Storage slot
+    #[schemars(with = "remote::U256")]
+    pub slot: U256,
+    /// Value at the end of the original timeline
+    #[schemars(with = "remote::U256")]
+    pub original: U256,
+    /// Value at the end of the derived timeline
+    #[schemars(with = "remote::U256")]
+    pub derived: U256,
+}
+
+/// Comparison of a derived (what-if) timeline against the original one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TimelineDiff {
+    /// Number of snapshots in the original timeline
+    pub original_snapshot_count: usize,
+    /// Number of snapshots in the derived timeline
+    pub derived_snapshot_count: usize,
+    /// First snapshot at which the two timelines execute different steps, `None` if they
+    /// step through exactly the same code
+    pub divergence: Option<usize>,
+    /// Result of the top-level call in the original timeline
+    pub original_result: Option<CallResult>,
+    /// Result of the top-level call in the derived timeline
+    pub derived_result: Option<CallResult>,
+    /// Storage slots whose values differ at the end of the two timelines
+    pub storage_changes: Vec<StorageChange>,
+}
+
+/// Result of a what-if re-execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct WhatIfResult {
+    /// Port of the debug server serving the derived timeline
+    pub port: u16,
+    /// Unix domain socket of the debug server, instead of a port (then 0), when the
+    /// server it was derived from listens on one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub socket: Option<PathBuf>,
+    /// Comparison of the derived timeline against the original one
+    pub diff: TimelineDiff,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn test_state_edit_roundtrip() {
+        let address = address!("1234567890123456789012345678901234567890");
+        let edits = [
+            StateEdit::Storage { address, slot: U256::from(5), value: U256::from(100) },
+            StateEdit::TransientStorage { address, slot: U256::from(1), value: U256::ZERO },
+            StateEdit::Balance { address, balance: U256::from(10).pow(U256::from(18)) },
+            StateEdit::Code { address, code: Bytes::from_static(&[0x60, 0x00]) },
+            StateEdit::Calldata { data: Bytes::from_static(&[0xa9, 0x05, 0x9c, 0xbb]) },
+            StateEdit::Stack { depth: 2, value: U256::from(0xdead) },
+            StateEdit::Memory { offset: 0x40, data: Bytes::from_static(&[0x80]) },
+            StateEdit::Local { name: "amount".to_string(), value: "-1".to_string() },
+        ];
+
+        for edit in edits {
+            assert_eq!(StateEdit::from_str(&edit.to_string()).unwrap(), edit);
+        }
+    }
+
+    #[test]
+    fn test_state_edit_from_str_invalid() {
+        assert!(StateEdit::from_str("storage[0x1234][5]=1").is_err());
+        assert!(StateEdit::from_str("balance[0x1234567890123456789012345678901234567890]").is_err());
+        assert!(StateEdit::from_str("calldata=0xzz").is_err());
+        assert!(StateEdit::from_str("locals[x]=1").is_err());
+        assert!(StateEdit::from_str("s.amount=1").is_err());
+        assert!(StateEdit::from_str("1x=1").is_err());
+    }
+}
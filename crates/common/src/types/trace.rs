@@ -22,17 +22,20 @@ use revm::{
         CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, InstructionResult,
     },
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+
+use crate::api::remote;
 use std::ops::{Deref, DerefMut};
 use tracing::error;
 
 /// Type of call/creation operation
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum CallType {
     /// Regular call to existing contract
-    Call(CallScheme),
+    Call(#[schemars(with = "remote::CallScheme")] CallScheme),
     /// Contract creation via CREATE opcode
-    Create(CreateScheme),
+    Create(#[schemars(with = "remote::CreateScheme")] CreateScheme),
 }
 
 /// Trait for converting inputs to call type representation for trace analysis
@@ -64,27 +67,33 @@ where
 }
 
 /// Result of a call/creation operation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum CallResult {
     /// Call succeeded
     Success {
         /// Output data from the call
+        #[schemars(with = "remote::Bytes")]
         output: Bytes,
         /// Result
+        #[schemars(with = "remote::InstructionResult")]
         result: InstructionResult,
     },
     /// Call reverted
     Revert {
         /// Output data from the call
+        #[schemars(with = "remote::Bytes")]
         output: Bytes,
         /// Result
+        #[schemars(with = "remote::InstructionResult")]
         result: InstructionResult,
     },
     /// Self-destruct
     Error {
         /// Output data from the call
+        #[schemars(with = "remote::Bytes")]
         output: Bytes,
         /// Result
+        #[schemars(with = "remote::InstructionResult")]
         result: InstructionResult,
     },
 }
@@ -189,7 +198,7 @@ where
 }
 
 /// Complete execution trace containing all call/creation entries for transaction analysis and debugging
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Trace {
     /// Internal vector storing all trace entries in chronological order
     inner: Vec<TraceEntry>,
@@ -265,7 +274,7 @@ impl<'a> IntoIterator for &'a mut Trace {
 }
 
 /// Single trace entry representing a call or creation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TraceEntry {
     /// Unique ID of this trace entry (its index in the trace vector)
     pub id: usize,
@@ -276,28 +285,37 @@ pub struct TraceEntry {
     /// Type of operation
     pub call_type: CallType,
     /// Address making the call
+    #[schemars(with = "remote::Address")]
     pub caller: Address,
     /// Target address for calls, or computed address for creates
+    #[schemars(with = "remote::Address")]
     pub target: Address,
     /// Address where the code actually lives (for delegate calls)
+    #[schemars(with = "remote::Address")]
     pub code_address: Address,
     /// Input data / constructor args
+    #[schemars(with = "remote::Bytes")]
     pub input: Bytes,
     /// Value transferred
+    #[schemars(with = "remote::U256")]
     pub value: U256,
     /// Result of the call (populated on call_end)
     pub result: Option<CallResult>,
     /// Whether this created a new contract
     pub created_contract: bool,
     /// Create scheme for contract creation
+    #[schemars(with = "Option<remote::CreateScheme>")]
     pub create_scheme: Option<CreateScheme>,
     /// The underlying running bytecode
+    #[schemars(with = "Option<remote::Bytes>")]
     pub bytecode: Option<Bytes>,
     /// Label of the target contract
     pub target_label: Option<String>,
     /// Self-destruct information
+    #[schemars(with = "Option<(remote::Address, remote::U256)>")]
     pub self_destruct: Option<(Address, U256)>,
     /// Events
+    #[schemars(with = "Vec<remote::LogData>")]
     pub events: Vec<LogData>,
     /// The first snapshot id that belongs to this entry
     pub first_snapshot_id: Option<usize>,
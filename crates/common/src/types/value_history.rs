@@ -0,0 +1,111 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Value history: every change of a value across a part of the timeline
+
+use std::{fmt, path::PathBuf};
+
+use alloy_primitives::Address;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::api::remote;
+
+use crate::types::{EdbSolValue, ExecutionFrameId};
+
+/// The part of the timeline scanned when collecting the history of a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub enum HistoryScope {
+    /// All snapshots of a call, including those after its nested calls return
+    Frame {
+        /// Trace entry of the call
+        trace_entry_id: usize,
+    },
+    /// All snapshots within an inclusive range
+    Range {
+        /// First snapshot of the range
+        start: usize,
+        /// Last snapshot of the range
+        end: usize,
+    },
+}
+
+impl HistoryScope {
+    /// Returns whether a snapshot of the given frame lies within the scope.
+    pub fn contains(&self, snapshot_id: usize, frame_id: ExecutionFrameId) -> bool {
+        match self {
+            Self::Frame { trace_entry_id } => frame_id.trace_entry_id() == *trace_entry_id,
+            Self::Range { start, end } => (*start..=*end).contains(&snapshot_id),
+        }
+    }
+}
+
+impl fmt::Display for HistoryScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Frame { trace_entry_id } => write!(f, "call #{trace_entry_id}"),
+            Self::Range { start, end } => write!(f, "steps {start}..={end}"),
+        }
+    }
+}
+
+/// A point of the timeline at which a value is first observed or changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ValueChange {
+    /// First snapshot at which the value is observed
+    pub snapshot_id: usize,
+    /// Execution frame of the snapshot
+    pub frame_id: ExecutionFrameId,
+    /// Address of the bytecode being executed
+    #[schemars(with = "remote::Address")]
+    pub bytecode_address: Address,
+    /// Source file of the step
+    pub path: Option<PathBuf>,
+    /// Character offset of the step
+    pub offset: Option<usize>,
+    /// Length of the step
+    pub length: Option<usize>,
+    /// 1-based line of the step
+    pub line: Option<usize>,
+    /// The observed value
+    pub value: EdbSolValue,
+}
+
+/// History of a value over a [`HistoryScope`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ValueHistory {
+    /// Number of snapshots at which the value could be observed
+    pub observed: usize,
+    /// The first observation, followed by every change in timeline order
+    pub changes: Vec<ValueChange>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_scope_contains() {
+        let frame = HistoryScope::Frame { trace_entry_id: 2 };
+        assert!(frame.contains(10, ExecutionFrameId::new(2, 1)));
+        assert!(!frame.contains(10, ExecutionFrameId::new(3, 0)));
+
+        let range = HistoryScope::Range { start: 5, end: 8 };
+        assert!(range.contains(5, ExecutionFrameId::new(0, 0)));
+        assert!(range.contains(8, ExecutionFrameId::new(0, 0)));
+        assert!(!range.contains(9, ExecutionFrameId::new(0, 0)));
+    }
+}
@@ -0,0 +1,198 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Types describing where a runtime value came from (backward data-flow tracing)
+
+use std::{fmt, path::PathBuf};
+
+use alloy_primitives::{Address, U256};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::api::remote;
+
+use crate::types::ExecutionFrameId;
+
+/// Location of a value in the EVM state at a given snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub enum ValueLocation {
+    /// A stack item, counted from the top of the stack (0 = top)
+    Stack {
+        /// Depth of the item from the top of the stack
+        depth: usize,
+    },
+    /// A range of memory in the current call frame
+    Memory {
+        /// Start offset of the range
+        offset: usize,
+        /// Size of the range in bytes
+        size: usize,
+    },
+    /// A range of calldata in the current call frame
+    Calldata {
+        /// Start offset of the range
+        offset: usize,
+        /// Size of the range in bytes
+        size: usize,
+    },
+    /// A range of the data returned by the latest call in the current call frame
+    Returndata {
+        /// Start offset of the range
+        offset: usize,
+        /// Size of the range in bytes
+        size: usize,
+    },
+    /// A persistent storage slot
+    Storage {
+        /// Address owning the storage
+        #[schemars(with = "remote::Address")]
+        address: Address,
+        /// Storage slot
+        #[schemars(with = "remote::U256")]
+        slot: U256,
+    },
+    /// A transient storage slot (EIP-1153)
+    TransientStorage {
+        /// Address owning the transient storage
+        #[schemars(with = "remote::Address")]
+        address: Address,
+        /// Transient storage slot
+        #[schemars(with = "remote::U256")]
+        slot: U256,
+    },
+}
+
+impl fmt::Display for ValueLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stack { depth } => write!(f, "stack[{depth}]"),
+            Self::Memory { offset, size } => write_range(f, "memory", *offset, *size),
+            Self::Calldata { offset, size } => write_range(f, "calldata", *offset, *size),
+            Self::Returndata { offset, size } => write_range(f, "returndata", *offset, *size),
+            Self::Storage { address, slot } => write!(f, "storage[{address}][{slot:#x}]"),
+            Self::TransientStorage { address, slot } => {
+                write!(f, "tstorage[{address}][{slot:#x}]")
+            }
+        }
+    }
+}
+
+/// Write a byte range as `name[start..end]`, or `name[start..+size]` if its end overflows
+fn write_range(f: &mut fmt::Formatter<'_>, name: &str, offset: usize, size: usize) -> fmt::Result {
+    match offset.checked_add(size) {
+        Some(end) => write!(f, "{name}[{offset:#x}..{end:#x}]"),
+        None => write!(f, "{name}[{offset:#x}..+{size:#x}]"),
+    }
+}
+
+/// One hop in the provenance chain of a value: an instruction (or source step) that
+/// wrote the traced value to `written`, optionally copying it from `read_from`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ValueOriginStep {
+    /// Snapshot of the instruction or source step that wrote the value
+    pub snapshot_id: usize,
+    /// Execution frame of the snapshot
+    pub frame_id: ExecutionFrameId,
+    /// Address of the bytecode being executed
+    #[schemars(with = "remote::Address")]
+    pub bytecode_address: Address,
+    /// Program counter, for opcode snapshots
+    pub pc: Option<usize>,
+    /// Opcode byte, for opcode snapshots
+    pub opcode: Option<u8>,
+    /// Source file, for hook (source-level) snapshots
+    pub path: Option<PathBuf>,
+    /// Character offset of the source step, for hook snapshots
+    pub offset: Option<usize>,
+    /// Length of the source step, for hook snapshots
+    pub length: Option<usize>,
+    /// 1-based line of the source step, for hook snapshots
+    pub line: Option<usize>,
+    /// Location the value was written to
+    pub written: ValueLocation,
+    /// Location the value was copied from, None if the step produced it
+    pub read_from: Option<ValueLocation>,
+}
+
+/// What ultimately produced a traced value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum ValueOriginKind {
+    /// A constant embedded in the bytecode (PUSH, CODECOPY, ...)
+    Constant,
+    /// The result of a computation over other stack values (ADD, KECCAK256, ...)
+    Computation,
+    /// A value read from the execution environment (CALLER, TIMESTAMP, BALANCE, ...)
+    Environment,
+    /// The status or address returned by a call/create, or returndata of a callee without code
+    CallOutcome,
+    /// Part of the top-level transaction input
+    TransactionInput,
+    /// The value was already there: untouched memory or storage that predates the transaction
+    Initial,
+    /// The value was written by an instrumented source-level step, whose internals are not traced
+    SourceStep,
+    /// Tracing stopped before reaching an origin
+    Unresolved(String),
+}
+
+/// Result of tracing a value backwards through the snapshot timeline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ValueOrigin {
+    /// Snapshot the trace started at
+    pub snapshot_id: usize,
+    /// Location the trace started at
+    pub location: ValueLocation,
+    /// Hops from the queried value back to its origin, most recent first
+    pub steps: Vec<ValueOriginStep>,
+    /// Kind of the origin, reached at the last step
+    pub kind: ValueOriginKind,
+}
+
+impl ValueOrigin {
+    /// Get the step that produced the value, if any
+    pub fn origin_step(&self) -> Option<&ValueOriginStep> {
+        self.steps.last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_location_display() {
+        assert_eq!(ValueLocation::Stack { depth: 2 }.to_string(), "stack[2]");
+        assert_eq!(
+            ValueLocation::Memory { offset: 0x80, size: 32 }.to_string(),
+            "memory[0x80..0xa0]"
+        );
+        assert_eq!(
+            ValueLocation::Calldata { offset: 4, size: 32 }.to_string(),
+            "calldata[0x4..0x24]"
+        );
+        assert_eq!(
+            ValueLocation::Memory { offset: usize::MAX, size: 32 }.to_string(),
+            format!("memory[{:#x}..+0x20]", usize::MAX)
+        );
+    }
+
+    #[test]
+    fn test_value_location_serde_roundtrip() {
+        let loc = ValueLocation::Storage { address: Address::ZERO, slot: U256::from(5) };
+        let json = serde_json::to_value(loc).unwrap();
+        assert_eq!(serde_json::from_value::<ValueLocation>(json).unwrap(), loc);
+    }
+}
@@ -0,0 +1,171 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Watchpoints: stop when a piece of contract state changes
+
+use std::{fmt, str::FromStr};
+
+use alloy_primitives::{Address, U256};
+use eyre::{bail, eyre, Error, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::api::remote;
+
+use crate::types::EdbSolValue;
+
+/// A piece of contract state whose changes are watched across the timeline.
+/// Unlike a conditional [`crate::types::Breakpoint`], a watchpoint is resolved by
+/// scanning recorded state rather than evaluating an expression at every step.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub enum Watchpoint {
+    /// A persistent storage slot
+    Storage {
+        /// Address owning the storage
+        #[schemars(with = "remote::Address")]
+        address: Address,
+        /// Storage slot
+        #[schemars(with = "remote::U256")]
+        slot: U256,
+    },
+    /// A transient storage slot (EIP-1153)
+    TransientStorage {
+        /// Address owning the transient storage
+        #[schemars(with = "remote::Address")]
+        address: Address,
+        /// Transient storage slot
+        #[schemars(with = "remote::U256")]
+        slot: U256,
+    },
+    /// A state variable without parameters (i.e., not a mapping or an array)
+    StateVariable {
+        /// Address owning the state variable
+        #[schemars(with = "remote::Address")]
+        address: Address,
+        /// Name of the state variable
+        name: String,
+    },
+}
+
+impl Watchpoint {
+    /// Returns the address whose state is watched.
+    pub fn address(&self) -> Address {
+        match self {
+            Self::Storage { address, .. }
+            | Self::TransientStorage { address, .. }
+            | Self::StateVariable { address, .. } => *address,
+        }
+    }
+}
+
+impl fmt::Display for Watchpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Storage { address, slot } => write!(f, "storage[{address}][{slot:#x}]"),
+            Self::TransientStorage { address, slot } => {
+                write!(f, "tstorage[{address}][{slot:#x}]")
+            }
+            Self::StateVariable { address, name } => write!(f, "{address}.{name}"),
+        }
+    }
+}
+
+impl FromStr for Watchpoint {
+    type Err = Error;
+
+    /// Parses a watchpoint from a string.
+    /// Format:
+    /// - `storage[<addr>][<slot>]` for persistent storage slots
+    /// - `tstorage[<addr>][<slot>]` for transient storage slots
+    /// - `<addr>.<name>` for state variables
+    fn from_str(s: &str) -> Result<Self> {
+        let trimmed = s.trim();
+
+        let slot_of = |rest: &str| -> Result<(Address, U256)> {
+            let Some((addr, slot)) = rest.strip_suffix(']').and_then(|rest| rest.split_once("]["))
+            else {
+                bail!("Invalid slot watchpoint format. Expected: [<addr>][<slot>], got: {s}");
+            };
+            let address = addr.parse::<Address>().map_err(|e| eyre!("Invalid address: {e}"))?;
+            let slot = slot.parse::<U256>().map_err(|e| eyre!("Invalid slot: {e}"))?;
+            Ok((address, slot))
+        };
+
+        if let Some(rest) = trimmed.strip_prefix("storage[") {
+            let (address, slot) = slot_of(rest)?;
+            Ok(Self::Storage { address, slot })
+        } else if let Some(rest) = trimmed.strip_prefix("tstorage[") {
+            let (address, slot) = slot_of(rest)?;
+            Ok(Self::TransientStorage { address, slot })
+        } else if let Some((addr, name)) = trimmed.split_once('.') {
+            let address = addr.parse::<Address>().map_err(|e| eyre!("Invalid address: {e}"))?;
+            if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                bail!("Invalid state variable name: {name}");
+            }
+            Ok(Self::StateVariable { address, name: name.to_string() })
+        } else {
+            bail!(
+                "Invalid watchpoint format. Expected: storage[<addr>][<slot>], tstorage[<addr>][<slot>] or <addr>.<name>, got: {s}"
+            )
+        }
+    }
+}
+
+/// A change of the watched state found in the timeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WatchpointHit {
+    /// First snapshot at which the new value is observed
+    pub snapshot_id: usize,
+    /// Last snapshot at which the old value is observed. For storage slots this is the
+    /// step that performed the write (e.g., the SSTORE); for state variables it is the
+    /// previous source step of the same contract.
+    pub prev_snapshot_id: usize,
+    /// Value before the change
+    pub old_value: EdbSolValue,
+    /// Value after the change
+    pub new_value: EdbSolValue,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn test_watchpoint_roundtrip() {
+        let address = address!("1234567890123456789012345678901234567890");
+        let watchpoints = [
+            Watchpoint::Storage { address, slot: U256::from(5) },
+            Watchpoint::TransientStorage { address, slot: U256::from(0x20) },
+            Watchpoint::StateVariable { address, name: "totalSupply".to_string() },
+        ];
+
+        for wp in watchpoints {
+            assert_eq!(Watchpoint::from_str(&wp.to_string()).unwrap(), wp);
+        }
+    }
+
+    #[test]
+    fn test_watchpoint_from_str_invalid() {
+        assert!(Watchpoint::from_str("storage[0x1234][5]").is_err());
+        assert!(Watchpoint::from_str(
+            "storage[0x1234567890123456789012345678901234567890][not_a_slot]"
+        )
+        .is_err());
+        assert!(Watchpoint::from_str("0x1234567890123456789012345678901234567890.").is_err());
+        assert!(Watchpoint::from_str("totalSupply").is_err());
+    }
+}
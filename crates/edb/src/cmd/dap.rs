@@ -0,0 +1,46 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! DAP command - serve_dap function
+
+use alloy_primitives::TxHash;
+use edb_engine::dap::{serve_dap_stdio, serve_dap_tcp};
+use eyre::{eyre, Result};
+use serde_json::Value;
+
+use crate::cmd::replay::replay_transaction;
+
+/// Serve a Debug Adapter Protocol client over stdio, or over TCP if a port is given.
+///
+/// The transaction to debug is given by the `launch` request, e.g.
+/// `{ "request": "launch", "txHash": "0x..." }`.
+pub async fn serve_dap(port: Option<u16>, cli: &crate::Cli, rpc_url: &str) -> Result<()> {
+    let launch = |args: Value| async move {
+        let tx_hash: TxHash = args
+            .get("txHash")
+            .and_then(Value::as_str)
+            .ok_or_else(|| eyre!("Missing launch argument txHash"))?
+            .parse()?;
+
+        tracing::info!("Replaying transaction for DAP client: {}", tx_hash);
+        replay_transaction(tx_hash, cli, rpc_url).await
+    };
+
+    match port {
+        Some(port) => serve_dap_tcp(port, launch).await,
+        None => serve_dap_stdio(launch).await,
+    }
+}
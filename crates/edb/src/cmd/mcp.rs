@@ -0,0 +1,34 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! MCP command - serve_mcp function
+
+use alloy_primitives::TxHash;
+use edb_engine::mcp::serve_mcp_stdio;
+use eyre::Result;
+
+use crate::cmd::replay::replay_transaction;
+
+/// Serve a Model Context Protocol client over stdio.
+///
+/// Every transaction loaded by the client is replayed and gets its own debug server.
+pub async fn serve_mcp(cli: &crate::Cli, rpc_url: &str) -> Result<()> {
+    serve_mcp_stdio(|tx_hash: TxHash| async move {
+        tracing::info!("Replaying transaction for MCP client: {}", tx_hash);
+        replay_transaction(tx_hash, cli, rpc_url).await
+    })
+    .await
+}
@@ -16,10 +16,14 @@
 
 //! Command modules for the EDB CLI
 
+pub mod dap;
 pub mod debug;
+pub mod mcp;
 pub mod proxy_status;
 pub mod replay;
 
+pub use dap::serve_dap;
 pub use debug::debug_foundry_test;
+pub use mcp::serve_mcp;
 pub use proxy_status::show_proxy_status;
 pub use replay::replay_transaction;
@@ -39,8 +39,13 @@ pub async fn replay_transaction(
     );
 
     // Step 2: Build inputs for the engine
-    let mut engine_config =
-        EngineConfig::default().with_quick_mode(cli.quick).with_rpc_proxy_url(rpc_url.into());
+    // DAP and MCP clients read their protocol messages from stdout
+    let quiet = matches!(cli.command, crate::Commands::Dap { .. } | crate::Commands::Mcp);
+    let mut engine_config = EngineConfig::default()
+        .with_quick_mode(cli.quick)
+        .with_quiet(quiet)
+        .with_rpc_proxy_url(rpc_url.into())
+        .with_rpc_server(cli.rpc_server_config()?);
     if let Some(api_key) = &cli.etherscan_api_key {
         engine_config = engine_config.with_etherscan_api_key(api_key.clone());
     }
@@ -18,10 +18,12 @@
 //!
 //! A step-by-step debugger for Ethereum transactions.
 
-use std::env;
+use std::{env, path::PathBuf};
 
 use alloy_primitives::TxHash;
 use clap::{Args, Parser, Subcommand, ValueEnum};
+use edb_common::types::RPC_TOKEN_ENV;
+use edb_engine::rpc::{generate_auth_token, utils::parse_bind_addr, RpcServerConfig};
 use eyre::Result;
 
 mod cmd;
@@ -66,6 +68,28 @@ pub struct Cli {
     #[arg(long, env = "EDB_CACHE_DIR")]
     pub cache_dir: Option<String>,
 
+    /// Address the debug RPC server binds to, optionally with a port (default: 127.0.0.1).
+    /// Addresses other than loopback ones require a token (--rpc-token or --rpc-auth).
+    ///
+    /// Example: --rpc-bind 0.0.0.0:3000 --rpc-auth
+    #[arg(long, value_name = "ADDR")]
+    pub rpc_bind: Option<String>,
+
+    /// Serve the debug RPC API on this Unix domain socket instead of TCP
+    #[arg(long, value_name = "PATH", conflicts_with = "rpc_bind")]
+    pub rpc_socket: Option<PathBuf>,
+
+    /// Token the debug RPC server requires from clients, as a bearer token.
+    ///
+    /// Prefer the environment variable, since command lines are visible to other users.
+    #[arg(long, env = "EDB_RPC_TOKEN", hide_env_values = true)]
+    pub rpc_token: Option<String>,
+
+    /// Require a random per-session token from the clients of the debug RPC server
+    /// (ignored when --rpc-token is given)
+    #[arg(long)]
+    pub rpc_auth: bool,
+
     /// TUI-specific options
     #[command(flatten)]
     pub tui_options: TuiOptions,
@@ -84,6 +108,32 @@ impl Cli {
             eprintln!("Warning: --disable-mouse flag has no effect when not using TUI mode");
         }
     }
+
+    /// Configuration of the debug RPC server
+    pub fn rpc_server_config(&self) -> Result<RpcServerConfig> {
+        let mut config = RpcServerConfig::default();
+        if let Some(bind) = &self.rpc_bind {
+            let (host, port) = parse_bind_addr(bind)?;
+            config = config.with_host(host);
+            if let Some(port) = port {
+                config = config.with_port(port);
+            }
+        }
+        if let Some(path) = &self.rpc_socket {
+            config = config.with_unix_socket(path.clone());
+        }
+        if let Some(token) = &self.rpc_token {
+            config = config.with_auth_token(token.clone());
+        }
+        if config.unix_socket.is_none() && !config.host.is_loopback() && config.auth_token.is_none()
+        {
+            eyre::bail!(
+                "The debug RPC server would be reachable from other hosts at {} without a token, use --rpc-auth or --rpc-token",
+                config.host
+            );
+        }
+        Ok(config)
+    }
 }
 
 /// TUI-specific options
@@ -100,6 +150,8 @@ pub struct TuiOptions {
 pub enum UiMode {
     /// Terminal User Interface
     Tui,
+    /// No user interface, only serve the debug RPC API until Ctrl+C
+    None,
 }
 
 /// Available commands
@@ -120,6 +172,18 @@ pub enum Commands {
     },
     /// Show RPC proxy provider status
     ProxyStatus,
+    /// Serve a Debug Adapter Protocol client (e.g., VS Code or Neovim) instead of the TUI
+    ///
+    /// The transaction to debug is given by the `txHash` argument of the launch request.
+    Dap {
+        /// Listen for the client on this port instead of using stdio
+        #[arg(long)]
+        port: Option<u16>,
+    },
+    /// Serve a Model Context Protocol client (e.g., an AI agent) over stdio instead of the TUI
+    ///
+    /// The agent picks the transactions to debug with the `load_transaction` tool.
+    Mcp,
 }
 
 #[tokio::main]
@@ -127,14 +191,33 @@ async fn main() -> Result<()> {
     // Load environment variables
     dotenv::dotenv().ok();
 
-    // Initialize logging
-    edb_common::logging::init_logging("edb", true)?;
-
     // Parse CLI arguments
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+
+    // Initialize logging, keeping stdout clean for protocol messages in DAP and MCP modes
+    if matches!(cli.command, Commands::Dap { .. }) {
+        edb_common::logging::init_file_only_logging("edb-dap")?;
+    } else if matches!(cli.command, Commands::Mcp) {
+        edb_common::logging::init_file_only_logging("edb-mcp")?;
+    } else {
+        edb_common::logging::init_logging("edb", true)?;
+    }
 
     // Validate CLI arguments
     cli.validate();
+    if cli.rpc_socket.is_some()
+        && matches!(cli.ui, UiMode::Tui)
+        && matches!(cli.command, Commands::Replay { .. } | Commands::Test { .. })
+    {
+        eyre::bail!("The terminal UI connects over TCP, use --ui none with --rpc-socket");
+    }
+
+    // Pick the per-session token once, so the engine and the TUI share it
+    if cli.rpc_auth && cli.rpc_token.is_none() {
+        cli.rpc_token = Some(generate_auth_token());
+    }
+    // Check the debug RPC server options before preparing anything
+    cli.rpc_server_config()?;
 
     if let Some(cache_dir) = &cli.cache_dir {
         tracing::info!("Using cache directory: {cache_dir}");
@@ -155,6 +238,16 @@ async fn main() -> Result<()> {
         return cmd::show_proxy_status(&cli).await;
     }
 
+    // The DAP client drives the session, including which transaction to debug
+    if let Commands::Dap { port } = &cli.command {
+        return cmd::serve_dap(*port, &cli, &effective_rpc_url).await;
+    }
+
+    // Likewise, the MCP client loads the transactions to debug
+    if let Commands::Mcp = &cli.command {
+        return cmd::serve_mcp(&cli, &effective_rpc_url).await;
+    }
+
     // Execute the command to get RPC server handle
     let rpc_server_handle = match &cli.command {
         Commands::Replay { tx_hash } => {
@@ -166,42 +259,64 @@ async fn main() -> Result<()> {
             tracing::info!("Debugging test: {}", test_name);
             cmd::debug_foundry_test(test_name, *block, &cli, &effective_rpc_url).await?
         }
-        Commands::ProxyStatus => unreachable!(), // Handled above
+        Commands::ProxyStatus | Commands::Dap { .. } | Commands::Mcp => unreachable!(), // Handled above
     };
 
-    println!("Engine preparation complete. RPC server is running on {}", rpc_server_handle.addr);
-
-    // Launch Terminal UI
-    tracing::info!("Launching Terminal UI...");
-
-    // Find the edb-tui binary
-    let tui_binary = utils::find_tui_binary()?;
-    tracing::debug!("Found TUI binary at: {:?}", tui_binary);
-
-    // Spawn TUI as a child process with inherited stdio
-    let mut cmd = std::process::Command::new(&tui_binary);
-    cmd.arg("--url").arg(format!("http://{}", rpc_server_handle.addr));
-
-    // Only pass --mouse flag if requested and using TUI mode
-    if matches!(cli.ui, UiMode::Tui) && !cli.tui_options.disable_mouse {
-        cmd.arg("--mouse");
-    }
-
-    let mut child = cmd
-        .stdin(std::process::Stdio::inherit())
-        .stdout(std::process::Stdio::inherit())
-        .stderr(std::process::Stdio::inherit())
-        .spawn()
-        .map_err(|e| eyre::eyre!("Failed to spawn TUI: {}", e))?;
-
-    // Wait for TUI to exit
-    let status = child.wait()?;
-    tracing::info!("TUI exited with status: {:?}", status);
-
-    // Return a dummy handle since we're waiting synchronously
-    let ui_handle = tokio::spawn(async {});
-
-    tracing::info!("Both RPC server and UI are running. Press Ctrl+C to exit.");
+    println!(
+        "Engine preparation complete. RPC server is running on {}",
+        rpc_server_handle.endpoint
+    );
+
+    let ui_handle = match (cli.ui, rpc_server_handle.addr()) {
+        (UiMode::Tui, Some(addr)) => {
+            // Launch Terminal UI
+            tracing::info!("Launching Terminal UI...");
+
+            // Find the edb-tui binary
+            let tui_binary = utils::find_tui_binary()?;
+            tracing::debug!("Found TUI binary at: {:?}", tui_binary);
+
+            // Spawn TUI as a child process with inherited stdio
+            let mut cmd = std::process::Command::new(&tui_binary);
+            cmd.arg("--url").arg(format!("http://{addr}"));
+
+            // Hand the token over in the environment, which other users cannot read
+            if let Some(token) = &cli.rpc_token {
+                cmd.env(RPC_TOKEN_ENV, token);
+            }
+
+            // Only pass --mouse flag if requested and using TUI mode
+            if !cli.tui_options.disable_mouse {
+                cmd.arg("--mouse");
+            }
+
+            let mut child = cmd
+                .stdin(std::process::Stdio::inherit())
+                .stdout(std::process::Stdio::inherit())
+                .stderr(std::process::Stdio::inherit())
+                .spawn()
+                .map_err(|e| eyre::eyre!("Failed to spawn TUI: {}", e))?;
+            tracing::info!("Both RPC server and UI are running");
+
+            // Wait for TUI to exit
+            let status = child.wait()?;
+            tracing::info!("TUI exited with status: {:?}", status);
+
+            // Return a dummy handle since we're waiting synchronously
+            tokio::spawn(async {})
+        }
+        _ => {
+            tracing::info!("RPC server is running without a UI");
+            if cli.rpc_auth {
+                println!(
+                    "Clients must send the header: Authorization: Bearer {}",
+                    cli.rpc_token.as_deref().unwrap_or_default()
+                );
+            }
+            println!("Press Ctrl+C to exit.");
+            tokio::spawn(std::future::pending::<()>())
+        }
+    };
 
     // Wait for either:
     // 1. Ctrl+C signal
@@ -614,7 +614,7 @@ impl Analyzer {
         let scope = self.current_scope();
         let function = self.current_function.clone();
         let contract = self.current_contract.clone();
-        let uvid = UVID::next();
+        let uvid = UVID::from_ast_node(self.source_id, declaration.id);
         let state_variable = declaration.state_variable;
         let variable: VariableRef = Variable::Plain {
             uvid,
@@ -727,7 +727,7 @@ impl Analyzer {
 impl Analyzer {
     fn enter_new_contract(&mut self, contract: &ContractDefinition) -> eyre::Result<VisitorAction> {
         assert!(self.current_contract.is_none(), "Contract cannot be nested");
-        let new_contract: ContractRef = Contract::new(contract.clone()).into();
+        let new_contract: ContractRef = Contract::new(self.source_id, contract.clone()).into();
         self.current_contract = Some(new_contract);
         Ok(VisitorAction::Continue)
     }
@@ -749,7 +749,8 @@ impl Analyzer {
     fn enter_new_function(&mut self, function: &FunctionDefinition) -> eyre::Result<VisitorAction> {
         assert!(self.current_function.is_none(), "Function cannot be nested");
         let new_func: FunctionRef =
-            Function::new_function(self.current_contract.clone(), function.clone()).into();
+            Function::new_function(self.source_id, self.current_contract.clone(), function.clone())
+                .into();
         self.check_function_visibility_and_mutability(&new_func)?;
         self.current_function = Some(new_func.clone());
         Ok(VisitorAction::Continue)
@@ -767,7 +768,8 @@ impl Analyzer {
         let current_contract =
             self.current_contract.as_ref().expect("current contract should be set");
         let new_func: FunctionRef =
-            Function::new_modifier(current_contract.clone(), modifier.clone()).into();
+            Function::new_modifier(self.source_id, current_contract.clone(), modifier.clone())
+                .into();
         self.current_function = Some(new_func);
         Ok(VisitorAction::Continue)
     }
@@ -803,9 +805,10 @@ impl Analyzer {
         let current_scope = self.current_scope();
 
         macro_rules! step {
-            ($variant:ident, $stmt:expr, $loc:expr) => {{
+            ($variant:ident, $stmt:expr, $loc:expr, $id:expr) => {{
                 let variables_in_scope = current_scope.read().variables_recursive();
                 let new_step: StepRef = Step::new(
+                    USID::from_ast_node(self.source_id, $id),
                     current_function.ufid(),
                     StepVariant::$variant($stmt),
                     $loc,
@@ -820,7 +823,7 @@ impl Analyzer {
         }
         macro_rules! simple_stmt_to_step {
             ($stmt:expr) => {
-                step!(Statement, statement.clone(), $stmt.src)
+                step!(Statement, statement.clone(), $stmt.src, $stmt.id)
             };
         }
         match statement {
@@ -830,7 +833,7 @@ impl Analyzer {
             Statement::DoWhileStatement(do_while_statement) => {
                 // the step is the `while(...)`
                 let loc = sloc_rdiff(do_while_statement.src, do_while_statement.body.src);
-                step!(DoWhileLoop, *do_while_statement.clone(), loc);
+                step!(DoWhileLoop, *do_while_statement.clone(), loc, do_while_statement.id);
 
                 // we take over the walk of the sub ast tree in the do-while statement step.
                 let mut single_step_walker = AnalyzerSingleStepWalker { analyzer: self };
@@ -848,7 +851,7 @@ impl Analyzer {
             Statement::ForStatement(for_statement) => {
                 // the step is the `for(...)`
                 let loc = sloc_ldiff(for_statement.src, block_or_stmt_src(&for_statement.body));
-                step!(ForLoop, *for_statement.clone(), loc);
+                step!(ForLoop, *for_statement.clone(), loc, for_statement.id);
 
                 // we take over the walk of the sub ast tree in the for statement step.
                 let mut single_step_walker = AnalyzerSingleStepWalker { analyzer: self };
@@ -872,7 +875,7 @@ impl Analyzer {
             Statement::IfStatement(if_statement) => {
                 // the step is the `if(...)`
                 let loc = sloc_ldiff(if_statement.src, block_or_stmt_src(&if_statement.true_body));
-                step!(IfCondition, *if_statement.clone(), loc);
+                step!(IfCondition, *if_statement.clone(), loc, if_statement.id);
 
                 // we take over the walk of the sub ast tree in the if statement step.
                 let mut single_step_walker = AnalyzerSingleStepWalker { analyzer: self };
@@ -896,7 +899,7 @@ impl Analyzer {
                 // the step is the `try`
                 let first_clause = &try_statement.clauses[0];
                 let loc = sloc_ldiff(try_statement.src, first_clause.block.src);
-                step!(Try, *try_statement.clone(), loc);
+                step!(Try, *try_statement.clone(), loc, try_statement.id);
 
                 // we take over the walk of the sub ast tree in the try statement step.
                 let mut single_step_walker = AnalyzerSingleStepWalker { analyzer: self };
@@ -918,7 +921,7 @@ impl Analyzer {
             Statement::WhileStatement(while_statement) => {
                 // the step is the `while(...)`
                 let loc = sloc_ldiff(while_statement.src, block_or_stmt_src(&while_statement.body));
-                step!(WhileLoop, *while_statement.clone(), loc);
+                step!(WhileLoop, *while_statement.clone(), loc, while_statement.id);
 
                 // we take over the walk of the sub ast tree in the while statement step.
                 let mut single_step_walker = AnalyzerSingleStepWalker { analyzer: self };
@@ -952,6 +955,7 @@ impl Analyzer {
         let accessible_variables = current_scope.read().variables_recursive();
         let loc = sloc_ldiff(function.src, function.body.as_ref().unwrap().src);
         let new_step: StepRef = Step::new(
+            USID::from_ast_node(self.source_id, function.id),
             current_function.ufid(),
             StepVariant::FunctionEntry(function.clone()),
             loc,
@@ -995,6 +999,7 @@ impl Analyzer {
         let accessible_variables = current_scope.read().variables_recursive();
         let loc = sloc_ldiff(modifier.src, modifier.body.as_ref().unwrap().src);
         let new_step: StepRef = Step::new(
+            USID::from_ast_node(self.source_id, modifier.id),
             current_function.ufid(),
             StepVariant::ModifierEntry(modifier.clone()),
             loc,
@@ -2031,4 +2036,35 @@ contract TestContract {
         let variable = variables.first().unwrap();
         assert_eq!(variable.type_descriptions.type_string.as_ref().unwrap(), "address[]");
     }
+
+    #[test]
+    fn test_deterministic_ids() {
+        let source = r#"
+contract TestContract {
+    uint256 total;
+
+    function add(uint256 amount) public returns (uint256 sum) {
+        sum = total + amount;
+        total = sum;
+    }
+}
+"#;
+
+        // Two analyses of the same source agree on every identifier
+        let (_sources, first) = compile_and_analyze(source);
+        let (_sources, second) = compile_and_analyze(source);
+
+        let mut first_usids: Vec<USID> = first.step_table().into_keys().collect();
+        let mut second_usids: Vec<USID> = second.step_table().into_keys().collect();
+        first_usids.sort();
+        second_usids.sort();
+        assert_eq!(first_usids, second_usids);
+
+        let mut first_uvids: Vec<UVID> = first.variable_table().into_keys().collect();
+        let mut second_uvids: Vec<UVID> = second.variable_table().into_keys().collect();
+        first_uvids.sort();
+        second_uvids.sort();
+        assert_eq!(first_uvids, second_uvids);
+        assert_eq!(first_uvids.len(), 3);
+    }
 }
@@ -106,8 +106,8 @@ pub struct Contract {
 }
 
 impl Contract {
-    /// Creates a new Contract with the given definition.
-    pub fn new(definition: ContractDefinition) -> Self {
-        Self { ucid: UCID::next(), definition }
+    /// Creates a new Contract with the given definition from the given source.
+    pub fn new(source_id: u32, definition: ContractDefinition) -> Self {
+        Self { ucid: UCID::from_ast_node(source_id, definition.id), definition }
     }
 }
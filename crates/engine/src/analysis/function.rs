@@ -249,20 +249,28 @@ impl FunctionVariant {
 }
 
 impl Function {
-    /// Creates a new Function with the given contract and definition.
-    pub fn new_function(contract: Option<ContractRef>, definition: FunctionDefinition) -> Self {
+    /// Creates a new Function with the given contract and definition from the given source.
+    pub fn new_function(
+        source_id: u32,
+        contract: Option<ContractRef>,
+        definition: FunctionDefinition,
+    ) -> Self {
         Self {
-            ufid: UFID::next(),
+            ufid: UFID::from_ast_node(source_id, definition.id),
             contract,
             definition: FunctionVariant::Function(definition),
             steps: vec![],
         }
     }
 
-    /// Creates a new Function with the given contract and definition.
-    pub fn new_modifier(contract: ContractRef, definition: ModifierDefinition) -> Self {
+    /// Creates a new Function with the given contract and definition from the given source.
+    pub fn new_modifier(
+        source_id: u32,
+        contract: ContractRef,
+        definition: ModifierDefinition,
+    ) -> Self {
         Self {
-            ufid: UFID::next(),
+            ufid: UFID::from_ast_node(source_id, definition.id),
             contract: Some(contract),
             definition: FunctionVariant::Modifier(definition),
             steps: vec![],
@@ -54,23 +54,16 @@ mod macros {
             #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, PartialOrd, Ord, Serialize, Deserialize)]
             pub struct $name(u64);
 
-            paste::paste! {
-                lazy_static::lazy_static! {
-                    /// The global counter for the $name.
-                    #[doc = "The global counter for the " $name " object."]
-                    pub static ref [<NEXT_ $name>]: std::sync::Mutex<$name> = std::sync::Mutex::new($name($initial_value));
-                }
-            }
-
-            paste::paste! {
-                impl $name {
-                    /// Get the next value and increment the global counter.
-                    pub fn next() -> Self {
-                        let mut counter = [<NEXT_ $name>].lock().unwrap();
-                        let value = *counter;
-                        counter.0 += 1;
-                        value
-                    }
+            impl $name {
+                /// Derive the identifier of an AST node of a source file.
+                ///
+                /// Identifiers only depend on the node, so the same node of the same
+                /// compilation gets the same identifier in every analysis and session.
+                /// AST node IDs are unique within a compilation, and so are identifiers.
+                pub fn from_ast_node(source_id: u32, node_id: usize) -> Self {
+                    let offset = (u64::from(source_id) << 32) | (node_id as u64 & 0xffff_ffff);
+                    let initial_value: u64 = $initial_value;
+                    Self(initial_value.wrapping_add(offset))
                 }
             }
 
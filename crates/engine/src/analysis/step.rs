@@ -243,20 +243,22 @@ impl Step {
     ///
     /// # Arguments
     ///
+    /// * `usid` - The identifier of the step, see [`USID::from_ast_node`]
+    /// * `ufid` - The identifier of the function containing the step
     /// * `variant` - The type of step (statement, expression, etc.)
     /// * `src` - Source location information
     ///
     /// # Returns
     ///
-    /// A new Step instance with a unique USID and default hooks.
+    /// A new Step instance with default hooks.
     pub fn new(
+        usid: USID,
         ufid: UFID,
         variant: StepVariant,
         src: SourceLocation,
         scope: VariableScopeRef,
         accessible_variables: Vec<VariableRef>,
     ) -> Self {
-        let usid = USID::next();
         Self {
             usid,
             ufid,
@@ -359,6 +361,14 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_usid_from_ast_node() {
+        assert_eq!(USID::from_ast_node(1, 42), USID::from_ast_node(1, 42));
+        assert_ne!(USID::from_ast_node(1, 42), USID::from_ast_node(1, 43));
+        // The same node ID in another source is another node
+        assert_ne!(USID::from_ast_node(1, 42), USID::from_ast_node(2, 42));
+    }
+
     #[test]
     fn test_sloc_ldiff() {
         let a = sloc!(0, 10, 0);
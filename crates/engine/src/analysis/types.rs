@@ -123,7 +123,7 @@ pub struct UserDefinedType {
 impl UserDefinedType {
     /// Creates a new UserDefinedType from a UserDefinedValueTypeDefinition.
     pub fn new(source_id: u32, variant: UserDefinedTypeVariant) -> Self {
-        Self { utid: UTID::next(), variant, source_id }
+        Self { utid: UTID::from_ast_node(source_id, variant.ast_id()), variant, source_id }
     }
 }
 
@@ -64,11 +64,13 @@ universal_id! {
     /// # Examples
     ///
     /// ```rust
-    /// use edb::analysis::variable::{UVID, UVID::next};
+    /// use edb_engine::analysis::UVID;
     ///
-    /// let uvid1 = UVID::next();
-    /// let uvid2 = UVID::next();
+    /// // UVIDs are derived from the source ID and the AST node ID of the declaration
+    /// let uvid1 = UVID::from_ast_node(0, 12);
+    /// let uvid2 = UVID::from_ast_node(0, 13);
     /// assert_ne!(uvid1, uvid2);
+    /// assert_eq!(uvid1, UVID::from_ast_node(0, 12));
     /// ```
     UVID => EDB_RUNTIME_VALUE_OFFSET
 }
@@ -50,18 +50,21 @@
 //! The [`EngineContext`] is designed to be thread-safe and can be shared across
 //! multiple debugging clients through Arc wrapping. All database operations
 //! use read-only snapshots to ensure debugging session integrity.
+//! The only mutable state is the session [`StateOverlay`], which derived executions
+//! apply on top of the snapshot state.
 
 use std::{
     collections::{HashMap, HashSet},
-    sync::Arc,
+    path::PathBuf,
+    sync::{Arc, RwLock},
 };
 
 use alloy_dyn_abi::{DynSolValue, FunctionExt, JsonAbiExt};
 use alloy_json_abi::Function;
-use alloy_primitives::{Address, Bytes, TxHash, U256};
+use alloy_primitives::{Address, Bytes, TxHash};
 use edb_common::{
     disable_nonce_check, relax_evm_context_constraints, relax_evm_tx_constraints,
-    types::{parse_callable_abi_entries, Trace},
+    types::{decode_revert_reason, parse_callable_abi_entries, CallOptions, EvalCall, Trace},
     DerivedContext, ForkInfo,
 };
 use eyre::{eyre, Result};
@@ -76,7 +79,21 @@ use revm::{
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error};
 
-use crate::{analysis::AnalysisResult, Artifact, SnapshotDetail, Snapshots};
+use crate::{
+    analysis::{AnalysisResult, StepRef},
+    Artifact, EngineConfig, SnapshotDetail, Snapshots, StateOverlay, USID,
+};
+
+/// Location of a source-level step in its source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepSource {
+    /// Path of the source file
+    pub path: PathBuf,
+    /// Byte offset of the step in the source file
+    pub offset: Option<usize>,
+    /// Byte length of the step
+    pub length: Option<usize>,
+}
 
 /// Complete debugging context containing all analysis results and state snapshots
 ///
@@ -111,9 +128,22 @@ where
     pub analysis_results: HashMap<Address, AnalysisResult>,
     /// Execution trace showing call hierarchy and frame structure
     pub trace: Trace,
+    /// Database right before the target transaction, with instrumented bytecode. It is
+    /// the starting point of what-if re-executions.
+    pub pre_tx_db: Arc<CacheDB<DB>>,
+    /// Database right before the target transaction, with the original bytecode
+    pub pre_tx_original_db: Arc<CacheDB<DB>>,
+    /// Configuration of the engine that prepared this context, used to prepare derived
+    /// contexts
+    #[serde(skip)]
+    pub engine_config: EngineConfig,
     /// Relation between target addresses and their (delegated) code addresses
     #[serde(skip)]
     address_code_address_map: OnceCell<HashMap<Address, HashSet<Address>>>,
+    /// State changes made by the mutating pseudo-functions of the evaluator, applied on
+    /// top of the snapshot state by derived executions
+    #[serde(skip)]
+    overlay: Arc<RwLock<StateOverlay>>,
 }
 
 impl<DB> EngineContext<DB>
@@ -139,6 +169,9 @@ where
     /// * `recompiled_artifacts` - Recompiled artifacts with instrumentation
     /// * `analysis_results` - Analysis results identifying instrumentation points
     /// * `trace` - Execution trace showing call hierarchy
+    /// * `pre_tx_db` - Instrumented database right before the target transaction
+    /// * `pre_tx_original_db` - Original database right before the target transaction
+    /// * `engine_config` - Configuration of the engine preparing the context
     ///
     /// # Returns
     ///
@@ -155,6 +188,9 @@ where
         recompiled_artifacts: HashMap<Address, Artifact>,
         analysis_results: HashMap<Address, AnalysisResult>,
         trace: Trace,
+        pre_tx_db: Arc<CacheDB<DB>>,
+        pre_tx_original_db: Arc<CacheDB<DB>>,
+        engine_config: EngineConfig,
     ) -> Result<Self> {
         let mut context = Self {
             fork_info,
@@ -167,7 +203,11 @@ where
             recompiled_artifacts,
             analysis_results,
             trace,
+            pre_tx_db,
+            pre_tx_original_db,
+            engine_config,
             address_code_address_map: OnceCell::new(),
+            overlay: Arc::default(),
         };
 
         // Finalize the context to populate derived fields
@@ -259,7 +299,7 @@ where
                     snapshot.target_address(),
                     &state_variable.abi,
                     &[],
-                    None,
+                    &CallOptions::default(),
                 ) {
                     Ok(value) => {
                         states.insert(state_variable.name.clone(), Some(Arc::new(value.into())));
@@ -316,6 +356,42 @@ where
         self.trace.get(frame_id.trace_entry_id()).map(|entry| entry.target)
     }
 
+    /// Get the call stack at a snapshot.
+    ///
+    /// Each frame of the call stack is represented by a snapshot: frame 0 is the snapshot
+    /// itself, and every other frame is the most recent snapshot of a caller, i.e., the
+    /// step making the internal or external call. Evaluating an expression at the snapshot
+    /// of a frame resolves the locals of that frame.
+    ///
+    /// Returns the snapshot ids of the frames, innermost first, or None if the snapshot
+    /// doesn't exist.
+    pub fn get_call_stack(&self, snapshot_id: usize) -> Option<Vec<usize>> {
+        let mut frames = vec![snapshot_id];
+        let mut current = snapshot_id;
+        loop {
+            let (frame_id, snapshot) = self.snapshots.get(current)?;
+            frames.extend(snapshot.internal_callers().iter().rev());
+
+            // Find the step of the nearest caller frame that made the external call
+            let mut entry = self.trace.get(frame_id.trace_entry_id())?;
+            let caller = loop {
+                let Some(parent_id) = entry.parent_id else {
+                    return Some(frames);
+                };
+                if let Some(caller) = self.snapshots[..current]
+                    .iter()
+                    .rposition(|(frame_id, _)| frame_id.trace_entry_id() == parent_id)
+                {
+                    break caller;
+                }
+                entry = self.trace.get(parent_id)?;
+            };
+
+            frames.push(caller);
+            current = caller;
+        }
+    }
+
     /// Check if one trace entry is the parent of another.
     ///
     /// This method determines the parent-child relationship between trace entries,
@@ -341,6 +417,44 @@ where
             map
         })
     }
+
+    /// Get the source-level step identified by a USID in the bytecode at an address.
+    pub fn get_step(&self, address: Address, usid: USID) -> Option<StepRef> {
+        self.analysis_results.get(&address)?.usid_to_step.get(&usid).cloned()
+    }
+
+    /// Get the source file and byte range of a source-level step.
+    pub fn get_step_source(&self, address: Address, usid: USID) -> Option<StepSource> {
+        let analysis = self.analysis_results.get(&address)?;
+        let step = analysis.usid_to_step.get(&usid)?.read();
+        let source = analysis.sources.get(&(step.src.index.unwrap_or(0) as u32))?;
+        Some(StepSource {
+            path: source.path.clone(),
+            offset: step.src.start,
+            length: step.src.length,
+        })
+    }
+
+    /// Get the source file and (1-based) line of a source-level step.
+    ///
+    /// Returns None if the step or the content of its source file is unknown.
+    pub fn get_step_line(&self, address: Address, usid: USID) -> Option<(PathBuf, usize)> {
+        let StepSource { path, offset, .. } = self.get_step_source(address, usid)?;
+        let content = &self.artifacts.get(&address)?.input.sources.get(&path)?.content;
+        let offset = offset?.min(content.len());
+        let line = content.as_bytes()[..offset].iter().filter(|&&b| b == b'\n').count() + 1;
+        Some((path, line))
+    }
+
+    /// Get a copy of the session state overlay
+    pub fn overlay(&self) -> StateOverlay {
+        self.overlay.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Modify the session state overlay
+    pub fn update_overlay<T>(&self, update: impl FnOnce(&mut StateOverlay) -> T) -> T {
+        update(&mut self.overlay.write().unwrap_or_else(|e| e.into_inner()))
+    }
 }
 
 // EVM creation and expression evaluation methods
@@ -362,22 +476,29 @@ where
     ///
     /// # Returns
     ///
-    /// Returns a configured EVM instance or None if the snapshot doesn't exist.
+    /// Returns a configured EVM instance with the session overlay applied, or an error if
+    /// the snapshot doesn't exist or the overlay cannot be applied.
     pub fn create_evm_for_snapshot(
         &self,
         snapshot_id: usize,
-    ) -> Option<MainnetEvm<DerivedContext<DB>>> {
-        let (_, snapshot) = self.snapshots.get(snapshot_id)?;
+    ) -> Result<MainnetEvm<DerivedContext<DB>>> {
+        let (_, snapshot) = self
+            .snapshots
+            .get(snapshot_id)
+            .ok_or(eyre!("No EVM found at snapshot {}", snapshot_id))?;
 
-        let db = CacheDB::new(CacheDB::new(snapshot.db()));
+        let overlay = self.overlay();
+        let mut db = CacheDB::new(CacheDB::new(snapshot.db()));
+        overlay.apply_to_db(&mut db)?;
         let cfg = self.cfg.clone();
-        let block = self.block.clone();
+        let mut block = self.block.clone();
+        overlay.apply_to_block(&mut block);
 
         let mut ctx = Context::mainnet().with_db(db).with_cfg(cfg).with_block(block);
         relax_evm_context_constraints(&mut ctx);
         disable_nonce_check(&mut ctx);
 
-        Some(ctx.build_mainnet())
+        Ok(ctx.build_mainnet())
     }
 
     /// Send a mock transaction in a derived EVM.
@@ -391,7 +512,9 @@ where
     /// * `snapshot_id` - The snapshot ID to use as the base state
     /// * `to` - The target address for the transaction
     /// * `data` - The transaction data (call data)
-    /// * `value` - The value to send with the transaction
+    /// * `options` - The sender, value, and gas limit of the transaction. Without a sender,
+    ///   the transaction is sent by the pranked address of the session overlay, if any,
+    ///   or by the sender of the target transaction.
     ///
     /// # Returns
     ///
@@ -401,19 +524,20 @@ where
         snapshot_id: usize,
         to: Address,
         data: &[u8],
-        value: U256,
+        options: &CallOptions,
     ) -> Result<ExecutionResult> {
-        let mut evm = self
-            .create_evm_for_snapshot(snapshot_id)
-            .ok_or(eyre!("No EVM found at snapshot {}", snapshot_id))?;
+        let mut evm = self.create_evm_for_snapshot(snapshot_id)?;
 
         let mut tx_env = TxEnvBuilder::new()
-            .caller(self.tx.caller)
+            .caller(options.from.or(self.overlay().prank).unwrap_or(self.tx.caller))
             .call(to)
-            .value(value)
+            .value(options.value.unwrap_or_default())
             .data(Bytes::copy_from_slice(data))
             .build_fill();
         relax_evm_tx_constraints(&mut tx_env);
+        if let Some(gas) = options.gas {
+            tx_env.gas_limit = gas;
+        }
 
         evm.transact_one(tx_env).map_err(|e| eyre!(e.to_string()))
     }
@@ -430,7 +554,7 @@ where
     /// * `to` - The contract address to call
     /// * `function` - The ABI function definition
     /// * `args` - The function arguments
-    /// * `value` - Optional value to send with the call
+    /// * `options` - The sender, value, and gas limit of the call
     ///
     /// # Returns
     ///
@@ -441,29 +565,70 @@ where
         to: Address,
         function: &Function,
         args: &[DynSolValue],
-        value: Option<U256>,
+        options: &CallOptions,
     ) -> Result<DynSolValue> {
+        self.record_call_in_derived_evm(snapshot_id, to, function, args, options)?.0
+    }
+
+    /// Invoke a contract function call in a derived EVM and record its outcome.
+    ///
+    /// Unlike [`Self::call_in_derived_evm`], a call that was executed is also described
+    /// by an [`EvalCall`], carrying its revert reason and emitted events.
+    ///
+    /// # Returns
+    ///
+    /// Returns the decoded function result (or the revert error) together with the
+    /// record of the call, or an error if the call could not be executed at all.
+    pub fn record_call_in_derived_evm(
+        &self,
+        snapshot_id: usize,
+        to: Address,
+        function: &Function,
+        args: &[DynSolValue],
+        options: &CallOptions,
+    ) -> Result<(Result<DynSolValue>, EvalCall)> {
         let data = function.abi_encode_input(args).map_err(|e| eyre!(e.to_string()))?;
-        let value = value.unwrap_or_default();
-
-        let result = self.send_transaction_in_derived_evm(snapshot_id, to, &data, value)?;
-
-        match result {
-            ExecutionResult::Success { output, .. } => {
-                let decoded =
-                    function.abi_decode_output(output.data()).map_err(|e| eyre!(e.to_string()))?;
-                if decoded.len() == 1 {
-                    Ok(decoded.into_iter().next().unwrap())
-                } else {
-                    Ok(DynSolValue::Tuple(decoded))
-                }
-            }
+
+        // Record the actual sender, so that the call can be re-executed as it was
+        let options = CallOptions { from: options.from.or(self.overlay().prank), ..*options };
+        let result = self.send_transaction_in_derived_evm(snapshot_id, to, &data, &options)?;
+
+        let mut call = EvalCall {
+            to,
+            function: function.signature(),
+            input: data.into(),
+            options,
+            success: result.is_success(),
+            revert_reason: None,
+            gas_used: result.gas_used(),
+            logs: result.logs().to_vec(),
+        };
+
+        let value = match result {
+            ExecutionResult::Success { output, .. } => function
+                .abi_decode_output(output.data())
+                .map_err(|e| eyre!(e.to_string()))
+                .map(|mut decoded| {
+                    if decoded.len() == 1 {
+                        decoded.remove(0)
+                    } else {
+                        DynSolValue::Tuple(decoded)
+                    }
+                }),
             ExecutionResult::Revert { output, .. } => {
-                Err(eyre!("Call reverted with output: 0x{}", hex::encode(output)))
+                let reason = decode_revert_reason(&output);
+                let error = eyre!("Call to {} reverted: {}", call.function, reason);
+                call.revert_reason = Some(reason);
+                Err(error)
             }
             ExecutionResult::Halt { reason, .. } => {
-                Err(eyre!("Call halted with reason: {:?}", reason))
+                let reason = format!("{reason:?}");
+                let error = eyre!("Call to {} halted: {}", call.function, reason);
+                call.revert_reason = Some(reason);
+                Err(error)
             }
-        }
+        };
+
+        Ok((value, call))
     }
 }
@@ -57,16 +57,17 @@ use indicatif::{ProgressBar, ProgressStyle};
 use revm::{
     context::{
         result::{ExecutionResult, HaltReason},
-        Host, TxEnv,
+        ContextTr, Host, TxEnv,
     },
     database::CacheDB,
-    Database, DatabaseCommit, DatabaseRef, InspectEvm, MainBuilder,
+    Context, Database, DatabaseCommit, DatabaseRef, InspectEvm, MainBuilder, MainContext,
 };
 use semver::Version;
 use std::{
     collections::{HashMap, HashSet},
     env, fs,
-    path::PathBuf,
+    path::{Path, PathBuf},
+    sync::Arc,
     time::Duration,
 };
 use tracing::{debug, error, info, warn};
@@ -81,7 +82,7 @@ use crate::{
     analyze,
     inspector::{CallTracer, TraceReplayResult},
     instrument,
-    rpc::RpcServerHandle,
+    rpc::{RpcServerConfig, RpcServerHandle},
     start_debug_server,
     utils::{next_etherscan_api_key, Artifact, OnchainCompiler},
     CodeTweaker, EngineContext, HookSnapshotInspector, HookSnapshots, OpcodeSnapshotInspector,
@@ -100,6 +101,11 @@ pub struct EngineConfig {
     pub etherscan_api_key: Option<String>,
     /// Quick mode flag - when enabled, skips time-intensive operations for faster analysis
     pub quick: bool,
+    /// Where the debug RPC server listens, and whether it requires a token
+    pub rpc_server: RpcServerConfig,
+    /// Quiet mode flag - when enabled, the trace tree and snapshot summaries are not printed
+    /// to stdout (e.g., when stdout is a protocol channel or the replay is derived)
+    pub quiet: bool,
 }
 
 impl Default for EngineConfig {
@@ -108,6 +114,8 @@ impl Default for EngineConfig {
             rpc_proxy_url: "http://localhost:8545".into(),
             etherscan_api_key: None,
             quick: false,
+            rpc_server: RpcServerConfig::default(),
+            quiet: false,
         }
     }
 }
@@ -130,6 +138,18 @@ impl EngineConfig {
         self.rpc_proxy_url = url;
         self
     }
+
+    /// Set where the debug RPC server listens, and whether it requires a token
+    pub fn with_rpc_server(mut self, rpc_server: RpcServerConfig) -> Self {
+        self.rpc_server = rpc_server;
+        self
+    }
+
+    /// Enable or disable quiet mode, which keeps stdout free of summaries
+    pub fn with_quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
 }
 
 /// The main Engine struct that performs transaction analysis
@@ -137,12 +157,14 @@ impl EngineConfig {
 pub struct Engine {
     /// RPC Provider URL
     pub rpc_proxy_url: String,
-    /// Port for the JSON-RPC server
-    pub host_port: Option<u16>,
+    /// Where the JSON-RPC server listens, and whether it requires a token
+    pub rpc_server: RpcServerConfig,
     /// Etherscan API key for source code download
     pub etherscan_api_key: Option<String>,
     /// Quick mode - skip certain operations for faster analysis
     pub quick: bool,
+    /// Quiet mode - do not print the trace tree and snapshot summaries to stdout
+    pub quiet: bool,
 }
 
 impl Default for Engine {
@@ -154,8 +176,8 @@ impl Default for Engine {
 impl Engine {
     /// Create a new Engine instance from configuration
     pub fn new(config: EngineConfig) -> Self {
-        let EngineConfig { rpc_proxy_url, etherscan_api_key, quick } = config;
-        Self { rpc_proxy_url, host_port: None, etherscan_api_key, quick }
+        let EngineConfig { rpc_proxy_url, etherscan_api_key, quick, rpc_server, quiet } = config;
+        Self { rpc_proxy_url, rpc_server, etherscan_api_key, quick, quiet }
     }
 
     /// Main preparation method for the engine
@@ -177,18 +199,44 @@ impl Engine {
     {
         info!("Starting engine preparation for transaction: {:?}", fork_result.target_tx_hash);
 
-        // Step 0: Initialize context and database
-        let ForkResult { context: mut ctx, target_tx_env: tx, target_tx_hash: tx_hash, fork_info } =
-            fork_result;
-
         // Step 1: Replay the target transaction to collect call trace and touched contracts
         info!("Replaying transaction to collect call trace and touched contracts");
-        let replay_result = self.replay_and_collect_trace(ctx.clone(), tx.clone())?;
+        let replay_result = self.replay_and_collect_trace(
+            fork_result.context.clone(),
+            fork_result.target_tx_env.clone(),
+        )?;
 
         // Step 2: Download verified source code for each contract
         info!("Downloading verified source code for each contract");
-        let artifacts =
-            self.download_verified_source_code(&replay_result, ctx.chain_id().to::<u64>()).await?;
+        let chain_id = fork_result.context.chain_id().to::<u64>();
+        let artifacts = self.download_verified_source_code(&replay_result, chain_id).await?;
+
+        // Steps 3 to 7: Instrument the source code and collect snapshots
+        let context = self.prepare_with_artifacts(fork_result, replay_result, artifacts).await?;
+
+        // Step 8: Start RPC server with analysis results and snapshots
+        info!("Starting RPC server with analysis results and snapshots");
+        let rpc_handle = start_debug_server(context).await?;
+        info!("Debug RPC server started on {}", rpc_handle.endpoint);
+
+        Ok(rpc_handle)
+    }
+
+    /// Steps 3 to 7 of [`Engine::prepare`], given the replay of the target transaction and
+    /// the verified source code of the contracts it touches
+    pub(crate) async fn prepare_with_artifacts<DB>(
+        &self,
+        fork_result: ForkResult<DB>,
+        replay_result: TraceReplayResult,
+        artifacts: HashMap<Address, Artifact>,
+    ) -> Result<EngineContext<DB>>
+    where
+        DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+        <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+        <DB as Database>::Error: Clone + Send + Sync,
+    {
+        let ForkResult { context: mut ctx, target_tx_env: tx, target_tx_hash: tx_hash, fork_info } =
+            fork_result;
 
         // Step 3: Analyze source code to identify instrumentation points
         info!("Analyzing source code");
@@ -210,11 +258,13 @@ impl Engine {
 
         // Step 6: Replace original bytecode with instrumented versions
         info!("Tweaking bytecode");
+        let pre_tx_original_db = Arc::new(ctx.db().clone());
         let contracts_in_tx =
             self.tweak_bytecode(&mut ctx, &artifacts, &recompiled_artifacts, tx_hash).await?;
 
         // Step 7: Re-execute the transaction with snapshot collection
         info!("Re-executing transaction with snapshot collection");
+        let pre_tx_db = Arc::new(ctx.db().clone());
         let hook_creation =
             self.collect_creation_hooks(&artifacts, &recompiled_artifacts, contracts_in_tx)?;
         let hook_snapshots = self.capture_hook_snapshots(
@@ -225,12 +275,10 @@ impl Engine {
             &analysis_results,
         )?;
 
-        // Step 8: Start RPC server with analysis results and snapshots
-        info!("Starting RPC server with analysis results and snapshots");
         let mut snapshots = self.get_time_travel_snapshots(opcode_snapshots, hook_snapshots)?;
         snapshots.analyze(&replay_result.execution_trace, &analysis_results)?;
         // Let's pack the debug context
-        let context = EngineContext::build(
+        EngineContext::build(
             fork_info,
             ctx.cfg.clone(),
             ctx.block.clone(),
@@ -241,16 +289,164 @@ impl Engine {
             recompiled_artifacts,
             analysis_results,
             replay_result.execution_trace,
-        )?;
+            pre_tx_db,
+            pre_tx_original_db,
+            self.config(),
+        )
+    }
 
-        let rpc_handle = start_debug_server(context).await?;
-        info!("Debug RPC server started on port {}", rpc_handle.port());
+    /// Replay the target transaction of a prepared context, with the source code of one
+    /// contract replaced by a patched version
+    ///
+    /// The patched source goes through the same pipeline as the original one:
+    /// 1. Recompiles the patched source
+    /// 2. Analyzes the patched source code to identify instrumentation points
+    /// 3. Instruments and recompiles the patched source code
+    /// 4. Swaps in the patched bytecode, both original and instrumented
+    /// 5. Replays the transaction to collect the call trace and snapshots
+    ///
+    /// Contracts deployed by the target transaction itself cannot be patched, since their
+    /// code is not swapped in by [`CodeTweaker`].
+    pub async fn replay_with_patched_source<DB>(
+        &self,
+        context: &EngineContext<DB>,
+        address: Address,
+        path: &Path,
+        source: String,
+    ) -> Result<EngineContext<DB>>
+    where
+        DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+        <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+        <DB as Database>::Error: Clone + Send + Sync,
+    {
+        info!("Replaying transaction {:?} with patched source of {}", context.tx_hash, address);
 
-        Ok(rpc_handle)
+        if context.trace.iter().any(|entry| entry.created_contract && entry.target == address) {
+            eyre::bail!(
+                "Contract {} is deployed by the target transaction and cannot be patched",
+                address
+            );
+        }
+        let Some(artifact) = context.artifacts.get(&address) else {
+            eyre::bail!("No source code available for contract {}", address);
+        };
+
+        // Step 1: Recompile the patched source
+        info!("Recompiling patched source code");
+        let patched_artifacts = HashMap::from([(address, artifact.patch_source(path, source)?)]);
+
+        // Step 2: Analyze the patched source code
+        info!("Analyzing patched source code");
+        let patched_analysis = self.analyze_source_code(&patched_artifacts)?;
+
+        // Step 3: Instrument the patched source code
+        info!("Instrumenting patched source code");
+        let patched_recompiled =
+            self.instrument_and_recompile_source_code(&patched_artifacts, &patched_analysis)?;
+
+        // Step 4: Swap in the patched bytecode
+        info!("Tweaking patched bytecode");
+        let new_context = |db: &CacheDB<DB>| -> EdbContext<DB> {
+            Context::mainnet()
+                .with_db(db.clone())
+                .with_cfg(context.cfg.clone())
+                .with_block(context.block.clone())
+        };
+        let mut original_ctx = new_context(&context.pre_tx_original_db);
+        let mut ctx = new_context(&context.pre_tx_db);
+        for (ctx, patched) in
+            [(&mut original_ctx, &patched_artifacts), (&mut ctx, &patched_recompiled)]
+        {
+            let mut tweaker =
+                CodeTweaker::new(ctx, self.rpc_proxy_url.clone(), self.etherscan_api_key.clone());
+            tweaker.tweak(&address, artifact, &patched[&address], self.quick).await.map_err(
+                |e| eyre::eyre!("Failed to tweak bytecode for contract {}: {}", address, e),
+            )?;
+        }
+
+        let mut artifacts = context.artifacts.clone();
+        artifacts.extend(patched_artifacts);
+        let mut recompiled_artifacts = context.recompiled_artifacts.clone();
+        recompiled_artifacts.extend(patched_recompiled);
+        let mut analysis_results = context.analysis_results.clone();
+        analysis_results.extend(patched_analysis);
+
+        // Step 5: Replay the transaction with the patched bytecode
+        self.replay_with_patched_code(
+            context,
+            original_ctx,
+            ctx,
+            artifacts,
+            recompiled_artifacts,
+            analysis_results,
+        )
+    }
+
+    /// Replay the target transaction of a prepared context from pre-transaction states
+    /// with patched code (original and instrumented), whose artifacts and analysis results
+    /// replace those of the context
+    pub(crate) fn replay_with_patched_code<DB>(
+        &self,
+        context: &EngineContext<DB>,
+        original_ctx: EdbContext<DB>,
+        ctx: EdbContext<DB>,
+        artifacts: HashMap<Address, Artifact>,
+        recompiled_artifacts: HashMap<Address, Artifact>,
+        analysis_results: HashMap<Address, AnalysisResult>,
+    ) -> Result<EngineContext<DB>>
+    where
+        DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+        <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+        <DB as Database>::Error: Clone + Send + Sync,
+    {
+        info!("Replaying transaction with patched bytecode");
+        let contracts_in_tx: Vec<Address> = context
+            .trace
+            .iter()
+            .filter(|entry| entry.created_contract && artifacts.contains_key(&entry.target))
+            .map(|entry| entry.target)
+            .collect();
+        let tx = context.tx.clone();
+        let pre_tx_original_db = Arc::new(original_ctx.db().clone());
+        let pre_tx_db = Arc::new(ctx.db().clone());
+        let replay_result = self.replay_and_collect_trace(original_ctx.clone(), tx.clone())?;
+        let opcode_snapshots = self.capture_opcode_level_snapshots(
+            original_ctx,
+            tx.clone(),
+            artifacts.keys().cloned().collect(),
+            &replay_result.execution_trace,
+        )?;
+        let hook_creation =
+            self.collect_creation_hooks(&artifacts, &recompiled_artifacts, contracts_in_tx)?;
+        let hook_snapshots = self.capture_hook_snapshots(
+            ctx,
+            tx.clone(),
+            hook_creation,
+            &replay_result.execution_trace,
+            &analysis_results,
+        )?;
+
+        let mut snapshots = self.get_time_travel_snapshots(opcode_snapshots, hook_snapshots)?;
+        snapshots.analyze(&replay_result.execution_trace, &analysis_results)?;
+        EngineContext::build(
+            context.fork_info.clone(),
+            context.cfg.clone(),
+            context.block.clone(),
+            tx,
+            context.tx_hash,
+            snapshots,
+            artifacts,
+            recompiled_artifacts,
+            analysis_results,
+            replay_result.execution_trace,
+            pre_tx_db,
+            pre_tx_original_db,
+            self.config(),
+        )
     }
 
     /// Replay the target transaction and collect call trace with all touched addresses
-    fn replay_and_collect_trace<DB>(
+    pub(crate) fn replay_and_collect_trace<DB>(
         &self,
         ctx: EdbContext<DB>,
         tx: TxEnv,
@@ -286,7 +482,9 @@ impl Engine {
         }
 
         // Print the trace tree structure
-        result.execution_trace.print_trace_tree();
+        if !self.quiet {
+            result.execution_trace.print_trace_tree();
+        }
 
         Ok(result)
     }
@@ -412,7 +610,9 @@ impl Engine {
 
         let snapshots = inspector.into_snapshots();
 
-        snapshots.print_summary();
+        if !self.quiet {
+            snapshots.print_summary();
+        }
 
         Ok(snapshots)
     }
@@ -442,7 +642,9 @@ impl Engine {
 
         let snapshots = inspector.into_snapshots();
 
-        snapshots.print_summary();
+        if !self.quiet {
+            snapshots.print_summary();
+        }
 
         Ok(snapshots)
     }
@@ -593,7 +795,9 @@ impl Engine {
         <DB as Database>::Error: Clone,
     {
         let snapshots = Snapshots::merge(opcode_snapshots, hook_snapshots);
-        snapshots.print_summary();
+        if !self.quiet {
+            snapshots.print_summary();
+        }
 
         Ok(snapshots)
     }
@@ -601,6 +805,16 @@ impl Engine {
 
 // Helper functions
 impl Engine {
+    fn config(&self) -> EngineConfig {
+        EngineConfig {
+            rpc_proxy_url: self.rpc_proxy_url.clone(),
+            etherscan_api_key: self.etherscan_api_key.clone(),
+            quick: self.quick,
+            rpc_server: self.rpc_server.clone(),
+            quiet: self.quiet,
+        }
+    }
+
     fn get_etherscan_api_key(&self) -> String {
         self.etherscan_api_key.clone().unwrap_or(next_etherscan_api_key())
     }
@@ -878,3 +1092,91 @@ fn dump_source_for_debugging(
 
     Ok((original_dir, instrumented_dir))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use edb_common::types::{CallResult, StorageChange};
+    use revm::state::{AccountInfo, Bytecode};
+
+    use crate::{
+        diff_timelines,
+        test_utils::{prepare_test_context, TestDB, CALLEE_CONTRACT},
+    };
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_replay_with_patched_code() {
+        let context = prepare_test_context().await;
+        let engine = Engine::new(EngineConfig::default());
+
+        // The patched callee stores 1 in slot 1 and stops, instead of reverting
+        let code = Bytecode::new_raw(vec![0x60, 0x01, 0x60, 0x01, 0x55, 0x00].into());
+        let patched_ctx = |db: &CacheDB<TestDB>| -> EdbContext<TestDB> {
+            let mut db = db.clone();
+            db.insert_account_info(CALLEE_CONTRACT, AccountInfo::default().with_code(code.clone()));
+            Context::mainnet()
+                .with_db(db)
+                .with_cfg(context.cfg.clone())
+                .with_block(context.block.clone())
+        };
+        let patched = engine
+            .replay_with_patched_code(
+                &context,
+                patched_ctx(&context.pre_tx_original_db),
+                patched_ctx(&context.pre_tx_db),
+                HashMap::new(),
+                HashMap::new(),
+                HashMap::new(),
+            )
+            .unwrap();
+
+        assert!(matches!(context.trace[1].result, Some(CallResult::Revert { .. })));
+        assert!(matches!(patched.trace[1].result, Some(CallResult::Success { .. })));
+
+        let diff = diff_timelines(&context, &patched).unwrap();
+        // Both callees start with a PUSH at PC 0, and diverge from their second step
+        let first_callee_step = context.trace[1].first_snapshot_id.unwrap();
+        assert_eq!(diff.divergence, Some(first_callee_step + 1));
+        assert_eq!(
+            diff.storage_changes,
+            vec![StorageChange {
+                address: CALLEE_CONTRACT,
+                slot: U256::from(1),
+                original: U256::ZERO,
+                derived: U256::from(1),
+            }]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_refuse_patching_deployed_contract() {
+        let mut context = prepare_test_context().await;
+        let engine = Engine::new(EngineConfig::default());
+
+        // Contracts without source code cannot be patched
+        let err = engine
+            .replay_with_patched_source(
+                &context,
+                CALLEE_CONTRACT,
+                Path::new("A.sol"),
+                String::new(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("No source code available"), "{err}");
+
+        // Nor can contracts deployed by the transaction, whose code is not swapped in
+        context.trace[1].created_contract = true;
+        let err = engine
+            .replay_with_patched_source(
+                &context,
+                CALLEE_CONTRACT,
+                Path::new("A.sol"),
+                String::new(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("is deployed by the target transaction"), "{err}");
+    }
+}
@@ -0,0 +1,925 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Debug adapter mapping DAP requests onto the RPC methods of a debug server.
+//!
+//! The adapter keeps the current snapshot of the session and translates every request
+//! into calls of the same methods JSON-RPC clients use:
+//!
+//! - `stackTrace` - `edb_getCallStack` and `edb_getSnapshotInfo`
+//! - `scopes` / `variables` - locals and state variables of hook snapshots, or the stack
+//!   of opcode snapshots, from `edb_getSnapshotInfo`
+//! - `evaluate` - `edb_evalOnSnapshot`
+//! - `continue` / `reverseContinue` - `edb_findBreakpointHit`, with logpoint messages from
+//!   `edb_collectLogpoints` reported as output
+//! - `next` / `stepBack` / `stepIn` / `stepOut` - the snapshot links of `edb_getSnapshotInfo`
+//!   and `edb_getCallStack`
+//! - `source` - the verified sources of `edb_getCodeByAddress`
+//!
+//! The transaction is the only thread of the session, and every stack frame is identified
+//! by its snapshot.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use alloy_primitives::Address;
+use edb_common::api::method;
+use edb_common::types::{
+    Breakpoint, BreakpointHit, BreakpointLocation, Code, EdbSolValue, LogpointEntry,
+    SearchDirection, SnapshotInfo, SnapshotInfoDetail, SolValueFormatter, SolValueFormatterContext,
+    Trace,
+};
+use eyre::{bail, eyre, Result};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufRead, AsyncWrite};
+use tracing::{debug, warn};
+
+use super::protocol::{read_message, write_message, OutgoingMessage, Request};
+use crate::{offset_to_line_column, MethodDispatch, RpcServerHandle};
+
+/// Id of the only thread of a session: the transaction
+const THREAD_ID: i64 = 1;
+
+/// Scope of the local variables of a hook snapshot
+const SCOPE_LOCALS: usize = 0;
+/// Scope of the state variables of a hook snapshot
+const SCOPE_STATE: usize = 1;
+/// Scope of the stack of an opcode snapshot
+const SCOPE_STACK: usize = 2;
+/// Number of scope kinds, used to encode variable references
+const SCOPE_KINDS: usize = 3;
+
+/// A breakpoint set by the client, with the engine breakpoints it stands for
+#[derive(Debug, Clone)]
+struct ClientBreakpoint {
+    /// Id of the breakpoint reported to the client
+    id: i64,
+    /// Engine breakpoints, e.g., one per contract compiled from the same source line
+    breakpoints: Vec<Breakpoint>,
+}
+
+/// Debug adapter serving one client.
+pub struct DebugAdapter<W> {
+    /// Stream messages are written to
+    writer: W,
+    /// Sequence number of the last message sent
+    seq: i64,
+    /// Events to send once the response of the current request is sent
+    events: Vec<(String, Value)>,
+    /// Debug server of the launched transaction
+    server: Option<RpcServerHandle>,
+    /// Methods of the debug server
+    methods: Option<Arc<dyn MethodDispatch>>,
+    /// Whether to stop at the first snapshot once configured
+    stop_on_entry: bool,
+    /// Number of snapshots of the transaction
+    snapshot_count: usize,
+    /// Snapshot the session is stopped at
+    current: usize,
+    /// Call trace of the transaction
+    trace: Trace,
+    /// Code of every contract in the trace, loaded on first use
+    codes: Option<BTreeMap<Address, Code>>,
+    /// Sources without a file on disk, retrieved through `source` by their 1-based index
+    source_refs: Vec<(Address, PathBuf)>,
+    /// Source breakpoints, by client source
+    source_breakpoints: BTreeMap<String, Vec<ClientBreakpoint>>,
+    /// Function breakpoints, which accept any symbolic breakpoint location
+    function_breakpoints: Vec<ClientBreakpoint>,
+    /// Exception breakpoints, i.e. reverts
+    exception_breakpoints: Vec<ClientBreakpoint>,
+    /// Id of the last breakpoint reported to the client
+    breakpoint_id: i64,
+    /// Messages logged over the timeline, with the logpoints they were collected for
+    logpoint_log: Option<(Vec<Breakpoint>, Vec<LogpointEntry>)>,
+}
+
+impl<W> DebugAdapter<W>
+where
+    W: AsyncWrite + Unpin,
+{
+    /// Create an adapter writing its messages to `writer`
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            seq: 0,
+            events: Vec::new(),
+            server: None,
+            methods: None,
+            stop_on_entry: true,
+            snapshot_count: 0,
+            current: 0,
+            trace: Trace::default(),
+            codes: None,
+            source_refs: Vec::new(),
+            source_breakpoints: BTreeMap::new(),
+            function_breakpoints: Vec::new(),
+            exception_breakpoints: Vec::new(),
+            breakpoint_id: 0,
+            logpoint_log: None,
+        }
+    }
+
+    /// Serve the requests read from `reader` until the client disconnects.
+    ///
+    /// `launch` prepares the transaction named by the arguments of the `launch` request
+    /// and returns the debug server serving it.
+    pub async fn run<R, F, Fut>(mut self, mut reader: R, launch: F) -> Result<()>
+    where
+        R: AsyncBufRead + Unpin,
+        F: FnOnce(Value) -> Fut,
+        Fut: Future<Output = Result<RpcServerHandle>>,
+    {
+        let mut launch = Some(launch);
+        while let Some(request) = read_message(&mut reader).await? {
+            debug!("Handling DAP request: {}", request.command);
+
+            let result = match request.command.as_str() {
+                "launch" | "attach" => match launch.take() {
+                    Some(launch) => self.launch(&request, launch).await,
+                    None => Err(eyre!("The transaction has already been launched")),
+                },
+                _ => self.handle_request(&request).await,
+            };
+
+            let (success, message, body) = match result {
+                Ok(body) => (true, None, body),
+                Err(e) => {
+                    warn!("DAP request {} failed: {e}", request.command);
+                    (false, Some(e.to_string()), None)
+                }
+            };
+            self.seq += 1;
+            let response = OutgoingMessage::Response {
+                seq: self.seq,
+                request_seq: request.seq,
+                success,
+                command: request.command.clone(),
+                message,
+                body,
+            };
+            write_message(&mut self.writer, &response).await?;
+
+            // Events caused by the request follow its response
+            for (event, body) in std::mem::take(&mut self.events) {
+                self.seq += 1;
+                let event = OutgoingMessage::Event { seq: self.seq, event, body: Some(body) };
+                write_message(&mut self.writer, &event).await?;
+            }
+
+            if matches!(request.command.as_str(), "disconnect" | "terminate") {
+                break;
+            }
+        }
+
+        if let Some(server) = self.server.take() {
+            server.shutdown()?;
+        }
+        Ok(())
+    }
+
+    /// Prepare the transaction to debug
+    async fn launch<F, Fut>(&mut self, request: &Request, launch: F) -> Result<Option<Value>>
+    where
+        F: FnOnce(Value) -> Fut,
+        Fut: Future<Output = Result<RpcServerHandle>>,
+    {
+        self.stop_on_entry = request.arg("stopOnEntry").and_then(Value::as_bool).unwrap_or(true);
+
+        let server = launch(request.arguments.clone()).await?;
+        self.methods = Some(server.methods());
+        self.server = Some(server);
+
+        self.snapshot_count = self.call(method::GET_SNAPSHOT_COUNT, json!([])).await?;
+        self.trace = self.call(method::GET_TRACE, json!([])).await?;
+
+        // The client may now send its breakpoints
+        self.emit("initialized", json!({}));
+        Ok(None)
+    }
+
+    /// Handle any request but `launch`
+    async fn handle_request(&mut self, request: &Request) -> Result<Option<Value>> {
+        let body = match request.command.as_str() {
+            "initialize" => capabilities(),
+            "setBreakpoints" => self.set_breakpoints(request).await?,
+            "setFunctionBreakpoints" => self.set_function_breakpoints(request)?,
+            "setExceptionBreakpoints" => self.set_exception_breakpoints(request),
+            "configurationDone" => {
+                if self.stop_on_entry {
+                    self.stop("entry", None, Vec::new());
+                } else {
+                    self.resume(SearchDirection::Forward).await?;
+                }
+                return Ok(None);
+            }
+            "threads" => json!({ "threads": [{ "id": THREAD_ID, "name": "Transaction" }] }),
+            "stackTrace" => self.stack_trace(request).await?,
+            "scopes" => self.scopes(request).await?,
+            "variables" => self.variables(request).await?,
+            "evaluate" => self.evaluate(request).await?,
+            "source" => self.source_content(request).await?,
+            "continue" => {
+                self.resume(SearchDirection::Forward).await?;
+                json!({ "allThreadsContinued": true })
+            }
+            "reverseContinue" => {
+                self.resume(SearchDirection::Backward).await?;
+                return Ok(None);
+            }
+            "next" => {
+                let target = self.snapshot_info(self.current).await?.next_id();
+                self.step_to(target);
+                return Ok(None);
+            }
+            "stepBack" => {
+                let target = self.snapshot_info(self.current).await?.prev_id();
+                self.step_to(target);
+                return Ok(None);
+            }
+            "stepIn" => {
+                self.step_to(self.current + 1);
+                return Ok(None);
+            }
+            "stepOut" => {
+                let frames: Vec<usize> =
+                    self.call(method::GET_CALL_STACK, json!([self.current])).await?;
+                let target = match frames.get(1) {
+                    // Stop at the step following the call in the caller
+                    Some(caller) => self.snapshot_info(*caller).await?.next_id(),
+                    None => self.last_snapshot(),
+                };
+                self.step_to(target);
+                return Ok(None);
+            }
+            // Execution is always paused between requests
+            "pause" | "disconnect" | "terminate" => return Ok(None),
+            command => bail!("Unsupported request: {command}"),
+        };
+        Ok(Some(body))
+    }
+
+    /// Call an RPC method of the debug server
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let methods = self
+            .methods
+            .as_ref()
+            .ok_or_else(|| eyre!("No transaction is being debugged, launch one first"))?;
+        let value = methods.dispatch(method, Some(params)).await.map_err(|e| eyre!(e.message))?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    async fn snapshot_info(&self, snapshot_id: usize) -> Result<SnapshotInfo> {
+        self.call(method::GET_SNAPSHOT_INFO, json!([snapshot_id])).await
+    }
+
+    /// Messages logged by `logpoints` over the whole timeline, only collected again once
+    /// the logpoints change
+    async fn logpoint_log(&mut self, logpoints: Vec<Breakpoint>) -> Result<&[LogpointEntry]> {
+        if !matches!(&self.logpoint_log, Some((cached, _)) if *cached == logpoints) {
+            let entries = self.call(method::COLLECT_LOGPOINTS, json!([logpoints])).await?;
+            self.logpoint_log = Some((logpoints, entries));
+        }
+        Ok(self.logpoint_log.as_ref().map(|(_, entries)| entries.as_slice()).unwrap_or_default())
+    }
+
+    fn last_snapshot(&self) -> usize {
+        self.snapshot_count.saturating_sub(1)
+    }
+
+    /// Queue an event, sent after the response to the current request
+    fn emit(&mut self, event: &str, body: Value) {
+        self.events.push((event.to_string(), body));
+    }
+
+    fn stop(&mut self, reason: &str, description: Option<&str>, hit_breakpoint_ids: Vec<i64>) {
+        let mut body = json!({
+            "reason": reason,
+            "threadId": THREAD_ID,
+            "allThreadsStopped": true,
+            "hitBreakpointIds": hit_breakpoint_ids,
+        });
+        if let Some(description) = description {
+            body["description"] = json!(description);
+        }
+        self.emit("stopped", body);
+    }
+
+    fn step_to(&mut self, snapshot_id: usize) {
+        self.current = snapshot_id.min(self.last_snapshot());
+        self.stop("step", None, Vec::new());
+    }
+
+    /// Run to the nearest breakpoint hit in `direction`, or to the end of the timeline
+    async fn resume(&mut self, direction: SearchDirection) -> Result<()> {
+        let (ids, breakpoints): (Vec<i64>, Vec<Breakpoint>) = self
+            .source_breakpoints
+            .values()
+            .flatten()
+            .chain(&self.function_breakpoints)
+            .chain(&self.exception_breakpoints)
+            .flat_map(|client| client.breakpoints.iter().map(|bp| (client.id, bp.clone())))
+            .unzip();
+
+        let hit: Option<BreakpointHit> = if breakpoints.iter().all(Breakpoint::is_logpoint) {
+            None
+        } else {
+            self.call(method::FIND_BREAKPOINT_HIT, json!([breakpoints, self.current, direction]))
+                .await?
+        };
+
+        let from = self.current;
+        let hit_ids = match &hit {
+            Some(hit) => {
+                self.current = hit.snapshot_id;
+                hit.breakpoints.iter().filter_map(|i| ids.get(*i).copied()).collect()
+            }
+            None if direction == SearchDirection::Forward => {
+                self.current = self.last_snapshot();
+                BTreeSet::new()
+            }
+            None => {
+                self.current = 0;
+                BTreeSet::new()
+            }
+        };
+
+        // Report what the logpoints passed over would have logged
+        let logpoints: Vec<Breakpoint> =
+            breakpoints.iter().filter(|bp| bp.is_logpoint()).cloned().collect();
+        if direction == SearchDirection::Forward && !logpoints.is_empty() {
+            let to = self.current;
+            let entries = self.logpoint_log(logpoints).await?;
+            let output: Vec<String> = entries
+                .iter()
+                .filter(|entry| entry.snapshot_id > from && entry.snapshot_id <= to)
+                .map(|entry| format!("{}\n", entry.message))
+                .collect();
+            for output in output {
+                self.emit("output", json!({ "category": "console", "output": output }));
+            }
+        }
+
+        if hit.is_some() {
+            let reason = if hit_ids
+                .iter()
+                .all(|id| self.exception_breakpoints.iter().any(|client| client.id == *id))
+            {
+                "exception"
+            } else {
+                "breakpoint"
+            };
+            self.stop(reason, None, hit_ids.into_iter().collect());
+        } else if direction == SearchDirection::Forward {
+            self.stop("pause", Some("End of the transaction"), Vec::new());
+        } else {
+            self.stop("pause", Some("Start of the transaction"), Vec::new());
+        }
+        Ok(())
+    }
+
+    fn next_breakpoint_id(&mut self) -> i64 {
+        self.breakpoint_id += 1;
+        self.breakpoint_id
+    }
+
+    async fn set_breakpoints(&mut self, request: &Request) -> Result<Value> {
+        let source = request.arg("source").cloned().unwrap_or_default();
+        let sources = self.find_sources(&source).await?;
+
+        let mut clients = Vec::new();
+        let mut results = Vec::new();
+        for args in request.arg("breakpoints").and_then(Value::as_array).into_iter().flatten() {
+            let line = args.get("line").and_then(Value::as_u64).unwrap_or_default() as usize;
+
+            let mut breakpoints = Vec::new();
+            for (address, path) in &sources {
+                let line_count = self.source_text(*address, path).map_or(0, |s| s.lines().count());
+                if line == 0 || line > line_count {
+                    continue;
+                }
+                let loc = BreakpointLocation::Source {
+                    bytecode_address: *address,
+                    file_path: path.clone(),
+                    line_number: line,
+                };
+                breakpoints.push(client_breakpoint(Some(loc), args)?);
+            }
+
+            let id = self.next_breakpoint_id();
+            let mut result = json!({ "id": id, "verified": !breakpoints.is_empty(), "line": line });
+            if breakpoints.is_empty() {
+                result["message"] = json!("No contract of the transaction runs this line");
+            }
+            results.push(result);
+            clients.push(ClientBreakpoint { id, breakpoints });
+        }
+
+        self.source_breakpoints.insert(source.to_string(), clients);
+        Ok(json!({ "breakpoints": results }))
+    }
+
+    fn set_function_breakpoints(&mut self, request: &Request) -> Result<Value> {
+        let mut clients = Vec::new();
+        let mut results = Vec::new();
+        for args in request.arg("breakpoints").and_then(Value::as_array).into_iter().flatten() {
+            let name = args.get("name").and_then(Value::as_str).unwrap_or_default();
+            let id = self.next_breakpoint_id();
+
+            // Any symbolic location is accepted, e.g. `Vault.withdraw` or `event:Transfer(...)`
+            match name.trim().trim_start_matches('@').parse::<BreakpointLocation>() {
+                Ok(loc) => {
+                    clients.push(ClientBreakpoint {
+                        id,
+                        breakpoints: vec![client_breakpoint(Some(loc), args)?],
+                    });
+                    results.push(json!({ "id": id, "verified": true }));
+                }
+                Err(e) => {
+                    results.push(json!({ "id": id, "verified": false, "message": e.to_string() }))
+                }
+            }
+        }
+
+        self.function_breakpoints = clients;
+        Ok(json!({ "breakpoints": results }))
+    }
+
+    fn set_exception_breakpoints(&mut self, request: &Request) -> Value {
+        let filters = request.arg("filters").and_then(Value::as_array).cloned().unwrap_or_default();
+
+        self.exception_breakpoints.clear();
+        let mut results = Vec::new();
+        if filters.iter().any(|filter| filter == "revert") {
+            let id = self.next_breakpoint_id();
+            let bp = Breakpoint::new(Some(BreakpointLocation::Revert), None);
+            self.exception_breakpoints.push(ClientBreakpoint { id, breakpoints: vec![bp] });
+            results.push(json!({ "id": id, "verified": true }));
+        }
+        json!({ "breakpoints": results })
+    }
+
+    async fn stack_trace(&mut self, request: &Request) -> Result<Value> {
+        let frames: Vec<usize> = self.call(method::GET_CALL_STACK, json!([self.current])).await?;
+        let start = request.arg("startFrame").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let levels = match request.arg("levels").and_then(Value::as_u64) {
+            Some(levels) if levels > 0 => levels as usize,
+            _ => frames.len(),
+        };
+
+        let mut stack_frames = Vec::new();
+        for snapshot_id in frames.iter().skip(start).take(levels) {
+            let info = self.snapshot_info(*snapshot_id).await?;
+            let contract = self
+                .trace
+                .get(info.frame_id().trace_entry_id())
+                .and_then(|entry| entry.target_label.clone())
+                .unwrap_or_else(|| info.target_address.to_string());
+
+            let mut frame = json!({ "id": snapshot_id + 1, "line": 0, "column": 0 });
+            match info.detail() {
+                SnapshotInfoDetail::Hook(detail) => {
+                    self.load_codes().await?;
+                    if let Some((line, column)) =
+                        self.position(info.bytecode_address, &detail.path, detail.offset)
+                    {
+                        frame["line"] = json!(line);
+                        frame["column"] = json!(column);
+                    }
+                    frame["name"] = json!(contract);
+                    frame["source"] = self.source(info.bytecode_address, &detail.path);
+                }
+                SnapshotInfoDetail::Opcode(detail) => {
+                    frame["name"] = json!(format!("{contract} (pc {})", detail.pc));
+                }
+            }
+            stack_frames.push(frame);
+        }
+
+        Ok(json!({ "stackFrames": stack_frames, "totalFrames": frames.len() }))
+    }
+
+    async fn scopes(&mut self, request: &Request) -> Result<Value> {
+        let snapshot_id = frame_snapshot(request).unwrap_or(self.current);
+        let reference = |kind: usize| snapshot_id * SCOPE_KINDS + kind + 1;
+
+        let scopes = match self.snapshot_info(snapshot_id).await?.detail() {
+            SnapshotInfoDetail::Hook(_) => json!([
+                { "name": "Locals", "presentationHint": "locals", "variablesReference": reference(SCOPE_LOCALS), "expensive": false },
+                { "name": "State Variables", "variablesReference": reference(SCOPE_STATE), "expensive": false },
+            ]),
+            SnapshotInfoDetail::Opcode(_) => json!([
+                { "name": "Stack", "variablesReference": reference(SCOPE_STACK), "expensive": false },
+            ]),
+        };
+        Ok(json!({ "scopes": scopes }))
+    }
+
+    async fn variables(&mut self, request: &Request) -> Result<Value> {
+        let reference = request
+            .arg("variablesReference")
+            .and_then(Value::as_u64)
+            .filter(|reference| *reference > 0)
+            .ok_or_else(|| eyre!("Invalid variables reference"))? as usize
+            - 1;
+        let (snapshot_id, kind) = (reference / SCOPE_KINDS, reference % SCOPE_KINDS);
+
+        let ctx = SolValueFormatterContext::new();
+        let variables = match (self.snapshot_info(snapshot_id).await?.detail(), kind) {
+            (SnapshotInfoDetail::Hook(detail), SCOPE_LOCALS | SCOPE_STATE) => {
+                let values =
+                    if kind == SCOPE_LOCALS { &detail.locals } else { &detail.state_variables };
+                let values: BTreeMap<_, _> = values.iter().collect();
+                values
+                    .into_iter()
+                    .map(|(name, value)| match value {
+                        Some(value) => json!({
+                            "name": name,
+                            "value": value.format_value(&ctx),
+                            "type": value.format_type(),
+                            "variablesReference": 0,
+                        }),
+                        None => json!({
+                            "name": name,
+                            "value": "<unavailable>",
+                            "variablesReference": 0,
+                        }),
+                    })
+                    .collect()
+            }
+            (SnapshotInfoDetail::Opcode(detail), SCOPE_STACK) => detail
+                .stack
+                .iter()
+                .rev()
+                .enumerate()
+                .map(|(i, value)| {
+                    json!({ "name": i.to_string(), "value": format!("{value:#x}"), "variablesReference": 0 })
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        Ok(json!({ "variables": variables }))
+    }
+
+    async fn evaluate(&mut self, request: &Request) -> Result<Value> {
+        let expression = request
+            .arg("expression")
+            .and_then(Value::as_str)
+            .ok_or_else(|| eyre!("Missing expression"))?;
+        // Accept the `$` prefix the TUI uses for expressions
+        let expression = expression.trim().trim_start_matches('$').trim();
+        let snapshot_id = frame_snapshot(request).unwrap_or(self.current);
+
+        let value: Result<EdbSolValue, String> =
+            self.call(method::EVAL_ON_SNAPSHOT, json!([snapshot_id, expression])).await?;
+        let value = value.map_err(|e| eyre!(e))?;
+
+        let ctx = SolValueFormatterContext::new();
+        Ok(json!({
+            "result": value.format_value(&ctx),
+            "type": value.format_type(),
+            "variablesReference": 0,
+        }))
+    }
+
+    async fn source_content(&mut self, request: &Request) -> Result<Value> {
+        let reference = request
+            .arg("sourceReference")
+            .or_else(|| request.arg("source").and_then(|source| source.get("sourceReference")))
+            .and_then(Value::as_u64)
+            .unwrap_or_default() as usize;
+        let (address, path) = reference
+            .checked_sub(1)
+            .and_then(|index| self.source_refs.get(index))
+            .cloned()
+            .ok_or_else(|| eyre!("Unknown source reference {reference}"))?;
+
+        self.load_codes().await?;
+        let content = self
+            .source_text(address, &path)
+            .ok_or_else(|| eyre!("Source {} not found", path.display()))?;
+        Ok(json!({ "content": content, "mimeType": "text/x-solidity" }))
+    }
+
+    /// Load the code of every contract in the trace
+    async fn load_codes(&mut self) -> Result<()> {
+        if self.codes.is_some() {
+            return Ok(());
+        }
+
+        let addresses: BTreeSet<Address> = self.trace.iter().map(|e| e.code_address).collect();
+        let mut codes = BTreeMap::new();
+        for address in addresses {
+            match self.call::<Code>(method::GET_CODE_BY_ADDRESS, json!([address])).await {
+                Ok(code) => {
+                    codes.insert(address, code);
+                }
+                Err(e) => debug!("No code for {address}: {e}"),
+            }
+        }
+        self.codes = Some(codes);
+        Ok(())
+    }
+
+    fn source_text(&self, address: Address, path: &Path) -> Option<&str> {
+        match self.codes.as_ref()?.get(&address)? {
+            Code::Source(info) => info.sources.get(path).map(String::as_str),
+            Code::Opcode(_) => None,
+        }
+    }
+
+    /// Contract sources matching a source of the client, given by path or reference
+    async fn find_sources(&mut self, source: &Value) -> Result<Vec<(Address, PathBuf)>> {
+        self.load_codes().await?;
+
+        if let Some(reference) = source.get("sourceReference").and_then(Value::as_u64) {
+            if reference > 0 {
+                return Ok(self
+                    .source_refs
+                    .get(reference as usize - 1)
+                    .cloned()
+                    .into_iter()
+                    .collect());
+            }
+        }
+
+        let Some(client_path) = source.get("path").and_then(Value::as_str).map(Path::new) else {
+            return Ok(Vec::new());
+        };
+        let mut sources = Vec::new();
+        for (address, code) in self.codes.iter().flatten() {
+            if let Code::Source(info) = code {
+                // Files on disk are absolute, while contracts are compiled from relative paths
+                sources.extend(
+                    info.sources
+                        .keys()
+                        .filter(|path| client_path == path.as_path() || client_path.ends_with(path))
+                        .map(|path| (*address, path.clone())),
+                );
+            }
+        }
+        Ok(sources)
+    }
+
+    /// 1-based line and column of a source offset
+    fn position(&self, address: Address, path: &Path, offset: usize) -> Option<(usize, usize)> {
+        let text = self.source_text(address, path)?;
+        offset_to_line_column(text, offset.min(text.len()))
+    }
+
+    /// DAP source of a contract source, referenced by path if it exists on disk
+    fn source(&mut self, address: Address, path: &Path) -> Value {
+        let name = path.file_name().map(|name| name.to_string_lossy().to_string());
+        if let Ok(path) = path.canonicalize() {
+            return json!({ "name": name, "path": path });
+        }
+
+        let reference = match self.source_refs.iter().position(|r| *r == (address, path.into())) {
+            Some(index) => index + 1,
+            None => {
+                self.source_refs.push((address, path.to_path_buf()));
+                self.source_refs.len()
+            }
+        };
+        json!({
+            "name": name,
+            "path": path,
+            "sourceReference": reference,
+            "origin": format!("verified source of {address}"),
+        })
+    }
+}
+
+/// Capabilities of the adapter, returned by `initialize`
+fn capabilities() -> Value {
+    json!({
+        "supportsConfigurationDoneRequest": true,
+        "supportsFunctionBreakpoints": true,
+        "supportsConditionalBreakpoints": true,
+        "supportsHitConditionalBreakpoints": true,
+        "supportsLogPoints": true,
+        "supportsEvaluateForHovers": true,
+        "supportsStepBack": true,
+        "supportsTerminateRequest": true,
+        "exceptionBreakpointFilters": [
+            { "filter": "revert", "label": "Reverts", "default": false },
+        ],
+    })
+}
+
+/// Snapshot of the frame a request refers to
+fn frame_snapshot(request: &Request) -> Option<usize> {
+    let frame_id = request.arg("frameId").and_then(Value::as_u64)?;
+    (frame_id as usize).checked_sub(1)
+}
+
+/// Engine breakpoint for a client breakpoint, with its condition, hit condition and log message
+fn client_breakpoint(loc: Option<BreakpointLocation>, args: &Value) -> Result<Breakpoint> {
+    let mut bp = Breakpoint::new(loc, None);
+
+    if let Some(condition) = args.get("condition").and_then(Value::as_str) {
+        if !condition.trim().is_empty() {
+            bp.set_condition(condition);
+        }
+    }
+
+    // Stopping at the n-th hit means ignoring the n-1 hits before
+    if let Some(hit_condition) = args.get("hitCondition").and_then(Value::as_str) {
+        let hit_condition = hit_condition.trim();
+        if !hit_condition.is_empty() {
+            let count = hit_condition.parse::<usize>().map_err(|_| {
+                eyre!("Unsupported hit condition {hit_condition}, expected a number of hits")
+            })?;
+            bp.ignore_count = count.saturating_sub(1);
+        }
+    }
+
+    if let Some(log) = args.get("logMessage").and_then(Value::as_str) {
+        bp.log = Some(log.to_string());
+    }
+    Ok(bp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{start_debug_server, test_utils::prepare_test_context};
+
+    #[test]
+    fn test_client_breakpoint() {
+        let args = json!({ "line": 12, "condition": "amount > 0", "hitCondition": "3" });
+        let bp = client_breakpoint(None, &args).unwrap();
+        assert_eq!(bp.condition, Some("amount > 0".to_string()));
+        assert_eq!(bp.ignore_count, 2);
+        assert!(!bp.is_logpoint());
+
+        let args = json!({ "line": 12, "logMessage": "amount = {amount}" });
+        let bp = client_breakpoint(None, &args).unwrap();
+        assert_eq!(bp.log, Some("amount = {amount}".to_string()));
+        assert_eq!(bp.condition, None);
+
+        assert!(client_breakpoint(None, &json!({ "hitCondition": "> 3" })).is_err());
+    }
+
+    /// Frame requests as a client writes them to the adapter
+    fn client_input(requests: &[Value]) -> String {
+        requests
+            .iter()
+            .map(|message| {
+                let content = message.to_string();
+                format!("Content-Length: {}\r\n\r\n{content}", content.len())
+            })
+            .collect()
+    }
+
+    /// Parse the messages written by the adapter, failing on anything but framed JSON
+    fn adapter_output(output: Vec<u8>) -> Vec<Value> {
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("Content-Length: "), "unexpected output: {output}");
+        output
+            .split("Content-Length: ")
+            .skip(1)
+            .map(|message| {
+                let (_, content) = message.split_once("\r\n\r\n").unwrap();
+                serde_json::from_str(content).unwrap()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_requests_before_launch() {
+        let input = client_input(&[
+            json!({ "seq": 1, "type": "request", "command": "initialize", "arguments": {} }),
+            json!({ "seq": 2, "type": "request", "command": "evaluate", "arguments": { "expression": "x" } }),
+            json!({ "seq": 3, "type": "request", "command": "disconnect" }),
+        ]);
+
+        let mut output = Vec::new();
+        DebugAdapter::new(&mut output)
+            .run(input.as_bytes(), |_| async { Err(eyre!("no transaction to launch")) })
+            .await
+            .unwrap();
+
+        let responses = adapter_output(output);
+        assert_eq!(responses.len(), 3);
+
+        assert_eq!(responses[0]["success"], json!(true));
+        assert_eq!(responses[0]["body"]["supportsStepBack"], json!(true));
+        assert_eq!(responses[1]["request_seq"], json!(2));
+        assert_eq!(responses[1]["success"], json!(false));
+        assert_eq!(responses[2]["command"], json!("disconnect"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_launch_and_stack_trace() {
+        let input = client_input(&[
+            json!({ "seq": 1, "type": "request", "command": "initialize", "arguments": {} }),
+            json!({ "seq": 2, "type": "request", "command": "launch", "arguments": {} }),
+            json!({ "seq": 3, "type": "request", "command": "configurationDone" }),
+            json!({ "seq": 4, "type": "request", "command": "stackTrace", "arguments": { "threadId": THREAD_ID } }),
+            json!({ "seq": 5, "type": "request", "command": "disconnect" }),
+        ]);
+
+        let mut output = Vec::new();
+        DebugAdapter::new(&mut output)
+            .run(input.as_bytes(), |_| async {
+                start_debug_server(prepare_test_context().await).await
+            })
+            .await
+            .unwrap();
+
+        // Every message on the output is a framed protocol message
+        let messages = adapter_output(output);
+        let response = |seq: u64| {
+            messages
+                .iter()
+                .find(|message| {
+                    message["type"] == json!("response") && message["request_seq"] == json!(seq)
+                })
+                .unwrap()
+        };
+        for seq in 1..=5 {
+            assert_eq!(response(seq)["success"], json!(true), "{}", response(seq));
+        }
+
+        let events: Vec<&Value> =
+            messages.iter().filter(|message| message["type"] == json!("event")).collect();
+        assert_eq!(events[0]["event"], json!("initialized"));
+        assert_eq!(events[1]["event"], json!("stopped"));
+        assert_eq!(events[1]["body"]["reason"], json!("entry"));
+
+        // Stopped on entry, in the outermost call
+        let frames = response(4)["body"]["stackFrames"].as_array().unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0]["id"], json!(1));
+        assert!(frames[0]["name"].as_str().unwrap().contains("(pc 0)"), "{}", frames[0]);
+    }
+
+    /// Counts the calls of each method, returning no logpoint messages
+    #[derive(Default)]
+    struct CountingMethods {
+        calls: parking_lot::Mutex<BTreeMap<String, usize>>,
+    }
+
+    impl MethodDispatch for CountingMethods {
+        fn dispatch<'a>(
+            &'a self,
+            method: &'a str,
+            _params: Option<Value>,
+        ) -> crate::DispatchFuture<'a> {
+            *self.calls.lock().entry(method.to_string()).or_default() += 1;
+            Box::pin(async { Ok(json!([])) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_logpoint_log_is_cached() {
+        let methods = Arc::new(CountingMethods::default());
+        let mut adapter = DebugAdapter::new(Vec::new());
+        adapter.methods = Some(methods.clone());
+        let collected = || methods.calls.lock().get(method::COLLECT_LOGPOINTS).copied();
+
+        let logpoint = Breakpoint { log: Some("x = {x}".to_string()), ..Default::default() };
+        adapter.logpoint_log(vec![logpoint.clone()]).await.unwrap();
+        adapter.logpoint_log(vec![logpoint.clone()]).await.unwrap();
+        assert_eq!(collected(), Some(1));
+
+        // Changed logpoints are collected again
+        let other = Breakpoint { log: Some("y = {y}".to_string()), ..Default::default() };
+        adapter.logpoint_log(vec![logpoint, other]).await.unwrap();
+        assert_eq!(collected(), Some(2));
+    }
+
+    #[test]
+    fn test_frame_snapshot() {
+        let request: Request = serde_json::from_value(
+            json!({ "seq": 1, "command": "scopes", "arguments": { "frameId": 43 } }),
+        )
+        .unwrap();
+        assert_eq!(frame_snapshot(&request), Some(42));
+
+        let request: Request = serde_json::from_value(
+            json!({ "seq": 2, "command": "evaluate", "arguments": { "expression": "x" } }),
+        )
+        .unwrap();
+        assert_eq!(frame_snapshot(&request), None);
+    }
+}
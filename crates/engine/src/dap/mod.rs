@@ -0,0 +1,79 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Debug Adapter Protocol (DAP) server for EDB.
+//!
+//! This module lets editors with DAP support (VS Code, Neovim, JetBrains IDEs) debug
+//! transactions without a bespoke extension. The adapter speaks DAP over stdio or TCP and
+//! maps its requests onto the RPC methods of the debug server of the transaction.
+//!
+//! # Session
+//!
+//! 1. The client sends `initialize`, then `launch` with the transaction to debug; how the
+//!    launch arguments are interpreted is up to the caller, e.g. `{ "txHash": "0x..." }`.
+//! 2. Once the transaction is prepared, the adapter raises `initialized` and the client
+//!    sends its source, function and exception breakpoints, then `configurationDone`.
+//! 3. The adapter stops at the first snapshot (unless `stopOnEntry` is `false`) and serves
+//!    stepping, stack, variable and evaluation requests until `disconnect`.
+//!
+//! Function breakpoints accept any symbolic breakpoint location, e.g. `Vault.withdraw`,
+//! `event:Transfer(address,address,uint256)` or `call:0x...`, and the `revert` exception
+//! filter stops at reverts. Hit conditions are numbers of hits, and log messages use the
+//! logpoint format (`amount = {amount}`).
+
+pub mod adapter;
+pub mod protocol;
+
+pub use adapter::DebugAdapter;
+
+use std::{future::Future, net::SocketAddr};
+
+use eyre::Result;
+use serde_json::Value;
+use tokio::{io::BufReader, net::TcpListener};
+use tracing::info;
+
+use crate::RpcServerHandle;
+
+/// Serve a DAP client over stdin and stdout.
+///
+/// `launch` prepares the transaction named by the arguments of the `launch` request.
+pub async fn serve_dap_stdio<F, Fut>(launch: F) -> Result<()>
+where
+    F: FnOnce(Value) -> Fut,
+    Fut: Future<Output = Result<RpcServerHandle>>,
+{
+    info!("DAP server serving stdio");
+    DebugAdapter::new(tokio::io::stdout()).run(BufReader::new(tokio::io::stdin()), launch).await
+}
+
+/// Serve the first DAP client connecting to `port` on localhost.
+///
+/// `launch` prepares the transaction named by the arguments of the `launch` request.
+pub async fn serve_dap_tcp<F, Fut>(port: u16, launch: F) -> Result<()>
+where
+    F: FnOnce(Value) -> Fut,
+    Fut: Future<Output = Result<RpcServerHandle>>,
+{
+    let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], port))).await?;
+    info!("DAP server listening on {}", listener.local_addr()?);
+
+    let (stream, peer) = listener.accept().await?;
+    info!("DAP client connected from {}", peer);
+
+    let (reader, writer) = stream.into_split();
+    DebugAdapter::new(writer).run(BufReader::new(reader), launch).await
+}
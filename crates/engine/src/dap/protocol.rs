@@ -0,0 +1,180 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Debug Adapter Protocol messages and their wire format.
+//!
+//! Every message is a JSON object preceded by a `Content-Length` header, as in the
+//! Language Server Protocol. Only the fields EDB relies on are modeled; request
+//! arguments and response bodies stay as raw JSON values.
+
+use eyre::{bail, eyre, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// A request sent by the client.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Request {
+    /// Sequence number of the request
+    pub seq: i64,
+    /// The command to execute, e.g. `stackTrace`
+    pub command: String,
+    /// Arguments of the command
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+impl Request {
+    /// Get an argument of the request
+    pub fn arg(&self, name: &str) -> Option<&Value> {
+        self.arguments.get(name)
+    }
+}
+
+/// A message sent by the adapter.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum OutgoingMessage {
+    /// Response to a request
+    Response {
+        /// Sequence number of the message
+        seq: i64,
+        /// Sequence number of the request answered
+        request_seq: i64,
+        /// Whether the request succeeded
+        success: bool,
+        /// The command of the request answered
+        command: String,
+        /// Error message, if the request failed
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+        /// Result of the request
+        #[serde(skip_serializing_if = "Option::is_none")]
+        body: Option<Value>,
+    },
+    /// Event raised by the adapter
+    Event {
+        /// Sequence number of the message
+        seq: i64,
+        /// Type of the event, e.g. `stopped`
+        event: String,
+        /// Details of the event
+        #[serde(skip_serializing_if = "Option::is_none")]
+        body: Option<Value>,
+    },
+}
+
+/// Read the next message, or `None` once the client closes the stream.
+pub async fn read_message<R>(reader: &mut R) -> Result<Option<Request>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            // Tolerate blank lines between messages
+            continue;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("Content-Length") {
+                content_length = Some(
+                    value
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|e| eyre!("Invalid header {line}: {e}"))?,
+                );
+            }
+        } else {
+            bail!("Invalid header: {line}");
+        }
+    }
+
+    let mut content = vec![0; content_length.unwrap_or_default()];
+    reader.read_exact(&mut content).await?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+/// Write a message with its header.
+pub async fn write_message<W>(writer: &mut W, message: &OutgoingMessage) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let content = serde_json::to_vec(message)?;
+    writer.write_all(format!("Content-Length: {}\r\n\r\n", content.len()).as_bytes()).await?;
+    writer.write_all(&content).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::io::BufReader;
+
+    #[tokio::test]
+    async fn test_read_messages() {
+        let first =
+            r#"{"seq":1,"type":"request","command":"initialize","arguments":{"adapterID":"edb"}}"#;
+        let second = r#"{"seq":2,"type":"request","command":"threads"}"#;
+        let input = format!(
+            "Content-Length: {}\r\n\r\n{first}Content-Length: {}\r\n\r\n{second}",
+            first.len(),
+            second.len()
+        );
+        let mut reader = BufReader::new(input.as_bytes());
+
+        let request = read_message(&mut reader).await.unwrap().unwrap();
+        assert_eq!(request.seq, 1);
+        assert_eq!(request.command, "initialize");
+        assert_eq!(request.arg("adapterID"), Some(&json!("edb")));
+
+        let request = read_message(&mut reader).await.unwrap().unwrap();
+        assert_eq!(request.command, "threads");
+        assert_eq!(request.arguments, Value::Null);
+
+        assert!(read_message(&mut reader).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_write_message() {
+        let mut output = Vec::new();
+        let message = OutgoingMessage::Event {
+            seq: 3,
+            event: "stopped".to_string(),
+            body: Some(json!({ "reason": "entry" })),
+        };
+        write_message(&mut output, &message).await.unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let (header, content) = output.split_once("\r\n\r\n").unwrap();
+        assert_eq!(header, format!("Content-Length: {}", content.len()));
+        assert_eq!(
+            serde_json::from_str::<Value>(content).unwrap(),
+            json!({ "type": "event", "seq": 3, "event": "stopped", "body": { "reason": "entry" } })
+        );
+    }
+}
@@ -22,7 +22,8 @@
 use std::sync::Arc;
 
 use alloy_dyn_abi::DynSolValue;
-use eyre::Result;
+use edb_common::types::EvalCall;
+use eyre::{eyre, Result};
 use revm::{database::CacheDB, Database, DatabaseCommit, DatabaseRef};
 
 use crate::{EngineContext, ExpressionEvaluator};
@@ -70,3 +71,58 @@ where
     let evaluator = ExpressionEvaluator::new_edb(context);
     evaluator.eval(expr, snapshot_id)
 }
+
+/// Evaluate a Solidity expression string within the context of a specific debug snapshot,
+/// also returning the contract calls made during the evaluation.
+///
+/// Calls may carry options, e.g. `withdraw{from: owner}()`. Each executed call is recorded
+/// with its revert reason and emitted events, even if it made the evaluation fail.
+///
+/// # Arguments
+/// * `context` - The EDB engine context containing snapshots and trace data
+/// * `expr` - The expression string to evaluate (e.g., "token.transfer{from: alice}(bob, 5)")
+/// * `snapshot_id` - The ID of the debug snapshot to evaluate against
+///
+/// # Returns
+/// The result of the expression evaluation together with the recorded calls
+pub fn eval_on_snapshot_with_calls<DB>(
+    context: Arc<EngineContext<DB>>,
+    expr: &str,
+    snapshot_id: usize,
+) -> (Result<DynSolValue>, Vec<EvalCall>)
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let evaluator = ExpressionEvaluator::new_edb(context);
+    evaluator.eval_with_calls(expr, snapshot_id)
+}
+
+/// Resolve the snapshot at which expressions are evaluated in a frame of the call stack.
+///
+/// Frame 0 is the snapshot itself, and frame `n` is the most recent snapshot of its `n`-th
+/// caller (see [`EngineContext::get_call_stack`]), whose locals and `this` are then resolved.
+///
+/// # Errors
+/// Returns an error if the snapshot does not exist or the call stack has no such frame.
+pub fn frame_snapshot<DB>(
+    context: &EngineContext<DB>,
+    snapshot_id: usize,
+    frame: usize,
+) -> Result<usize>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let frames = context
+        .get_call_stack(snapshot_id)
+        .ok_or_else(|| eyre!("Snapshot with id {snapshot_id} not found"))?;
+    frames.get(frame).copied().ok_or_else(|| {
+        eyre!(
+            "Frame {frame} not found: the call stack at snapshot {snapshot_id} has {} frames",
+            frames.len()
+        )
+    })
+}
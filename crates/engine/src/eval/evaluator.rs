@@ -16,19 +16,20 @@
 
 use std::sync::Arc;
 
-use alloy_dyn_abi::DynSolValue;
-use alloy_primitives::{Address, B256, I256, U256};
-use eyre::{bail, Result};
+use alloy_dyn_abi::{DynSolType, DynSolValue};
+use alloy_primitives::{keccak256, Address, B256, I256, U256};
+use edb_common::types::{CallOptions, EvalCall};
+use eyre::{bail, eyre, Result};
 use revm::database::CacheDB;
 use revm::{Database, DatabaseCommit, DatabaseRef};
-use solang_parser::pt::{Expression, Identifier, Loc, Parameter, Type};
+use solang_parser::pt::{Expression, Identifier, Loc, NamedArgument, Parameter, Statement, Type};
 
 use crate::eval::handlers::debug::create_debug_handlers;
 use crate::eval::handlers::edb::EdbHandler;
 use crate::EngineContext;
 
-use super::handlers::EvaluatorHandlers;
-use super::utils::parse_input;
+use super::handlers::{BlockHandler, EvaluatorHandlers, MsgHandler, TxHandler};
+use super::utils::{parse_input, TIME_TRAVEL_PREFIX};
 
 /// Main expression evaluator for Solidity-like expressions.
 ///
@@ -51,16 +52,21 @@ use super::utils::parse_input;
 /// # Supported Operations
 ///
 /// - **Variables**: Local variables, state variables, special variables (`this`)
-/// - **Literals**: Numbers (decimal/hex), strings, booleans, addresses
+/// - **Literals**: Numbers (decimal/hex/rational, with units like `1 ether` or `1 days`),
+///   strings, booleans, addresses
 /// - **Arithmetic**: `+`, `-`, `*`, `/`, `%`, `**`
 /// - **Comparison**: `==`, `!=`, `<`, `<=`, `>`, `>=`
 /// - **Logical**: `&&`, `||`, `!`
 /// - **Bitwise**: `&`, `|`, `^`, `~`, `<<`, `>>`
 /// - **Indexing**: Arrays and mappings with bracket notation
 /// - **Member Access**: Dot notation for structs and contract members
-/// - **Function Calls**: Contract functions and built-in functions
+/// - **Function Calls**: Contract functions (also with named arguments) and built-in functions
+/// - **ABI Built-ins**: `abi.encode`, `abi.encodePacked`, `abi.encodeWithSelector`,
+///   `abi.encodeWithSignature`, `abi.decode`, `bytes.concat`, and `string.concat`
+/// - **Type Information**: `type(T).min` and `type(T).max` for integer types
 /// - **Type Casting**: Explicit type conversions (e.g., `uint256(value)`)
 /// - **Ternary**: Conditional operator `? :`
+/// - **Time Travel**: Sub-expressions at other snapshots (e.g., `@entry(totalSupply)`)
 ///
 /// # Example
 ///
@@ -111,6 +117,24 @@ impl ExpressionEvaluator {
 
     /// Evaluate an expression against a specific snapshot
     pub fn eval(&self, expr: &str, snapshot_id: usize) -> Result<DynSolValue> {
+        self.eval_with_calls(expr, snapshot_id).0
+    }
+
+    /// Evaluate an expression against a specific snapshot, also returning the contract
+    /// calls made during the evaluation (with their revert reasons and events)
+    pub fn eval_with_calls(
+        &self,
+        expr: &str,
+        snapshot_id: usize,
+    ) -> (Result<DynSolValue>, Vec<EvalCall>) {
+        // Discard calls left over from an interrupted evaluation
+        self.take_recorded_calls();
+
+        let value = self.eval_inner(expr, snapshot_id);
+        (value, self.take_recorded_calls())
+    }
+
+    fn eval_inner(&self, expr: &str, snapshot_id: usize) -> Result<DynSolValue> {
         // Parse the expression
         let parsed_expr =
             parse_input(expr).map_err(|_| eyre::eyre!("Invalid expression \"{expr}\""))?;
@@ -130,12 +154,11 @@ impl ExpressionEvaluator {
     fn evaluate_expression(&self, expr: &Expression, snapshot_id: usize) -> Result<DynSolValue> {
         match expr {
             // Literals
-            Expression::NumberLiteral(_, value, _, ident) => {
-                if ident.is_none() {
-                    self.evaluate_number_literal(value)
-                } else {
-                    bail!("Invalid number literal")
-                }
+            Expression::NumberLiteral(_, value, exponent, unit) => {
+                self.evaluate_decimal_literal(value, "", exponent, unit.as_ref())
+            }
+            Expression::RationalNumberLiteral(_, integer, fraction, exponent, unit) => {
+                self.evaluate_decimal_literal(integer, fraction, exponent, unit.as_ref())
             }
             Expression::HexNumberLiteral(_, value, ident) => {
                 if ident.is_none() {
@@ -165,6 +188,9 @@ impl ExpressionEvaluator {
             Expression::FunctionCall(_, func, args) => {
                 self.evaluate_function_call(func, args, snapshot_id)
             }
+            Expression::NamedFunctionCall(_, func, args) => {
+                self.evaluate_named_function_call(func, args, snapshot_id)
+            }
 
             // Arithmetic operations
             Expression::Add(_, left, right) => {
@@ -281,8 +307,6 @@ impl ExpressionEvaluator {
             | Expression::AssignOr(..)
             | Expression::AssignXor(..)
             | Expression::FunctionCallBlock(..)
-            | Expression::NamedFunctionCall(..)
-            | Expression::RationalNumberLiteral(..)
             | Expression::Type(..) => bail!("Unsupported expression type: {:?}", expr),
         }
     }
@@ -302,6 +326,60 @@ impl ExpressionEvaluator {
         Ok(DynSolValue::Uint(val, 256))
     }
 
+    /// Evaluate decimal literals with an optional fraction, exponent, and unit
+    /// (e.g., `1e18`, `0.5 ether`, `2 days`). The resulting value must be an integer.
+    fn evaluate_decimal_literal(
+        &self,
+        integer: &str,
+        fraction: &str,
+        exponent: &str,
+        unit: Option<&Identifier>,
+    ) -> Result<DynSolValue> {
+        let multiplier = match unit.map(|unit| unit.name.as_str()) {
+            None | Some("wei") | Some("seconds") => U256::from(1),
+            Some("gwei") => U256::from(1_000_000_000u64),
+            Some("ether") => U256::from(1_000_000_000_000_000_000u64),
+            Some("minutes") => U256::from(60),
+            Some("hours") => U256::from(3_600),
+            Some("days") => U256::from(86_400),
+            Some("weeks") => U256::from(604_800),
+            Some(unit) => bail!("Unknown unit: {unit}"),
+        };
+
+        let fraction = fraction.replace('_', "");
+        let fraction = fraction.trim_end_matches('0');
+        let digits = format!("{}{fraction}", integer.replace('_', ""));
+        let exponent: i64 = match exponent.replace('_', "") {
+            e if e.is_empty() => 0,
+            e => e.parse().map_err(|_| eyre!("Invalid exponent: {e}"))?,
+        };
+
+        let out_of_range = || eyre!("Number literal is out of the uint256 range");
+        let mantissa =
+            if digits.is_empty() { U256::ZERO } else { U256::from_str_radix(&digits, 10)? };
+        let value = mantissa.checked_mul(multiplier).ok_or_else(out_of_range)?;
+
+        // Shift the decimal point by the exponent, net of the fraction digits
+        let scale = exponent - fraction.len() as i64;
+        let ten = U256::from(10);
+        let value = if scale >= 0 {
+            let factor = ten.checked_pow(U256::from(scale)).ok_or_else(out_of_range)?;
+            if value.is_zero() {
+                value
+            } else {
+                value.checked_mul(factor).ok_or_else(out_of_range)?
+            }
+        } else {
+            match ten.checked_pow(U256::from(-scale)) {
+                Some(divisor) if (value % divisor).is_zero() => value / divisor,
+                None if value.is_zero() => value,
+                _ => bail!("Number literal is not an integer"),
+            }
+        };
+
+        Ok(DynSolValue::Uint(value, 256))
+    }
+
     /// Evaluate string literals
     fn evaluate_string_literal(
         &self,
@@ -341,14 +419,45 @@ impl ExpressionEvaluator {
         member: &Identifier,
         snapshot_id: usize,
     ) -> Result<DynSolValue> {
+        // Type information like type(uint8).max
+        if let Expression::FunctionCall(_, func, args) = base {
+            if matches!(func.as_ref(), Expression::Variable(ident) if ident.name == "type") {
+                return self.evaluate_type_property(args, &member.name);
+            }
+        }
+
         // Special handling for global variables (msg, tx, block)
         if let Expression::Variable(base_ident) = base {
             match (base_ident.name.as_str(), member.name.as_str()) {
                 ("msg", "sender") => return self.get_msg_sender(snapshot_id),
                 ("msg", "value") => return self.get_msg_value(snapshot_id),
+                ("msg", "data") => return self.with_msg_handler(|h| h.get_msg_data(snapshot_id)),
+                ("msg", "sig") => return self.with_msg_handler(|h| h.get_msg_sig(snapshot_id)),
                 ("tx", "origin") => return self.get_tx_origin(snapshot_id),
+                ("tx", "gasprice") => {
+                    return self.with_tx_handler(|h| h.get_tx_gas_price(snapshot_id))
+                }
                 ("block", "number") => return self.get_block_number(snapshot_id),
                 ("block", "timestamp") => return self.get_block_timestamp(snapshot_id),
+                ("block", "basefee") => {
+                    return self.with_block_handler(|h| h.get_block_basefee(snapshot_id))
+                }
+                ("block", "chainid") => {
+                    return self.with_block_handler(|h| h.get_block_chainid(snapshot_id))
+                }
+                ("block", "coinbase") => {
+                    return self.with_block_handler(|h| h.get_block_coinbase(snapshot_id))
+                }
+                // `block.difficulty` is an alias of `block.prevrandao` since the merge
+                ("block", "prevrandao" | "difficulty") => {
+                    return self.with_block_handler(|h| h.get_block_prevrandao(snapshot_id))
+                }
+                ("block", "gaslimit") => {
+                    return self.with_block_handler(|h| h.get_block_gaslimit(snapshot_id))
+                }
+                ("block", "blobbasefee") => {
+                    return self.with_block_handler(|h| h.get_block_blobbasefee(snapshot_id))
+                }
                 // Additional msg/tx/block properties should be handled by extending handlers
                 // rather than hardcoding values here
                 _ => {}
@@ -427,6 +536,45 @@ impl ExpressionEvaluator {
         args: &[Expression],
         snapshot_id: usize,
     ) -> Result<DynSolValue> {
+        // Call options like `withdraw{from: owner}()` only apply to calls into contracts
+        if let Expression::FunctionCallBlock(_, func, block) = func {
+            let options = self.evaluate_call_options(block, snapshot_id)?;
+            let arg_values = args
+                .iter()
+                .map(|arg| self.evaluate_expression(arg, snapshot_id))
+                .collect::<Result<Vec<_>>>()?;
+            let (func_name, callee) = self.resolve_callee(func, snapshot_id)?;
+            return self.call_function(
+                &func_name,
+                &arg_values,
+                callee.as_ref(),
+                &options,
+                snapshot_id,
+            );
+        }
+
+        // Time-travel expressions evaluate their argument at another snapshot
+        if let Expression::Variable(ident) = func {
+            if let Some(selector) = ident.name.strip_prefix(TIME_TRAVEL_PREFIX) {
+                return self.evaluate_time_travel(selector, args, snapshot_id);
+            }
+        }
+
+        // Built-in namespaces, whose arguments may be types
+        if let Expression::MemberAccess(_, base, member) = func {
+            match base.as_ref() {
+                Expression::Variable(ident) if ident.name == "abi" => {
+                    return self.evaluate_abi_call(&member.name, args, snapshot_id);
+                }
+                Expression::Type(_, ty @ (Type::DynamicBytes | Type::String))
+                    if member.name == "concat" =>
+                {
+                    return self.evaluate_concat(ty, args, snapshot_id);
+                }
+                _ => {}
+            }
+        }
+
         // Evaluate arguments
         let arg_values = args
             .iter()
@@ -435,7 +583,15 @@ impl ExpressionEvaluator {
 
         // Determine function name and callee
         let (func_name, callee) = match func {
-            Expression::Variable(ident) => (ident.name.clone(), None),
+            Expression::Variable(ident) => match (ident.name.as_str(), arg_values.as_slice()) {
+                ("blockhash", [DynSolValue::Uint(number, _)]) => {
+                    return self.with_block_handler(|h| h.get_block_hash(*number, snapshot_id));
+                }
+                ("blockhash", _) => bail!("blockhash() expects a single uint argument"),
+                ("gasleft", []) => return self.with_msg_handler(|h| h.get_gas_left(snapshot_id)),
+                ("gasleft", _) => bail!("gasleft() takes no arguments"),
+                _ => (ident.name.clone(), None),
+            },
             Expression::MemberAccess(_, base, member) => {
                 let callee = self.evaluate_expression(base, snapshot_id)?;
 
@@ -457,7 +613,260 @@ impl ExpressionEvaluator {
         };
 
         // Call the function
-        self.call_function(&func_name, &arg_values, callee.as_ref(), snapshot_id)
+        self.call_function(
+            &func_name,
+            &arg_values,
+            callee.as_ref(),
+            &CallOptions::default(),
+            snapshot_id,
+        )
+    }
+
+    /// Evaluate function calls with named arguments, e.g. `transfer({to: user, amount: 1})`
+    fn evaluate_named_function_call(
+        &self,
+        func: &Expression,
+        args: &[NamedArgument],
+        snapshot_id: usize,
+    ) -> Result<DynSolValue> {
+        let (func, options) = match func {
+            Expression::FunctionCallBlock(_, func, block) => {
+                (func.as_ref(), self.evaluate_call_options(block, snapshot_id)?)
+            }
+            _ => (func, CallOptions::default()),
+        };
+
+        let arg_values = args
+            .iter()
+            .map(|arg| {
+                Ok((arg.name.name.clone(), self.evaluate_expression(&arg.expr, snapshot_id)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let (func_name, callee) = self.resolve_callee(func, snapshot_id)?;
+        self.call_function_with_named_args(
+            &func_name,
+            &arg_values,
+            callee.as_ref(),
+            &options,
+            snapshot_id,
+        )
+    }
+
+    /// Resolve the name and callee of a call into a contract, e.g. `token.transfer`
+    fn resolve_callee(
+        &self,
+        func: &Expression,
+        snapshot_id: usize,
+    ) -> Result<(String, Option<DynSolValue>)> {
+        match func {
+            Expression::Variable(ident) => Ok((ident.name.clone(), None)),
+            Expression::MemberAccess(_, base, member) => {
+                Ok((member.name.clone(), Some(self.evaluate_expression(base, snapshot_id)?)))
+            }
+            _ => bail!("Unsupported function call expression: {}", func),
+        }
+    }
+
+    /// Evaluate call options like `{from: owner, value: 1 ether, gas: 100000}`
+    fn evaluate_call_options(&self, block: &Statement, snapshot_id: usize) -> Result<CallOptions> {
+        let Statement::Args(_, args) = block else {
+            bail!("Unsupported call options: {}", block);
+        };
+
+        let mut options = CallOptions::default();
+        for arg in args {
+            let value = self.evaluate_expression(&arg.expr, snapshot_id)?;
+            match (arg.name.name.as_str(), value) {
+                ("from", DynSolValue::Address(from)) if options.from.is_none() => {
+                    options.from = Some(from);
+                }
+                ("value", DynSolValue::Uint(value, _)) if options.value.is_none() => {
+                    options.value = Some(value);
+                }
+                ("gas", DynSolValue::Uint(gas, _)) if options.gas.is_none() => {
+                    options.gas =
+                        Some(gas.try_into().map_err(|_| eyre!("Gas limit {gas} is too large"))?);
+                }
+                (name @ ("from" | "value" | "gas"), value) => {
+                    bail!("Invalid or duplicate call option {name}: {value:?}")
+                }
+                (name, _) => bail!("Unsupported call option: {name} (expected from, value or gas)"),
+            }
+        }
+        Ok(options)
+    }
+
+    /// Evaluate type information like `type(uint8).max`
+    fn evaluate_type_property(&self, args: &[Expression], property: &str) -> Result<DynSolValue> {
+        let [Expression::Type(_, ty)] = args else {
+            bail!("type(...) expects a single elementary type");
+        };
+
+        match (ty, property) {
+            (Type::Uint(bits), "min") => Ok(DynSolValue::Uint(U256::ZERO, *bits as usize)),
+            (Type::Uint(bits), "max") => {
+                Ok(DynSolValue::Uint(U256::MAX >> (256 - *bits as usize), *bits as usize))
+            }
+            (Type::Int(bits), "min") => {
+                let min = I256::MIN.asr(256 - *bits as usize);
+                Ok(DynSolValue::Int(min, *bits as usize))
+            }
+            (Type::Int(bits), "max") => {
+                let max = I256::MAX.asr(256 - *bits as usize);
+                Ok(DynSolValue::Int(max, *bits as usize))
+            }
+            _ => bail!("type({ty}).{property} is not supported"),
+        }
+    }
+
+    /// Evaluate functions of the `abi` namespace
+    fn evaluate_abi_call(
+        &self,
+        function: &str,
+        args: &[Expression],
+        snapshot_id: usize,
+    ) -> Result<DynSolValue> {
+        if function == "decode" {
+            let [data, types] = args else {
+                bail!("abi.decode expects a bytes value and a list of types");
+            };
+            let DynSolValue::Bytes(data) = self.evaluate_expression(data, snapshot_id)? else {
+                bail!("abi.decode expects a bytes value to decode");
+            };
+            let types = match types {
+                Expression::List(_, params) => params
+                    .iter()
+                    .map(|(_, param)| match param {
+                        Some(param) => self.resolve_abi_type(&param.ty),
+                        None => bail!("Invalid none type in abi.decode"),
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                ty => vec![self.resolve_abi_type(ty)?],
+            };
+
+            // A single type decodes to the value itself rather than a tuple
+            return match DynSolType::Tuple(types).abi_decode_params(&data)? {
+                DynSolValue::Tuple(mut values) if values.len() == 1 => Ok(values.remove(0)),
+                values => Ok(values),
+            };
+        }
+
+        let mut values = args
+            .iter()
+            .map(|arg| self.evaluate_expression(arg, snapshot_id))
+            .collect::<Result<Vec<_>>>()?;
+
+        let encoded = match function {
+            "encode" => DynSolValue::Tuple(values).abi_encode_params(),
+            "encodePacked" => DynSolValue::Tuple(values).abi_encode_packed(),
+            "encodeWithSelector" | "encodeWithSignature" => {
+                if values.is_empty() {
+                    bail!("abi.{function} expects a selector or signature as first argument");
+                }
+                let selector = match values.remove(0) {
+                    DynSolValue::FixedBytes(bytes, 4) => bytes[..4].to_vec(),
+                    DynSolValue::String(signature) if function == "encodeWithSignature" => {
+                        keccak256(signature.as_bytes())[..4].to_vec()
+                    }
+                    other => bail!("Invalid first argument of abi.{function}: {:?}", other),
+                };
+
+                let mut encoded = selector;
+                encoded.extend(DynSolValue::Tuple(values).abi_encode_params());
+                encoded
+            }
+            _ => bail!("Unsupported ABI function: abi.{function}"),
+        };
+
+        Ok(DynSolValue::Bytes(encoded))
+    }
+
+    /// Resolve a type written in an expression (e.g., in `abi.decode`) into an ABI type
+    fn resolve_abi_type(&self, expr: &Expression) -> Result<DynSolType> {
+        match expr {
+            Expression::Type(_, ty) => match ty {
+                Type::Address | Type::AddressPayable => Ok(DynSolType::Address),
+                Type::Bool => Ok(DynSolType::Bool),
+                Type::String => Ok(DynSolType::String),
+                Type::DynamicBytes => Ok(DynSolType::Bytes),
+                Type::Bytes(size) => Ok(DynSolType::FixedBytes(*size as usize)),
+                Type::Uint(bits) => Ok(DynSolType::Uint(*bits as usize)),
+                Type::Int(bits) => Ok(DynSolType::Int(*bits as usize)),
+                _ => bail!("Unsupported ABI type: {ty}"),
+            },
+            Expression::ArraySubscript(_, inner, None) => {
+                Ok(DynSolType::Array(Box::new(self.resolve_abi_type(inner)?)))
+            }
+            Expression::ArraySubscript(_, inner, Some(size)) => {
+                let size = match size.as_ref() {
+                    Expression::NumberLiteral(_, size, exponent, None) if exponent.is_empty() => {
+                        size.parse().map_err(|_| eyre!("Invalid array size: {size}"))?
+                    }
+                    _ => bail!("Array size must be a number literal, got {size}"),
+                };
+                Ok(DynSolType::FixedArray(Box::new(self.resolve_abi_type(inner)?), size))
+            }
+            Expression::Parenthesis(_, inner) => self.resolve_abi_type(inner),
+            Expression::List(_, params) => Ok(DynSolType::Tuple(
+                params
+                    .iter()
+                    .map(|(_, param)| match param {
+                        Some(param) => self.resolve_abi_type(&param.ty),
+                        None => bail!("Invalid none type in tuple"),
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            )),
+            _ => bail!("Expected an ABI type, got {expr}"),
+        }
+    }
+
+    /// Evaluate `bytes.concat(...)` and `string.concat(...)`
+    fn evaluate_concat(
+        &self,
+        ty: &Type,
+        args: &[Expression],
+        snapshot_id: usize,
+    ) -> Result<DynSolValue> {
+        let mut result = Vec::new();
+        for arg in args {
+            match (ty, self.evaluate_expression(arg, snapshot_id)?) {
+                (Type::DynamicBytes, DynSolValue::Bytes(bytes)) => result.extend(bytes),
+                (Type::DynamicBytes, DynSolValue::FixedBytes(bytes, size)) => {
+                    result.extend_from_slice(&bytes[..size])
+                }
+                (_, DynSolValue::String(s)) => result.extend(s.into_bytes()),
+                (_, value) => bail!("Cannot concatenate {:?} with {ty}.concat", value),
+            }
+        }
+
+        match ty {
+            Type::String => Ok(DynSolValue::String(
+                String::from_utf8(result).map_err(|_| eyre!("Invalid UTF-8 bytes"))?,
+            )),
+            _ => Ok(DynSolValue::Bytes(result)),
+        }
+    }
+
+    /// Evaluate a time-travel expression like `@entry(x)` at the selected snapshot
+    fn evaluate_time_travel(
+        &self,
+        selector: &str,
+        args: &[Expression],
+        snapshot_id: usize,
+    ) -> Result<DynSolValue> {
+        let [arg] = args else {
+            bail!("@{selector}(...) expects exactly one expression, got {}", args.len());
+        };
+
+        let target_id = self.resolve_snapshot(selector, snapshot_id)?;
+        let value = self.evaluate_expression(arg, target_id)?;
+
+        // Placeholders are only meaningful at the snapshot they were resolved at
+        match &self.handlers.validation_handler {
+            Some(validation_handler) => validation_handler.validate_value(value),
+            None => Ok(value),
+        }
     }
 
     /// Handle built-in properties (like string.length, address.balance)
@@ -1229,6 +1638,14 @@ impl ExpressionEvaluator {
         }
     }
 
+    /// Resolve a time-travel selector to a snapshot using handler
+    fn resolve_snapshot(&self, selector: &str, snapshot_id: usize) -> Result<usize> {
+        match &self.handlers.snapshot_handler {
+            Some(handler) => handler.resolve_snapshot(selector, snapshot_id),
+            None => bail!("No snapshot handler configured"),
+        }
+    }
+
     /// Get mapping or array value using multiple indices
     fn get_mapping_or_array_value(
         &self,
@@ -1248,14 +1665,39 @@ impl ExpressionEvaluator {
         name: &str,
         args: &[DynSolValue],
         callee: Option<&DynSolValue>,
+        options: &CallOptions,
         snapshot_id: usize,
     ) -> Result<DynSolValue> {
         match &self.handlers.function_call_handler {
-            Some(handler) => handler.call_function(name, args, callee, snapshot_id),
+            Some(handler) => handler.call_function(name, args, callee, options, snapshot_id),
             None => bail!("No function call handler configured"),
         }
     }
 
+    fn call_function_with_named_args(
+        &self,
+        name: &str,
+        args: &[(String, DynSolValue)],
+        callee: Option<&DynSolValue>,
+        options: &CallOptions,
+        snapshot_id: usize,
+    ) -> Result<DynSolValue> {
+        match &self.handlers.function_call_handler {
+            Some(handler) => {
+                handler.call_function_with_named_args(name, args, callee, options, snapshot_id)
+            }
+            None => bail!("No function call handler configured"),
+        }
+    }
+
+    /// Take the contract calls recorded by the function call handler
+    fn take_recorded_calls(&self) -> Vec<EvalCall> {
+        match &self.handlers.function_call_handler {
+            Some(handler) => handler.take_recorded_calls(),
+            None => Vec::new(),
+        }
+    }
+
     /// Access member of a value (for handler delegation)
     fn access_member(
         &self,
@@ -1310,6 +1752,39 @@ impl ExpressionEvaluator {
             None => bail!("No block handler configured"),
         }
     }
+
+    /// Resolve a global through the msg handler
+    fn with_msg_handler(
+        &self,
+        f: impl FnOnce(&dyn MsgHandler) -> Result<DynSolValue>,
+    ) -> Result<DynSolValue> {
+        match &self.handlers.msg_handler {
+            Some(handler) => f(handler.as_ref()),
+            None => bail!("No msg handler configured"),
+        }
+    }
+
+    /// Resolve a global through the tx handler
+    fn with_tx_handler(
+        &self,
+        f: impl FnOnce(&dyn TxHandler) -> Result<DynSolValue>,
+    ) -> Result<DynSolValue> {
+        match &self.handlers.tx_handler {
+            Some(handler) => f(handler.as_ref()),
+            None => bail!("No tx handler configured"),
+        }
+    }
+
+    /// Resolve a global through the block handler
+    fn with_block_handler(
+        &self,
+        f: impl FnOnce(&dyn BlockHandler) -> Result<DynSolValue>,
+    ) -> Result<DynSolValue> {
+        match &self.handlers.block_handler {
+            Some(handler) => f(handler.as_ref()),
+            None => bail!("No block handler configured"),
+        }
+    }
 }
 
 // Operation enums for better code organization
@@ -1353,6 +1828,7 @@ mod tests {
     use super::*;
     use crate::eval::handlers::debug::{create_debug_handlers, create_simulation_debug_handlers};
     use crate::eval::handlers::{MappingArrayHandler, MemberAccessHandler};
+    use crate::test_utils::{prepare_test_context, SENDER};
     use alloy_primitives::{address, U256};
 
     #[test]
@@ -3241,4 +3717,245 @@ mod tests {
         println!("Total operations logged: {}", total_operations);
         println!("Sample log entries: {:?}", log.iter().take(5).collect::<Vec<_>>());
     }
+
+    #[test]
+    fn test_time_travel_expressions() {
+        let (handlers, debug_handler) = create_simulation_debug_handlers();
+        debug_handler.set_variable("totalSupply", DynSolValue::Uint(U256::from(1000), 256));
+        let evaluator = ExpressionEvaluator::new(handlers);
+
+        let result = evaluator.eval("totalSupply - @7(totalSupply)", 3).unwrap();
+        assert_eq!(result, DynSolValue::Uint(U256::ZERO, 256));
+
+        let log = debug_handler.get_log();
+        assert!(log.iter().any(|entry| entry.contains("resolve_snapshot: selector='7'")));
+        assert!(log
+            .iter()
+            .any(|entry| entry == "get_variable_value: name='totalSupply', snapshot_id=7"));
+        assert!(log
+            .iter()
+            .any(|entry| entry == "get_variable_value: name='totalSupply', snapshot_id=3"));
+
+        assert!(evaluator.eval("@entry(totalSupply) == totalSupply", 3).is_ok());
+        assert!(evaluator.eval("@entry(totalSupply, 1)", 3).is_err());
+    }
+
+    #[test]
+    fn test_eval_unit_and_rational_literals() {
+        let evaluator = ExpressionEvaluator::new_default();
+        let eval_uint = |expr: &str| match evaluator.eval(expr, 0) {
+            Ok(DynSolValue::Uint(val, 256)) => val,
+            other => panic!("Unexpected result for {expr}: {other:?}"),
+        };
+
+        assert_eq!(eval_uint("1e18"), U256::from(10).pow(U256::from(18)));
+        assert_eq!(eval_uint("1 ether"), U256::from(10).pow(U256::from(18)));
+        assert_eq!(eval_uint("0.5 ether"), U256::from(5) * U256::from(10).pow(U256::from(17)));
+        assert_eq!(eval_uint("3 gwei"), U256::from(3_000_000_000u64));
+        assert_eq!(eval_uint("2.50 gwei"), U256::from(2_500_000_000u64));
+        assert_eq!(eval_uint("1 days"), U256::from(86_400));
+        assert_eq!(eval_uint("1.5 hours"), U256::from(5_400));
+        assert_eq!(eval_uint("2.5e-1 gwei"), U256::from(250_000_000));
+        assert_eq!(eval_uint("1 days + 1 weeks"), U256::from(86_400 + 604_800));
+
+        // Fractional values and overflows are rejected
+        assert!(evaluator.eval("0.5", 0).is_err());
+        assert!(evaluator.eval("1.5 wei", 0).is_err());
+        assert!(evaluator.eval("1e78", 0).is_err());
+    }
+
+    #[test]
+    fn test_eval_type_min_max() {
+        let evaluator = ExpressionEvaluator::new_default();
+
+        assert_eq!(
+            evaluator.eval("type(uint8).max", 0).unwrap(),
+            DynSolValue::Uint(U256::from(255), 8)
+        );
+        assert_eq!(
+            evaluator.eval("type(uint256).max", 0).unwrap(),
+            DynSolValue::Uint(U256::MAX, 256)
+        );
+        assert_eq!(
+            evaluator.eval("type(uint32).min", 0).unwrap(),
+            DynSolValue::Uint(U256::ZERO, 32)
+        );
+        assert_eq!(
+            evaluator.eval("type(int8).min", 0).unwrap(),
+            DynSolValue::Int(I256::try_from(-128).unwrap(), 8)
+        );
+        assert_eq!(
+            evaluator.eval("type(int8).max", 0).unwrap(),
+            DynSolValue::Int(I256::try_from(127).unwrap(), 8)
+        );
+        assert_eq!(
+            evaluator.eval("type(int256).min", 0).unwrap(),
+            DynSolValue::Int(I256::MIN, 256)
+        );
+        assert!(evaluator.eval("type(address).max", 0).is_err());
+    }
+
+    #[test]
+    fn test_eval_abi_builtins() {
+        let evaluator = ExpressionEvaluator::new_default();
+
+        let encoded = evaluator.eval("abi.encode(1, true)", 0).unwrap();
+        let DynSolValue::Bytes(bytes) = &encoded else { panic!("Expected bytes: {encoded:?}") };
+        assert_eq!(bytes.len(), 64);
+        assert_eq!(bytes[31], 1);
+        assert_eq!(bytes[63], 1);
+
+        assert_eq!(
+            evaluator.eval("abi.encodePacked(uint8(1), uint16(2))", 0).unwrap(),
+            DynSolValue::Bytes(vec![1, 0, 2])
+        );
+
+        // Round trip through abi.decode
+        assert_eq!(
+            evaluator.eval("abi.decode(abi.encode(42), (uint256))", 0).unwrap(),
+            DynSolValue::Uint(U256::from(42), 256)
+        );
+        assert_eq!(
+            evaluator
+                .eval("abi.decode(abi.encode(42, 0x0000000000000000000000000000000000000001), (uint256, address))", 0)
+                .unwrap(),
+            DynSolValue::Tuple(vec![
+                DynSolValue::Uint(U256::from(42), 256),
+                DynSolValue::Address(address!("0x0000000000000000000000000000000000000001")),
+            ])
+        );
+
+        // transfer(address,uint256) has selector 0xa9059cbb
+        let with_signature =
+            evaluator.eval("abi.encodeWithSignature(\"transfer(address,uint256)\", 1)", 0).unwrap();
+        let with_selector =
+            evaluator.eval("abi.encodeWithSelector(bytes4(0xa9059cbb), 1)", 0).unwrap();
+        assert_eq!(with_signature, with_selector);
+        let DynSolValue::Bytes(bytes) = with_selector else { panic!("Expected bytes") };
+        assert_eq!(&bytes[..4], &[0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(bytes.len(), 36);
+    }
+
+    #[test]
+    fn test_eval_concat() {
+        let evaluator = ExpressionEvaluator::new_default();
+
+        assert_eq!(
+            evaluator.eval("string.concat(\"foo\", \"bar\")", 0).unwrap(),
+            DynSolValue::String("foobar".to_string())
+        );
+        assert_eq!(
+            evaluator.eval("bytes.concat(hex\"01\", bytes2(0x0203), \"a\")", 0).unwrap(),
+            DynSolValue::Bytes(vec![1, 2, 3, b'a'])
+        );
+        assert!(evaluator.eval("string.concat(\"foo\", 1)", 0).is_err());
+    }
+
+    #[test]
+    fn test_eval_named_function_call() {
+        let (handlers, debug_handler) = create_simulation_debug_handlers();
+        let evaluator = ExpressionEvaluator::new(handlers);
+
+        let result = evaluator.eval("balanceOf({account: msg.sender})", 0).unwrap();
+        assert_eq!(result, DynSolValue::Uint(U256::from(1000000), 256));
+
+        let log = debug_handler.get_log();
+        assert!(log.iter().any(|entry| entry.contains("names=[account]")));
+
+        // The debug handler rejects named arguments
+        let evaluator = ExpressionEvaluator::new_debug();
+        assert!(evaluator.eval("transfer({to: this, amount: 1})", 0).is_err());
+    }
+
+    #[test]
+    fn test_eval_global_context_variables() {
+        let (handlers, debug_handler) = create_simulation_debug_handlers();
+        let evaluator = ExpressionEvaluator::new(handlers);
+
+        assert_eq!(
+            evaluator.eval("msg.sig", 0).unwrap(),
+            DynSolValue::FixedBytes(B256::right_padding_from(&[0xa9, 0x05, 0x9c, 0xbb]), 4)
+        );
+        assert_eq!(
+            evaluator.eval("block.chainid", 0).unwrap(),
+            DynSolValue::Uint(U256::from(1), 256)
+        );
+        assert_eq!(
+            evaluator.eval("tx.gasprice > block.basefee", 0).unwrap(),
+            DynSolValue::Bool(true)
+        );
+        assert_eq!(
+            evaluator.eval("block.difficulty == block.prevrandao", 0).unwrap(),
+            DynSolValue::Bool(true)
+        );
+        assert_eq!(
+            evaluator.eval("gasleft()", 0).unwrap(),
+            DynSolValue::Uint(U256::from(1000000), 256)
+        );
+        assert_eq!(
+            evaluator.eval("blockhash(100)", 0).unwrap(),
+            DynSolValue::FixedBytes(keccak256(U256::from(100).to_be_bytes::<32>()), 32)
+        );
+        for expr in ["msg.data", "block.coinbase", "block.gaslimit", "block.blobbasefee"] {
+            assert!(evaluator.eval(expr, 0).is_ok(), "{expr} should evaluate");
+        }
+        assert!(evaluator.eval("gasleft(1)", 0).is_err());
+        assert!(evaluator.eval("blockhash()", 0).is_err());
+
+        let log = debug_handler.get_log();
+        assert!(log.iter().any(|entry| entry.contains("get_block_hash: number=100")));
+
+        // Globals require handlers
+        let evaluator = ExpressionEvaluator::new_default();
+        assert!(evaluator.eval("block.basefee", 0).is_err());
+    }
+
+    #[test]
+    fn test_eval_call_options() {
+        let (handlers, debug_handler) = create_simulation_debug_handlers();
+        let evaluator = ExpressionEvaluator::new(handlers);
+
+        let (result, calls) = evaluator.eval_with_calls(
+            "token.balanceOf{from: address(1), value: 1 gwei, gas: 50000}(msg.sender)",
+            0,
+        );
+        assert_eq!(result.unwrap(), DynSolValue::Uint(U256::from(1000000), 256));
+        assert!(calls.is_empty()); // The simulation handler executes no real calls
+
+        let log = debug_handler.get_log();
+        let expected = CallOptions {
+            from: Some(address!("0x0000000000000000000000000000000000000001")),
+            value: Some(U256::from(1_000_000_000u64)),
+            gas: Some(50000),
+        };
+        assert!(log.iter().any(|entry| entry.contains(&format!("options={expected}"))));
+
+        // Options combine with named arguments
+        let result = evaluator.eval("transfer{value: 1}({to: this, amount: 1})", 0);
+        assert_eq!(result.unwrap(), DynSolValue::Bool(true));
+
+        assert!(evaluator.eval("withdraw{salt: 1}()", 0).is_err());
+        assert!(evaluator.eval("withdraw{value: 1, value: 2}()", 0).is_err());
+        assert!(evaluator.eval("withdraw{from: 1}()", 0).is_err());
+        assert!(evaluator.eval("withdraw{gas: 2 ** 64}()", 0).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_overlay_mutation_changes_later_evaluation() {
+        let context = Arc::new(prepare_test_context().await);
+        let evaluator = ExpressionEvaluator::new_edb(context);
+        let balance = format!("address({SENDER}).balance");
+        let eval = |expr: &str| evaluator.eval(expr, 0).unwrap();
+
+        let original = eval(&balance);
+        assert_ne!(original, DynSolValue::Uint(U256::from(5), 256));
+        eval(&format!("edb_deal(address({SENDER}), 5)"));
+        assert_eq!(eval(&balance), DynSolValue::Uint(U256::from(5), 256));
+
+        eval("edb_warp(12345)");
+        assert_eq!(eval("block.timestamp"), DynSolValue::Uint(U256::from(12345), 256));
+
+        eval("edb_reset()");
+        assert_eq!(eval(&balance), original);
+    }
 }
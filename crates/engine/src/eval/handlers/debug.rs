@@ -61,7 +61,7 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use alloy_dyn_abi::DynSolValue;
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{keccak256, Address, B256, U256};
 use eyre::{bail, Result};
 
 use super::*;
@@ -214,15 +214,38 @@ impl FunctionCallHandler for DebugHandler {
         name: &str,
         args: &[DynSolValue],
         callee: Option<&DynSolValue>,
+        options: &CallOptions,
         snapshot_id: usize,
     ) -> Result<DynSolValue> {
         let args_str = args.iter().map(|v| format!("{v:?}")).collect::<Vec<_>>().join(", ");
         let callee_str = callee.map(|c| format!("{c:?}")).unwrap_or_else(|| "None".to_string());
         bail!(
-            "DebugHandler::call_function called with name='{}', args=[{}], callee={}, snapshot_id={}",
+            "DebugHandler::call_function called with name='{}', args=[{}], callee={}, options={}, snapshot_id={}",
             name,
             args_str,
             callee_str,
+            options,
+            snapshot_id
+        )
+    }
+
+    fn call_function_with_named_args(
+        &self,
+        name: &str,
+        args: &[(String, DynSolValue)],
+        callee: Option<&DynSolValue>,
+        options: &CallOptions,
+        snapshot_id: usize,
+    ) -> Result<DynSolValue> {
+        let args_str =
+            args.iter().map(|(n, v)| format!("{n}: {v:?}")).collect::<Vec<_>>().join(", ");
+        let callee_str = callee.map(|c| format!("{c:?}")).unwrap_or_else(|| "None".to_string());
+        bail!(
+            "DebugHandler::call_function_with_named_args called with name='{}', args={{{}}}, callee={}, options={}, snapshot_id={}",
+            name,
+            args_str,
+            callee_str,
+            options,
             snapshot_id
         )
     }
@@ -252,12 +275,28 @@ impl MsgHandler for DebugHandler {
     fn get_msg_value(&self, snapshot_id: usize) -> Result<DynSolValue> {
         bail!("DebugHandler::get_msg_value called with snapshot_id={}", snapshot_id)
     }
+
+    fn get_msg_data(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        bail!("DebugHandler::get_msg_data called with snapshot_id={}", snapshot_id)
+    }
+
+    fn get_msg_sig(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        bail!("DebugHandler::get_msg_sig called with snapshot_id={}", snapshot_id)
+    }
+
+    fn get_gas_left(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        bail!("DebugHandler::get_gas_left called with snapshot_id={}", snapshot_id)
+    }
 }
 
 impl TxHandler for DebugHandler {
     fn get_tx_origin(&self, snapshot_id: usize) -> Result<DynSolValue> {
         bail!("DebugHandler::get_tx_origin called with snapshot_id={}", snapshot_id)
     }
+
+    fn get_tx_gas_price(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        bail!("DebugHandler::get_tx_gas_price called with snapshot_id={}", snapshot_id)
+    }
 }
 
 impl BlockHandler for DebugHandler {
@@ -268,6 +307,38 @@ impl BlockHandler for DebugHandler {
     fn get_block_timestamp(&self, snapshot_id: usize) -> Result<DynSolValue> {
         bail!("DebugHandler::get_block_timestamp called with snapshot_id={}", snapshot_id)
     }
+
+    fn get_block_basefee(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        bail!("DebugHandler::get_block_basefee called with snapshot_id={}", snapshot_id)
+    }
+
+    fn get_block_chainid(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        bail!("DebugHandler::get_block_chainid called with snapshot_id={}", snapshot_id)
+    }
+
+    fn get_block_coinbase(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        bail!("DebugHandler::get_block_coinbase called with snapshot_id={}", snapshot_id)
+    }
+
+    fn get_block_prevrandao(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        bail!("DebugHandler::get_block_prevrandao called with snapshot_id={}", snapshot_id)
+    }
+
+    fn get_block_gaslimit(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        bail!("DebugHandler::get_block_gaslimit called with snapshot_id={}", snapshot_id)
+    }
+
+    fn get_block_blobbasefee(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        bail!("DebugHandler::get_block_blobbasefee called with snapshot_id={}", snapshot_id)
+    }
+
+    fn get_block_hash(&self, number: U256, snapshot_id: usize) -> Result<DynSolValue> {
+        bail!(
+            "DebugHandler::get_block_hash called with number={}, snapshot_id={}",
+            number,
+            snapshot_id
+        )
+    }
 }
 
 impl ValidationHandler for DebugHandler {
@@ -276,6 +347,16 @@ impl ValidationHandler for DebugHandler {
     }
 }
 
+impl SnapshotHandler for DebugHandler {
+    fn resolve_snapshot(&self, selector: &str, snapshot_id: usize) -> Result<usize> {
+        bail!(
+            "DebugHandler::resolve_snapshot called with selector='{}', snapshot_id={}",
+            selector,
+            snapshot_id
+        )
+    }
+}
+
 // Implement handler traits for SimulationDebugHandler
 impl VariableHandler for SimulationDebugHandler {
     fn get_variable_value(&self, name: &str, snapshot_id: usize) -> Result<DynSolValue> {
@@ -336,12 +417,13 @@ impl FunctionCallHandler for SimulationDebugHandler {
         name: &str,
         args: &[DynSolValue],
         callee: Option<&DynSolValue>,
+        options: &CallOptions,
         snapshot_id: usize,
     ) -> Result<DynSolValue> {
         let args_str = args.iter().map(|v| format!("{v:?}")).collect::<Vec<_>>().join(", ");
         let callee_str = callee.map(|c| format!("{c:?}")).unwrap_or_else(|| "None".to_string());
         self.log_operation(format!(
-            "call_function: name='{name}', args=[{args_str}], callee={callee_str}, snapshot_id={snapshot_id}"
+            "call_function: name='{name}', args=[{args_str}], callee={callee_str}, options={options}, snapshot_id={snapshot_id}"
         ));
 
         if let Ok(funcs) = self.functions.lock() {
@@ -365,6 +447,24 @@ impl FunctionCallHandler for SimulationDebugHandler {
         self.log_operation(format!("  -> returning generated value: {result:?}"));
         Ok(result)
     }
+
+    fn call_function_with_named_args(
+        &self,
+        name: &str,
+        args: &[(String, DynSolValue)],
+        callee: Option<&DynSolValue>,
+        options: &CallOptions,
+        snapshot_id: usize,
+    ) -> Result<DynSolValue> {
+        let names = args.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>().join(", ");
+        self.log_operation(format!(
+            "call_function_with_named_args: name='{name}', names=[{names}]"
+        ));
+
+        // Without parameter names to match, arguments are passed in the written order
+        let values = args.iter().map(|(_, v)| v.clone()).collect::<Vec<_>>();
+        self.call_function(name, &values, callee, options, snapshot_id)
+    }
 }
 
 impl MemberAccessHandler for SimulationDebugHandler {
@@ -405,6 +505,28 @@ impl MsgHandler for SimulationDebugHandler {
         self.log_operation(format!("  -> returning: {result:?}"));
         Ok(result)
     }
+
+    fn get_msg_data(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.log_operation(format!("get_msg_data: snapshot_id={snapshot_id}"));
+        let result = DynSolValue::Bytes(vec![0xa9, 0x05, 0x9c, 0xbb]); // Mock transfer calldata
+        self.log_operation(format!("  -> returning: {result:?}"));
+        Ok(result)
+    }
+
+    fn get_msg_sig(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.log_operation(format!("get_msg_sig: snapshot_id={snapshot_id}"));
+        let result =
+            DynSolValue::FixedBytes(B256::right_padding_from(&[0xa9, 0x05, 0x9c, 0xbb]), 4);
+        self.log_operation(format!("  -> returning: {result:?}"));
+        Ok(result)
+    }
+
+    fn get_gas_left(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.log_operation(format!("get_gas_left: snapshot_id={snapshot_id}"));
+        let result = DynSolValue::Uint(U256::from(1000000), 256); // Mock remaining gas
+        self.log_operation(format!("  -> returning: {result:?}"));
+        Ok(result)
+    }
 }
 
 impl TxHandler for SimulationDebugHandler {
@@ -414,6 +536,13 @@ impl TxHandler for SimulationDebugHandler {
         self.log_operation(format!("  -> returning: {result:?}"));
         Ok(result)
     }
+
+    fn get_tx_gas_price(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.log_operation(format!("get_tx_gas_price: snapshot_id={snapshot_id}"));
+        let result = DynSolValue::Uint(U256::from(20000000000u64), 256); // 20 gwei
+        self.log_operation(format!("  -> returning: {result:?}"));
+        Ok(result)
+    }
 }
 
 impl BlockHandler for SimulationDebugHandler {
@@ -430,6 +559,67 @@ impl BlockHandler for SimulationDebugHandler {
         self.log_operation(format!("  -> returning: {result:?}"));
         Ok(result)
     }
+
+    fn get_block_basefee(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.log_operation(format!("get_block_basefee: snapshot_id={snapshot_id}"));
+        let result = DynSolValue::Uint(U256::from(10000000000u64), 256); // 10 gwei
+        self.log_operation(format!("  -> returning: {result:?}"));
+        Ok(result)
+    }
+
+    fn get_block_chainid(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.log_operation(format!("get_block_chainid: snapshot_id={snapshot_id}"));
+        let result = DynSolValue::Uint(U256::from(1), 256); // Mainnet
+        self.log_operation(format!("  -> returning: {result:?}"));
+        Ok(result)
+    }
+
+    fn get_block_coinbase(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.log_operation(format!("get_block_coinbase: snapshot_id={snapshot_id}"));
+        let result = DynSolValue::Address(Address::from([0x22; 20])); // Mock coinbase
+        self.log_operation(format!("  -> returning: {result:?}"));
+        Ok(result)
+    }
+
+    fn get_block_prevrandao(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.log_operation(format!("get_block_prevrandao: snapshot_id={snapshot_id}"));
+        let result = DynSolValue::Uint(U256::from(0x1234567890abcdefu64), 256); // Mock randomness
+        self.log_operation(format!("  -> returning: {result:?}"));
+        Ok(result)
+    }
+
+    fn get_block_gaslimit(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.log_operation(format!("get_block_gaslimit: snapshot_id={snapshot_id}"));
+        let result = DynSolValue::Uint(U256::from(30000000), 256); // Mock gas limit
+        self.log_operation(format!("  -> returning: {result:?}"));
+        Ok(result)
+    }
+
+    fn get_block_blobbasefee(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.log_operation(format!("get_block_blobbasefee: snapshot_id={snapshot_id}"));
+        let result = DynSolValue::Uint(U256::from(1), 256); // Minimum blob base fee
+        self.log_operation(format!("  -> returning: {result:?}"));
+        Ok(result)
+    }
+
+    fn get_block_hash(&self, number: U256, snapshot_id: usize) -> Result<DynSolValue> {
+        self.log_operation(format!("get_block_hash: number={number}, snapshot_id={snapshot_id}"));
+        let result = DynSolValue::FixedBytes(keccak256(number.to_be_bytes::<32>()), 32); // Mock hash
+        self.log_operation(format!("  -> returning: {result:?}"));
+        Ok(result)
+    }
+}
+
+impl SnapshotHandler for SimulationDebugHandler {
+    fn resolve_snapshot(&self, selector: &str, snapshot_id: usize) -> Result<usize> {
+        self.log_operation(format!(
+            "resolve_snapshot: selector='{selector}', snapshot_id={snapshot_id}"
+        ));
+        // Snapshot ids are taken as is, while named selectors stay at the current snapshot
+        let result = selector.parse().unwrap_or(snapshot_id);
+        self.log_operation(format!("  -> returning: {result}"));
+        Ok(result)
+    }
 }
 
 // Implement traits for Arc<SimulationDebugHandler> to allow sharing
@@ -456,9 +646,21 @@ impl FunctionCallHandler for Arc<SimulationDebugHandler> {
         name: &str,
         args: &[DynSolValue],
         callee: Option<&DynSolValue>,
+        options: &CallOptions,
+        snapshot_id: usize,
+    ) -> Result<DynSolValue> {
+        self.as_ref().call_function(name, args, callee, options, snapshot_id)
+    }
+
+    fn call_function_with_named_args(
+        &self,
+        name: &str,
+        args: &[(String, DynSolValue)],
+        callee: Option<&DynSolValue>,
+        options: &CallOptions,
         snapshot_id: usize,
     ) -> Result<DynSolValue> {
-        self.as_ref().call_function(name, args, callee, snapshot_id)
+        self.as_ref().call_function_with_named_args(name, args, callee, options, snapshot_id)
     }
 }
 
@@ -481,12 +683,28 @@ impl MsgHandler for Arc<SimulationDebugHandler> {
     fn get_msg_value(&self, snapshot_id: usize) -> Result<DynSolValue> {
         self.as_ref().get_msg_value(snapshot_id)
     }
+
+    fn get_msg_data(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.as_ref().get_msg_data(snapshot_id)
+    }
+
+    fn get_msg_sig(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.as_ref().get_msg_sig(snapshot_id)
+    }
+
+    fn get_gas_left(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.as_ref().get_gas_left(snapshot_id)
+    }
 }
 
 impl TxHandler for Arc<SimulationDebugHandler> {
     fn get_tx_origin(&self, snapshot_id: usize) -> Result<DynSolValue> {
         self.as_ref().get_tx_origin(snapshot_id)
     }
+
+    fn get_tx_gas_price(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.as_ref().get_tx_gas_price(snapshot_id)
+    }
 }
 
 impl BlockHandler for Arc<SimulationDebugHandler> {
@@ -497,6 +715,34 @@ impl BlockHandler for Arc<SimulationDebugHandler> {
     fn get_block_timestamp(&self, snapshot_id: usize) -> Result<DynSolValue> {
         self.as_ref().get_block_timestamp(snapshot_id)
     }
+
+    fn get_block_basefee(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.as_ref().get_block_basefee(snapshot_id)
+    }
+
+    fn get_block_chainid(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.as_ref().get_block_chainid(snapshot_id)
+    }
+
+    fn get_block_coinbase(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.as_ref().get_block_coinbase(snapshot_id)
+    }
+
+    fn get_block_prevrandao(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.as_ref().get_block_prevrandao(snapshot_id)
+    }
+
+    fn get_block_gaslimit(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.as_ref().get_block_gaslimit(snapshot_id)
+    }
+
+    fn get_block_blobbasefee(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        self.as_ref().get_block_blobbasefee(snapshot_id)
+    }
+
+    fn get_block_hash(&self, number: U256, snapshot_id: usize) -> Result<DynSolValue> {
+        self.as_ref().get_block_hash(number, snapshot_id)
+    }
 }
 
 impl ValidationHandler for Arc<SimulationDebugHandler> {
@@ -505,6 +751,12 @@ impl ValidationHandler for Arc<SimulationDebugHandler> {
     }
 }
 
+impl SnapshotHandler for Arc<SimulationDebugHandler> {
+    fn resolve_snapshot(&self, selector: &str, snapshot_id: usize) -> Result<usize> {
+        self.as_ref().resolve_snapshot(selector, snapshot_id)
+    }
+}
+
 /// Create debug handlers for all traits (original error-only version)
 pub fn create_debug_handlers() -> EvaluatorHandlers {
     EvaluatorHandlers::new()
@@ -516,6 +768,7 @@ pub fn create_debug_handlers() -> EvaluatorHandlers {
         .with_tx_handler(Box::new(DebugHandler::new()))
         .with_block_handler(Box::new(DebugHandler::new()))
         .with_validation_handler(Box::new(DebugHandler::new()))
+        .with_snapshot_handler(Box::new(DebugHandler::new()))
 }
 
 /// Create simulation debug handlers that return mock values and log operations
@@ -529,7 +782,8 @@ pub fn create_simulation_debug_handlers() -> (EvaluatorHandlers, Arc<SimulationD
         .with_msg_handler(Box::new(handler.clone()))
         .with_tx_handler(Box::new(handler.clone()))
         .with_block_handler(Box::new(handler.clone()))
-        .with_validation_handler(Box::new(handler.clone()));
+        .with_validation_handler(Box::new(handler.clone()))
+        .with_snapshot_handler(Box::new(handler.clone()));
 
     (handlers, handler)
 }
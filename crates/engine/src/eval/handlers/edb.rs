@@ -27,6 +27,7 @@
 //! - **Storage Access**: Read from contract storage, transient storage, memory, and stack
 //! - **Blockchain Context**: Access `msg`, `tx`, and `block` global variables
 //! - **Cross-Contract Calls**: Function calls and state access on different addresses
+//! - **Time Travel**: Evaluate sub-expressions at other snapshots (e.g., `@entry(x)`)
 //!
 //! # Pre-compiled Functions (EDB-Version)
 //!
@@ -35,6 +36,12 @@
 //! - `edb_stack(index)` - Read EVM stack (opcode mode only)
 //! - `edb_memory(offset, size)` - Read EVM memory (opcode mode only)
 //! - `edb_calldata(offset, size)` - Read call data slice
+//! - `edb_sstore(address, slot, value)` - Overwrite storage slot in the session overlay
+//! - `edb_deal(address, wei)` - Overwrite balance in the session overlay
+//! - `edb_etch(address, code)` - Replace runtime bytecode in the session overlay
+//! - `edb_warp(timestamp)` / `edb_roll(number)` - Overwrite block timestamp / number
+//! - `edb_prank(address)` - Set the default sender of calls
+//! - `edb_reset()` - Clear the session overlay
 //! - `keccak256(bytes)` - Compute keccak256 hash
 //! - `edb_help()` - Show help information
 //!
@@ -46,17 +53,28 @@
 //! let result = evaluator.eval("balances[msg.sender]", snapshot_id)?;
 //! ```
 
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
 
 use alloy_dyn_abi::DynSolValue;
-use alloy_primitives::U256;
-use edb_common::types::{parse_callable_abi_entries, CallableAbiEntry, TraceEntry};
+use alloy_primitives::{Address, B256, U256};
+use edb_common::{
+    types::{parse_callable_abi_entries, CallOptions, CallableAbiEntry, EvalCall, TraceEntry},
+    OpcodeTr, OVERLAY_FUNCTIONS,
+};
 use eyre::{bail, eyre, Result};
-use revm::{database::CacheDB, Database, DatabaseCommit, DatabaseRef};
+use revm::{
+    bytecode::OpCode,
+    context_interface::{Block, Transaction},
+    database::CacheDB,
+    Database, DatabaseCommit, DatabaseRef,
+};
 use tracing::debug;
 
 use super::*;
-use crate::{EngineContext, Snapshot, SnapshotDetail};
+use crate::{analysis::StepRef, EngineContext, Snapshot, SnapshotDetail, StateOverlay};
 
 static EDB_EVAL_PLACEHOLDER_MAGIC: &str = "edb_eval_placeholder";
 
@@ -93,6 +111,8 @@ where
     <DB as Database>::Error: Clone + Send + Sync,
 {
     context: Arc<EngineContext<DB>>,
+    /// Contract calls executed during the current evaluation
+    calls: Mutex<Vec<EvalCall>>,
 }
 
 impl<DB> EdbHandler<DB>
@@ -109,7 +129,7 @@ where
     /// # Returns
     /// A new [`EdbHandler`] instance
     pub fn new(context: Arc<EngineContext<DB>>) -> Self {
-        Self { context }
+        Self { context, calls: Mutex::default() }
     }
 
     /// Create all handlers using this EDB context
@@ -124,7 +144,8 @@ where
             .with_msg_handler(Box::new(EdbMsgHandler(handler.clone())))
             .with_tx_handler(Box::new(EdbTxHandler(handler.clone())))
             .with_block_handler(Box::new(EdbBlockHandler(handler.clone())))
-            .with_validation_handler(Box::new(EdbValidationHandler(handler)))
+            .with_validation_handler(Box::new(EdbValidationHandler(handler.clone())))
+            .with_snapshot_handler(Box::new(EdbSnapshotHandler(handler)))
     }
 }
 
@@ -179,8 +200,8 @@ where
 
 /// EDB implementation of [`MsgHandler`].
 ///
-/// Provides access to transaction context variables (`msg.sender`, `msg.value`)
-/// from the current debug snapshot's trace entry.
+/// Provides access to call context variables (`msg.sender`, `msg.value`, `msg.data`,
+/// `msg.sig`, `gasleft()`) from the current debug snapshot's trace entry.
 #[derive(Clone)]
 pub struct EdbMsgHandler<DB>(Arc<EdbHandler<DB>>)
 where
@@ -190,8 +211,8 @@ where
 
 /// EDB implementation of [`TxHandler`].
 ///
-/// Provides access to transaction-level context (`tx.origin`) from the root
-/// trace entry of the current debug session.
+/// Provides access to transaction-level context (`tx.origin`, `tx.gasprice`) from the
+/// root trace entry and transaction environment of the current debug session.
 #[derive(Clone)]
 pub struct EdbTxHandler<DB>(Arc<EdbHandler<DB>>)
 where
@@ -201,8 +222,8 @@ where
 
 /// EDB implementation of [`BlockHandler`].
 ///
-/// Provides access to blockchain context (`block.number`, `block.timestamp`)
-/// from the EDB engine's fork information and block data.
+/// Provides access to blockchain context (`block.*`, `blockhash(n)`) from the EDB
+/// engine's fork information and block data.
 #[derive(Clone)]
 pub struct EdbBlockHandler<DB>(Arc<EdbHandler<DB>>)
 where
@@ -222,6 +243,17 @@ where
     <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
     <DB as Database>::Error: Clone + Send + Sync;
 
+/// EDB implementation of [`SnapshotHandler`].
+///
+/// Resolves time-travel selectors to snapshots using the navigation information
+/// of the debug snapshots (e.g., function entries and call sites).
+#[derive(Clone)]
+pub struct EdbSnapshotHandler<DB>(Arc<EdbHandler<DB>>)
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync;
+
 // Implement handler traits for each wrapper
 impl<DB> VariableHandler for EdbVariableHandler<DB>
 where
@@ -303,7 +335,13 @@ where
             })?;
             let to = info.target_address();
 
-            self.0.context.call_in_derived_evm(snapshot_id, to, &abi_info.abi, &indices, None)
+            self.0.context.call_in_derived_evm(
+                snapshot_id,
+                to,
+                &abi_info.abi,
+                &indices,
+                &CallOptions::default(),
+            )
         } else {
             // Handle direct DynSolValue types recursively
             if indices.is_empty() {
@@ -413,6 +451,7 @@ fn edb_keccak256(bytes: DynSolValue) -> Result<DynSolValue> {
 
 fn edb_sload<DB>(
     snapshot: &Snapshot<DB>,
+    overlay: &StateOverlay,
     address: &DynSolValue,
     slot: &DynSolValue,
 ) -> Result<DynSolValue>
@@ -423,6 +462,10 @@ where
 {
     match (address, slot) {
         (DynSolValue::Address(address), DynSolValue::Uint(slot, ..)) => {
+            if let Some(value) = overlay.storage.get(&(*address, *slot)) {
+                return Ok(DynSolValue::Uint(*value, 256));
+            }
+
             let db = snapshot.db();
             let cached_storage = db
                 .cache
@@ -469,6 +512,49 @@ where
     }
 }
 
+/// Read `balance`, `code`, or `codehash` of an account at a snapshot
+fn edb_account_property<DB>(
+    snapshot: &Snapshot<DB>,
+    overlay: &StateOverlay,
+    address: Address,
+    property: &str,
+) -> Result<DynSolValue>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    match (property, overlay.balances.get(&address), overlay.codes.get(&address)) {
+        ("balance", Some(balance), _) => return Ok(DynSolValue::Uint(*balance, 256)),
+        ("code", _, Some(code)) => return Ok(DynSolValue::Bytes(code.to_vec())),
+        ("codehash", _, Some(code)) => {
+            return Ok(DynSolValue::FixedBytes(alloy_primitives::keccak256(code), 32))
+        }
+        _ => {}
+    }
+
+    let db = snapshot.db();
+    let account = db
+        .basic_ref(address)
+        .map_err(|e| eyre!("Failed to retrieve account {address}: {e}"))?
+        .unwrap_or_default();
+
+    match property {
+        "balance" => Ok(DynSolValue::Uint(account.balance, 256)),
+        "codehash" => Ok(DynSolValue::FixedBytes(account.code_hash, 32)),
+        "code" => {
+            let code = match account.code {
+                Some(code) => code,
+                None => db
+                    .code_by_hash_ref(account.code_hash)
+                    .map_err(|e| eyre!("Failed to retrieve code of {address}: {e}"))?,
+            };
+            Ok(DynSolValue::Bytes(code.original_bytes().to_vec()))
+        }
+        _ => bail!("Unknown account property: {property}"),
+    }
+}
+
 fn edb_tsload<DB>(
     snapshot: &Snapshot<DB>,
     address: &DynSolValue,
@@ -569,6 +655,53 @@ fn edb_calldata(
     }
 }
 
+/// Change the session overlay by one of the [`OVERLAY_FUNCTIONS`]
+fn edb_update_overlay<DB>(
+    context: &EngineContext<DB>,
+    name: &str,
+    args: &[DynSolValue],
+) -> Result<DynSolValue>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    use DynSolValue::{Address as A, Bytes as B, Uint as U};
+
+    context.update_overlay(|overlay| {
+        match (name, args) {
+            ("edb_sstore", [A(address), U(slot, _), U(value, _)]) => {
+                overlay.storage.insert((*address, *slot), *value);
+            }
+            ("edb_deal", [A(address), U(balance, _)]) => {
+                overlay.balances.insert(*address, *balance);
+            }
+            ("edb_etch", [A(address), B(code)]) => {
+                overlay.codes.insert(*address, code.clone().into());
+            }
+            ("edb_warp", [U(timestamp, _)]) => overlay.timestamp = Some(*timestamp),
+            ("edb_roll", [U(number, _)]) => overlay.number = Some(*number),
+            ("edb_prank", [A(address)]) => overlay.prank = Some(*address),
+            ("edb_reset", []) => *overlay = StateOverlay::default(),
+            _ => {
+                let expected = match name {
+                    "edb_sstore" => "(address, u256, u256)",
+                    "edb_deal" => "(address, u256)",
+                    "edb_etch" => "(address, bytes)",
+                    "edb_warp" | "edb_roll" => "(u256)",
+                    "edb_prank" => "(address)",
+                    _ => "()",
+                };
+                bail!("Invalid arguments to {name}: expected {expected}, got {args:?}");
+            }
+        }
+        Ok(())
+    })?;
+
+    debug!("Updated session overlay with {}({:?})", name, args);
+    Ok(DynSolValue::Tuple(vec![]))
+}
+
 fn edb_help() -> Result<DynSolValue> {
     let help_text = r#"EDB Expression Evaluator Help
 
@@ -585,9 +718,17 @@ VARIABLES:
 BLOCKCHAIN CONTEXT:
 • msg.sender     - Transaction sender address
 • msg.value      - Transaction value in wei
+• msg.data       - Call data of the current frame
+• msg.sig        - Function selector of the current frame
+• gasleft()      - Remaining gas (opcode mode only)
 • tx.origin      - Original transaction sender
+• tx.gasprice    - Effective gas price of the transaction
 • block.number   - Current block number
 • block.timestamp - Current block timestamp
+• block.basefee, block.blobbasefee, block.chainid, block.coinbase,
+  block.gaslimit, block.prevrandao (alias: block.difficulty)
+• blockhash(n)   - Hash of one of the 256 most recent blocks
+• address(x).balance, address(x).code, address(x).codehash
 
 PRE-COMPILED FUNCTIONS (EDB-VERSION):
 • edb_sload(address, slot)      - Read storage slot from address
@@ -598,12 +739,45 @@ PRE-COMPILED FUNCTIONS (EDB-VERSION):
 • keccak256(bytes)              - Compute keccak256 hash
 • edb_help()                    - Show this help
 
+SESSION OVERLAY:
+Hypothetical state seen by later calls, edb_sload, balance/code reads, block.timestamp,
+block.number, nested call timelines and what-if runs (at any step, until reset):
+• edb_sstore(address, slot, value) - Overwrite storage slot
+• edb_deal(address, wei)           - Overwrite balance
+• edb_etch(address, code)          - Replace runtime bytecode
+• edb_warp(timestamp)              - Overwrite block.timestamp
+• edb_roll(number)                 - Overwrite block.number
+• edb_prank(address)               - Send later calls from address
+• edb_reset()                      - Clear the overlay
+
+BUILT-INS:
+• abi.encode(...), abi.encodePacked(...)      - ABI-encode values
+• abi.encodeWithSelector(bytes4, ...)         - ABI-encode a call with a selector
+• abi.encodeWithSignature("f(uint256)", ...)  - ABI-encode a call with a signature
+• abi.decode(data, (uint256, address))        - ABI-decode data
+• bytes.concat(...), string.concat(...)       - Concatenate bytes or strings
+• type(uint8).max, type(int256).min           - Integer type bounds
+• 1 ether, 0.5 gwei, 2 days, 1e18             - Unit and scientific literals
+• f({a: 1, b: 2})                             - Named arguments
+
+TIME TRAVEL:
+• @123(expr)       - Evaluate expr at snapshot 123
+• @entry(expr)     - Evaluate expr at the entry of the current function
+• @prev(expr)      - Evaluate expr at the previous step
+• @next(expr)      - Evaluate expr at the next step
+• @prev_call(expr) - Evaluate expr at the previous call
+• @next_call(expr) - Evaluate expr at the next call
+
 CONTRACT FUNCTIONS:
 • Call any contract function by name with arguments
 • Access state variables and view functions
 • Member access on addresses (e.g., addr.balanceOf(user))
 • Cross-contract calls (e.g., token.transfer(to, amount))
 • State variable access on different addresses (e.g., addr.owner)
+• Call options: f{from: addr, value: amount, gas: limit}(args), where
+  `from` (an EDB pseudo-option) sets msg.sender of the call
+• Revert reasons and emitted events of calls are reported by
+  edb_evalOnSnapshotWithCalls (and shown by the TUI)
 
 OPERATORS:
 • Arithmetic: +, -, *, /, %, **
@@ -621,6 +795,10 @@ EXAMPLES:
 • token.balanceOf(user) * price / 1e18
 • addr.owner == this
 • contractAddr.getUserBalance(msg.sender)
+• balances[user] < @entry(balances[user])
+• lastUpdate + 1 days < block.timestamp
+• withdraw{from: owner}(1 ether)
+• edb_warp(block.timestamp + 7 days)
 
 Note: Use 'this' to reference the current contract address in expressions."#;
 
@@ -638,11 +816,12 @@ where
         name: &str,
         args: &[DynSolValue],
         callee: Option<&DynSolValue>,
+        options: &CallOptions,
         snapshot_id: usize,
     ) -> Result<DynSolValue> {
         debug!(
-            "EdbHandler::call_function name='{}', args={:?}, callee={:?}, snapshot_id={}",
-            name, args, callee, snapshot_id
+            "EdbHandler::call_function name='{}', args={:?}, callee={:?}, options={}, snapshot_id={}",
+            name, args, callee, options, snapshot_id
         );
 
         let (frame_id, snapshot) = self.0.context.snapshots.get(snapshot_id).ok_or_else(|| {
@@ -653,9 +832,11 @@ where
             eyre::eyre!("Frame ID {} not found in EdbHandler::call_function", frame_id)
         })?;
 
-        // Let's first handle our edb-specific pseudo-functions
-        if name == "edb_sload" && args.len() == 2 {
-            return edb_sload(snapshot, &args[0], &args[1]);
+        // Let's first handle our edb-specific pseudo-functions, which take no call options
+        if !options.is_empty() && (name.starts_with("edb_") || name == "keccak256") {
+            bail!("Call options are not supported by {}()", name);
+        } else if name == "edb_sload" && args.len() == 2 {
+            return edb_sload(snapshot, &self.0.context.overlay(), &args[0], &args[1]);
         } else if name == "edb_tsload" && args.len() == 2 {
             return edb_tsload(snapshot, &args[0], &args[1]);
         } else if name == "edb_stack" && args.len() == 1 {
@@ -668,9 +849,71 @@ where
             return edb_keccak256(args[0].clone());
         } else if name == "edb_help" && args.is_empty() {
             return edb_help();
+        } else if OVERLAY_FUNCTIONS.contains(&name) {
+            return edb_update_overlay(&self.0.context, name, args);
         }
 
         // Let's then handle calls to functions in the contract's ABI
+        self.call_abi_function(name, &args, callee, options, snapshot_id, |entry| {
+            (entry.inputs.len() == args.len()).then(|| args.to_vec())
+        })
+    }
+
+    fn call_function_with_named_args(
+        &self,
+        name: &str,
+        args: &[(String, DynSolValue)],
+        callee: Option<&DynSolValue>,
+        options: &CallOptions,
+        snapshot_id: usize,
+    ) -> Result<DynSolValue> {
+        debug!(
+            "EdbHandler::call_function_with_named_args name='{}', args={:?}, callee={:?}, options={}, snapshot_id={}",
+            name, args, callee, options, snapshot_id
+        );
+
+        // Arguments are matched against the parameter names of each overload
+        self.call_abi_function(name, &args, callee, options, snapshot_id, |entry| {
+            if entry.abi.inputs.len() != args.len() {
+                return None;
+            }
+            entry
+                .abi
+                .inputs
+                .iter()
+                .map(|param| {
+                    args.iter().find(|(arg, _)| *arg == param.name).map(|(_, value)| value.clone())
+                })
+                .collect()
+        })
+    }
+
+    fn take_recorded_calls(&self) -> Vec<EvalCall> {
+        self.0.calls.lock().map(|mut calls| std::mem::take(&mut *calls)).unwrap_or_default()
+    }
+}
+
+impl<DB> EdbFunctionCallHandler<DB>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    /// Call a function in the ABI of the callee (or the current contract), trying every
+    /// overload named `name` whose arguments can be arranged by `select_args`.
+    fn call_abi_function(
+        &self,
+        name: &str,
+        args: &dyn std::fmt::Debug,
+        callee: Option<&DynSolValue>,
+        options: &CallOptions,
+        snapshot_id: usize,
+        select_args: impl Fn(&CallableAbiEntry) -> Option<Vec<DynSolValue>>,
+    ) -> Result<DynSolValue> {
+        let (_, snapshot) = self.0.context.snapshots.get(snapshot_id).ok_or_else(|| {
+            eyre::eyre!("Snapshot ID {} not found in EdbHandler::call_function", snapshot_id)
+        })?;
+
         let to = if let Some(v) = callee {
             match v {
                 DynSolValue::Address(addr) => *addr,
@@ -703,14 +946,21 @@ where
                 .and_then(|art| art.contract())
             {
                 for entry in parse_callable_abi_entries(contract) {
-                    if entry.name == name && entry.inputs.len() == args.len() {
-                        match self.0.context.call_in_derived_evm(
-                            snapshot_id,
-                            to,
-                            &entry.abi,
-                            args,
-                            None,
-                        ) {
+                    if entry.name != name {
+                        continue;
+                    }
+                    if let Some(args) = select_args(&entry) {
+                        let outcome = self
+                            .0
+                            .context
+                            .record_call_in_derived_evm(snapshot_id, to, &entry.abi, &args, options)
+                            .and_then(|(result, call)| {
+                                if let Ok(mut calls) = self.0.calls.lock() {
+                                    calls.push(call);
+                                }
+                                result
+                            });
+                        match outcome {
                             Ok(result) => return Ok(result),
                             Err(e) => {
                                 errors.push(e);
@@ -766,6 +1016,13 @@ where
         snapshot_id: usize,
     ) -> Result<DynSolValue> {
         if let DynSolValue::Address(addr) = value {
+            if matches!(member, "balance" | "code" | "codehash") {
+                let (_, snapshot) = self.0.context.snapshots.get(snapshot_id).ok_or_else(|| {
+                    eyre!("Snapshot ID {} not found in EdbHandler::access_member", snapshot_id)
+                })?;
+                return edb_account_property(snapshot, &self.0.context.overlay(), addr, member);
+            }
+
             let mut address_candidates = self
                 .0
                 .context
@@ -795,7 +1052,7 @@ where
                                 addr,
                                 &entry.abi,
                                 &[],
-                                None,
+                                &CallOptions::default(),
                             ) {
                                 Ok(result) => return Ok(result),
                                 Err(_e) => {
@@ -849,6 +1106,31 @@ where
 
         Ok(DynSolValue::Uint(entry.value, 256))
     }
+
+    fn get_msg_data(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        let entry = snapshot_trace_entry(&self.0.context, snapshot_id)?;
+        Ok(DynSolValue::Bytes(entry.input.to_vec()))
+    }
+
+    fn get_msg_sig(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        let entry = snapshot_trace_entry(&self.0.context, snapshot_id)?;
+        let selector = &entry.input[..entry.input.len().min(4)];
+        Ok(DynSolValue::FixedBytes(B256::right_padding_from(selector), 4))
+    }
+
+    fn get_gas_left(&self, snapshot_id: usize) -> Result<DynSolValue> {
+        let (_, snapshot) = self.0.context.snapshots.get(snapshot_id).ok_or_else(|| {
+            eyre::eyre!("Snapshot ID {} not found in EdbHandler::get_gas_left", snapshot_id)
+        })?;
+
+        match snapshot.detail() {
+            SnapshotDetail::Opcode(detail) => {
+                Ok(DynSolValue::Uint(U256::from(detail.gas_remaining), 256))
+            }
+            // Instrumentation changes the gas usage of source-level steps
+            SnapshotDetail::Hook(_) => bail!("gasleft() is only available in opcode mode"),
+        }
+    }
 }
 
 impl<DB> TxHandler for EdbTxHandler<DB>
@@ -867,6 +1149,12 @@ where
 
         Ok(DynSolValue::Address(entry.caller))
     }
+
+    fn get_tx_gas_price(&self, _snapshot_id: usize) -> Result<DynSolValue> {
+        let context = &self.0.context;
+        let gas_price = context.tx.effective_gas_price(context.block.basefee as u128);
+        Ok(DynSolValue::Uint(U256::from(gas_price), 256))
+    }
 }
 
 impl<DB> BlockHandler for EdbBlockHandler<DB>
@@ -876,11 +1164,69 @@ where
     <DB as Database>::Error: Clone + Send + Sync,
 {
     fn get_block_number(&self, _snapshot_id: usize) -> Result<DynSolValue> {
-        Ok(DynSolValue::Uint(U256::from(self.0.context.fork_info.block_number), 256))
+        let number = self.0.context.overlay().number;
+        Ok(DynSolValue::Uint(
+            number.unwrap_or(U256::from(self.0.context.fork_info.block_number)),
+            256,
+        ))
     }
 
     fn get_block_timestamp(&self, _snapshot_id: usize) -> Result<DynSolValue> {
-        Ok(DynSolValue::Uint(self.0.context.block.timestamp, 256))
+        let timestamp = self.0.context.overlay().timestamp;
+        Ok(DynSolValue::Uint(timestamp.unwrap_or(self.0.context.block.timestamp), 256))
+    }
+
+    fn get_block_basefee(&self, _snapshot_id: usize) -> Result<DynSolValue> {
+        Ok(DynSolValue::Uint(U256::from(self.0.context.block.basefee), 256))
+    }
+
+    fn get_block_chainid(&self, _snapshot_id: usize) -> Result<DynSolValue> {
+        Ok(DynSolValue::Uint(U256::from(self.0.context.cfg.chain_id), 256))
+    }
+
+    fn get_block_coinbase(&self, _snapshot_id: usize) -> Result<DynSolValue> {
+        Ok(DynSolValue::Address(self.0.context.block.beneficiary))
+    }
+
+    fn get_block_prevrandao(&self, _snapshot_id: usize) -> Result<DynSolValue> {
+        let block = &self.0.context.block;
+        let randomness = match block.prevrandao {
+            Some(prevrandao) => prevrandao.into(),
+            None => block.difficulty,
+        };
+        Ok(DynSolValue::Uint(randomness, 256))
+    }
+
+    fn get_block_gaslimit(&self, _snapshot_id: usize) -> Result<DynSolValue> {
+        Ok(DynSolValue::Uint(U256::from(self.0.context.block.gas_limit), 256))
+    }
+
+    fn get_block_blobbasefee(&self, _snapshot_id: usize) -> Result<DynSolValue> {
+        let blob_base_fee = self
+            .0
+            .context
+            .block
+            .blob_gasprice()
+            .ok_or_else(|| eyre!("block.blobbasefee is not available before Cancun"))?;
+        Ok(DynSolValue::Uint(U256::from(blob_base_fee), 256))
+    }
+
+    fn get_block_hash(&self, number: U256, snapshot_id: usize) -> Result<DynSolValue> {
+        let (_, snapshot) = self.0.context.snapshots.get(snapshot_id).ok_or_else(|| {
+            eyre::eyre!("Snapshot ID {} not found in EdbHandler::get_block_hash", snapshot_id)
+        })?;
+
+        // Like BLOCKHASH, only the 256 most recent blocks are available
+        let current = U256::from(self.0.context.fork_info.block_number);
+        if number >= current || current - number > U256::from(256) {
+            return Ok(DynSolValue::FixedBytes(B256::ZERO, 32));
+        }
+
+        let hash = snapshot
+            .db()
+            .block_hash_ref(number.to())
+            .map_err(|e| eyre!("Failed to retrieve the hash of block {number}: {e}"))?;
+        Ok(DynSolValue::FixedBytes(hash, 32))
     }
 }
 
@@ -898,3 +1244,118 @@ where
         }
     }
 }
+
+/// Get the trace entry of the call frame that a snapshot belongs to
+fn snapshot_trace_entry<DB>(context: &EngineContext<DB>, snapshot_id: usize) -> Result<&TraceEntry>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let (frame_id, _) = context
+        .snapshots
+        .get(snapshot_id)
+        .ok_or_else(|| eyre!("Snapshot ID {} not found", snapshot_id))?;
+
+    context
+        .trace
+        .get(frame_id.trace_entry_id())
+        .ok_or_else(|| eyre!("Frame ID {} not found", frame_id))
+}
+
+/// Get the source step of a hook snapshot
+fn snapshot_step<DB>(context: &EngineContext<DB>, snapshot_id: usize) -> Option<StepRef>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let usid = context.snapshots.get(snapshot_id)?.1.usid()?;
+    context.get_step(context.get_bytecode_address(snapshot_id)?, usid)
+}
+
+/// Check whether a snapshot makes a call, either a call opcode or a function call in a step
+fn snapshot_makes_call<DB>(context: &EngineContext<DB>, snapshot_id: usize) -> bool
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    match context.snapshots.get(snapshot_id).map(|(_, snapshot)| snapshot.detail()) {
+        Some(SnapshotDetail::Opcode(detail)) => {
+            unsafe { OpCode::new_unchecked(detail.opcode) }.is_call()
+        }
+        Some(SnapshotDetail::Hook(_)) => {
+            snapshot_step(context, snapshot_id).is_some_and(|step| step.function_calls() > 0)
+        }
+        None => false,
+    }
+}
+
+/// Find the snapshot at the entry of the function that a snapshot belongs to.
+///
+/// We step back until we reach a function (or modifier) entry. Stepping back skips over
+/// internal calls that have already returned, so the entries of callees are never reached.
+/// Without source information, the first snapshot of the call is the entry.
+fn function_entry_snapshot<DB>(context: &EngineContext<DB>, snapshot_id: usize) -> usize
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let trace_entry_id = |id: usize| context.snapshots[id].0.trace_entry_id();
+
+    let mut current = snapshot_id;
+    loop {
+        if snapshot_step(context, current)
+            .is_some_and(|step| step.function_entry().is_some() || step.modifier_entry().is_some())
+        {
+            return current;
+        }
+
+        match context.snapshots[current].1.prev_id() {
+            Some(prev) if prev < current && trace_entry_id(prev) == trace_entry_id(current) => {
+                current = prev;
+            }
+            _ => return current,
+        }
+    }
+}
+
+impl<DB> SnapshotHandler for EdbSnapshotHandler<DB>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    fn resolve_snapshot(&self, selector: &str, snapshot_id: usize) -> Result<usize> {
+        let context = &self.0.context;
+        let (_, snapshot) = context.snapshots.get(snapshot_id).ok_or_else(|| {
+            eyre!("Snapshot ID {} not found in EdbHandler::resolve_snapshot", snapshot_id)
+        })?;
+
+        let target_id = match selector {
+            "entry" => function_entry_snapshot(context, snapshot_id),
+            "prev" => snapshot.prev_id().unwrap_or(snapshot_id),
+            "next" => snapshot.next_id().unwrap_or(snapshot_id),
+            "prev_call" => (0..snapshot_id)
+                .rev()
+                .find(|id| snapshot_makes_call(context, *id))
+                .ok_or_else(|| eyre!("No call before snapshot {}", snapshot_id))?,
+            "next_call" => (snapshot_id + 1..context.snapshots.len())
+                .find(|id| snapshot_makes_call(context, *id))
+                .ok_or_else(|| eyre!("No call after snapshot {}", snapshot_id))?,
+            _ => match selector.parse::<usize>() {
+                Ok(id) if id < context.snapshots.len() => id,
+                Ok(id) => bail!("Snapshot {} not found", id),
+                Err(_) => bail!(
+                    "Unknown time-travel selector '@{}'; expected a snapshot id, entry, prev, next, prev_call, or next_call",
+                    selector
+                ),
+            },
+        };
+
+        debug!("EdbHandler::resolve_snapshot @{} at {} -> {}", selector, snapshot_id, target_id);
+        Ok(target_id)
+    }
+}
@@ -31,6 +31,7 @@
 //! - [`TxHandler`] - Provides transaction globals (`tx.origin`)
 //! - [`BlockHandler`] - Provides block context (`block.number`, `block.timestamp`)
 //! - [`ValidationHandler`] - Validates final expression results
+//! - [`SnapshotHandler`] - Resolves time-travel selectors (e.g., `@entry(...)`) to snapshots
 //!
 //! # Usage
 //!
@@ -44,6 +45,8 @@
 //! ```
 
 use alloy_dyn_abi::DynSolValue;
+use alloy_primitives::U256;
+use edb_common::types::{CallOptions, EvalCall};
 use eyre::Result;
 
 /// Handler trait for variable value resolution.
@@ -95,6 +98,7 @@ pub trait FunctionCallHandler {
     /// * `name` - The function name to call
     /// * `args` - Function arguments as [`DynSolValue`] array
     /// * `callee` - Optional target address for the call (None uses current context)
+    /// * `options` - Call options like `{from: owner, value: 1 ether}`, empty by default
     /// * `snapshot_id` - The execution context identifier
     ///
     /// # Returns
@@ -104,8 +108,36 @@ pub trait FunctionCallHandler {
         name: &str,
         args: &[DynSolValue],
         callee: Option<&DynSolValue>,
+        options: &CallOptions,
         snapshot_id: usize,
     ) -> Result<DynSolValue>;
+
+    /// Call a function with named arguments, e.g. `transfer({to: user, amount: 1})`.
+    ///
+    /// # Arguments
+    /// * `name` - The function name to call
+    /// * `args` - Function arguments as (parameter name, value) pairs, in the written order
+    /// * `callee` - Optional target address for the call (None uses current context)
+    /// * `options` - Call options like `{from: owner, value: 1 ether}`, empty by default
+    /// * `snapshot_id` - The execution context identifier
+    ///
+    /// # Returns
+    /// The function's return value, or an error if the call fails
+    fn call_function_with_named_args(
+        &self,
+        name: &str,
+        args: &[(String, DynSolValue)],
+        callee: Option<&DynSolValue>,
+        options: &CallOptions,
+        snapshot_id: usize,
+    ) -> Result<DynSolValue>;
+
+    /// Take the records of the contract calls executed since the last time this was called.
+    ///
+    /// Handlers that do not execute calls in an EVM have nothing to record.
+    fn take_recorded_calls(&self) -> Vec<EvalCall> {
+        Vec::new()
+    }
 }
 
 /// Handler trait for member access operations.
@@ -132,7 +164,8 @@ pub trait MemberAccessHandler {
 
 /// Handler trait for `msg` global variables.
 ///
-/// Provides access to transaction context variables like `msg.sender` and `msg.value`.
+/// Provides access to call context variables like `msg.sender`, `msg.value`, and `msg.data`,
+/// as well as the remaining gas of the call.
 pub trait MsgHandler {
     /// Get the message sender address (`msg.sender`).
     ///
@@ -151,11 +184,38 @@ pub trait MsgHandler {
     /// # Returns
     /// The message value in wei as a [`DynSolValue::Uint`]
     fn get_msg_value(&self, snapshot_id: usize) -> Result<DynSolValue>;
+
+    /// Get the complete calldata of the current call (`msg.data`).
+    ///
+    /// # Arguments
+    /// * `snapshot_id` - The execution context identifier
+    ///
+    /// # Returns
+    /// The calldata as a [`DynSolValue::Bytes`]
+    fn get_msg_data(&self, snapshot_id: usize) -> Result<DynSolValue>;
+
+    /// Get the function selector of the current call (`msg.sig`).
+    ///
+    /// # Arguments
+    /// * `snapshot_id` - The execution context identifier
+    ///
+    /// # Returns
+    /// The first four bytes of the calldata as a [`DynSolValue::FixedBytes`]
+    fn get_msg_sig(&self, snapshot_id: usize) -> Result<DynSolValue>;
+
+    /// Get the remaining gas of the current call (`gasleft()`).
+    ///
+    /// # Arguments
+    /// * `snapshot_id` - The execution context identifier
+    ///
+    /// # Returns
+    /// The remaining gas as a [`DynSolValue::Uint`]
+    fn get_gas_left(&self, snapshot_id: usize) -> Result<DynSolValue>;
 }
 
 /// Handler trait for `tx` global variables.
 ///
-/// Provides access to transaction-level context like `tx.origin`.
+/// Provides access to transaction-level context like `tx.origin` and `tx.gasprice`.
 pub trait TxHandler {
     /// Get the transaction origin address (`tx.origin`).
     ///
@@ -165,6 +225,15 @@ pub trait TxHandler {
     /// # Returns
     /// The origin address as a [`DynSolValue::Address`]
     fn get_tx_origin(&self, snapshot_id: usize) -> Result<DynSolValue>;
+
+    /// Get the effective gas price of the transaction (`tx.gasprice`).
+    ///
+    /// # Arguments
+    /// * `snapshot_id` - The execution context identifier
+    ///
+    /// # Returns
+    /// The gas price in wei as a [`DynSolValue::Uint`]
+    fn get_tx_gas_price(&self, snapshot_id: usize) -> Result<DynSolValue>;
 }
 
 /// Handler trait for final expression validation.
@@ -184,7 +253,7 @@ pub trait ValidationHandler {
 
 /// Handler trait for `block` global variables.
 ///
-/// Provides access to blockchain context like block number and timestamp.
+/// Provides access to blockchain context like block number, timestamp, and recent block hashes.
 pub trait BlockHandler {
     /// Get the current block number (`block.number`).
     ///
@@ -203,6 +272,89 @@ pub trait BlockHandler {
     /// # Returns
     /// The block timestamp as a [`DynSolValue::Uint`]
     fn get_block_timestamp(&self, snapshot_id: usize) -> Result<DynSolValue>;
+
+    /// Get the base fee of the current block (`block.basefee`).
+    ///
+    /// # Arguments
+    /// * `snapshot_id` - The execution context identifier
+    ///
+    /// # Returns
+    /// The base fee in wei as a [`DynSolValue::Uint`]
+    fn get_block_basefee(&self, snapshot_id: usize) -> Result<DynSolValue>;
+
+    /// Get the chain id (`block.chainid`).
+    ///
+    /// # Arguments
+    /// * `snapshot_id` - The execution context identifier
+    ///
+    /// # Returns
+    /// The chain id as a [`DynSolValue::Uint`]
+    fn get_block_chainid(&self, snapshot_id: usize) -> Result<DynSolValue>;
+
+    /// Get the beneficiary of the current block (`block.coinbase`).
+    ///
+    /// # Arguments
+    /// * `snapshot_id` - The execution context identifier
+    ///
+    /// # Returns
+    /// The beneficiary address as a [`DynSolValue::Address`]
+    fn get_block_coinbase(&self, snapshot_id: usize) -> Result<DynSolValue>;
+
+    /// Get the randomness of the current block (`block.prevrandao`, or `block.difficulty`
+    /// before the merge).
+    ///
+    /// # Arguments
+    /// * `snapshot_id` - The execution context identifier
+    ///
+    /// # Returns
+    /// The randomness as a [`DynSolValue::Uint`]
+    fn get_block_prevrandao(&self, snapshot_id: usize) -> Result<DynSolValue>;
+
+    /// Get the gas limit of the current block (`block.gaslimit`).
+    ///
+    /// # Arguments
+    /// * `snapshot_id` - The execution context identifier
+    ///
+    /// # Returns
+    /// The gas limit as a [`DynSolValue::Uint`]
+    fn get_block_gaslimit(&self, snapshot_id: usize) -> Result<DynSolValue>;
+
+    /// Get the blob base fee of the current block (`block.blobbasefee`).
+    ///
+    /// # Arguments
+    /// * `snapshot_id` - The execution context identifier
+    ///
+    /// # Returns
+    /// The blob base fee in wei as a [`DynSolValue::Uint`]
+    fn get_block_blobbasefee(&self, snapshot_id: usize) -> Result<DynSolValue>;
+
+    /// Get the hash of a recent block (`blockhash(n)`).
+    ///
+    /// # Arguments
+    /// * `number` - The block number
+    /// * `snapshot_id` - The execution context identifier
+    ///
+    /// # Returns
+    /// The block hash as a [`DynSolValue::FixedBytes`], zero for blocks that are not
+    /// among the 256 most recent ones
+    fn get_block_hash(&self, number: U256, snapshot_id: usize) -> Result<DynSolValue>;
+}
+
+/// Handler trait for time-travel snapshot resolution.
+///
+/// Resolves the selector of a time-travel expression like `@entry(totalSupply)` to the
+/// snapshot at which its sub-expression is evaluated. Numeric selectors (e.g., `@123(x)`)
+/// refer to snapshots directly and are passed through as well.
+pub trait SnapshotHandler {
+    /// Resolve a time-travel selector relative to a snapshot.
+    ///
+    /// # Arguments
+    /// * `selector` - The selector name (e.g., `entry`, `prev_call`, or a snapshot id)
+    /// * `snapshot_id` - The execution context identifier
+    ///
+    /// # Returns
+    /// The id of the selected snapshot, or an error if there is no such snapshot
+    fn resolve_snapshot(&self, selector: &str, snapshot_id: usize) -> Result<usize>;
 }
 
 /// Combined handlers struct for the expression evaluator.
@@ -237,6 +389,8 @@ pub struct EvaluatorHandlers {
     pub block_handler: Option<Box<dyn BlockHandler>>,
     /// Handler for final value validation
     pub validation_handler: Option<Box<dyn ValidationHandler>>,
+    /// Handler for time-travel snapshot resolution
+    pub snapshot_handler: Option<Box<dyn SnapshotHandler>>,
 }
 
 impl Clone for EvaluatorHandlers {
@@ -353,6 +507,18 @@ impl EvaluatorHandlers {
         self.validation_handler = Some(handler);
         self
     }
+
+    /// Set the snapshot handler.
+    ///
+    /// # Arguments
+    /// * `handler` - The snapshot handler implementation
+    ///
+    /// # Returns
+    /// Self for method chaining
+    pub fn with_snapshot_handler(mut self, handler: Box<dyn SnapshotHandler>) -> Self {
+        self.snapshot_handler = Some(handler);
+        self
+    }
 }
 
 /// Debug handler implementations for testing and simulation
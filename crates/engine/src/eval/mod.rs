@@ -45,14 +45,20 @@
 //!
 //! - **Variables**: `balance`, `owner`, `this`
 //! - **Mappings/Arrays**: `balances[addr]`, `users[0]`
-//! - **Function Calls**: `balanceOf(user)`, `totalSupply()`
+//! - **Function Calls**: `balanceOf(user)`, `totalSupply()`, `transfer({to: user, amount: 1})`
 //! - **Member Access**: `token.symbol`, `addr.balance`
 //! - **Arithmetic**: `+`, `-`, `*`, `/`, `%`, `**`
 //! - **Comparison**: `==`, `!=`, `<`, `<=`, `>`, `>=`
 //! - **Logical**: `&&`, `||`, `!`
 //! - **Ternary**: `condition ? true_value : false_value`
 //! - **Type Casting**: `uint256(value)`, `address(0x123...)`
-//! - **Blockchain Context**: `msg.sender`, `msg.value`, `block.number`, `tx.origin`
+//! - **Type Information**: `type(uint8).max`, `type(int256).min`
+//! - **ABI Built-ins**: `abi.encode(x, y)`, `abi.decode(data, (uint256, address))`,
+//!   `abi.encodeWithSignature("f(uint256)", x)`, `bytes.concat(a, b)`, `string.concat(a, b)`
+//! - **Unit Literals**: `1 ether`, `0.5 gwei`, `1 days`, `1e18`
+//! - **Blockchain Context**: `msg.sender`, `msg.data`, `tx.gasprice`, `block.basefee`,
+//!   `blockhash(n)`, `gasleft()`, `address(this).balance`
+//! - **Time Travel**: `@123(balances[user])`, `@entry(totalSupply)`, `@prev_call(x)`
 
 mod common;
 pub use common::*;
@@ -25,6 +25,12 @@ use solang_parser::{
     pt::{Expression, SourceUnit, SourceUnitPart, Statement},
 };
 
+/// Name prefix of the pseudo-functions that time-travel expressions are rewritten to.
+///
+/// Since `@entry(x)` is not valid Solidity, [`parse_input`] rewrites it to
+/// `__edb_at_entry(x)` before parsing, and the evaluator recognizes the prefix.
+pub const TIME_TRAVEL_PREFIX: &str = "__edb_at_";
+
 /// Parse a string input into a Solidity expression AST.
 ///
 /// This function wraps the input string in a dummy function definition to
@@ -46,7 +52,8 @@ use solang_parser::{
 /// let expr = parse_input("totalSupply() * price / 1e18")?;
 /// ```
 pub fn parse_input(input: &str) -> Result<Expression> {
-    let trimmed = input.trim();
+    let rewritten = rewrite_time_travel(input);
+    let trimmed = rewritten.trim();
     let wrapped_input = if trimmed.ends_with(";") {
         format!("function __edb_sol_repl_() public {{ {trimmed} }}")
     } else {
@@ -81,6 +88,52 @@ pub fn parse_input(input: &str) -> Result<Expression> {
     }
 }
 
+/// Rewrite time-travel expressions like `@123(x)` or `@entry(x)` into calls to
+/// pseudo-functions named with [`TIME_TRAVEL_PREFIX`], leaving string literals untouched.
+fn rewrite_time_travel(input: &str) -> String {
+    let chars: Vec<char> = input.chars().collect();
+    let mut output = String::with_capacity(input.len());
+    let mut quote = None;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match quote {
+            Some(q) => {
+                output.push(c);
+                if c == '\\' {
+                    if let Some(&escaped) = chars.get(i + 1) {
+                        output.push(escaped);
+                        i += 1;
+                    }
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                output.push(c);
+            }
+            None if c == '@' => {
+                let selector: String = chars[i + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_alphanumeric() || **c == '_')
+                    .collect();
+                let next = chars[i + 1 + selector.len()..].iter().find(|c| !c.is_whitespace());
+                if !selector.is_empty() && next == Some(&'(') {
+                    output.push_str(TIME_TRAVEL_PREFIX);
+                } else {
+                    output.push(c);
+                }
+            }
+            None => output.push(c),
+        }
+        i += 1;
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use solang_parser::pt::Identifier;
@@ -151,4 +204,27 @@ mod tests {
             panic!("Parsed expression is not a multiplication");
         }
     }
+
+    #[test]
+    fn test_rewrite_time_travel() {
+        assert_eq!(rewrite_time_travel("@123(balances[user])"), "__edb_at_123(balances[user])");
+        assert_eq!(
+            rewrite_time_travel("totalSupply < @entry (totalSupply)"),
+            "totalSupply < __edb_at_entry (totalSupply)"
+        );
+        assert_eq!(rewrite_time_travel("name == \"@entry(x)\""), "name == \"@entry(x)\"");
+        assert_eq!(rewrite_time_travel("a @ b"), "a @ b");
+
+        let expr = parse_input("@prev_call(x) + 1").unwrap();
+        let Expression::Add(_, left, _) = expr else {
+            panic!("Parsed expression is not an addition")
+        };
+        let Expression::FunctionCall(_, func, args) = *left else {
+            panic!("Time-travel expression is not a function call")
+        };
+        assert!(
+            matches!(*func, Expression::Variable(Identifier { ref name, .. }) if name == "__edb_at_prev_call")
+        );
+        assert_eq!(args.len(), 1);
+    }
 }
@@ -237,8 +237,9 @@ where
     /// Creation hooks (original contract bytecode, hooked bytecode, constructor args)
     creation_hooks: Vec<(Bytes, Bytes, Bytes)>,
 
-    /// The latest value of each UVID encountered (for variable tracking)
-    uvid_values: HashMap<UVID, Arc<EdbSolValue>>,
+    /// The latest value of each UVID encountered (for variable tracking), keyed by
+    /// bytecode address since UVIDs are only unique within an analysis
+    uvid_values: HashMap<(Address, UVID), Arc<EdbSolValue>>,
 }
 
 impl<'a, DB> HookSnapshotInspector<'a, DB>
@@ -265,22 +266,7 @@ where
         &mut self,
         hooks: Vec<(&Contract, &Contract, &Bytes)>,
     ) -> Result<()> {
-        for (original, hooked, args) in hooks {
-            self.creation_hooks.push((
-                original
-                    .get_bytecode_bytes()
-                    .ok_or(eyre::eyre!("Failed to get bytecode for contract"))?
-                    .as_ref()
-                    .clone(),
-                hooked
-                    .get_bytecode_bytes()
-                    .ok_or(eyre::eyre!("Failed to get bytecode for contract"))?
-                    .as_ref()
-                    .clone(),
-                args.clone(),
-            ));
-        }
-
+        self.creation_hooks.extend(creation_hook_bytecodes(hooks)?);
         Ok(())
     }
 
@@ -371,7 +357,7 @@ where
             }
             let uvid = variable.id();
             let name = variable.declaration().name.clone();
-            locals.insert(name, self.uvid_values.get(&uvid).cloned());
+            locals.insert(name, self.uvid_values.get(&(address, uvid)).cloned());
         }
 
         // Update the last frame with this snapshot
@@ -496,58 +482,13 @@ where
             "Found variable update",
         );
 
-        self.uvid_values.insert(uvid, Arc::new(value.into()));
+        self.uvid_values.insert((address, uvid), Arc::new(value.into()));
     }
 
-    /// Check and apply creation hooks if the bytecode matches
-    fn check_and_apply_creation_hooks(
-        &mut self,
-        inputs: &mut CreateInputs,
-        ctx: &mut EdbContext<DB>,
-    ) {
-        // Get the nonce from the caller account
-        let Ok(account) = ctx.journaled_state.load_account(inputs.caller) else {
-            error!("Failed to load account for caller {:?}", inputs.caller);
-            return;
-        };
-
-        // Calculate what address would be created using the built-in method
-        let nonce = account.info.nonce;
-        let predicted_address = inputs.created_address(nonce);
-
-        for (original_bytecode, hooked_bytecode, constructor_args) in &self.creation_hooks {
-            // Check if constructor arguments are at the tail of input bytes
-            if inputs.init_code.len() >= constructor_args.len() {
-                let input_args_start = inputs.init_code.len() - constructor_args.len();
-                let input_args = &inputs.init_code[input_args_start..];
-
-                // Check if constructor args match
-                if input_args == constructor_args.as_ref() {
-                    // Get the creation bytecode (without constructor args)
-                    let input_bytecode = &inputs.init_code[..input_args_start];
-
-                    // Check if bytecode is very similar to original
-                    // For now, we do exact match, but could be made fuzzy
-                    if input_bytecode == original_bytecode.as_ref() {
-                        // Match found! Replace with hooked bytecode + constructor args
-                        let mut new_init_code = Vec::from(hooked_bytecode.as_ref());
-                        new_init_code.extend_from_slice(constructor_args.as_ref());
-                        inputs.init_code = Bytes::from(new_init_code);
-
-                        // Update creation schema
-                        inputs.scheme = CreateScheme::Custom { address: predicted_address };
-
-                        // Log the replacement
-                        debug!(
-                            "Replaced creation bytecode with hooked version for {:?} -> {:?}",
-                            inputs.caller, predicted_address
-                        );
-
-                        break; // Found a match, no need to check other hooks
-                    }
-                }
-            }
-        }
+    /// Override the latest value of a variable, e.g. after it has been edited, so that the
+    /// following snapshots see the new value until the variable is updated again
+    pub fn set_variable_value(&mut self, address: Address, uvid: UVID, value: DynSolValue) {
+        self.uvid_values.insert((address, uvid), Arc::new(value.into()));
     }
 
     /// Clear all recorded data
@@ -645,7 +586,7 @@ where
         inputs: &mut CreateInputs,
     ) -> Option<CreateOutcome> {
         // Check and apply creation hooks if applicable
-        self.check_and_apply_creation_hooks(inputs, context);
+        apply_creation_hooks(&self.creation_hooks, inputs, context);
 
         // Start tracking new execution frame for contract creation
         self.push_frame(self.current_trace_id);
@@ -817,6 +758,86 @@ where
     }
 }
 
+/// Convert creation hooks (original contract, hooked contract, constructor args) into
+/// their creation bytecodes.
+pub(crate) fn creation_hook_bytecodes(
+    hooks: Vec<(&Contract, &Contract, &Bytes)>,
+) -> Result<Vec<(Bytes, Bytes, Bytes)>> {
+    hooks
+        .into_iter()
+        .map(|(original, hooked, args)| {
+            Ok((
+                original
+                    .get_bytecode_bytes()
+                    .ok_or(eyre::eyre!("Failed to get bytecode for contract"))?
+                    .as_ref()
+                    .clone(),
+                hooked
+                    .get_bytecode_bytes()
+                    .ok_or(eyre::eyre!("Failed to get bytecode for contract"))?
+                    .as_ref()
+                    .clone(),
+                args.clone(),
+            ))
+        })
+        .collect()
+}
+
+/// Check and apply creation hooks if the bytecode matches
+pub(crate) fn apply_creation_hooks<DB>(
+    creation_hooks: &[(Bytes, Bytes, Bytes)],
+    inputs: &mut CreateInputs,
+    ctx: &mut EdbContext<DB>,
+) where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone,
+    <CacheDB<DB> as Database>::Error: Clone,
+    <DB as Database>::Error: Clone,
+{
+    // Get the nonce from the caller account
+    let Ok(account) = ctx.journaled_state.load_account(inputs.caller) else {
+        error!("Failed to load account for caller {:?}", inputs.caller);
+        return;
+    };
+
+    // Calculate what address would be created using the built-in method
+    let nonce = account.info.nonce;
+    let predicted_address = inputs.created_address(nonce);
+
+    for (original_bytecode, hooked_bytecode, constructor_args) in creation_hooks {
+        // Check if constructor arguments are at the tail of input bytes
+        if inputs.init_code.len() >= constructor_args.len() {
+            let input_args_start = inputs.init_code.len() - constructor_args.len();
+            let input_args = &inputs.init_code[input_args_start..];
+
+            // Check if constructor args match
+            if input_args == constructor_args.as_ref() {
+                // Get the creation bytecode (without constructor args)
+                let input_bytecode = &inputs.init_code[..input_args_start];
+
+                // Check if bytecode is very similar to original
+                // For now, we do exact match, but could be made fuzzy
+                if input_bytecode == original_bytecode.as_ref() {
+                    // Match found! Replace with hooked bytecode + constructor args
+                    let mut new_init_code = Vec::from(hooked_bytecode.as_ref());
+                    new_init_code.extend_from_slice(constructor_args.as_ref());
+                    inputs.init_code = Bytes::from(new_init_code);
+
+                    // Update creation schema
+                    inputs.scheme = CreateScheme::Custom { address: predicted_address };
+
+                    // Log the replacement
+                    debug!(
+                        "Replaced creation bytecode with hooked version for {:?} -> {:?}",
+                        inputs.caller, predicted_address
+                    );
+
+                    break; // Found a match, no need to check other hooks
+                }
+            }
+        }
+    }
+}
+
 /// Decode the variable value from the given ABI-encoded data according to the variable declaration.
 ///
 /// This function takes raw ABI-encoded data and decodes it according to the variable's
@@ -38,6 +38,10 @@
 //! stack, memory, storage, and transient storage. Useful for detailed execution
 //! analysis and opcode-level debugging.
 //!
+//! ## [`StateEditInspector`]
+//! Applies state edits (storage, balances, code, calldata, stack, memory) at a given
+//! snapshot while the transaction is replayed, enabling what-if re-execution.
+//!
 //! ## [`TweakInspector`]
 //! Allows runtime modification of contract bytecode and behavior for debugging
 //! purposes. Can inject custom logic, modify return values, and alter execution
@@ -62,9 +66,11 @@
 mod call_tracer;
 mod hook_snapshot_inspector;
 mod opcode_snapshot_inspector;
+mod state_edit_inspector;
 mod tweak_inspector;
 
 pub use call_tracer::*;
 pub use hook_snapshot_inspector::*;
 pub use opcode_snapshot_inspector::*;
+pub use state_edit_inspector::*;
 pub use tweak_inspector::*;
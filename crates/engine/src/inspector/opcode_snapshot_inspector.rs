@@ -68,6 +68,8 @@ where
     pub bytecode_address: Address,
     /// Current opcode
     pub opcode: u8,
+    /// Remaining gas before executing the opcode
+    pub gas_remaining: u64,
     /// Memory state (shared via Arc when unchanged)
     pub memory: Arc<Vec<u8>>,
     /// Stack state (always cloned as most opcodes modify it)
@@ -235,6 +237,22 @@ where
         }
     }
 
+    /// Re-capture the database and transient storage, and forget the memory and calldata
+    /// of the current frame. This is needed when the state has been modified by something
+    /// other than the executed opcodes (e.g., a what-if edit).
+    pub fn refresh_state(&mut self, ctx: &mut EdbContext<DB>) {
+        let mut inner = ctx.journal().to_inner();
+        let changes = inner.finalize();
+        let mut snap = ctx.db().clone();
+        snap.commit(changes);
+        self.database = Arc::new(snap);
+        self.transition_storage = Arc::new(ctx.journal().transient_storage.clone());
+
+        if let Some(frame_id) = self.current_frame_id() {
+            self.frame_states.remove(&frame_id);
+        }
+    }
+
     /// Record a snapshot at the current step
     fn record_snapshot(&mut self, interp: &Interpreter, ctx: &mut EdbContext<DB>) {
         // Get current opcode safely
@@ -301,6 +319,7 @@ where
             bytecode_address: entry.map(|t| t.code_address).unwrap_or(address),
             target_address: entry.map(|t| t.target).unwrap_or(address),
             opcode: opcode.get(),
+            gas_remaining: interp.gas.remaining(),
             memory: memory.clone(),
             stack: interp.stack.data().clone(),
             calldata: calldata.clone(),
@@ -0,0 +1,613 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! State edit inspector for what-if re-execution
+//!
+//! This inspector finds a snapshot of the original timeline again while the transaction
+//! is replayed, and applies a list of [`StateEdit`]s right before the snapshot is taken.
+//! Since the replay is deterministic up to that point, everything executed afterwards
+//! shows how the transaction continues with the edited state.
+//!
+//! A snapshot is identified by its execution frame and its ordinal within the frame,
+//! i.e., the n-th executed instruction for opcode snapshots, or the n-th snapshot hook
+//! for hook snapshots.
+//!
+//! World state edits go through the journal, so they are reverted together with the
+//! frame they are applied in, just like a write made by the contract itself.
+//!
+//! Local variable edits are applied as stack or memory edits. While replaying the edited
+//! frame, the variable update hooks of an edited variable tell its value, and the stack
+//! items holding it (or, for `bytes` and `string`, pointing to it in memory) are
+//! candidates for its location. Locals keep their stack position during a function
+//! invocation, so the candidates of successive updates are intersected, and the one
+//! still holding the value at the edited snapshot is overwritten.
+
+use std::collections::HashMap;
+
+use alloy_dyn_abi::DynSolValue;
+use alloy_primitives::{Bytes, U256};
+use edb_common::{
+    types::{ExecutionFrameId, StateEdit},
+    EdbContext,
+};
+use eyre::{bail, eyre, Result};
+use foundry_compilers::artifacts::Contract;
+use revm::{
+    bytecode::{Bytecode, OpCode},
+    context::{JournalEntry, JournalEntryTr, JournalTr},
+    database::CacheDB,
+    interpreter::{
+        interpreter_types::Jumps, CallInput, CallInputs, CallOutcome, CreateInputs, CreateOutcome,
+        Interpreter,
+    },
+    Database, DatabaseCommit, DatabaseRef, Inspector,
+};
+
+use super::hook_snapshot_inspector::{
+    apply_creation_hooks, creation_hook_bytecodes, MAGIC_SNAPSHOT_NUMBER,
+    MAGIC_VARIABLE_UPDATE_NUMBER,
+};
+use crate::analysis::UVID;
+
+/// A local variable edit, resolved against the analysis of the edited snapshot
+#[derive(Debug, Clone)]
+pub struct LocalEdit {
+    /// Name of the variable
+    pub name: String,
+    /// UVID of the variable
+    pub uvid: UVID,
+    /// Whether the variable points to memory (`bytes` and `string`) rather than being
+    /// kept on the stack
+    pub in_memory: bool,
+    /// New value of the variable
+    pub value: DynSolValue,
+}
+
+/// Where a local variable may be kept, according to its variable update hooks
+#[derive(Debug, Clone, Default)]
+struct LocalCandidates {
+    /// Stack indices (from the bottom of the stack) holding the value, or a pointer to it
+    indices: Vec<usize>,
+    /// Value at the last update: the stack word, or the bytes in memory
+    value: Vec<u8>,
+}
+
+/// Inspector that applies state edits at a given snapshot during a replay
+#[derive(Debug)]
+pub struct StateEditInspector<'a> {
+    /// Frame of the edited snapshot
+    frame_id: ExecutionFrameId,
+
+    /// Ordinal of the edited snapshot within its frame
+    ordinal: usize,
+
+    /// Whether the edited snapshot is a hook snapshot
+    at_hook: bool,
+
+    /// Edits to apply
+    edits: &'a [StateEdit],
+
+    /// Stack to track current execution frames
+    frame_stack: Vec<ExecutionFrameId>,
+
+    /// Current trace entry counter
+    current_trace_id: usize,
+
+    /// Number of snapshots of the edited frame seen so far
+    seen: usize,
+
+    /// Whether the edits have been applied
+    applied: bool,
+
+    /// Errors raised while applying the edits
+    errors: Vec<String>,
+
+    /// Creation hooks (original contract bytecode, hooked bytecode, constructor args)
+    creation_hooks: Vec<(Bytes, Bytes, Bytes)>,
+
+    /// Resolved local variable edits, by name
+    locals: HashMap<String, LocalEdit>,
+
+    /// Candidate locations of the edited local variables
+    candidates: HashMap<UVID, LocalCandidates>,
+}
+
+impl<'a> StateEditInspector<'a> {
+    /// Create a new state edit inspector for the `ordinal`-th snapshot of `frame_id`
+    pub fn new(
+        frame_id: ExecutionFrameId,
+        ordinal: usize,
+        at_hook: bool,
+        edits: &'a [StateEdit],
+    ) -> Self {
+        Self {
+            frame_id,
+            ordinal,
+            at_hook,
+            edits,
+            frame_stack: Vec::new(),
+            current_trace_id: 0,
+            seen: 0,
+            applied: false,
+            errors: Vec::new(),
+            creation_hooks: Vec::new(),
+            locals: HashMap::new(),
+            candidates: HashMap::new(),
+        }
+    }
+
+    /// Add the resolved local variable edits, applied for the [`StateEdit::Local`] edits
+    /// of the same name
+    pub fn with_locals(&mut self, locals: Vec<LocalEdit>) {
+        self.locals.extend(locals.into_iter().map(|local| (local.name.clone(), local)));
+    }
+
+    /// Add creation hooks, so that contracts deployed by the transaction run their
+    /// instrumented code as in the hooked replay
+    pub fn with_creation_hooks(
+        &mut self,
+        hooks: Vec<(&Contract, &Contract, &Bytes)>,
+    ) -> Result<()> {
+        self.creation_hooks.extend(creation_hook_bytecodes(hooks)?);
+        Ok(())
+    }
+
+    /// Whether the edits have been applied
+    pub fn is_applied(&self) -> bool {
+        self.applied
+    }
+
+    /// Consume the inspector and check that all edits have been applied successfully
+    pub fn into_result(self) -> Result<()> {
+        if !self.errors.is_empty() {
+            bail!("Failed to apply state edits: {}", self.errors.join("; "));
+        }
+        if !self.applied {
+            bail!(
+                "Snapshot {} of frame {} was not reached during re-execution",
+                self.ordinal,
+                self.frame_id
+            );
+        }
+        Ok(())
+    }
+
+    /// Get the current execution frame ID
+    fn current_frame_id(&self) -> Option<ExecutionFrameId> {
+        self.frame_stack.last().copied()
+    }
+
+    /// Start tracking a new execution frame
+    fn push_frame(&mut self, trace_id: usize) {
+        self.frame_stack.push(ExecutionFrameId::new(trace_id, 0));
+    }
+
+    /// Stop tracking current execution frame and increment re-entry count
+    fn pop_frame(&mut self) {
+        if self.frame_stack.pop().is_some() {
+            if let Some(parent_frame_id) = self.frame_stack.last_mut() {
+                parent_frame_id.increment_re_entry();
+            }
+        }
+    }
+
+    /// Apply all edits
+    fn apply_edits<DB>(&mut self, interp: &mut Interpreter, ctx: &mut EdbContext<DB>)
+    where
+        DB: Database + DatabaseCommit + DatabaseRef + Clone,
+        <CacheDB<DB> as Database>::Error: Clone,
+        <DB as Database>::Error: Clone,
+    {
+        for edit in self.edits {
+            let result = match edit {
+                StateEdit::Local { name, .. } => {
+                    self.locate_local(name, interp).and_then(|edit| apply_edit(&edit, interp, ctx))
+                }
+                _ => apply_edit(edit, interp, ctx),
+            };
+            if let Err(e) = result {
+                self.errors.push(format!("{edit}: {e}"));
+            }
+        }
+        self.applied = true;
+    }
+
+    /// Record the candidate locations of an edited local variable at its update hook
+    fn record_local_update(&mut self, interp: &Interpreter, data: &[u8]) {
+        // The data is encoded as (uint256 uvid, abi.encode(value)), see
+        // `HookSnapshotInspector::check_and_record_variable_update`
+        let Some(uvid) = data.get(..32).and_then(|w| UVID::try_from(U256::from_be_slice(w)).ok())
+        else {
+            return;
+        };
+        let Some(local) = self.locals.values().find(|local| local.uvid == uvid) else {
+            return;
+        };
+        let Some(encoded) = data.get(96..) else { return };
+
+        let value = if local.in_memory {
+            // abi.encode(bytes) is (offset, length, data)
+            let Some(len) = encoded.get(32..64).map(U256::from_be_slice) else { return };
+            let Ok(len) = usize::try_from(len) else { return };
+            let Some(bytes) = encoded.get(64..64usize.saturating_add(len)) else { return };
+            bytes.to_vec()
+        } else {
+            let Some(word) = encoded.get(..32) else { return };
+            word.to_vec()
+        };
+
+        let indices = matching_indices(interp, local.in_memory, &value);
+        let candidates = self.candidates.entry(uvid).or_default();
+        let kept: Vec<_> =
+            candidates.indices.iter().copied().filter(|i| indices.contains(i)).collect();
+        candidates.indices = if kept.is_empty() { indices } else { kept };
+        candidates.value = value;
+    }
+
+    /// Locate an edited local variable at the edited snapshot, as a stack or memory edit
+    fn locate_local(&self, name: &str, interp: &Interpreter) -> Result<StateEdit> {
+        let local = self.locals.get(name).ok_or(eyre!("Local variable {name} is not resolved"))?;
+        let Some(candidates) = self.candidates.get(&local.uvid) else {
+            bail!("{name} has not been assigned in this call");
+        };
+
+        let current = matching_indices(interp, local.in_memory, &candidates.value);
+        let indices: Vec<_> =
+            candidates.indices.iter().copied().filter(|i| current.contains(i)).collect();
+        let index = match indices.as_slice() {
+            [index] => *index,
+            [] => bail!("{name} was not found on the stack"),
+            _ => bail!("{name} cannot be located: {} stack items hold its value", indices.len()),
+        };
+
+        let stack = interp.stack.data();
+        if local.in_memory {
+            let new = match &local.value {
+                DynSolValue::Bytes(bytes) => bytes.clone(),
+                DynSolValue::String(s) => s.as_bytes().to_vec(),
+                _ => bail!("{name} is not bytes or a string"),
+            };
+            // The new value has to fit in the memory allocated for the current one, whose
+            // remainder is cleared
+            let allocated = candidates.value.len().div_ceil(32) * 32;
+            if new.len() > allocated {
+                bail!(
+                    "The new value of {name} is longer than the {allocated} bytes allocated for it"
+                );
+            }
+            let offset = usize::try_from(stack[index])?;
+            let mut data = U256::from(new.len()).to_be_bytes::<32>().to_vec();
+            data.extend(&new);
+            data.resize(32 + allocated, 0);
+            Ok(StateEdit::Memory { offset, data: data.into() })
+        } else {
+            let value = U256::from_be_slice(&local.value.abi_encode());
+            Ok(StateEdit::Stack { depth: stack.len() - 1 - index, value })
+        }
+    }
+}
+
+/// Stack indices (from the bottom of the stack) holding a word, or pointing to `bytes`
+/// in memory
+fn matching_indices(interp: &Interpreter, in_memory: bool, value: &[u8]) -> Vec<usize> {
+    let stack = interp.stack.data();
+    let matches = |item: &U256| {
+        if !in_memory {
+            return item.to_be_bytes::<32>()[..] == *value;
+        }
+        let Ok(offset) = usize::try_from(*item) else { return false };
+        let end = offset.checked_add(32).and_then(|end| end.checked_add(value.len()));
+        if end.is_none_or(|end| end > interp.memory.len()) {
+            return false;
+        }
+        U256::from_be_slice(&interp.memory.slice_len(offset, 32)) == U256::from(value.len())
+            && *interp.memory.slice_len(offset + 32, value.len()) == *value
+    };
+    stack.iter().enumerate().filter(|(_, item)| matches(item)).map(|(i, _)| i).collect()
+}
+
+/// Input of the hook executed by the current instruction, if it is a hook: the magic
+/// number and the data following it
+fn hook_input(interp: &Interpreter) -> Option<(U256, Vec<u8>)> {
+    if interp.bytecode.opcode() != OpCode::KECCAK256.get() {
+        return None;
+    }
+
+    let stack = interp.stack.data();
+    let [.., len, offset] = stack.as_slice() else { return None };
+    let (Ok(offset), Ok(len)) = (usize::try_from(*offset), usize::try_from(*len)) else {
+        return None;
+    };
+    if len < 32 || offset.checked_add(len).is_none_or(|end| end > interp.memory.len()) {
+        return None;
+    }
+
+    let data = interp.memory.slice_len(offset, len);
+    Some((U256::from_be_slice(&data[..32]), data[32..].to_vec()))
+}
+
+/// Check whether the current instruction is a snapshot hook
+fn is_snapshot_hook(interp: &Interpreter) -> bool {
+    if interp.bytecode.opcode() != OpCode::KECCAK256.get() {
+        return false;
+    }
+
+    let stack = interp.stack.data();
+    let [.., len, offset] = stack.as_slice() else { return false };
+    let (Ok(offset), Ok(len)) = (usize::try_from(*offset), usize::try_from(*len)) else {
+        return false;
+    };
+    if len < 32 || offset.saturating_add(32) > interp.memory.len() {
+        return false;
+    }
+
+    U256::from_be_slice(&interp.memory.slice_len(offset, 32)) == MAGIC_SNAPSHOT_NUMBER
+}
+
+/// Apply a single edit to the interpreter or the journaled state
+fn apply_edit<DB>(
+    edit: &StateEdit,
+    interp: &mut Interpreter,
+    ctx: &mut EdbContext<DB>,
+) -> Result<()>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone,
+    <CacheDB<DB> as Database>::Error: Clone,
+    <DB as Database>::Error: Clone,
+{
+    let journal = &mut ctx.journaled_state;
+    match edit {
+        StateEdit::Storage { address, slot, value } => {
+            journal.load_account(*address).map_err(|e| eyre!("{e}"))?;
+            journal.sstore(*address, *slot, *value).map_err(|e| eyre!("{e}"))?;
+        }
+        StateEdit::TransientStorage { address, slot, value } => {
+            journal.tstore(*address, *slot, *value);
+        }
+        StateEdit::Balance { address, balance } => {
+            let mut account = journal.load_account(*address).map_err(|e| eyre!("{e}"))?;
+            let old_balance = std::mem::replace(&mut account.info.balance, *balance);
+            journal.journal.push(JournalEntry::balance_changed(*address, old_balance));
+            journal.touch_account(*address);
+        }
+        StateEdit::Code { address, code } => {
+            journal.load_account(*address).map_err(|e| eyre!("{e}"))?;
+            journal.set_code(*address, Bytecode::new_raw(code.clone()));
+        }
+        StateEdit::Calldata { data } => {
+            interp.input.input = CallInput::Bytes(data.clone());
+        }
+        StateEdit::Stack { depth, value } => {
+            let stack = interp.stack.data_mut();
+            if *depth >= stack.len() {
+                bail!("stack has only {} items", stack.len());
+            }
+            let index = stack.len() - 1 - depth;
+            stack[index] = *value;
+        }
+        StateEdit::Memory { offset, data } => {
+            let end = offset.checked_add(data.len()).ok_or(eyre!("offset overflow"))?;
+            if end > interp.memory.len() {
+                interp.memory.resize(end.div_ceil(32) * 32);
+            }
+            interp.memory.set(*offset, data);
+        }
+        StateEdit::Local { name, .. } => bail!("local variable {name} is not located"),
+    }
+    Ok(())
+}
+
+impl<'a, DB> Inspector<EdbContext<DB>> for StateEditInspector<'a>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone,
+    <CacheDB<DB> as Database>::Error: Clone,
+    <DB as Database>::Error: Clone,
+{
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EdbContext<DB>) {
+        if self.applied || self.current_frame_id() != Some(self.frame_id) {
+            return;
+        }
+
+        if !self.locals.is_empty() {
+            match hook_input(interp) {
+                Some((magic, data)) if magic == MAGIC_VARIABLE_UPDATE_NUMBER => {
+                    self.record_local_update(interp, &data)
+                }
+                _ => {}
+            }
+        }
+
+        if self.at_hook && !is_snapshot_hook(interp) {
+            return;
+        }
+
+        if self.seen == self.ordinal {
+            self.apply_edits(interp, context);
+        }
+        self.seen += 1;
+    }
+
+    fn call(
+        &mut self,
+        _context: &mut EdbContext<DB>,
+        _inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.push_frame(self.current_trace_id);
+        self.current_trace_id += 1;
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EdbContext<DB>,
+        _inputs: &CallInputs,
+        _outcome: &mut CallOutcome,
+    ) {
+        self.pop_frame();
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EdbContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        apply_creation_hooks(&self.creation_hooks, inputs, context);
+
+        self.push_frame(self.current_trace_id);
+        self.current_trace_id += 1;
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EdbContext<DB>,
+        _inputs: &CreateInputs,
+        _outcome: &mut CreateOutcome,
+    ) {
+        self.pop_frame();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, Address, TxKind};
+    use edb_common::relax_evm_constraints;
+    use revm::{
+        context::TxEnv,
+        database::EmptyDB,
+        state::{AccountInfo, Bytecode},
+        Context, InspectEvm, MainBuilder, MainContext,
+    };
+
+    const CONTRACT: Address = address!("0x00000000000000000000000000000000000000aa");
+    const UVID_X: u64 = 1;
+
+    /// Bytecode emulating the hooks of an instrumented contract
+    #[derive(Default)]
+    struct Code(Vec<u8>);
+
+    impl Code {
+        fn push(&mut self, value: U256) -> &mut Self {
+            self.0.push(0x7f); // PUSH32
+            self.0.extend(value.to_be_bytes::<32>());
+            self
+        }
+
+        fn op(&mut self, opcode: u8) -> &mut Self {
+            self.0.push(opcode);
+            self
+        }
+
+        fn mstore(&mut self, offset: usize, value: U256) -> &mut Self {
+            self.push(value).push(U256::from(offset)).op(0x52)
+        }
+
+        /// Hash `len` bytes of memory from offset 0, as hooks do
+        fn keccak(&mut self, len: usize) -> &mut Self {
+            self.push(U256::from(len)).push(U256::ZERO).op(0x20).op(0x50)
+        }
+
+        /// Variable update hook of `x`, kept at stack `depth`: the value is encoded
+        /// (with the `DUP` of `x`) as `abi.encode(magic, uvid, abi.encode(x))`
+        fn value_update(&mut self, depth: u8) -> &mut Self {
+            self.mstore(0, MAGIC_VARIABLE_UPDATE_NUMBER).mstore(32, U256::from(UVID_X));
+            self.mstore(64, U256::from(0x60)).mstore(96, U256::from(32));
+            self.op(0x80 + depth).push(U256::from(128)).op(0x52).keccak(160)
+        }
+
+        /// Snapshot hook with USID 1
+        fn snapshot(&mut self) -> &mut Self {
+            self.mstore(0, MAGIC_SNAPSHOT_NUMBER).mstore(32, U256::from(1)).keccak(64)
+        }
+
+        /// Log the memory word at `offset`
+        fn log_word(&mut self, offset: usize) -> &mut Self {
+            self.push(U256::from(offset)).op(0x51).push(U256::ZERO).op(0x52);
+            self.push(U256::from(32)).push(U256::ZERO).op(0xa0)
+        }
+    }
+
+    /// Run the code with a local variable edit at its first snapshot hook, returning the
+    /// logged words
+    fn run_with_local(code: &Code, local: LocalEdit) -> Result<Vec<U256>> {
+        let mut db = CacheDB::new(CacheDB::new(EmptyDB::default()));
+        let bytecode = Bytecode::new_raw(code.0.clone().into());
+        db.insert_account_info(CONTRACT, AccountInfo::default().with_code(bytecode));
+        let mut ctx: EdbContext<CacheDB<EmptyDB>> = Context::mainnet().with_db(db);
+        let mut tx =
+            TxEnv { kind: TxKind::Call(CONTRACT), gas_limit: 1_000_000, ..Default::default() };
+        relax_evm_constraints(&mut ctx, &mut tx);
+
+        let edits = [StateEdit::Local { name: local.name.clone(), value: String::new() }];
+        let mut editor = StateEditInspector::new(ExecutionFrameId::new(0, 0), 0, true, &edits);
+        editor.with_locals(vec![local]);
+        let result = {
+            let mut evm = ctx.build_mainnet_with_inspector(&mut editor);
+            evm.inspect_one_tx(tx).map_err(|e| eyre!("{e:?}"))?
+        };
+        editor.into_result()?;
+        Ok(result.logs().iter().map(|log| U256::from_be_slice(&log.data.data)).collect())
+    }
+
+    fn local_x(in_memory: bool, value: DynSolValue) -> LocalEdit {
+        LocalEdit { name: "x".to_string(), uvid: UVID::from(UVID_X), in_memory, value }
+    }
+
+    #[test]
+    fn test_edit_local_on_stack() {
+        // x = 5 and y = 9 are kept on the stack
+        let mut code = Code::default();
+        code.push(U256::from(5)).push(U256::from(9)).value_update(1).snapshot();
+        code.op(0x81).push(U256::from(0x200)).op(0x52).log_word(0x200);
+
+        let value = DynSolValue::Uint(U256::from(7), 256);
+        assert_eq!(run_with_local(&code, local_x(false, value)).unwrap(), [U256::from(7)]);
+    }
+
+    #[test]
+    fn test_edit_local_ambiguous() {
+        // x = 5 and y = 5 cannot be told apart
+        let mut code = Code::default();
+        code.push(U256::from(5)).push(U256::from(5)).value_update(1).snapshot();
+        code.op(0x00);
+
+        let value = DynSolValue::Uint(U256::from(7), 256);
+        let err = run_with_local(&code, local_x(false, value)).unwrap_err().to_string();
+        assert!(err.contains("x cannot be located: 2 stack items hold its value"), "{err}");
+    }
+
+    #[test]
+    fn test_edit_local_in_memory() {
+        // x = "abc" is kept at memory offset 0x200, pointed to by the stack
+        let mut code = Code::default();
+        code.mstore(0x200, U256::from(3)).mstore(0x220, U256::from_be_slice(b"abc") << 232);
+        code.push(U256::from(0x200));
+        code.mstore(0, MAGIC_VARIABLE_UPDATE_NUMBER).mstore(32, U256::from(UVID_X));
+        code.mstore(64, U256::from(0x60)).mstore(96, U256::from(96));
+        code.mstore(128, U256::from(32)).mstore(160, U256::from(3));
+        code.mstore(192, U256::from_be_slice(b"abc") << 232).keccak(224);
+        code.snapshot().log_word(0x200).log_word(0x220);
+
+        let value = DynSolValue::String("xy".to_string());
+        let logs = run_with_local(&code, local_x(true, value)).unwrap();
+        assert_eq!(logs, [U256::from(2), U256::from_be_slice(b"xy") << 240]);
+
+        let value = DynSolValue::String("x".repeat(33));
+        let err = run_with_local(&code, local_x(true, value)).unwrap_err().to_string();
+        assert!(err.contains("longer than the 32 bytes allocated for it"), "{err}");
+    }
+}
@@ -45,6 +45,8 @@
 //! - Complete debugging API for frontend integration
 //! - WebSocket and HTTP support for real-time debugging
 //! - Comprehensive error handling and protocol compliance
+//! - Debug Adapter Protocol server for VS Code, Neovim and JetBrains IDEs
+//! - Model Context Protocol server exposing the debugger to AI agents
 //!
 //! ## 🛠 **Advanced Analysis**
 //! - Contract instrumentation for debugging hooks
@@ -57,13 +59,18 @@
 //! - [`analysis`] - Source code analysis and contract instrumentation
 //! - [`core`] - Core engine types and execution management
 //! - [`context`] - Engine context and state management
+//! - [`dap`] - Debug Adapter Protocol server for editor front-ends
 //! - [`eval`] - Expression evaluation system
 //! - [`inspector`] - EVM execution inspectors for data collection
 //! - [`instrumentation`] - Contract instrumentation and code generation
+//! - [`mcp`] - Model Context Protocol server for AI agents
+//! - [`nested_call`] - Nested timelines of calls made by evaluated expressions
+//! - [`overlay`] - Session state overlay set up by evaluated expressions
 //! - [`rpc`] - JSON-RPC debugging API
 //! - [`snapshot`] - Snapshot management and analysis
 //! - [`tweak`] - Runtime contract modification for debugging
 //! - [`utils`] - Utility functions and helpers
+//! - [`what_if`] - What-if re-execution with state edits
 //!
 //! # Quick Start
 //!
@@ -101,6 +108,8 @@ pub use core::*;
 pub mod context;
 pub use context::*;
 
+pub mod dap;
+
 pub mod eval;
 pub use eval::*;
 
@@ -111,14 +120,28 @@ pub use inspector::*;
 pub mod instrumentation;
 pub use instrumentation::*;
 
+pub mod mcp;
+
+pub mod nested_call;
+pub use nested_call::*;
+
+pub mod overlay;
+pub use overlay::*;
+
 pub mod rpc;
 pub use rpc::*;
 
 pub mod snapshot;
 pub use snapshot::*;
 
+#[cfg(test)]
+mod test_utils;
+
 pub mod tweak;
 pub use tweak::*;
 
 pub mod utils;
 pub use utils::*;
+
+pub mod what_if;
+pub use what_if::*;
@@ -0,0 +1,57 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Model Context Protocol (MCP) server for EDB.
+//!
+//! This module exposes the debugger to AI agents as MCP tools over stdio. An agent loads
+//! a transaction with `load_transaction` and then investigates it with typed tools,
+//! each described by a JSON schema of its arguments:
+//!
+//! - `get_trace` - list the calls of the transaction and their results
+//! - `get_snapshot` / `navigate` - inspect a snapshot and move between snapshots
+//! - `get_source` - read the source (or disassembly) around a snapshot
+//! - `evaluate` - evaluate a Solidity expression at a snapshot
+//! - `get_storage_diff` - list the storage changed by the executing contract
+//! - `find_reverts` - find the reverted calls and their reasons
+//!
+//! The tools are implemented on top of the RPC methods of the debug server of the loaded
+//! transaction, so their answers match what the TUI and the DAP adapter show.
+
+pub mod server;
+pub mod tools;
+
+pub use server::McpServer;
+
+use std::future::Future;
+
+use alloy_primitives::TxHash;
+use eyre::Result;
+use tokio::io::BufReader;
+use tracing::info;
+
+use crate::RpcServerHandle;
+
+/// Serve an MCP client over stdin and stdout.
+///
+/// `launch` prepares the debug server of each transaction the agent loads.
+pub async fn serve_mcp_stdio<F, Fut>(launch: F) -> Result<()>
+where
+    F: FnMut(TxHash) -> Fut,
+    Fut: Future<Output = Result<RpcServerHandle>>,
+{
+    info!("MCP server serving stdio");
+    McpServer::new(tokio::io::stdout()).run(BufReader::new(tokio::io::stdin()), launch).await
+}
@@ -0,0 +1,298 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! MCP server loop.
+//!
+//! Messages are JSON-RPC 2.0 objects, one per line, as in the stdio transport of MCP.
+
+use std::future::Future;
+
+use alloy_primitives::TxHash;
+use eyre::Result;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+use tracing::{debug, warn};
+
+use super::tools::{tool_definitions, ToolSession};
+use crate::RpcServerHandle;
+
+/// Protocol versions the server speaks, the latest last
+const PROTOCOL_VERSIONS: &[&str] = &["2024-11-05", "2025-03-26", "2025-06-18"];
+
+/// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// MCP server exposing the debugger tools.
+pub struct McpServer<W> {
+    writer: W,
+    session: ToolSession,
+}
+
+impl<W: AsyncWrite + Unpin> McpServer<W> {
+    /// Create a server answering on `writer`
+    pub fn new(writer: W) -> Self {
+        Self { writer, session: ToolSession::default() }
+    }
+
+    /// Serve messages from `reader` until it is closed.
+    ///
+    /// `launch` prepares the debug server of the transaction given to `load_transaction`.
+    pub async fn run<R, F, Fut>(mut self, mut reader: R, mut launch: F) -> Result<()>
+    where
+        R: AsyncBufRead + Unpin,
+        F: FnMut(TxHash) -> Fut,
+        Fut: Future<Output = Result<RpcServerHandle>>,
+    {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).await? == 0 {
+                break;
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = match serde_json::from_str::<Value>(&line) {
+                Ok(message) => self.handle_message(message, &mut launch).await,
+                Err(e) => {
+                    Some(error_response(Value::Null, PARSE_ERROR, &format!("Parse error: {e}")))
+                }
+            };
+
+            if let Some(response) = response {
+                let mut data = serde_json::to_vec(&response)?;
+                data.push(b'\n');
+                self.writer.write_all(&data).await?;
+                self.writer.flush().await?;
+            }
+        }
+
+        self.session.shutdown();
+        Ok(())
+    }
+
+    /// Handle a message, returning the response to requests
+    async fn handle_message<F, Fut>(&mut self, message: Value, launch: &mut F) -> Option<Value>
+    where
+        F: FnMut(TxHash) -> Fut,
+        Fut: Future<Output = Result<RpcServerHandle>>,
+    {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // Responses from the client are not expected, as we send no requests
+            if message.get("id").is_none() {
+                return Some(error_response(Value::Null, INVALID_REQUEST, "Invalid request"));
+            }
+            return None;
+        };
+        // Notifications need no response
+        let id = message.get("id")?.clone();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        debug!("MCP request {}: {}", id, method);
+
+        match method {
+            "initialize" => {
+                let requested = params.get("protocolVersion").and_then(Value::as_str);
+                let version = requested
+                    .filter(|version| PROTOCOL_VERSIONS.contains(version))
+                    .unwrap_or(PROTOCOL_VERSIONS[PROTOCOL_VERSIONS.len() - 1]);
+                Some(result_response(
+                    id,
+                    json!({
+                        "protocolVersion": version,
+                        "capabilities": { "tools": {} },
+                        "serverInfo": { "name": "edb", "version": env!("CARGO_PKG_VERSION") },
+                        "instructions": "Debug Ethereum transactions: call load_transaction first, then inspect the trace, snapshots, source, storage and reverts, and evaluate Solidity expressions at any snapshot.",
+                    }),
+                ))
+            }
+            "ping" => Some(result_response(id, json!({}))),
+            "tools/list" => Some(result_response(id, json!({ "tools": tool_definitions() }))),
+            "tools/call" => {
+                let Some(name) = params.get("name").and_then(Value::as_str) else {
+                    return Some(error_response(id, INVALID_PARAMS, "Missing tool name"));
+                };
+                if !is_tool(name) {
+                    return Some(error_response(
+                        id,
+                        INVALID_PARAMS,
+                        &format!("Unknown tool: {name}"),
+                    ));
+                }
+
+                let args = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+                let result = self.call_tool(name, &args, launch).await;
+                Some(result_response(id, tool_result(result)))
+            }
+            method => {
+                Some(error_response(id, METHOD_NOT_FOUND, &format!("Method not found: {method}")))
+            }
+        }
+    }
+
+    async fn call_tool<F, Fut>(&mut self, name: &str, args: &Value, launch: &mut F) -> Result<Value>
+    where
+        F: FnMut(TxHash) -> Fut,
+        Fut: Future<Output = Result<RpcServerHandle>>,
+    {
+        if name != "load_transaction" {
+            return self.session.call_tool(name, args).await;
+        }
+
+        let tx_hash: TxHash = args
+            .get("tx_hash")
+            .and_then(Value::as_str)
+            .ok_or_else(|| eyre::eyre!("Missing argument tx_hash"))?
+            .parse()?;
+        let server = launch(tx_hash).await?;
+        self.session.load(tx_hash, server).await
+    }
+}
+
+/// Whether `name` is one of the tools
+fn is_tool(name: &str) -> bool {
+    tool_definitions().as_array().is_some_and(|tools| tools.iter().any(|tool| tool["name"] == name))
+}
+
+/// Content of a `tools/call` response; tool failures are reported to the agent
+fn tool_result(result: Result<Value>) -> Value {
+    match result {
+        Ok(value) => json!({
+            "content": [{
+                "type": "text",
+                "text": serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string()),
+            }],
+            "isError": false,
+        }),
+        Err(e) => {
+            warn!("MCP tool failed: {e}");
+            json!({ "content": [{ "type": "text", "text": format!("{e}") }], "isError": true })
+        }
+    }
+}
+
+fn result_response(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{start_debug_server, test_utils::prepare_test_context};
+    use tokio::io::BufReader;
+
+    async fn exchange(input: &str) -> Vec<Value> {
+        let mut output = Vec::new();
+        McpServer::new(&mut output)
+            .run(BufReader::new(input.as_bytes()), |_| async {
+                Err::<RpcServerHandle, _>(eyre::eyre!("no chain in tests"))
+            })
+            .await
+            .unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_handshake_and_tools() {
+        let responses = exchange(concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26","capabilities":{},"clientInfo":{"name":"test","version":"0"}}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"get_trace","arguments":{}}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"load_transaction","arguments":{"tx_hash":"0x01"}}}"#,
+            "\n",
+        ))
+        .await;
+
+        // The notification gets no response
+        assert_eq!(responses.len(), 4);
+        assert_eq!(responses[0]["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(responses[1]["result"]["tools"].as_array().unwrap().len(), 8);
+        // get_trace needs a transaction
+        assert_eq!(responses[2]["result"]["isError"], true);
+        // Malformed hashes are tool errors
+        assert_eq!(responses[3]["result"]["isError"], true);
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let responses = exchange(concat!(
+            "not json\n",
+            r#"{"jsonrpc":"2.0","id":1,"method":"resources/list"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"rm_rf"}}"#,
+            "\n",
+        ))
+        .await;
+
+        assert_eq!(responses[0]["error"]["code"], PARSE_ERROR);
+        assert_eq!(responses[0]["id"], Value::Null);
+        assert_eq!(responses[1]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(responses[2]["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_load_transaction() {
+        let input = concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"load_transaction","arguments":{"tx_hash":"0x0000000000000000000000000000000000000000000000000000000000000001"}}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"find_reverts","arguments":{}}}"#,
+            "\n",
+        );
+
+        let mut output = Vec::new();
+        McpServer::new(&mut output)
+            .run(BufReader::new(input.as_bytes()), |_| async {
+                start_debug_server(prepare_test_context().await).await
+            })
+            .await
+            .unwrap();
+
+        // Nothing but one JSON-RPC response per line is written
+        let output = String::from_utf8(output).unwrap();
+        let responses: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap_or_else(|_| panic!("not JSON: {line}")))
+            .collect();
+        assert_eq!(responses.len(), 2);
+        for (id, response) in responses.iter().enumerate() {
+            assert_eq!(response["jsonrpc"], "2.0");
+            assert_eq!(response["id"], id + 1);
+            assert_eq!(response["result"]["isError"], false, "{response}");
+        }
+
+        let loaded: Value =
+            serde_json::from_str(responses[0]["result"]["content"][0]["text"].as_str().unwrap())
+                .unwrap();
+        assert_eq!(loaded["trace_entries"], 2);
+        assert_eq!(loaded["reverted"], false);
+    }
+}
@@ -0,0 +1,538 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! MCP tools exposing the debugger to agents.
+//!
+//! Every tool is described by a JSON schema of its arguments and answers with a JSON
+//! object. Apart from `load_transaction`, tools are stateless: they take the snapshot
+//! to work on as an argument, and are implemented on top of the RPC methods of the
+//! debug server of the loaded transaction.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use alloy_primitives::{Address, TxHash, U256};
+use edb_common::api::method;
+use edb_common::types::{
+    decode_revert_reason, Breakpoint, BreakpointLocation, CallResult, Code, EdbSolValue,
+    SnapshotInfo, SnapshotInfoDetail, SolValueFormatter, SolValueFormatterContext, Trace,
+    TraceEntry,
+};
+use eyre::{bail, eyre, Result};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::{offset_to_line_column, MethodDispatch, RpcServerHandle};
+
+/// Default number of lines shown around the current one by `get_source`
+const DEFAULT_CONTEXT_LINES: usize = 5;
+
+/// Descriptions and argument schemas of all tools, as returned by `tools/list`
+pub fn tool_definitions() -> Value {
+    let snapshot_id = json!({
+        "type": "integer",
+        "minimum": 0,
+        "description": "Id of the snapshot, i.e. the step of the execution (0-based)"
+    });
+
+    json!([
+        {
+            "name": "load_transaction",
+            "description": "Replay a transaction and prepare it for debugging. Must be called before any other tool; loading another transaction replaces the current one.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "tx_hash": {
+                        "type": "string",
+                        "pattern": "^0x[0-9a-fA-F]{64}$",
+                        "description": "Hash of the transaction to debug"
+                    }
+                },
+                "required": ["tx_hash"]
+            }
+        },
+        {
+            "name": "get_trace",
+            "description": "List the calls and contract creations of the transaction, with their callers, targets, selectors, results and first snapshots.",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "get_snapshot",
+            "description": "Describe a snapshot: the contract and call it belongs to, its source location or opcode, its local variables and its call stack.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "snapshot_id": snapshot_id },
+                "required": ["snapshot_id"]
+            }
+        },
+        {
+            "name": "get_source",
+            "description": "Read the source code around a snapshot, or the disassembly around it for contracts without verified source.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "snapshot_id": snapshot_id,
+                    "context_lines": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "default": DEFAULT_CONTEXT_LINES,
+                        "description": "Number of lines (or instructions) shown before and after the current one"
+                    }
+                },
+                "required": ["snapshot_id"]
+            }
+        },
+        {
+            "name": "evaluate",
+            "description": "Evaluate a Solidity expression (variables, mappings, arithmetic, view calls, msg/tx/block members) at a snapshot.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "snapshot_id": snapshot_id,
+                    "expression": {
+                        "type": "string",
+                        "description": "The expression, e.g. \"balances[msg.sender] > amount\""
+                    },
+                    "frame": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "default": 0,
+                        "description": "Frame of the call stack to evaluate in, 0 being the snapshot's own frame"
+                    }
+                },
+                "required": ["snapshot_id", "expression"]
+            }
+        },
+        {
+            "name": "get_storage_diff",
+            "description": "List the storage slots of the contract executing at a snapshot that changed since the start of the transaction, with their old and new values.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "snapshot_id": snapshot_id },
+                "required": ["snapshot_id"]
+            }
+        },
+        {
+            "name": "find_reverts",
+            "description": "Find the calls that reverted, with their decoded revert reasons and the snapshots at which they revert.",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "navigate",
+            "description": "Move from a snapshot to a related one and describe it.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "snapshot_id": snapshot_id,
+                    "action": {
+                        "type": "string",
+                        "enum": ["step_over", "step_back", "step_into", "step_out", "next_call", "prev_call"],
+                        "description": "step_over/step_back move to the next/previous step of the same frame, step_into to the very next snapshot, step_out to the step after the call in the caller, next_call/prev_call to the next/previous call"
+                    }
+                },
+                "required": ["snapshot_id", "action"]
+            }
+        }
+    ])
+}
+
+/// Debugging session of the loaded transaction, serving the tools.
+#[derive(Default)]
+pub struct ToolSession {
+    /// Debug server of the loaded transaction
+    server: Option<RpcServerHandle>,
+    /// Methods of the debug server
+    methods: Option<Arc<dyn MethodDispatch>>,
+    /// Number of snapshots of the transaction
+    snapshot_count: usize,
+    /// Call trace of the transaction
+    trace: Trace,
+    /// Code of the contracts looked at so far
+    codes: BTreeMap<Address, Code>,
+}
+
+impl ToolSession {
+    /// Debug the transaction served by `server`, replacing the current one
+    pub async fn load(&mut self, tx_hash: TxHash, server: RpcServerHandle) -> Result<Value> {
+        self.shutdown();
+        self.methods = Some(server.methods());
+        self.server = Some(server);
+        self.codes.clear();
+
+        self.snapshot_count = self.call(method::GET_SNAPSHOT_COUNT, json!([])).await?;
+        self.trace = self.call(method::GET_TRACE, json!([])).await?;
+
+        let reverted = self.trace.first().is_some_and(|entry| is_revert(entry.result.as_ref()));
+        Ok(json!({
+            "tx_hash": tx_hash,
+            "snapshot_count": self.snapshot_count,
+            "trace_entries": self.trace.len(),
+            "reverted": reverted,
+        }))
+    }
+
+    /// Shut down the debug server of the loaded transaction
+    pub fn shutdown(&mut self) {
+        self.methods = None;
+        if let Some(server) = self.server.take() {
+            let _ = server.shutdown();
+        }
+    }
+
+    /// Run any tool but `load_transaction`
+    pub async fn call_tool(&mut self, name: &str, args: &Value) -> Result<Value> {
+        if self.methods.is_none() {
+            bail!("No transaction is loaded, call load_transaction first");
+        }
+
+        match name {
+            "get_trace" => Ok(self.get_trace()),
+            "get_snapshot" => self.get_snapshot(arg_usize(args, "snapshot_id")?).await,
+            "get_source" => {
+                let context_lines =
+                    arg_usize(args, "context_lines").unwrap_or(DEFAULT_CONTEXT_LINES);
+                self.get_source(arg_usize(args, "snapshot_id")?, context_lines).await
+            }
+            "evaluate" => self.evaluate(args).await,
+            "get_storage_diff" => self.get_storage_diff(arg_usize(args, "snapshot_id")?).await,
+            "find_reverts" => self.find_reverts().await,
+            "navigate" => self.navigate(args).await,
+            name => bail!("Unknown tool: {name}"),
+        }
+    }
+
+    /// Call an RPC method of the debug server
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let methods = self.methods.as_ref().ok_or_else(|| eyre!("No transaction is loaded"))?;
+        let value = methods.dispatch(method, Some(params)).await.map_err(|e| eyre!(e.message))?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    async fn snapshot_info(&self, snapshot_id: usize) -> Result<SnapshotInfo> {
+        self.call(method::GET_SNAPSHOT_INFO, json!([snapshot_id])).await
+    }
+
+    async fn code(&mut self, address: Address) -> Result<&Code> {
+        if !self.codes.contains_key(&address) {
+            let code = self.call(method::GET_CODE_BY_ADDRESS, json!([address])).await?;
+            self.codes.insert(address, code);
+        }
+        Ok(&self.codes[&address])
+    }
+
+    fn get_trace(&self) -> Value {
+        let entries: Vec<Value> = self.trace.iter().map(trace_entry_summary).collect();
+        json!({ "entries": entries })
+    }
+
+    async fn get_snapshot(&mut self, snapshot_id: usize) -> Result<Value> {
+        let info = self.snapshot_info(snapshot_id).await?;
+        let call_stack: Vec<usize> =
+            self.call(method::GET_CALL_STACK, json!([snapshot_id])).await?;
+        let entry = self.trace.get(info.frame_id().trace_entry_id());
+
+        let mut summary = json!({
+            "snapshot_id": snapshot_id,
+            "snapshot_count": self.snapshot_count,
+            "trace_entry_id": info.frame_id().trace_entry_id(),
+            "address": info.target_address,
+            "label": entry.and_then(|entry| entry.target_label.clone()),
+            "bytecode_address": info.bytecode_address,
+            "next_step": info.next_id(),
+            "prev_step": info.prev_id(),
+            "call_stack": call_stack,
+        });
+
+        let ctx = SolValueFormatterContext::new();
+        match info.detail() {
+            SnapshotInfoDetail::Hook(detail) => {
+                let line = match self.code(info.bytecode_address).await? {
+                    Code::Source(code) => code
+                        .sources
+                        .get(&detail.path)
+                        .and_then(|source| offset_to_line_column(source, detail.offset))
+                        .map(|(line, _)| line),
+                    Code::Opcode(_) => None,
+                };
+                let locals: BTreeMap<&String, String> = detail
+                    .locals
+                    .iter()
+                    .map(|(name, value)| {
+                        let value = value
+                            .as_ref()
+                            .map_or("<unavailable>".to_string(), |value| value.format_value(&ctx));
+                        (name, value)
+                    })
+                    .collect();
+
+                summary["kind"] = json!("source");
+                summary["path"] = json!(detail.path);
+                summary["line"] = json!(line);
+                summary["locals"] = json!(locals);
+            }
+            SnapshotInfoDetail::Opcode(detail) => {
+                summary["kind"] = json!("opcode");
+                summary["pc"] = json!(detail.pc);
+                summary["opcode"] =
+                    json!(self.instruction(info.bytecode_address, detail.pc).await?);
+                summary["stack_top"] = json!(detail
+                    .stack
+                    .iter()
+                    .rev()
+                    .take(8)
+                    .map(|value| format!("{value:#x}"))
+                    .collect::<Vec<_>>());
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Disassembled instruction at `pc`
+    async fn instruction(&mut self, address: Address, pc: usize) -> Result<Option<String>> {
+        Ok(match self.code(address).await? {
+            Code::Opcode(code) => code.codes.get(&pc).cloned(),
+            Code::Source(_) => None,
+        })
+    }
+
+    async fn get_source(&mut self, snapshot_id: usize, context_lines: usize) -> Result<Value> {
+        let info = self.snapshot_info(snapshot_id).await?;
+        let address = info.bytecode_address;
+
+        match (info.detail(), self.code(address).await?) {
+            (SnapshotInfoDetail::Hook(detail), Code::Source(code)) => {
+                let source = code
+                    .sources
+                    .get(&detail.path)
+                    .ok_or_else(|| eyre!("Source {} not found", detail.path.display()))?;
+                let (line, column) =
+                    offset_to_line_column(source, detail.offset).ok_or_else(|| {
+                        eyre!("Offset {} out of {}", detail.offset, detail.path.display())
+                    })?;
+
+                let first = line.saturating_sub(context_lines).max(1);
+                let snippet: Vec<String> = source
+                    .lines()
+                    .enumerate()
+                    .skip(first - 1)
+                    .take(line + context_lines + 1 - first)
+                    .map(|(i, text)| {
+                        let marker = if i + 1 == line { "=>" } else { "  " };
+                        format!("{marker} {:>5} | {text}", i + 1)
+                    })
+                    .collect();
+
+                Ok(json!({
+                    "snapshot_id": snapshot_id,
+                    "path": detail.path,
+                    "line": line,
+                    "column": column,
+                    "code": source.get(detail.offset..detail.offset + detail.length),
+                    "source": snippet.join("\n"),
+                }))
+            }
+            (SnapshotInfoDetail::Opcode(detail), Code::Opcode(code)) => {
+                let pcs: Vec<&usize> = {
+                    let mut pcs: Vec<&usize> = code.codes.keys().collect();
+                    pcs.sort_unstable();
+                    pcs
+                };
+                let index = pcs.partition_point(|pc| **pc < detail.pc);
+                let disassembly: Vec<String> = pcs[index.saturating_sub(context_lines)
+                    ..(index + context_lines + 1).min(pcs.len())]
+                    .iter()
+                    .map(|pc| {
+                        let marker = if **pc == detail.pc { "=>" } else { "  " };
+                        format!("{marker} {:>6} | {}", pc, code.codes[*pc])
+                    })
+                    .collect();
+
+                Ok(json!({
+                    "snapshot_id": snapshot_id,
+                    "address": address,
+                    "pc": detail.pc,
+                    "disassembly": disassembly.join("\n"),
+                }))
+            }
+            _ => bail!("No code available for snapshot {snapshot_id}"),
+        }
+    }
+
+    async fn evaluate(&mut self, args: &Value) -> Result<Value> {
+        let snapshot_id = arg_usize(args, "snapshot_id")?;
+        let expression = args
+            .get("expression")
+            .and_then(Value::as_str)
+            .ok_or_else(|| eyre!("Missing argument expression"))?;
+        let frame = arg_usize(args, "frame").unwrap_or(0);
+
+        let value: Result<EdbSolValue, String> =
+            self.call(method::EVAL_ON_SNAPSHOT, json!([snapshot_id, expression, frame])).await?;
+        let value = value.map_err(|e| eyre!("Failed to evaluate {expression}: {e}"))?;
+
+        let ctx = SolValueFormatterContext::new();
+        Ok(json!({
+            "expression": expression,
+            "type": value.format_type(),
+            "value": value.format_value(&ctx),
+        }))
+    }
+
+    async fn get_storage_diff(&mut self, snapshot_id: usize) -> Result<Value> {
+        let info = self.snapshot_info(snapshot_id).await?;
+        let diff: BTreeMap<U256, (U256, U256)> =
+            self.call(method::GET_STORAGE_DIFF, json!([snapshot_id])).await?;
+
+        let changes: Vec<Value> = diff
+            .into_iter()
+            .map(|(slot, (before, after))| {
+                json!({ "slot": format!("{slot:#x}"), "before": format!("{before:#x}"), "after": format!("{after:#x}") })
+            })
+            .collect();
+        Ok(
+            json!({ "snapshot_id": snapshot_id, "address": info.target_address, "changes": changes }),
+        )
+    }
+
+    async fn find_reverts(&mut self) -> Result<Value> {
+        // The engine tells at which snapshots calls revert
+        let revert = Breakpoint::new(Some(BreakpointLocation::Revert), None);
+        let hits: Vec<Vec<usize>> =
+            self.call(method::COUNT_BREAKPOINT_HITS, json!([[revert]])).await?;
+        let mut revert_snapshots = BTreeMap::new();
+        for snapshot_id in hits.into_iter().flatten() {
+            let info = self.snapshot_info(snapshot_id).await?;
+            revert_snapshots.insert(info.frame_id().trace_entry_id(), snapshot_id);
+        }
+
+        let reverts: Vec<Value> = self
+            .trace
+            .iter()
+            .filter(|entry| is_revert(entry.result.as_ref()))
+            .map(|entry| {
+                let mut summary = trace_entry_summary(entry);
+                summary["revert_snapshot_id"] = json!(revert_snapshots.get(&entry.id));
+                summary
+            })
+            .collect();
+        Ok(json!({ "reverts": reverts }))
+    }
+
+    async fn navigate(&mut self, args: &Value) -> Result<Value> {
+        let snapshot_id = arg_usize(args, "snapshot_id")?;
+        let action = args
+            .get("action")
+            .and_then(Value::as_str)
+            .ok_or_else(|| eyre!("Missing argument action"))?;
+        let last = self.snapshot_count.saturating_sub(1);
+
+        let target = match action {
+            "step_over" => self.snapshot_info(snapshot_id).await?.next_id(),
+            "step_back" => self.snapshot_info(snapshot_id).await?.prev_id(),
+            "step_into" => (snapshot_id + 1).min(last),
+            "step_out" => {
+                let call_stack: Vec<usize> =
+                    self.call(method::GET_CALL_STACK, json!([snapshot_id])).await?;
+                match call_stack.get(1) {
+                    Some(caller) => self.snapshot_info(*caller).await?.next_id(),
+                    None => last,
+                }
+            }
+            "next_call" => self.call(method::GET_NEXT_CALL, json!([snapshot_id])).await?,
+            "prev_call" => self.call(method::GET_PREV_CALL, json!([snapshot_id])).await?,
+            action => bail!("Unknown action: {action}"),
+        };
+
+        self.get_snapshot(target).await
+    }
+}
+
+/// Whether a call reverted (or failed otherwise)
+fn is_revert(result: Option<&CallResult>) -> bool {
+    matches!(result, Some(CallResult::Revert { .. } | CallResult::Error { .. }))
+}
+
+/// Agent-friendly summary of a trace entry
+fn trace_entry_summary(entry: &TraceEntry) -> Value {
+    let (status, reason) = match &entry.result {
+        Some(CallResult::Success { .. }) => ("success", None),
+        Some(CallResult::Revert { output, .. }) => ("revert", Some(decode_revert_reason(output))),
+        Some(CallResult::Error { output, result }) => {
+            let reason = if output.is_empty() {
+                format!("{result:?}")
+            } else {
+                decode_revert_reason(output)
+            };
+            ("error", Some(reason))
+        }
+        None => ("unfinished", None),
+    };
+
+    json!({
+        "id": entry.id,
+        "parent_id": entry.parent_id,
+        "depth": entry.depth,
+        "call_type": format!("{:?}", entry.call_type),
+        "caller": entry.caller,
+        "target": entry.target,
+        "label": entry.target_label,
+        "selector": (!entry.created_contract && entry.input.len() >= 4)
+            .then(|| format!("0x{}", alloy_primitives::hex::encode(&entry.input[..4]))),
+        "value": (!entry.value.is_zero()).then(|| entry.value.to_string()),
+        "status": status,
+        "revert_reason": reason,
+        "events": entry.events.len(),
+        "first_snapshot_id": entry.first_snapshot_id,
+    })
+}
+
+/// Get a non-negative integer argument
+fn arg_usize(args: &Value, name: &str) -> Result<usize> {
+    args.get(name)
+        .and_then(Value::as_u64)
+        .map(|value| value as usize)
+        .ok_or_else(|| eyre!("Missing or invalid argument {name}, expected a non-negative integer"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_definitions() {
+        let tools = tool_definitions();
+        let tools = tools.as_array().unwrap();
+        assert_eq!(tools.len(), 8);
+
+        for tool in tools {
+            let schema = &tool["inputSchema"];
+            assert_eq!(schema["type"], "object", "{}", tool["name"]);
+
+            // Every required argument is described
+            for required in schema["required"].as_array().into_iter().flatten() {
+                let required = required.as_str().unwrap();
+                assert!(schema["properties"].get(required).is_some(), "{}", tool["name"]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_arg_usize() {
+        let args = json!({ "snapshot_id": 12, "frame": -1 });
+        assert_eq!(arg_usize(&args, "snapshot_id").unwrap(), 12);
+        assert!(arg_usize(&args, "frame").is_err());
+        assert!(arg_usize(&args, "missing").is_err());
+    }
+}
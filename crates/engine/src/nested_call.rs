@@ -0,0 +1,217 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Nested timelines: debug a call made while evaluating an expression.
+//!
+//! Expressions call contract functions opaquely in a derived EVM (see
+//! [`EngineContext::call_in_derived_evm`]). To see why such a call returns what it does,
+//! [`debug_call_at_snapshot`] re-executes it from the state of the snapshot with the opcode
+//! and hook inspectors attached, and packs the result into a timeline of its own. The nested
+//! timeline has its own trace, whose root is the debugged call, and can be debugged with the
+//! very same API as the timeline it is nested in.
+
+use std::sync::Arc;
+
+use alloy_primitives::Address;
+use edb_common::{disable_nonce_check, relax_evm_constraints, types::EvalCall, EdbContext};
+use eyre::{bail, eyre, Result};
+use revm::{
+    context::tx::TxEnvBuilder, database::CacheDB, Context, Database, DatabaseCommit, DatabaseRef,
+    InspectEvm, MainBuilder, MainContext,
+};
+use tracing::{debug, info};
+
+use crate::{
+    CallTracer, EngineContext, HookSnapshotInspector, OpcodeSnapshotInspector, SnapshotAnalysis,
+    Snapshots,
+};
+
+/// Re-execute a call recorded during an expression evaluation as a nested timeline.
+///
+/// # Arguments
+///
+/// * `context` - The timeline the call was made in
+/// * `snapshot_id` - The snapshot at which the call was made
+/// * `call` - The recorded call, providing the callee, call data, and call options
+///
+/// # Returns
+///
+/// Returns the nested timeline as a finalized [`EngineContext`].
+///
+/// # Errors
+///
+/// Returns an error if the snapshot does not exist or the call cannot be executed.
+pub fn debug_call_at_snapshot<DB>(
+    context: &EngineContext<DB>,
+    snapshot_id: usize,
+    call: &EvalCall,
+) -> Result<EngineContext<DB>>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let Some((_, snapshot)) = context.snapshots.get(snapshot_id) else {
+        bail!("Snapshot with id {snapshot_id} not found");
+    };
+
+    // Source-level steps require the instrumented bytecode, while opcode snapshots of the
+    // original collection hold the original one
+    let state = snapshot.db();
+    let instrumented_code = context
+        .snapshots
+        .iter()
+        .rev()
+        .find(|(_, snapshot)| snapshot.is_hook())
+        .map(|(_, snapshot)| snapshot.db())
+        .unwrap_or_else(|| context.pre_tx_db.clone());
+    let mut db = with_code_of(&state, &instrumented_code, context.recompiled_artifacts.keys())?;
+    let mut original_db =
+        with_code_of(&state, &context.pre_tx_original_db, context.artifacts.keys())?;
+
+    // The call was made with the session overlay applied
+    let overlay = context.overlay();
+    overlay.apply_to_db(&mut db)?;
+    overlay.apply_to_db(&mut original_db)?;
+    let mut block = context.block.clone();
+    overlay.apply_to_block(&mut block);
+
+    let mut ctx: EdbContext<DB> = Context::mainnet()
+        .with_db(db.clone())
+        .with_cfg(context.cfg.clone())
+        .with_block(block.clone());
+    let mut tx = TxEnvBuilder::new()
+        .caller(call.options.from.unwrap_or(context.tx.caller))
+        .call(call.to)
+        .value(call.options.value.unwrap_or_default())
+        .data(call.input.clone())
+        .build_fill();
+    relax_evm_constraints(&mut ctx, &mut tx);
+    disable_nonce_check(&mut ctx);
+    if let Some(gas) = call.options.gas {
+        tx.gas_limit = gas;
+    }
+
+    // Step 1: Execute the call to collect its call trace
+    info!("Debugging {} on {} at snapshot {}", call.function, call.to, snapshot_id);
+    let mut tracer = CallTracer::new();
+    {
+        let mut evm = ctx.clone().build_mainnet_with_inspector(&mut tracer);
+        evm.inspect_one_tx(tx.clone())
+            .map_err(|e| eyre!("Failed to execute the nested call: {:?}", e))?;
+    }
+    let trace = tracer.into_replay_result().execution_trace;
+
+    // Step 2: Execute the call again to collect snapshots
+    let mut opcode_inspector = OpcodeSnapshotInspector::new(&ctx, &trace);
+    opcode_inspector.with_excluded_addresses(context.artifacts.keys().copied().collect());
+    let mut hook_inspector = HookSnapshotInspector::new(&trace, &context.analysis_results);
+    {
+        let mut evm =
+            ctx.build_mainnet_with_inspector((&mut opcode_inspector, &mut hook_inspector));
+        evm.inspect_one_tx(tx.clone())
+            .map_err(|e| eyre!("Failed to execute the nested call: {:?}", e))?;
+    }
+
+    // Step 3: Pack the nested timeline
+    let mut snapshots =
+        Snapshots::merge(opcode_inspector.into_snapshots(), hook_inspector.into_snapshots());
+    snapshots.analyze(&trace, &context.analysis_results)?;
+    debug!("Nested timeline has {} snapshots", snapshots.len());
+
+    EngineContext::build(
+        context.fork_info.clone(),
+        context.cfg.clone(),
+        block,
+        tx,
+        context.tx_hash,
+        snapshots,
+        context.artifacts.clone(),
+        context.recompiled_artifacts.clone(),
+        context.analysis_results.clone(),
+        trace,
+        Arc::new(db),
+        Arc::new(original_db),
+        context.engine_config.clone(),
+    )
+}
+
+/// Copy a state, swapping in the bytecode that `code` holds for the given addresses.
+///
+/// Accounts that do not exist in the state (yet) or in `code` are left untouched.
+fn with_code_of<'a, DB>(
+    state: &CacheDB<DB>,
+    code: &CacheDB<DB>,
+    addresses: impl IntoIterator<Item = &'a Address>,
+) -> Result<CacheDB<DB>>
+where
+    DB: DatabaseRef + Clone,
+{
+    let mut db = state.clone();
+    for &address in addresses {
+        let read = |db: &CacheDB<DB>| {
+            db.basic_ref(address).map_err(|e| eyre!("Failed to retrieve account {address}: {e}"))
+        };
+        let (Some(mut account), Some(source)) = (read(state)?, read(code)?) else {
+            continue;
+        };
+        if account.code_hash == source.code_hash {
+            continue;
+        }
+
+        account.code_hash = source.code_hash;
+        account.code = match source.code {
+            Some(bytecode) => Some(bytecode),
+            None => Some(
+                code.code_by_hash_ref(source.code_hash)
+                    .map_err(|e| eyre!("Failed to retrieve code of {address}: {e}"))?,
+            ),
+        };
+        db.insert_account_info(address, account);
+    }
+    Ok(db)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{address, Bytes, U256};
+    use revm::{database::EmptyDB, state::AccountInfo, state::Bytecode};
+
+    use super::*;
+
+    fn account_with_code(code: &'static [u8]) -> AccountInfo {
+        let bytecode = Bytecode::new_raw(Bytes::from_static(code));
+        AccountInfo::default().with_code_hash(bytecode.hash_slow()).with_code(bytecode)
+    }
+
+    #[test]
+    fn test_with_code_of() {
+        let target = address!("0x00000000000000000000000000000000000000aa");
+        let missing = address!("0x00000000000000000000000000000000000000bb");
+
+        let mut state = CacheDB::new(EmptyDB::default());
+        state.insert_account_info(target, account_with_code(&[0x00]).with_balance(U256::from(1)));
+        let mut code = CacheDB::new(EmptyDB::default());
+        code.insert_account_info(target, account_with_code(&[0x60, 0x00, 0x00]));
+        code.insert_account_info(missing, account_with_code(&[0x00]));
+
+        let db = with_code_of(&state, &code, [&target, &missing]).unwrap();
+        let account = db.basic_ref(target).unwrap().unwrap();
+        assert_eq!(account.code_hash, code.basic_ref(target).unwrap().unwrap().code_hash);
+        assert_eq!(account.balance, U256::from(1));
+        assert_eq!(db.basic_ref(missing).unwrap(), None);
+    }
+}
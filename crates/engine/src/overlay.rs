@@ -0,0 +1,153 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Session state overlay set up by the mutating pseudo-functions of the evaluator.
+//!
+//! Expressions like `edb_sstore(token, 0x5, 0)` or `edb_warp(1700000000)` do not change
+//! any snapshot. They record the change in a [`StateOverlay`] kept by the debugging
+//! session, which is then applied on top of the snapshot state by everything that derives
+//! an execution from it: calls in evaluated expressions, nested call timelines, and
+//! what-if re-executions. `edb_reset()` clears the overlay.
+
+use std::collections::{BTreeMap, HashMap};
+
+use alloy_primitives::{Address, Bytes, U256};
+use edb_common::types::StateEdit;
+use eyre::{eyre, Result};
+use revm::{bytecode::Bytecode, context::BlockEnv, database::CacheDB, DatabaseRef};
+
+/// State changes applied on top of any snapshot state by derived executions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateOverlay {
+    /// Overwritten storage slots, by address and slot
+    pub storage: BTreeMap<(Address, U256), U256>,
+    /// Overwritten balances
+    pub balances: HashMap<Address, U256>,
+    /// Replaced runtime bytecode
+    pub codes: HashMap<Address, Bytes>,
+    /// Overwritten block timestamp
+    pub timestamp: Option<U256>,
+    /// Overwritten block number
+    pub number: Option<U256>,
+    /// Default sender of derived calls, overridden by the `from` call option
+    pub prank: Option<Address>,
+}
+
+impl StateOverlay {
+    /// Whether the overlay changes nothing
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply the account and storage changes to a database.
+    pub fn apply_to_db<T>(&self, db: &mut CacheDB<T>) -> Result<()>
+    where
+        T: DatabaseRef,
+    {
+        for (&(address, slot), &value) in &self.storage {
+            db.insert_account_storage(address, slot, value)
+                .map_err(|e| eyre!("Failed to overwrite storage of {address}: {e}"))?;
+        }
+
+        for (&address, &balance) in &self.balances {
+            let account = db
+                .load_account(address)
+                .map_err(|e| eyre!("Failed to retrieve account {address}: {e}"))?;
+            account.info.balance = balance;
+        }
+
+        for (&address, code) in &self.codes {
+            let mut info = db
+                .basic_ref(address)
+                .map_err(|e| eyre!("Failed to retrieve account {address}: {e}"))?
+                .unwrap_or_default();
+            let bytecode = Bytecode::new_raw(code.clone());
+            info.code_hash = bytecode.hash_slow();
+            info.code = Some(bytecode);
+            db.insert_account_info(address, info);
+        }
+
+        Ok(())
+    }
+
+    /// Apply the block changes to a block environment.
+    pub fn apply_to_block(&self, block: &mut BlockEnv) {
+        if let Some(timestamp) = self.timestamp {
+            block.timestamp = timestamp;
+        }
+        if let Some(number) = self.number {
+            block.number = number;
+        }
+    }
+
+    /// The account and storage changes as state edits, e.g., to be applied by a what-if
+    /// re-execution along with the edits of the user.
+    pub fn state_edits(&self) -> Vec<StateEdit> {
+        let storage = self.storage.iter().map(|(&(address, slot), &value)| StateEdit::Storage {
+            address,
+            slot,
+            value,
+        });
+        let balances = self
+            .balances
+            .iter()
+            .map(|(&address, &balance)| StateEdit::Balance { address, balance });
+        let codes = self
+            .codes
+            .iter()
+            .map(|(&address, code)| StateEdit::Code { address, code: code.clone() });
+
+        storage.chain(balances).chain(codes).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::address;
+    use revm::{database::EmptyDB, state::AccountInfo};
+
+    use super::*;
+
+    #[test]
+    fn test_apply_to_db() {
+        let target = address!("0x00000000000000000000000000000000000000aa");
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(target, AccountInfo::default().with_balance(U256::from(1)));
+
+        let mut overlay = StateOverlay::default();
+        assert!(overlay.is_empty());
+        overlay.storage.insert((target, U256::from(5)), U256::from(42));
+        overlay.balances.insert(target, U256::from(100));
+        overlay.codes.insert(target, Bytes::from_static(&[0x60, 0x00]));
+        overlay.apply_to_db(&mut db).unwrap();
+
+        let account = db.basic_ref(target).unwrap().unwrap();
+        assert_eq!(account.balance, U256::from(100));
+        assert_eq!(account.code.unwrap().original_bytes(), Bytes::from_static(&[0x60, 0x00]));
+        assert_eq!(db.storage_ref(target, U256::from(5)).unwrap(), U256::from(42));
+        assert_eq!(overlay.state_edits().len(), 3);
+    }
+
+    #[test]
+    fn test_apply_to_block() {
+        let mut block = BlockEnv { number: U256::from(10), ..Default::default() };
+        let overlay = StateOverlay { timestamp: Some(U256::from(1000)), ..Default::default() };
+        overlay.apply_to_block(&mut block);
+
+        assert_eq!(block.timestamp, U256::from(1000));
+        assert_eq!(block.number, U256::from(10));
+    }
+}
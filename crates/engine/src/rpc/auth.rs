@@ -0,0 +1,116 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Token authentication for the debug RPC server.
+//!
+//! When the server is configured with a token, JSON-RPC requests must carry it as a
+//! bearer token (`Authorization: Bearer <token>`). WebSocket clients that cannot set
+//! headers (e.g., browsers) may pass it as the `token` query parameter of `/ws` instead.
+//! The health check endpoint stays open, so clients can probe the server before
+//! authenticating.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use rand::RngCore;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Generate a random token, for a per-session secret
+pub fn generate_auth_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Reject requests that do not carry the expected token
+pub(super) async fn require_token(
+    State(token): State<Arc<str>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let provided = bearer_token(&request).or_else(|| query_token(&request));
+    match provided {
+        Some(provided) if tokens_match(&token, &provided) => next.run(request).await,
+        _ => {
+            warn!("Rejected unauthenticated RPC request to {}", request.uri().path());
+            (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, "Bearer")]).into_response()
+        }
+    }
+}
+
+/// Extract the bearer token of a request
+fn bearer_token(request: &Request) -> Option<String> {
+    let value = request.headers().get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    scheme.eq_ignore_ascii_case("bearer").then(|| token.trim().to_string())
+}
+
+/// Extract the token passed as a query parameter of a WebSocket upgrade
+fn query_token(request: &Request) -> Option<String> {
+    if request.uri().path() != "/ws" {
+        return None;
+    }
+    request
+        .uri()
+        .query()?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+        .map(str::to_string)
+}
+
+/// Compare tokens in constant time, so the comparison does not leak the expected token
+fn tokens_match(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len()
+        && expected.bytes().zip(provided.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    fn request(uri: &str, authorization: Option<&str>) -> Request {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(value) = authorization {
+            builder = builder.header(header::AUTHORIZATION, value);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_token_extraction() {
+        assert_eq!(bearer_token(&request("/", Some("Bearer abc"))).as_deref(), Some("abc"));
+        assert_eq!(bearer_token(&request("/", Some("bearer abc"))).as_deref(), Some("abc"));
+        assert_eq!(bearer_token(&request("/", Some("Basic abc"))), None);
+        assert_eq!(bearer_token(&request("/", None)), None);
+
+        assert_eq!(query_token(&request("/ws?token=abc", None)).as_deref(), Some("abc"));
+        assert_eq!(query_token(&request("/?token=abc", None)), None);
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        let token = generate_auth_token();
+        assert_eq!(token.len(), 64);
+        assert!(tokens_match(&token, &token.clone()));
+        assert!(!tokens_match(&token, &generate_auth_token()));
+        assert!(!tokens_match(&token, &token[1..]));
+    }
+}
@@ -19,7 +19,8 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use alloy_primitives::{Address, Bytes};
+use alloy_primitives::Bytes;
+use edb_common::api;
 use edb_common::types::{Code, OpcodeInfo, SourceInfo};
 use revm::{database::CacheDB, Database, DatabaseCommit, DatabaseRef};
 use serde_json::Value;
@@ -49,17 +50,7 @@ where
     <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
     <DB as Database>::Error: Clone + Send + Sync,
 {
-    // Parse the snapshot ID from parameters
-    let snapshot_id = params
-        .as_ref()
-        .and_then(|p| p.as_array())
-        .and_then(|arr| arr.first())
-        .and_then(|v| v.as_u64())
-        .ok_or_else(|| RpcError {
-            code: error_codes::INVALID_PARAMS,
-            message: "Invalid params: expected [snapshot_id]".to_string(),
-            data: None,
-        })? as usize;
+    let (snapshot_id,) = api::params::get_code(params.as_ref())?;
 
     // Get the snapshot at the specified index
     let (frame_id, snapshot) = context.snapshots.get(snapshot_id).ok_or_else(|| RpcError {
@@ -139,17 +130,7 @@ where
     <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
     <DB as Database>::Error: Clone + Send + Sync,
 {
-    // Parse the address as the first argument
-    let address: Address = params
-        .as_ref()
-        .and_then(|p| p.as_array())
-        .and_then(|arr| arr.first())
-        .and_then(|v| serde_json::from_value(v.clone()).ok())
-        .ok_or_else(|| RpcError {
-            code: error_codes::INVALID_PARAMS,
-            message: "Invalid params: expected [address]".to_string(),
-            data: None,
-        })?;
+    let (address,) = api::params::get_code_by_address(params.as_ref())?;
 
     let code = match context.artifacts.get(&address) {
         Some(artifact) => {
@@ -207,17 +188,7 @@ where
     <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
     <DB as Database>::Error: Clone + Send + Sync,
 {
-    // Parse the address as the first argument
-    let address: Address = params
-        .as_ref()
-        .and_then(|p| p.as_array())
-        .and_then(|arr| arr.first())
-        .and_then(|v| serde_json::from_value(v.clone()).ok())
-        .ok_or_else(|| RpcError {
-            code: error_codes::INVALID_PARAMS,
-            message: "Invalid params: expected [address]".to_string(),
-            data: None,
-        })?;
+    let (address,) = api::params::get_constructor_args(params.as_ref())?;
 
     let args =
         context.artifacts.get(&address).map(|artifact| artifact.meta.constructor_arguments.clone());
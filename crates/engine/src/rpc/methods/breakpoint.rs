@@ -0,0 +1,172 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Breakpoint RPC methods.
+//!
+//! This module implements continue / reverse-continue on the server: instead of
+//! evaluating breakpoint conditions one round-trip at a time, clients send their
+//! breakpoints and the engine scans the snapshots itself.
+//!
+//! # Available Methods
+//!
+//! - `edb_findBreakpointHit` - Find the nearest snapshot at which any breakpoint is hit
+//! - `edb_collectLogpoints` - Collect the messages logged by logpoints over the timeline
+//! - `edb_countBreakpointHits` - Collect the hits of each breakpoint over the timeline
+//!
+//! # Example Usage
+//!
+//! ```json
+//! // Request
+//! {
+//!   "method": "edb_findBreakpointHit",
+//!   "params": [[{ "loc": null, "condition": "balance > 100" }], 150, "forward"]
+//! }
+//!
+//! // Response
+//! {
+//!   "result": { "snapshot_id": 212, "breakpoints": [0] }
+//! }
+//! ```
+
+use std::sync::Arc;
+
+use edb_common::api;
+use revm::{database::CacheDB, Database, DatabaseCommit, DatabaseRef};
+use serde_json::Value;
+use tracing::debug;
+
+use crate::{
+    collect_breakpoint_hits, collect_logpoint_log, error_codes, find_breakpoint_hit, EngineContext,
+    RpcError,
+};
+
+/// Find the nearest snapshot at which any of the given breakpoints is hit.
+///
+/// # Parameters
+/// - `breakpoints` (array) - Serialized [`Breakpoint`]s to search for
+/// - `snapshot_id` (number) - The snapshot to search from (excluded from the search)
+/// - `direction` (string) - `"forward"` or `"backward"`
+///
+/// # Returns
+/// A `BreakpointHit` with the snapshot and the indices of the breakpoints hit there,
+/// or `null` if no breakpoint is hit in that direction.
+pub fn find_breakpoint_hit_from_snapshot<DB>(
+    context: &Arc<EngineContext<DB>>,
+    params: Option<Value>,
+) -> Result<Value, RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let (breakpoints, snapshot_id, direction) = api::params::find_breakpoint_hit(params.as_ref())?;
+
+    if snapshot_id >= context.snapshots.len() {
+        return Err(RpcError {
+            code: error_codes::SNAPSHOT_OUT_OF_BOUNDS,
+            message: format!("Snapshot with id {snapshot_id} not found"),
+            data: None,
+        });
+    }
+
+    let hit = find_breakpoint_hit(context, &breakpoints, snapshot_id, direction).map_err(|e| {
+        RpcError {
+            code: error_codes::INTERNAL_ERROR,
+            message: format!("Failed to search breakpoints: {e}"),
+            data: None,
+        }
+    })?;
+
+    let json_value = serde_json::to_value(hit).map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to serialize breakpoint hit: {e}"),
+        data: None,
+    })?;
+
+    debug!(
+        "Searched {} breakpoints {:?} from snapshot {}",
+        breakpoints.len(),
+        direction,
+        snapshot_id
+    );
+    Ok(json_value)
+}
+
+/// Collect the messages logged by logpoints over the whole timeline.
+///
+/// # Parameters
+/// - `logpoints` (array) - Serialized [`Breakpoint`]s with a `log` format
+///
+/// # Returns
+/// An array of `LogpointEntry` in timeline order, each with the snapshot, the index
+/// of the logpoint hit and the formatted message.
+pub fn collect_logpoints<DB>(
+    context: &Arc<EngineContext<DB>>,
+    params: Option<Value>,
+) -> Result<Value, RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let (logpoints,) = api::params::collect_logpoints(params.as_ref())?;
+
+    let log = collect_logpoint_log(context, &logpoints).map_err(|e| RpcError {
+        code: error_codes::INVALID_PARAMS,
+        message: format!("Invalid logpoint: {e}"),
+        data: None,
+    })?;
+
+    let json_value = serde_json::to_value(&log).map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to serialize logpoint log: {e}"),
+        data: None,
+    })?;
+
+    debug!("Collected {} messages from {} logpoints", log.len(), logpoints.len());
+    Ok(json_value)
+}
+
+/// Collect the snapshots at which each breakpoint is hit over the whole timeline.
+///
+/// # Parameters
+/// - `breakpoints` (array) - Serialized [`Breakpoint`]s
+///
+/// # Returns
+/// An array with, for each breakpoint, the array of snapshots at which it is hit (in
+/// timeline order, without applying ignore counts).
+pub fn count_breakpoint_hits<DB>(
+    context: &Arc<EngineContext<DB>>,
+    params: Option<Value>,
+) -> Result<Value, RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let (breakpoints,) = api::params::count_breakpoint_hits(params.as_ref())?;
+
+    let hits = collect_breakpoint_hits(context, &breakpoints);
+
+    let json_value = serde_json::to_value(&hits).map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to serialize breakpoint hits: {e}"),
+        data: None,
+    })?;
+
+    debug!("Counted the hits of {} breakpoints", breakpoints.len());
+    Ok(json_value)
+}
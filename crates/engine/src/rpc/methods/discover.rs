@@ -0,0 +1,33 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! API discovery RPC method.
+
+use edb_common::api::openrpc_document;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::RpcError;
+
+/// Get the OpenRPC document describing the API.
+///
+/// # Returns
+/// - The OpenRPC document generated from the API definition of [`edb_common::api`]
+pub fn rpc_discover() -> Result<Value, RpcError> {
+    let document = openrpc_document(env!("CARGO_PKG_VERSION"));
+    debug!("Generated the OpenRPC document");
+    Ok(document)
+}
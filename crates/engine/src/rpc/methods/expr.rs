@@ -24,6 +24,8 @@
 //! # Available Methods
 //!
 //! - `edb_evalOnSnapshot` - Evaluate an expression against a specific snapshot
+//! - `edb_getValueHistory` - Collect every change of an expression's value over a call or a range
+//! - `edb_findSnapshots` - Find every snapshot at which a boolean expression holds
 //!
 //! # Supported Expressions
 //!
@@ -58,12 +60,16 @@
 
 use std::sync::Arc;
 
-use edb_common::types::EdbSolValue;
+use edb_common::api;
+use edb_common::types::{EdbSolValue, EvalReport, HistoryScope, ValueHistory};
 use revm::{database::CacheDB, Database, DatabaseCommit, DatabaseRef};
 use serde_json::Value;
 use tracing::debug;
 
-use crate::{error_codes, eval, EngineContext, RpcError};
+use crate::{
+    collect_value_history, error_codes, eval, find_snapshots as find_matching_snapshots,
+    EngineContext, RpcError,
+};
 
 /// Evaluate a Solidity-like expression against a specific snapshot.
 ///
@@ -74,6 +80,8 @@ use crate::{error_codes, eval, EngineContext, RpcError};
 /// # Parameters
 /// - `snapshot_id` (number) - The snapshot ID to evaluate against (0-indexed)
 /// - `expr` (string) - The expression to evaluate
+/// - `frame` (number, optional) - The frame of the call stack to evaluate in, 0 (the
+///   snapshot's own frame) by default. See `edb_getCallStack`.
 ///
 /// # Returns
 /// An object containing the evaluated result with type information:
@@ -105,34 +113,13 @@ where
     <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
     <DB as Database>::Error: Clone + Send + Sync,
 {
-    // Parse the snapshot ID as the first argument
-    let snapshot_id = params
-        .as_ref()
-        .and_then(|p| p.as_array())
-        .and_then(|arr| arr.first())
-        .and_then(|v| v.as_u64())
-        .ok_or_else(|| RpcError {
-            code: error_codes::INVALID_PARAMS,
-            message: "Invalid params: expected [snapshot_id, expr]".to_string(),
-            data: None,
-        })? as usize;
-
-    // Parse the expression as the second argument
-    let expr = params
-        .as_ref()
-        .and_then(|p| p.as_array())
-        .and_then(|arr| arr.get(1))
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| RpcError {
-            code: error_codes::INVALID_PARAMS,
-            message: "Invalid params: expected [snapshot_id, expr]".to_string(),
-            data: None,
-        })?;
-
-    let value: Result<EdbSolValue, String> =
-        eval::eval_on_snapshot(context.clone(), expr, snapshot_id)
-            .map(|v| v.into())
-            .map_err(|e| e.to_string());
+    let (snapshot_id, expr, frame) = api::params::eval_on_snapshot(params.as_ref())?;
+    let frame = frame.unwrap_or_default();
+
+    let value: Result<EdbSolValue, String> = eval::frame_snapshot(context, snapshot_id, frame)
+        .and_then(|id| eval::eval_on_snapshot(context.clone(), &expr, id))
+        .map(|v| v.into())
+        .map_err(|e| e.to_string());
 
     let json_value = serde_json::to_value(value).map_err(|e| RpcError {
         code: error_codes::INTERNAL_ERROR,
@@ -143,3 +130,144 @@ where
     debug!("Evaluated expression '{}' on snapshot {}: {:?}", expr, snapshot_id, json_value);
     Ok(json_value)
 }
+
+/// Evaluate an expression against a specific snapshot, reporting the calls it makes.
+///
+/// Calls into contracts are executed in a derived EVM and may carry Solidity call
+/// options plus the EDB pseudo-option `from`, e.g. `withdraw{from: owner, gas: 100000}()`.
+///
+/// # Parameters
+/// - `snapshot_id` (number) - The snapshot ID to evaluate against (0-indexed)
+/// - `expr` (string) - The expression to evaluate
+/// - `frame` (number, optional) - The frame of the call stack to evaluate in
+///
+/// # Returns
+/// An [`EvalReport`] with the value of the expression (or the evaluation error) and every
+/// executed call, including its revert reason, gas usage and emitted events.
+pub fn eval_on_snapshot_with_calls<DB>(
+    context: &Arc<EngineContext<DB>>,
+    params: Option<Value>,
+) -> Result<serde_json::Value, RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let (snapshot_id, expr, frame) = api::params::eval_on_snapshot_with_calls(params.as_ref())?;
+    let frame = frame.unwrap_or_default();
+
+    let (value, calls) = match eval::frame_snapshot(context, snapshot_id, frame) {
+        Ok(id) => eval::eval_on_snapshot_with_calls(context.clone(), &expr, id),
+        Err(e) => (Err(e), vec![]),
+    };
+    let report = EvalReport { value: value.map(|v| v.into()).map_err(|e| e.to_string()), calls };
+
+    let json_value = serde_json::to_value(report).map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to serialize evaluation report: {e}"),
+        data: None,
+    })?;
+
+    debug!(
+        "Evaluated expression '{}' with calls on snapshot {}: {:?}",
+        expr, snapshot_id, json_value
+    );
+    Ok(json_value)
+}
+
+/// Collect every change of an expression's value over a call or a range of snapshots.
+///
+/// The expression is evaluated at each source-level snapshot within the scope, so any
+/// expression accepted by `edb_evalOnSnapshot` can be used, including plain local and
+/// state variable names.
+///
+/// # Parameters
+/// - `expr` (string) - The expression to track
+/// - `scope` (object) - A serialized [`HistoryScope`], e.g. `{ "Frame": { "trace_entry_id": 3 } }`
+///   or `{ "Range": { "start": 100, "end": 250 } }`
+///
+/// # Returns
+/// A `Result<ValueHistory, String>`: the first observed value followed by every change,
+/// each with its snapshot and source location, or the error if the expression cannot be
+/// evaluated anywhere in the scope.
+pub fn get_value_history<DB>(
+    context: &Arc<EngineContext<DB>>,
+    params: Option<Value>,
+) -> Result<serde_json::Value, RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let (expr, scope) = api::params::get_value_history(params.as_ref())?;
+
+    match scope {
+        HistoryScope::Frame { trace_entry_id } if trace_entry_id >= context.trace.len() => {
+            return Err(RpcError {
+                code: error_codes::TRACE_ENTRY_NOT_FOUND,
+                message: format!("Trace entry with id {trace_entry_id} not found"),
+                data: None,
+            });
+        }
+        HistoryScope::Range { start, end } if start > end || end >= context.snapshots.len() => {
+            return Err(RpcError {
+                code: error_codes::SNAPSHOT_OUT_OF_BOUNDS,
+                message: format!("Snapshot range {start}..={end} out of bounds"),
+                data: None,
+            });
+        }
+        _ => {}
+    }
+
+    let history: Result<ValueHistory, String> =
+        collect_value_history(context, &expr, scope).map_err(|e| e.to_string());
+
+    let json_value = serde_json::to_value(history).map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to serialize value history: {e}"),
+        data: None,
+    })?;
+
+    debug!("Collected value history of '{}' in {}", expr, scope);
+    Ok(json_value)
+}
+
+/// Find every snapshot at which a boolean expression holds.
+///
+/// Snapshots are first narrowed down by the filter, and the expression is then
+/// evaluated at the remaining snapshots in parallel. Snapshots at which the expression
+/// cannot be evaluated (e.g., a local variable out of scope) do not match.
+///
+/// # Parameters
+/// - `expr` (string) - The boolean expression, e.g. `msg.sender == pool`
+/// - `filter` (object, optional) - A serialized [`SnapshotFilter`], e.g.
+///   `{ "address": "0x...", "function": 12, "path": "src/Pool.sol", "kind": "Hook" }`.
+///   All fields are optional.
+///
+/// # Returns
+/// A `Result<Vec<usize>, String>`: the matching snapshot IDs in timeline order, or the
+/// error if the expression is not boolean or cannot be evaluated anywhere.
+pub fn find_snapshots<DB>(
+    context: &Arc<EngineContext<DB>>,
+    params: Option<Value>,
+) -> Result<serde_json::Value, RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let (expr, filter) = api::params::find_snapshots(params.as_ref())?;
+    let filter = filter.unwrap_or_default();
+
+    let matches: Result<Vec<usize>, String> =
+        find_matching_snapshots(context, &expr, &filter).map_err(|e| e.to_string());
+
+    let json_value = serde_json::to_value(&matches).map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to serialize snapshot matches: {e}"),
+        data: None,
+    })?;
+
+    debug!("Found snapshots matching '{}' in {}: {:?}", expr, filter, matches);
+    Ok(json_value)
+}
@@ -25,20 +25,45 @@
 //! - `edb_getCode` - Retrieve contract bytecode
 //! - `edb_getConstructorArgs` - Get constructor arguments
 //!
+//! ## API Discovery ([`discover`])
+//! - `edb_rpcDiscover` - Get the OpenRPC document describing the API
+//!
+//! ## Breakpoints ([`breakpoint`])
+//! - `edb_findBreakpointHit` - Find the nearest snapshot at which any breakpoint is hit
+//! - `edb_collectLogpoints` - Collect the messages logged by logpoints over the timeline
+//! - `edb_countBreakpointHits` - Collect the hits of each breakpoint over the timeline
+//!
 //! ## Expression Evaluation ([`expr`])
 //! - `edb_evalOnSnapshot` - Evaluate expressions against snapshots
+//! - `edb_evalOnSnapshotWithCalls` - Evaluate expressions, reporting the contract calls they make
+//! - `edb_getValueHistory` - Collect every change of an expression's value
+//! - `edb_findSnapshots` - Find every snapshot at which a boolean expression holds
+//!
+//! ## Nested Calls ([`nested_call`])
+//! - `edb_stepIntoCall` - Debug a call made by an expression as a nested timeline
 //!
 //! ## Navigation ([`navigation`])
 //! - `edb_getNextCall` - Navigate to next function call
 //! - `edb_getPrevCall` - Navigate to previous function call
+//! - `edb_getNextWatchpointHit` - Find the next change of watched storage or state variable
+//! - `edb_getPrevWatchpointHit` - Find the previous change of watched storage or state variable
+//!
+//! ## Data-Flow Tracing ([`provenance`])
+//! - `edb_traceValueOrigin` - Trace a value back to the instruction that produced it
 //!
 //! ## Resolution ([`resolve`])
 //! - `edb_getContractABI` - Resolve contract ABI information
 //! - `edb_getCallableABI` - Get callable function ABI details
 //!
+//! ## Sessions ([`session`])
+//! - `edb_createSession` - Prepare a transaction and host it as a new session
+//! - `edb_listSessions` - List the hosted sessions
+//! - `edb_closeSession` - Close a session
+//!
 //! ## Snapshot Management ([`snapshot`])
 //! - `edb_getSnapshotCount` - Get total number of snapshots
 //! - `edb_getSnapshotInfo` - Get detailed snapshot information
+//! - `edb_getCallStack` - Get the call stack frames at a snapshot
 //!
 //! ## Storage Inspection ([`storage`])
 //! - `edb_getStorage` - Read contract storage at specific snapshot
@@ -47,26 +72,115 @@
 //! ## Trace Analysis ([`trace`])
 //! - `edb_getTrace` - Get complete execution trace
 //!
+//! ## What-If Analysis ([`what_if`])
+//! - `edb_whatIf` - Apply state edits at a snapshot and re-execute the rest of the transaction
+//! - `edb_replayWithPatchedSource` - Replay the transaction with the patched source of a contract
+//!
 //! # Architecture
 //!
+//! The methods, with their parameters and results, are defined once in [`edb_common::api`],
+//! which also generates the OpenRPC document and the typed client of the API. Methods are
+//! dispatched by the names defined there.
+//!
 //! All methods are stateless and operate through the [`MethodHandler`] which
 //! provides access to the immutable debugging context. Methods follow a consistent
 //! pattern of parameter validation, operation execution, and result serialization.
+//! The only state kept by the handler are the debug servers started for derived
+//! timelines by the what-if and nested call methods, and for the sessions it hosts.
+//! Requests carrying a `session_id` are forwarded to the debug server of that session.
+//!
+//! # Notifications
+//!
+//! Long-running methods (snapshot searches, timeline-wide breakpoint scans, what-if
+//! re-executions and the preparation of new sessions) send `edb_progress` notifications
+//! to the WebSocket connection that requested them: when they start, every
+//! [`PROGRESS_INTERVAL`] while they run (and whenever they enter a new stage), and when
+//! they end, see [`OperationProgress`].
 
 mod artifact;
+mod breakpoint;
+mod discover;
 mod expr;
 mod navigation;
+mod nested_call;
+mod provenance;
 mod resolve;
+mod session;
 mod snapshot;
 mod storage;
 mod trace;
+mod what_if;
 
-use super::types::RpcError;
-use crate::{error_codes, EngineContext};
+use super::types::{
+    OperationProgress, OperationState, RpcError, RpcId, RpcNotification, RpcRequest,
+};
+use crate::{error_codes, EngineContext, RpcServerHandle};
+use edb_common::{
+    api::{self, is_long_running, method},
+    types::DEFAULT_SESSION_ID,
+};
 use revm::{database::CacheDB, Database, DatabaseCommit, DatabaseRef};
-use std::sync::Arc;
+use session::Sessions;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex, OnceLock,
+};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 use tracing::debug;
 
+/// Methods managing the sessions, always run by the hosting server
+const SESSION_METHODS: &[&str] =
+    &[method::CREATE_SESSION, method::LIST_SESSIONS, method::CLOSE_SESSION];
+
+/// Number of notifications kept for slow subscribers before they start missing some
+const NOTIFICATION_CAPACITY: usize = 256;
+
+/// Interval at which running long-running operations report their progress
+pub const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Implementation of a method computing its result synchronously
+type SyncMethod<DB> =
+    fn(&Arc<EngineContext<DB>>, Option<serde_json::Value>) -> Result<serde_json::Value, RpcError>;
+
+/// Sends the `edb_progress` notifications of a long-running operation to the connection
+/// that requested it
+#[derive(Clone)]
+pub(crate) struct ProgressReporter {
+    notifications: broadcast::Sender<RpcNotification>,
+    token: u64,
+    operation: String,
+    request_id: Option<RpcId>,
+    connection_id: u64,
+    start: Instant,
+}
+
+impl ProgressReporter {
+    fn report(&self, state: OperationState, stage: Option<&str>) {
+        let elapsed_ms =
+            (state != OperationState::Started).then(|| self.start.elapsed().as_millis() as u64);
+        let progress = OperationProgress {
+            token: self.token,
+            operation: self.operation.clone(),
+            request_id: self.request_id.clone(),
+            state,
+            stage: stage.map(str::to_string),
+            elapsed_ms,
+        };
+        if let Ok(params) = serde_json::to_value(progress) {
+            // The client may be gone, which is fine
+            let notification =
+                RpcNotification::new("edb_progress", params).to_connection(self.connection_id);
+            let _ = self.notifications.send(notification);
+        }
+    }
+
+    /// Report that the operation entered a new stage
+    pub(crate) fn stage(&self, stage: &str) {
+        self.report(OperationState::Running, Some(stage));
+    }
+}
+
 /// Stateless RPC method dispatcher for EDB debugging API.
 ///
 /// This handler provides a centralized entry point for all RPC methods.
@@ -83,6 +197,16 @@ where
 {
     /// Immutable debugging context providing read-only access to debugging data
     context: Arc<EngineContext<DB>>,
+    /// Debug servers of the timelines derived by what-if methods, shut down with this handler
+    derived_servers: Mutex<Vec<RpcServerHandle>>,
+    /// Channel of the notifications pushed to subscribed clients
+    notifications: broadcast::Sender<RpcNotification>,
+    /// Token of the next long-running operation
+    next_token: AtomicU64,
+    /// Sessions of other transactions hosted by the server
+    sessions: Sessions,
+    /// Port of the server, once it is started
+    port: OnceLock<u16>,
 }
 
 impl<DB> MethodHandler<DB>
@@ -93,7 +217,25 @@ where
 {
     /// Create a new method handler
     pub fn new(context: Arc<EngineContext<DB>>) -> Self {
-        Self { context }
+        let (notifications, _) = broadcast::channel(NOTIFICATION_CAPACITY);
+        Self {
+            context,
+            derived_servers: Mutex::new(Vec::new()),
+            notifications,
+            next_token: AtomicU64::new(0),
+            sessions: Sessions::new(),
+            port: OnceLock::new(),
+        }
+    }
+
+    /// Record the port the server serving this handler listens on
+    pub(crate) fn set_port(&self, port: u16) {
+        let _ = self.port.set(port);
+    }
+
+    /// Subscribe to the notifications pushed by the handler
+    pub fn subscribe(&self) -> broadcast::Receiver<RpcNotification> {
+        self.notifications.subscribe()
     }
 
     /// Handle an RPC method call with client-provided state
@@ -101,23 +243,162 @@ where
         &self,
         method: &str,
         params: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value, RpcError> {
+        self.handle_request_method(method, params, None).await
+    }
+
+    /// Handle an RPC request, forwarding it to the session it addresses
+    pub async fn handle_request(&self, request: RpcRequest) -> Result<serde_json::Value, RpcError> {
+        match request.session_id.as_deref() {
+            Some(session_id)
+                if session_id != DEFAULT_SESSION_ID
+                    && !SESSION_METHODS.contains(&request.method.as_str()) =>
+            {
+                let methods = self.sessions.methods(session_id)?;
+                methods.dispatch_request(RpcRequest { session_id: None, ..request }).await
+            }
+            _ => {
+                let progress = request.connection_id.map(|connection_id| ProgressReporter {
+                    notifications: self.notifications.clone(),
+                    token: self.next_token.fetch_add(1, Ordering::Relaxed),
+                    operation: request.method.clone(),
+                    request_id: request.id.clone(),
+                    connection_id,
+                    start: Instant::now(),
+                });
+                self.handle_request_method(&request.method, request.params, progress).await
+            }
+        }
+    }
+
+    /// Handle an RPC method call, reporting the progress of long-running methods through
+    /// `progress` if the client listens to it
+    async fn handle_request_method(
+        &self,
+        method: &str,
+        params: Option<serde_json::Value>,
+        progress: Option<ProgressReporter>,
+    ) -> Result<serde_json::Value, RpcError> {
+        let Some(progress) = progress.filter(|_| is_long_running(method)) else {
+            return self.dispatch_method(method, params, None).await;
+        };
+
+        progress.report(OperationState::Started, None);
+        let dispatch = self.dispatch_method(method, params, Some(&progress));
+        tokio::pin!(dispatch);
+        let mut ticks = tokio::time::interval_at(
+            tokio::time::Instant::now() + PROGRESS_INTERVAL,
+            PROGRESS_INTERVAL,
+        );
+        let result = loop {
+            tokio::select! {
+                result = &mut dispatch => break result,
+                _ = ticks.tick() => progress.report(OperationState::Running, None),
+            }
+        };
+        let state = if result.is_ok() { OperationState::Finished } else { OperationState::Failed };
+        progress.report(state, None);
+
+        result
+    }
+
+    /// Run a synchronous method on the blocking threads, so that the async workers stay
+    /// free to serve other requests and to report its progress
+    async fn run_blocking(
+        &self,
+        params: Option<serde_json::Value>,
+        method: SyncMethod<DB>,
+    ) -> Result<serde_json::Value, RpcError> {
+        let context = self.context.clone();
+        tokio::task::spawn_blocking(move || method(&context, params)).await.map_err(|e| {
+            RpcError {
+                code: error_codes::INTERNAL_ERROR,
+                message: format!("Method panicked: {e}"),
+                data: None,
+            }
+        })?
+    }
+
+    /// Route an RPC method call to its implementation
+    async fn dispatch_method(
+        &self,
+        method: &str,
+        params: Option<serde_json::Value>,
+        progress: Option<&ProgressReporter>,
     ) -> Result<serde_json::Value, RpcError> {
         debug!("Handling RPC method: {}", method);
 
         match method {
-            "edb_getTrace" => trace::get_trace(&self.context),
-            "edb_getCode" => artifact::get_code(&self.context, params),
-            "edb_getCodeByAddress" => artifact::get_code_by_address(&self.context, params),
-            "edb_getConstructorArgs" => artifact::get_constructor_args(&self.context, params),
-            "edb_getSnapshotCount" => snapshot::get_snapshot_count(&self.context),
-            "edb_getSnapshotInfo" => snapshot::get_snapshot_info(&self.context, params),
-            "edb_getContractABI" => resolve::get_contract_abi(&self.context, params),
-            "edb_getCallableABI" => resolve::get_callable_abi(&self.context, params),
-            "edb_getNextCall" => navigation::get_next_call(&self.context, params),
-            "edb_getPrevCall" => navigation::get_prev_call(&self.context, params),
-            "edb_getStorage" => storage::get_storage(&self.context, params),
-            "edb_getStorageDiff" => storage::get_storage_diff(&self.context, params),
-            "edb_evalOnSnapshot" => expr::eval_on_snapshot(&self.context, params),
+            method::RPC_DISCOVER => {
+                api::params::rpc_discover(params.as_ref())?;
+                discover::rpc_discover()
+            }
+            method::GET_TRACE => {
+                api::params::get_trace(params.as_ref())?;
+                trace::get_trace(&self.context)
+            }
+            method::GET_CODE => artifact::get_code(&self.context, params),
+            method::GET_CODE_BY_ADDRESS => artifact::get_code_by_address(&self.context, params),
+            method::GET_CONSTRUCTOR_ARGS => artifact::get_constructor_args(&self.context, params),
+            method::GET_SNAPSHOT_COUNT => {
+                api::params::get_snapshot_count(params.as_ref())?;
+                snapshot::get_snapshot_count(&self.context)
+            }
+            method::GET_SNAPSHOT_INFO => snapshot::get_snapshot_info(&self.context, params),
+            method::GET_CALL_STACK => snapshot::get_call_stack(&self.context, params),
+            method::GET_CONTRACT_ABI => resolve::get_contract_abi(&self.context, params),
+            method::GET_CALLABLE_ABI => resolve::get_callable_abi(&self.context, params),
+            method::GET_NEXT_CALL => navigation::get_next_call(&self.context, params),
+            method::GET_PREV_CALL => navigation::get_prev_call(&self.context, params),
+            method::GET_NEXT_WATCHPOINT_HIT => {
+                navigation::get_next_watchpoint_hit(&self.context, params)
+            }
+            method::GET_PREV_WATCHPOINT_HIT => {
+                navigation::get_prev_watchpoint_hit(&self.context, params)
+            }
+            method::GET_STORAGE => storage::get_storage(&self.context, params),
+            method::GET_STORAGE_DIFF => storage::get_storage_diff(&self.context, params),
+            method::EVAL_ON_SNAPSHOT => expr::eval_on_snapshot(&self.context, params),
+            method::EVAL_ON_SNAPSHOT_WITH_CALLS => {
+                expr::eval_on_snapshot_with_calls(&self.context, params)
+            }
+            method::GET_VALUE_HISTORY => self.run_blocking(params, expr::get_value_history).await,
+            method::FIND_SNAPSHOTS => self.run_blocking(params, expr::find_snapshots).await,
+            method::FIND_BREAKPOINT_HIT => {
+                self.run_blocking(params, breakpoint::find_breakpoint_hit_from_snapshot).await
+            }
+            method::COLLECT_LOGPOINTS => {
+                self.run_blocking(params, breakpoint::collect_logpoints).await
+            }
+            method::COUNT_BREAKPOINT_HITS => {
+                self.run_blocking(params, breakpoint::count_breakpoint_hits).await
+            }
+            method::TRACE_VALUE_ORIGIN => {
+                provenance::trace_value_origin_on_snapshot(&self.context, params)
+            }
+            method::WHAT_IF => what_if::what_if(&self.context, params, &self.derived_servers).await,
+            method::REPLAY_WITH_PATCHED_SOURCE => {
+                what_if::replay_with_patched_source(&self.context, params, &self.derived_servers)
+                    .await
+            }
+            method::STEP_INTO_CALL => {
+                nested_call::step_into_call(&self.context, params, &self.derived_servers).await
+            }
+            method::CREATE_SESSION => {
+                session::create_session(
+                    &self.context,
+                    params,
+                    &self.sessions,
+                    &self.notifications,
+                    progress.cloned(),
+                )
+                .await
+            }
+            method::LIST_SESSIONS => {
+                api::params::list_sessions(params.as_ref())?;
+                session::list_sessions(&self.context, self.port.get().copied(), &self.sessions)
+            }
+            method::CLOSE_SESSION => session::close_session(params, &self.sessions),
             // Unimplemented methods
             _ => Err(RpcError {
                 code: error_codes::METHOD_NOT_FOUND,
@@ -127,3 +408,178 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{prepare_test_context, CALLER_CONTRACT, SENDER};
+    use alloy_primitives::{Address, U256};
+    use edb_common::{
+        api::method_specs,
+        types::{Breakpoint, HistoryScope, SearchDirection, StateEdit, ValueLocation, Watchpoint},
+    };
+    use serde_json::json;
+
+    const TX_HASH: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+
+    fn request(method: &str, params: serde_json::Value, connection_id: Option<u64>) -> RpcRequest {
+        let request = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+        RpcRequest { connection_id, ..serde_json::from_value(request).unwrap() }
+    }
+
+    /// Well-formed params of every API method, on the test transaction
+    fn sample_params(name: &str) -> serde_json::Value {
+        let breakpoints = json!([Breakpoint::default()]);
+        let watchpoint = Watchpoint::Storage { address: CALLER_CONTRACT, slot: U256::ZERO };
+        match name {
+            method::GET_CODE
+            | method::GET_SNAPSHOT_INFO
+            | method::GET_CALL_STACK
+            | method::GET_NEXT_CALL
+            | method::GET_PREV_CALL
+            | method::GET_STORAGE_DIFF => json!([0]),
+            method::GET_CODE_BY_ADDRESS
+            | method::GET_CONSTRUCTOR_ARGS
+            | method::GET_CALLABLE_ABI => json!([CALLER_CONTRACT]),
+            method::GET_CONTRACT_ABI => json!([CALLER_CONTRACT, false]),
+            method::FIND_BREAKPOINT_HIT => json!([breakpoints, 0, SearchDirection::Forward]),
+            method::COLLECT_LOGPOINTS => json!([[]]),
+            method::COUNT_BREAKPOINT_HITS => json!([breakpoints]),
+            method::EVAL_ON_SNAPSHOT | method::EVAL_ON_SNAPSHOT_WITH_CALLS => {
+                json!([0, "1 + 1", 0])
+            }
+            method::GET_VALUE_HISTORY => json!(["1", HistoryScope::Range { start: 0, end: 0 }]),
+            method::FIND_SNAPSHOTS => json!(["true", null]),
+            method::STEP_INTO_CALL => json!([0, "1 + 1"]),
+            method::GET_NEXT_WATCHPOINT_HIT | method::GET_PREV_WATCHPOINT_HIT => {
+                json!([0, watchpoint])
+            }
+            method::TRACE_VALUE_ORIGIN => json!([0, ValueLocation::Stack { depth: 0 }]),
+            method::CREATE_SESSION => json!([TX_HASH]),
+            method::CLOSE_SESSION => json!(["unknown"]),
+            method::GET_STORAGE => json!([0, U256::ZERO]),
+            method::WHAT_IF => json!([
+                0,
+                [StateEdit::Storage {
+                    address: CALLER_CONTRACT,
+                    slot: U256::ZERO,
+                    value: U256::ZERO
+                }]
+            ]),
+            method::REPLAY_WITH_PATCHED_SOURCE => json!([CALLER_CONTRACT, "Caller.sol", ""]),
+            method::GET_SNAPSHOT_COUNT
+            | method::LIST_SESSIONS
+            | method::GET_TRACE
+            | method::RPC_DISCOVER => json!([]),
+            _ => panic!("No sample params for {name}"),
+        }
+    }
+
+    fn progress(
+        notifications: &mut broadcast::Receiver<RpcNotification>,
+    ) -> Vec<OperationProgress> {
+        std::iter::from_fn(|| notifications.try_recv().ok())
+            .map(|notification| {
+                assert_eq!(notification.method, "edb_progress");
+                assert_eq!(notification.connection_id, Some(3));
+                serde_json::from_value(notification.params).unwrap()
+            })
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_progress_notifications() {
+        let handler = MethodHandler::new(Arc::new(prepare_test_context().await));
+        let mut notifications = handler.subscribe();
+
+        // Requests without a connection to report to run silently
+        handler
+            .handle_request(request(method::FIND_SNAPSHOTS, json!(["true"]), None))
+            .await
+            .unwrap();
+        assert!(progress(&mut notifications).is_empty());
+
+        handler
+            .handle_request(request(method::FIND_SNAPSHOTS, json!(["true"]), Some(3)))
+            .await
+            .unwrap();
+        let states: Vec<_> = progress(&mut notifications).iter().map(|p| p.state).collect();
+        assert_eq!(states, [OperationState::Started, OperationState::Finished]);
+
+        // Preparing a session reports its stages, here failing without a chain to fork
+        let result = handler
+            .handle_request(request(method::CREATE_SESSION, json!([TX_HASH]), Some(3)))
+            .await;
+        assert!(result.is_err());
+        let reports = progress(&mut notifications);
+        assert_eq!(reports[0].state, OperationState::Started);
+        assert_eq!(reports[1].state, OperationState::Running);
+        assert_eq!(reports[1].stage.as_deref(), Some("forking"));
+        assert_eq!(reports.last().unwrap().state, OperationState::Failed);
+        assert!(reports.iter().all(|report| report.operation == method::CREATE_SESSION));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_call_stack_frames() {
+        let context = Arc::new(prepare_test_context().await);
+        let handler = MethodHandler::new(context.clone());
+        let first_callee_step = context.trace[1].first_snapshot_id.unwrap();
+
+        // Inside the callee, frame 1 is the CALL step of the caller, right before it
+        let frames =
+            handler.handle_method(method::GET_CALL_STACK, Some(json!([first_callee_step]))).await;
+        let call_step = first_callee_step - 1;
+        assert_eq!(frames.unwrap(), json!([first_callee_step, call_step]));
+        assert_eq!(context.snapshots[call_step].0.trace_entry_id(), 0);
+
+        // Expressions are evaluated in the frame they name
+        let eval = |frame: usize| {
+            let params = json!([first_callee_step, "msg.sender", frame]);
+            handler.handle_method(method::EVAL_ON_SNAPSHOT, Some(params))
+        };
+        let sender = |address: Address| json!({ "Ok": { "type": "Address", "value": address } });
+        assert_eq!(eval(0).await.unwrap(), sender(CALLER_CONTRACT));
+        assert_eq!(eval(1).await.unwrap(), sender(SENDER));
+
+        let result = eval(2).await.unwrap();
+        let error = result["Err"].as_str().unwrap();
+        assert!(error.starts_with("Frame 2 not found: the call stack at snapshot"), "{error}");
+
+        // Internal callers come before the callsite of the external call
+        let mut context = prepare_test_context().await;
+        let step = first_callee_step + 2;
+        context.snapshots[step].1.set_internal_callers(vec![first_callee_step]);
+        let handler = MethodHandler::new(Arc::new(context));
+        let frames = handler.handle_method(method::GET_CALL_STACK, Some(json!([step]))).await;
+        assert_eq!(frames.unwrap(), json!([step, first_callee_step, call_step]));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_every_method_dispatches() {
+        let handler = MethodHandler::new(Arc::new(prepare_test_context().await));
+
+        for spec in method_specs() {
+            let params = sample_params(spec.name);
+            let count = params.as_array().unwrap().len();
+            let required = spec.params.iter().filter(|param| param.required).count();
+            assert!((required..=spec.params.len()).contains(&count), "{}", spec.name);
+
+            // Methods may fail on the test transaction, but never for their params
+            if let Err(error) = handler.handle_method(spec.name, Some(params)).await {
+                assert_ne!(error.code, error_codes::METHOD_NOT_FOUND, "{}", spec.name);
+                assert_ne!(
+                    error.code,
+                    error_codes::INVALID_PARAMS,
+                    "{}: {}",
+                    spec.name,
+                    error.message
+                );
+            }
+        }
+
+        let error = handler.handle_method(method::GET_CODE, Some(json!(["0"]))).await.unwrap_err();
+        assert_eq!(error.code, error_codes::INVALID_PARAMS);
+        let error = handler.handle_method(method::GET_TRACE, Some(json!([0]))).await.unwrap_err();
+        assert_eq!(error.code, error_codes::INVALID_PARAMS);
+    }
+}
@@ -16,11 +16,16 @@
 
 //! Navigation RPC methods
 //!
-//! This module implements RPC methods for navigating through snapshots.
+//! This module implements RPC methods for navigating through snapshots: to the
+//! next/previous function call, or to the next/previous change of a watchpoint.
 
 use crate::rpc::types::RpcError;
-use crate::{error_codes, EngineContext, Snapshot, SnapshotDetail};
-use edb_common::types::ExecutionFrameId;
+use crate::{
+    error_codes, find_next_watchpoint_hit, find_prev_watchpoint_hit, EngineContext, Snapshot,
+    SnapshotDetail,
+};
+use edb_common::api;
+use edb_common::types::{ExecutionFrameId, WatchpointHit};
 use edb_common::OpcodeTr;
 use revm::bytecode::OpCode;
 use revm::database::CacheDB;
@@ -38,17 +43,7 @@ where
     <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
     <DB as Database>::Error: Clone + Send + Sync,
 {
-    // Parse the snapshot ID from parameters
-    let snapshot_id = params
-        .as_ref()
-        .and_then(|p| p.as_array())
-        .and_then(|arr| arr.first())
-        .and_then(|v| v.as_u64())
-        .ok_or_else(|| RpcError {
-            code: error_codes::INVALID_PARAMS,
-            message: "Invalid params: expected [snapshot_id]".to_string(),
-            data: None,
-        })? as usize;
+    let (snapshot_id,) = api::params::get_next_call(params.as_ref())?;
 
     // Get the snapshot at the specified index
     let _ = context.snapshots.get(snapshot_id).ok_or_else(|| RpcError {
@@ -85,17 +80,7 @@ where
     <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
     <DB as Database>::Error: Clone + Send + Sync,
 {
-    // Parse the snapshot ID from parameters
-    let snapshot_id = params
-        .as_ref()
-        .and_then(|p| p.as_array())
-        .and_then(|arr| arr.first())
-        .and_then(|v| v.as_u64())
-        .ok_or_else(|| RpcError {
-            code: error_codes::INVALID_PARAMS,
-            message: "Invalid params: expected [snapshot_id]".to_string(),
-            data: None,
-        })? as usize;
+    let (snapshot_id,) = api::params::get_prev_call(params.as_ref())?;
 
     // Get the snapshot at the specified index
     let _ = context.snapshots.get(snapshot_id).ok_or_else(|| RpcError {
@@ -123,6 +108,95 @@ where
     Ok(json_value)
 }
 
+/// Find the next change of a watchpoint after a snapshot.
+///
+/// # Parameters
+/// - `snapshot_id` (number) - The snapshot to search from
+/// - `watchpoint` (object) - A serialized [`Watchpoint`]
+///
+/// # Returns
+/// A `Result<Option<WatchpointHit>, String>`: `None` if the watched state does not
+/// change anymore, and `Err` if the watchpoint cannot be resolved (e.g. an unknown
+/// state variable) so clients can display it.
+pub fn get_next_watchpoint_hit<DB>(
+    context: &Arc<EngineContext<DB>>,
+    params: Option<Value>,
+) -> Result<Value, RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let (snapshot_id, watchpoint) = api::params::get_next_watchpoint_hit(params.as_ref())?;
+    check_snapshot_id(context, snapshot_id)?;
+
+    let hit: Result<Option<WatchpointHit>, String> =
+        find_next_watchpoint_hit(context, &watchpoint, snapshot_id).map_err(|e| e.to_string());
+
+    let json_value = serde_json::to_value(hit).map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to serialize watchpoint hit: {e}"),
+        data: None,
+    })?;
+
+    debug!("Retrieved next change of {} after snapshot {}", watchpoint, snapshot_id);
+    Ok(json_value)
+}
+
+/// Find the previous change of a watchpoint before a snapshot.
+///
+/// # Parameters
+/// - `snapshot_id` (number) - The snapshot to search from
+/// - `watchpoint` (object) - A serialized [`Watchpoint`]
+///
+/// # Returns
+/// A `Result<Option<WatchpointHit>, String>`, as for [`get_next_watchpoint_hit`].
+pub fn get_prev_watchpoint_hit<DB>(
+    context: &Arc<EngineContext<DB>>,
+    params: Option<Value>,
+) -> Result<Value, RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let (snapshot_id, watchpoint) = api::params::get_prev_watchpoint_hit(params.as_ref())?;
+    check_snapshot_id(context, snapshot_id)?;
+
+    let hit: Result<Option<WatchpointHit>, String> =
+        find_prev_watchpoint_hit(context, &watchpoint, snapshot_id).map_err(|e| e.to_string());
+
+    let json_value = serde_json::to_value(hit).map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to serialize watchpoint hit: {e}"),
+        data: None,
+    })?;
+
+    debug!("Retrieved previous change of {} before snapshot {}", watchpoint, snapshot_id);
+    Ok(json_value)
+}
+
+// Helper function
+fn check_snapshot_id<DB>(
+    context: &Arc<EngineContext<DB>>,
+    snapshot_id: usize,
+) -> Result<(), RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    if snapshot_id >= context.snapshots.len() {
+        return Err(RpcError {
+            code: error_codes::SNAPSHOT_OUT_OF_BOUNDS,
+            message: format!("Snapshot with id {snapshot_id} not found"),
+            data: None,
+        });
+    }
+
+    Ok(())
+}
+
 // Helper function
 fn snapshot_is_call<DB>(
     context: &Arc<EngineContext<DB>>,
@@ -0,0 +1,122 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Nested call RPC methods.
+//!
+//! This module steps into a call made while evaluating an expression. The call is
+//! re-executed with the snapshot inspectors attached, and the resulting nested timeline
+//! is served by a new debug server, just like the derived timelines of the what-if methods.
+//! The server of the current timeline keeps running, so clients can return to it at any time.
+//!
+//! # Available Methods
+//!
+//! - `edb_stepIntoCall` - Debug a call made by an expression as a nested timeline
+//!
+//! # Example Usage
+//!
+//! ```json
+//! // Request
+//! {
+//!   "method": "edb_stepIntoCall",
+//!   "params": [150, "vault.withdraw{from: owner}(1 ether)"]
+//! }
+//!
+//! // Response
+//! {
+//!   "result": { "Ok": { "port": 3001, "call": { "function": "withdraw(uint256)", ... } } }
+//! }
+//! ```
+
+use std::sync::{Arc, Mutex};
+
+use edb_common::api;
+use edb_common::types::NestedCallTimeline;
+use revm::{database::CacheDB, Database, DatabaseCommit, DatabaseRef};
+use serde_json::Value;
+use tracing::debug;
+
+use super::what_if::serve_timeline;
+use crate::{debug_call_at_snapshot, error_codes, eval, EngineContext, RpcError, RpcServerHandle};
+
+/// Debug a call made by an expression as a nested timeline.
+///
+/// # Parameters
+/// - `snapshot_id` (number) - The snapshot to evaluate the expression against
+/// - `expr` (string) - The expression making the call
+/// - `call_index` (number, optional) - Index of the call among the calls the expression
+///   makes, in execution order. Defaults to the last call, i.e., the outermost one.
+///
+/// # Returns
+/// A `Result<NestedCallTimeline, String>`, the error describing why the expression cannot
+/// be stepped into (e.g., it does not evaluate or makes no calls).
+pub async fn step_into_call<DB>(
+    context: &Arc<EngineContext<DB>>,
+    params: Option<Value>,
+    derived_servers: &Mutex<Vec<RpcServerHandle>>,
+) -> Result<Value, RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let (snapshot_id, expr, call_index) = api::params::step_into_call(params.as_ref())?;
+
+    if snapshot_id >= context.snapshots.len() {
+        return Err(RpcError {
+            code: error_codes::SNAPSHOT_OUT_OF_BOUNDS,
+            message: format!("Snapshot with id {snapshot_id} not found"),
+            data: None,
+        });
+    }
+
+    // The value of the expression does not matter: a call that reverts is worth
+    // stepping into as well
+    let (_, mut calls) = eval::eval_on_snapshot_with_calls(context.clone(), &expr, snapshot_id);
+    let call = match call_index {
+        _ if calls.is_empty() => Err(format!("Expression '{expr}' makes no contract calls")),
+        None => Ok(calls.swap_remove(calls.len() - 1)),
+        Some(index) if index < calls.len() => Ok(calls.swap_remove(index)),
+        Some(index) => Err(format!(
+            "Call index {index} out of range: expression '{expr}' makes {} calls",
+            calls.len()
+        )),
+    };
+
+    let result = match call {
+        Ok(call) => {
+            let (endpoint, call) = serve_timeline(context, derived_servers, move |original| {
+                let nested = debug_call_at_snapshot(original, snapshot_id, &call)?;
+                Ok((nested, call))
+            })
+            .await?;
+            Ok(NestedCallTimeline {
+                port: endpoint.port().unwrap_or_default(),
+                socket: endpoint.unix_socket().map(Into::into),
+                call,
+            })
+        }
+        Err(e) => Err(e),
+    };
+
+    let json_value = serde_json::to_value(&result).map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to serialize nested call timeline: {e}"),
+        data: None,
+    })?;
+
+    debug!("Stepped into expression '{}' on snapshot {}: {:?}", expr, snapshot_id, json_value);
+    Ok(json_value)
+}
@@ -0,0 +1,97 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Data-flow tracing RPC methods.
+//!
+//! This module exposes backward data-flow tracing, answering "where did this value
+//! come from" for a stack item, memory/calldata range or storage slot at a snapshot.
+//!
+//! # Available Methods
+//!
+//! - `edb_traceValueOrigin` - Trace a value back to the instruction that produced it
+//!
+//! # Example Usage
+//!
+//! ```json
+//! // Request
+//! {
+//!   "method": "edb_traceValueOrigin",
+//!   "params": [150, { "Stack": { "depth": 0 } }]
+//! }
+//!
+//! // Response
+//! {
+//!   "result": {
+//!     "Ok": {
+//!       "snapshot_id": 150,
+//!       "location": { "Stack": { "depth": 0 } },
+//!       "steps": [ ... ],
+//!       "kind": "Computation"
+//!     }
+//!   }
+//! }
+//! ```
+
+use std::sync::Arc;
+
+use edb_common::api;
+use edb_common::types::ValueOrigin;
+use revm::{database::CacheDB, Database, DatabaseCommit, DatabaseRef};
+use serde_json::Value;
+use tracing::debug;
+
+use crate::{error_codes, trace_value_origin, EngineContext, RpcError};
+
+/// Trace a value at a snapshot back to its origin.
+///
+/// # Parameters
+/// - `snapshot_id` (number) - The snapshot at which the value is observed
+/// - `location` (object) - A serialized [`ValueLocation`]
+///
+/// # Returns
+/// A `Result<ValueOrigin, String>`: tracing failures (e.g. a stack item requested at a
+/// hook snapshot) are reported as `Err` so clients can display them.
+pub fn trace_value_origin_on_snapshot<DB>(
+    context: &Arc<EngineContext<DB>>,
+    params: Option<Value>,
+) -> Result<Value, RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let (snapshot_id, location) = api::params::trace_value_origin(params.as_ref())?;
+
+    if snapshot_id >= context.snapshots.len() {
+        return Err(RpcError {
+            code: error_codes::SNAPSHOT_OUT_OF_BOUNDS,
+            message: format!("Snapshot with id {snapshot_id} not found"),
+            data: None,
+        });
+    }
+
+    let origin: Result<ValueOrigin, String> =
+        trace_value_origin(context, snapshot_id, location).map_err(|e| e.to_string());
+
+    let json_value = serde_json::to_value(origin).map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to serialize value origin: {e}"),
+        data: None,
+    })?;
+
+    debug!("Traced origin of {} at snapshot {}", location, snapshot_id);
+    Ok(json_value)
+}
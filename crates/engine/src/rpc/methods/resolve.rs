@@ -17,6 +17,7 @@
 use std::sync::Arc;
 
 use alloy_primitives::Address;
+use edb_common::api;
 use edb_common::types::{parse_callable_abi_info, CallableAbiInfo, ContractTy};
 use revm::{database::CacheDB, Database, DatabaseCommit, DatabaseRef};
 use serde_json::Value;
@@ -33,29 +34,7 @@ where
     <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
     <DB as Database>::Error: Clone + Send + Sync,
 {
-    // Parse the address as the first argument
-    let address: Address = params
-        .as_ref()
-        .and_then(|p| p.as_array())
-        .and_then(|arr| arr.first())
-        .and_then(|v| serde_json::from_value(v.clone()).ok())
-        .ok_or_else(|| RpcError {
-            code: error_codes::INVALID_PARAMS,
-            message: "Invalid params: expected [address, recompiled]".to_string(),
-            data: None,
-        })?;
-
-    // Parse recompiled as the second argument
-    let recompiled = params
-        .as_ref()
-        .and_then(|p| p.as_array())
-        .and_then(|arr| arr.get(1))
-        .and_then(|v| v.as_bool())
-        .ok_or_else(|| RpcError {
-            code: error_codes::INVALID_PARAMS,
-            message: "Invalid params: expected [address, recompiled]".to_string(),
-            data: None,
-        })?;
+    let (address, recompiled) = api::params::get_contract_abi(params.as_ref())?;
 
     let abi = if recompiled {
         context
@@ -101,17 +80,7 @@ where
     <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
     <DB as Database>::Error: Clone + Send + Sync,
 {
-    // Parse the address as the first argument
-    let address: Address = params
-        .as_ref()
-        .and_then(|p| p.as_array())
-        .and_then(|arr| arr.first())
-        .and_then(|v| serde_json::from_value(v.clone()).ok())
-        .ok_or_else(|| RpcError {
-            code: error_codes::INVALID_PARAMS,
-            message: "Invalid params: expected [address]".to_string(),
-            data: None,
-        })?;
+    let (address,) = api::params::get_callable_abi(params.as_ref())?;
 
     // Let's figure whether the address is a normal, proxy, or implementation contract
     let mut related_addresses: Vec<(Address, ContractTy)> = Vec::new();
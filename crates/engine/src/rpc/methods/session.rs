@@ -0,0 +1,467 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Session RPC methods.
+//!
+//! A debug server can host debugging sessions of other transactions besides its own, so
+//! that several transactions can be compared without running several engines. Each
+//! session is prepared by the engine like the server's own transaction and served by its
+//! own debug server; requests carrying a `session_id` member are forwarded to it.
+//!
+//! Notifications pushed by a session are relayed to the clients of the hosting server,
+//! with the `session_id` of the session added to their parameters.
+//!
+//! Sessions live until they are closed, or until they are evicted, least recently used
+//! first, when the system runs low on memory: one idle session is evicted per memory
+//! check, so that the memory freed by each eviction is seen before evicting another.
+//!
+//! # Available Methods
+//!
+//! - `edb_createSession` - Prepare a transaction and host it as a new session
+//! - `edb_listSessions` - List the hosted sessions
+//! - `edb_closeSession` - Close a session
+//!
+//! # Example Usage
+//!
+//! ```json
+//! // Request
+//! { "method": "edb_createSession", "params": ["0x5bedd885..."] }
+//!
+//! // Response
+//! { "result": { "session_id": "1", "tx_hash": "0x5bedd885...", "port": 3001, "idle_ms": 0 } }
+//!
+//! // Any method, in the new session
+//! { "method": "edb_getSnapshotCount", "session_id": "1" }
+//! ```
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use alloy_primitives::TxHash;
+use edb_common::api;
+use edb_common::{
+    fork_and_prepare,
+    types::{SessionInfo, DEFAULT_SESSION_ID},
+};
+use revm::{database::CacheDB, Database, DatabaseCommit, DatabaseRef};
+use serde_json::Value;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+use super::ProgressReporter;
+use crate::{
+    error_codes, Engine, EngineContext, MethodDispatch, RpcError, RpcNotification, RpcServerHandle,
+};
+
+/// Sessions are evicted while less than this fraction of the system memory is available
+const MIN_AVAILABLE_MEMORY: f64 = 0.1;
+
+/// Sessions used more recently than this are never evicted
+const MIN_IDLE_TIME: Duration = Duration::from_secs(60);
+
+/// Minimal interval between two checks of the available memory
+const MEMORY_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A hosted debugging session
+struct Session {
+    /// Transaction debugged in the session
+    tx_hash: TxHash,
+    /// Debug server of the session, shut down when the session is dropped
+    handle: RpcServerHandle,
+    /// Methods of the debug server
+    methods: Arc<dyn MethodDispatch>,
+    /// Last time the session served a request
+    last_used: Instant,
+}
+
+impl Session {
+    fn info(&self, session_id: &str) -> SessionInfo {
+        SessionInfo {
+            session_id: session_id.to_string(),
+            tx_hash: self.tx_hash,
+            port: self.handle.port().unwrap_or_default(),
+            socket: self.handle.endpoint.unix_socket().map(Into::into),
+            idle_ms: self.last_used.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+/// Probe of the fraction of the system memory that is available, if it can be told
+type MemoryProbe = Box<dyn Fn() -> Option<f64> + Send + Sync>;
+
+/// Debugging sessions hosted by a debug server
+pub(super) struct Sessions {
+    /// Hosted sessions by id
+    sessions: Mutex<HashMap<String, Session>>,
+    /// Number of sessions created so far, giving the id of the next one
+    created: AtomicU64,
+    /// Last time the available memory was checked
+    last_memory_check: Mutex<Instant>,
+    /// Tells how much memory is available
+    memory_probe: MemoryProbe,
+}
+
+impl Sessions {
+    pub(super) fn new() -> Self {
+        Self::with_memory_probe(Box::new(available_memory_ratio))
+    }
+
+    /// Create the sessions, checking the available memory with `memory_probe`
+    fn with_memory_probe(memory_probe: MemoryProbe) -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+            created: AtomicU64::new(0),
+            last_memory_check: Mutex::new(Instant::now()),
+            memory_probe,
+        }
+    }
+
+    /// Get the methods of a session, marking it as used
+    pub(super) fn methods(&self, session_id: &str) -> Result<Arc<dyn MethodDispatch>, RpcError> {
+        self.evict_if_memory_low(false);
+
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let session = sessions.get_mut(session_id).ok_or_else(|| session_not_found(session_id))?;
+        session.last_used = Instant::now();
+        Ok(session.methods.clone())
+    }
+
+    /// Host a session, relaying its notifications to `relay`
+    fn insert(
+        &self,
+        tx_hash: TxHash,
+        handle: RpcServerHandle,
+        relay: &broadcast::Sender<RpcNotification>,
+    ) -> SessionInfo {
+        let session_id = (self.created.fetch_add(1, Ordering::Relaxed) + 1).to_string();
+
+        if let Some(mut notifications) = handle.methods().subscribe() {
+            let relay = relay.clone();
+            let session_id = session_id.clone();
+            // Ends once the session is dropped
+            tokio::spawn(async move {
+                loop {
+                    match notifications.recv().await {
+                        Ok(mut notification) => {
+                            if let Some(params) = notification.params.as_object_mut() {
+                                params.insert("session_id".to_string(), session_id.clone().into());
+                            }
+                            let _ = relay.send(notification);
+                        }
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+        }
+
+        let session =
+            Session { tx_hash, methods: handle.methods(), handle, last_used: Instant::now() };
+        let info = session.info(&session_id);
+
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner()).insert(session_id, session);
+        info
+    }
+
+    fn remove(&self, session_id: &str) -> Option<SessionInfo> {
+        let session = self.sessions.lock().unwrap_or_else(|e| e.into_inner()).remove(session_id)?;
+        Some(session.info(session_id))
+    }
+
+    fn list(&self) -> Vec<SessionInfo> {
+        let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let mut infos: Vec<SessionInfo> =
+            sessions.iter().map(|(session_id, session)| session.info(session_id)).collect();
+        infos.sort_by_key(|info| info.session_id.parse::<u64>().unwrap_or(u64::MAX));
+        infos
+    }
+
+    /// Evict the least recently used idle session if memory is low.
+    ///
+    /// A single session is evicted per check: memory is not given back to the system as
+    /// soon as a session is dropped, so the next check tells whether more must go.
+    /// Unless `force` is set, memory is checked at most once per [`MEMORY_CHECK_INTERVAL`].
+    fn evict_if_memory_low(&self, force: bool) {
+        {
+            let mut last_check = self.last_memory_check.lock().unwrap_or_else(|e| e.into_inner());
+            if !force && last_check.elapsed() < MEMORY_CHECK_INTERVAL {
+                return;
+            }
+            *last_check = Instant::now();
+        }
+
+        if !(self.memory_probe)().is_some_and(|ratio| ratio < MIN_AVAILABLE_MEMORY) {
+            return;
+        }
+
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(session_id) = sessions
+            .iter()
+            .filter(|(_, session)| session.last_used.elapsed() >= MIN_IDLE_TIME)
+            .min_by_key(|(_, session)| session.last_used)
+            .map(|(session_id, _)| session_id.clone())
+        else {
+            return;
+        };
+
+        // Dropping the session shuts its server down and frees its context
+        if let Some(session) = sessions.remove(&session_id) {
+            warn!("Memory is low, evicting idle session {} ({})", session_id, session.tx_hash);
+        }
+    }
+}
+
+/// Prepare a transaction and host it as a new session.
+///
+/// The transaction is prepared with the configuration of the engine that prepared the
+/// current one (RPC endpoint, Etherscan API key, quick mode).
+///
+/// # Parameters
+/// - `tx_hash` (string) - Hash of the transaction to debug
+///
+/// # Returns
+/// The [`SessionInfo`] of the new session.
+pub async fn create_session<DB>(
+    context: &Arc<EngineContext<DB>>,
+    params: Option<Value>,
+    sessions: &Sessions,
+    notifications: &broadcast::Sender<RpcNotification>,
+    progress: Option<ProgressReporter>,
+) -> Result<Value, RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let (tx_hash,) = api::params::create_session(params.as_ref())?;
+
+    // Make room for the new session first
+    sessions.evict_if_memory_low(true);
+
+    // Sessions are reached by port and prepared without printing to the server's
+    // stdout, like derived timelines
+    let config = context
+        .engine_config
+        .clone()
+        .with_quiet(true)
+        .with_rpc_server(context.engine_config.rpc_server.derived());
+
+    // Preparation replays and instruments the transaction, so it runs off the async
+    // workers, like the re-executions of what-if methods
+    let handle = tokio::task::spawn_blocking(move || {
+        tokio::runtime::Handle::current().block_on(async move {
+            let stage = |stage| {
+                if let Some(progress) = &progress {
+                    progress.stage(stage);
+                }
+            };
+            stage("forking");
+            let fork_result =
+                fork_and_prepare(&config.rpc_proxy_url, tx_hash, config.quick).await?;
+            stage("analyzing");
+            Engine::new(config).prepare(fork_result).await
+        })
+    })
+    .await
+    .map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Session preparation panicked: {e}"),
+        data: None,
+    })?
+    .map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to prepare transaction {tx_hash}: {e}"),
+        data: None,
+    })?;
+
+    let info = sessions.insert(tx_hash, handle, notifications);
+    info!("Created session {} for transaction {}", info.session_id, tx_hash);
+
+    serde_json::to_value(info).map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to serialize session: {e}"),
+        data: None,
+    })
+}
+
+/// List the sessions hosted by the server.
+///
+/// # Returns
+/// An array of [`SessionInfo`], starting with the server's own session (`"default"`).
+pub fn list_sessions<DB>(
+    context: &Arc<EngineContext<DB>>,
+    port: Option<u16>,
+    sessions: &Sessions,
+) -> Result<Value, RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let own = SessionInfo {
+        session_id: DEFAULT_SESSION_ID.to_string(),
+        tx_hash: context.tx_hash,
+        port: port.unwrap_or_default(),
+        socket: context.engine_config.rpc_server.unix_socket.clone(),
+        idle_ms: 0,
+    };
+    let infos: Vec<SessionInfo> = std::iter::once(own).chain(sessions.list()).collect();
+
+    let json_value = serde_json::to_value(&infos).map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to serialize sessions: {e}"),
+        data: None,
+    })?;
+
+    debug!("Listed {} sessions", infos.len());
+    Ok(json_value)
+}
+
+/// Close a session, shutting down its debug server.
+///
+/// # Parameters
+/// - `session_id` (string) - The session to close; the server's own session cannot be closed
+///
+/// # Returns
+/// The [`SessionInfo`] of the closed session.
+pub fn close_session(params: Option<Value>, sessions: &Sessions) -> Result<Value, RpcError> {
+    let (session_id,) = api::params::close_session(params.as_ref())?;
+
+    if session_id == DEFAULT_SESSION_ID {
+        return Err(RpcError {
+            code: error_codes::INVALID_PARAMS,
+            message: "The default session cannot be closed".to_string(),
+            data: None,
+        });
+    }
+
+    let info = sessions.remove(&session_id).ok_or_else(|| session_not_found(&session_id))?;
+
+    let json_value = serde_json::to_value(&info).map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to serialize session: {e}"),
+        data: None,
+    })?;
+
+    info!("Closed session {}", session_id);
+    Ok(json_value)
+}
+
+fn session_not_found(session_id: &str) -> RpcError {
+    RpcError {
+        code: error_codes::SESSION_NOT_FOUND,
+        message: format!("Session '{session_id}' not found, it may have been closed or evicted"),
+        data: None,
+    }
+}
+
+/// Fraction of the system memory that is available, if it can be told (Linux only)
+fn available_memory_ratio() -> Option<f64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    parse_available_memory_ratio(&meminfo)
+}
+
+/// Compute the available memory fraction from the content of `/proc/meminfo`
+fn parse_available_memory_ratio(meminfo: &str) -> Option<f64> {
+    let field = |name: &str| {
+        meminfo.lines().find_map(|line| {
+            let value = line.strip_prefix(name)?.strip_prefix(':')?;
+            value.split_whitespace().next()?.parse::<u64>().ok()
+        })
+    };
+
+    let total = field("MemTotal")?;
+    let available = field("MemAvailable")?;
+    (total > 0).then(|| available as f64 / total as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{start_debug_server, test_utils::prepare_test_context};
+
+    #[test]
+    fn test_parse_available_memory_ratio() {
+        let meminfo = "MemTotal:       16000000 kB\nMemFree:         1000000 kB\nMemAvailable:    4000000 kB\n";
+        assert_eq!(parse_available_memory_ratio(meminfo), Some(0.25));
+
+        assert_eq!(parse_available_memory_ratio("MemTotal: 100 kB\n"), None);
+        assert_eq!(parse_available_memory_ratio(""), None);
+    }
+
+    #[test]
+    fn test_unknown_session() {
+        let sessions = Sessions::new();
+        assert!(sessions.list().is_empty());
+        assert_eq!(
+            sessions.methods("1").err().map(|e| e.code),
+            Some(error_codes::SESSION_NOT_FOUND)
+        );
+        assert_eq!(
+            close_session(Some(serde_json::json!(["default"])), &sessions).err().map(|e| e.code),
+            Some(error_codes::INVALID_PARAMS)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_evict_one_idle_session_per_check() {
+        let memory_low = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let probe = memory_low.clone();
+        let sessions = Sessions::with_memory_probe(Box::new(move || {
+            Some(if probe.load(Ordering::Relaxed) { 0.05 } else { 0.5 })
+        }));
+
+        let (relay, _) = broadcast::channel(1);
+        for _ in 0..3 {
+            let handle = start_debug_server(prepare_test_context().await).await.unwrap();
+            sessions.insert(TxHash::ZERO, handle, &relay);
+        }
+        // Sessions 1 and 2 have been idle for long, session 1 the longest. The monotonic
+        // clock may start at boot, too recently to date them back that far.
+        for (session_id, idle) in [("1", 300), ("2", 120)] {
+            let Some(last_used) = Instant::now().checked_sub(Duration::from_secs(idle)) else {
+                eprintln!("Skipping: the clock is too young to date sessions back {idle}s");
+                return;
+            };
+            let mut hosted = sessions.sessions.lock().unwrap();
+            hosted.get_mut(session_id).unwrap().last_used = last_used;
+        }
+
+        sessions.evict_if_memory_low(true);
+        assert_eq!(sessions.list().len(), 3);
+
+        memory_low.store(true, Ordering::Relaxed);
+        sessions.evict_if_memory_low(true);
+        let ids: Vec<String> = sessions.list().into_iter().map(|info| info.session_id).collect();
+        assert_eq!(ids, ["2", "3"]);
+
+        // Checks are rate-limited unless forced
+        sessions.evict_if_memory_low(false);
+        assert_eq!(sessions.list().len(), 2);
+
+        // Recently used sessions are kept, however low memory is
+        sessions.evict_if_memory_low(true);
+        sessions.evict_if_memory_low(true);
+        let ids: Vec<String> = sessions.list().into_iter().map(|info| info.session_id).collect();
+        assert_eq!(ids, ["3"]);
+    }
+}
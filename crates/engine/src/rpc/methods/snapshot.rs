@@ -24,6 +24,7 @@
 //!
 //! - `edb_getSnapshotCount` - Get the total number of available snapshots
 //! - `edb_getSnapshotInfo` - Get detailed information about a specific snapshot
+//! - `edb_getCallStack` - Get the frames of the call stack at a specific snapshot
 //!
 //! # Snapshot Types
 //!
@@ -33,6 +34,7 @@
 
 use std::sync::Arc;
 
+use edb_common::api;
 use edb_common::types::{
     HookSnapshotInfoDetail, OpcodeSnapshotInfoDetail, SnapshotInfo, SnapshotInfoDetail,
 };
@@ -65,17 +67,7 @@ where
     <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
     <DB as Database>::Error: Clone + Send + Sync,
 {
-    // Parse the snapshot ID from parameters
-    let snapshot_id = params
-        .as_ref()
-        .and_then(|p| p.as_array())
-        .and_then(|arr| arr.first())
-        .and_then(|v| v.as_u64())
-        .ok_or_else(|| RpcError {
-            code: error_codes::INVALID_PARAMS,
-            message: "Invalid params: expected [snapshot_id]".to_string(),
-            data: None,
-        })? as usize;
+    let (snapshot_id,) = api::params::get_snapshot_info(params.as_ref())?;
 
     // Get the snapshot at the specified index
     let (frame_id, snapshot) = context.snapshots.get(snapshot_id).ok_or_else(|| RpcError {
@@ -226,3 +218,46 @@ where
         data: None,
     })
 }
+
+/// Get the call stack at a specific snapshot.
+///
+/// Each frame is represented by a snapshot: frame 0 is the given snapshot, and every other
+/// frame is the most recent snapshot of a caller, i.e., the step making the internal or
+/// external call. Expressions can be evaluated in a frame by evaluating them at its snapshot.
+///
+/// # Parameters
+/// - `id`: The snapshot ID (0-indexed)
+///
+/// # Returns
+/// The snapshot IDs of the frames, innermost first
+///
+/// # Example Response
+/// ```json
+/// [1523, 1490, 812]
+/// ```
+pub fn get_call_stack<DB>(
+    context: &Arc<EngineContext<DB>>,
+    params: Option<Value>,
+) -> Result<Value, RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let (snapshot_id,) = api::params::get_call_stack(params.as_ref())?;
+
+    let frames = context.get_call_stack(snapshot_id).ok_or_else(|| RpcError {
+        code: error_codes::SNAPSHOT_OUT_OF_BOUNDS,
+        message: format!("Snapshot with id {snapshot_id} not found"),
+        data: None,
+    })?;
+
+    let json_value = serde_json::to_value(&frames).map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to serialize call stack: {e}"),
+        data: None,
+    })?;
+
+    debug!("Retrieved call stack with {} frames for snapshot {}", frames.len(), snapshot_id);
+    Ok(json_value)
+}
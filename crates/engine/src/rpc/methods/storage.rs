@@ -16,10 +16,8 @@
 
 use std::sync::Arc;
 
-use alloy_primitives::{
-    map::foldhash::{HashMap, HashMapExt},
-    U256,
-};
+use alloy_primitives::map::foldhash::{HashMap, HashMapExt};
+use edb_common::api;
 use revm::{database::CacheDB, Database, DatabaseCommit, DatabaseRef};
 use serde_json::Value;
 use tracing::debug;
@@ -35,17 +33,7 @@ where
     <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
     <DB as Database>::Error: Clone + Send + Sync,
 {
-    // Parse the snapshot ID from parameters
-    let snapshot_id = params
-        .as_ref()
-        .and_then(|p| p.as_array())
-        .and_then(|arr| arr.first())
-        .and_then(|v| v.as_u64())
-        .ok_or_else(|| RpcError {
-            code: error_codes::INVALID_PARAMS,
-            message: "Invalid params: expected [snapshot_id, slot]".to_string(),
-            data: None,
-        })? as usize;
+    let (snapshot_id,) = api::params::get_storage_diff(params.as_ref())?;
 
     let (f_id, snapshot) = context.snapshots.get(snapshot_id).ok_or_else(|| RpcError {
         code: error_codes::SNAPSHOT_OUT_OF_BOUNDS,
@@ -115,29 +103,7 @@ where
     <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
     <DB as Database>::Error: Clone + Send + Sync,
 {
-    // Parse the snapshot ID from parameters
-    let snapshot_id = params
-        .as_ref()
-        .and_then(|p| p.as_array())
-        .and_then(|arr| arr.first())
-        .and_then(|v| v.as_u64())
-        .ok_or_else(|| RpcError {
-            code: error_codes::INVALID_PARAMS,
-            message: "Invalid params: expected [snapshot_id, slot]".to_string(),
-            data: None,
-        })? as usize;
-
-    // Parse recompiled as the second argument
-    let slot: U256 = params
-        .as_ref()
-        .and_then(|p| p.as_array())
-        .and_then(|arr| arr.get(1))
-        .and_then(|v| serde_json::from_value(v.clone()).ok())
-        .ok_or_else(|| RpcError {
-            code: error_codes::INVALID_PARAMS,
-            message: "Invalid params: expected [snapshot_id, slot]".to_string(),
-            data: None,
-        })?;
+    let (snapshot_id, slot) = api::params::get_storage(params.as_ref())?;
 
     let (f_id, snapshot) = context.snapshots.get(snapshot_id).ok_or_else(|| RpcError {
         code: error_codes::SNAPSHOT_OUT_OF_BOUNDS,
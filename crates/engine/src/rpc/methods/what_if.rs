@@ -0,0 +1,218 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! What-if RPC methods.
+//!
+//! This module re-executes the transaction in a different scenario, either with state
+//! edits applied at a snapshot or with the patched source code of a contract. The derived
+//! timeline is served by a new debug server, so that clients can debug it with the very
+//! same API, while the response compares it against the current timeline.
+//!
+//! # Available Methods
+//!
+//! - `edb_whatIf` - Apply state edits at a snapshot and re-execute the rest of the transaction
+//! - `edb_replayWithPatchedSource` - Replay the transaction with the patched source of a contract
+//!
+//! # Example Usage
+//!
+//! ```json
+//! // Request
+//! {
+//!   "method": "edb_whatIf",
+//!   "params": [150, [{ "Storage": { "address": "0x...", "slot": "0x5", "value": "0x0" } }]]
+//! }
+//!
+//! // Response
+//! {
+//!   "result": { "port": 3001, "diff": { "divergence": 163, ... } }
+//! }
+//! ```
+
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use edb_common::api;
+use edb_common::types::WhatIfResult;
+use revm::{database::CacheDB, Database, DatabaseCommit, DatabaseRef};
+use serde_json::Value;
+use tracing::debug;
+
+use crate::{
+    diff_timelines, error_codes, re_execute_with_edits, start_debug_server, Engine, EngineContext,
+    RpcEndpoint, RpcError, RpcServerHandle,
+};
+
+/// Apply state edits at a snapshot and re-execute the rest of the transaction.
+///
+/// # Parameters
+/// - `snapshot_id` (number) - The snapshot at which the edits are applied
+/// - `edits` (array) - Serialized [`StateEdit`]s to apply
+///
+/// # Returns
+/// A [`WhatIfResult`] with the port of the debug server serving the derived timeline,
+/// and the comparison of the derived timeline against the current one.
+pub async fn what_if<DB>(
+    context: &Arc<EngineContext<DB>>,
+    params: Option<Value>,
+    derived_servers: &Mutex<Vec<RpcServerHandle>>,
+) -> Result<Value, RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let (snapshot_id, edits) = api::params::what_if(params.as_ref())?;
+
+    if snapshot_id >= context.snapshots.len() {
+        return Err(RpcError {
+            code: error_codes::SNAPSHOT_OUT_OF_BOUNDS,
+            message: format!("Snapshot with id {snapshot_id} not found"),
+            data: None,
+        });
+    }
+
+    let edit_count = edits.len();
+    let result = serve_derived_timeline(context, derived_servers, move |original| {
+        re_execute_with_edits(original, snapshot_id, &edits)
+    })
+    .await?;
+
+    debug!("Applied {} state edits at snapshot {}", edit_count, snapshot_id);
+    Ok(result)
+}
+
+/// Replay the transaction with the patched source code of a contract.
+///
+/// # Parameters
+/// - `address` (string) - Address of the patched contract
+/// - `path` (string) - Path of the patched source file, matched against the source files
+///   of the contract (e.g., `src/Vault.sol` or just `Vault.sol`)
+/// - `source` (string) - Patched source code
+///
+/// # Returns
+/// A [`WhatIfResult`] with the port of the debug server serving the patched timeline,
+/// and the comparison of the patched timeline against the current one.
+pub async fn replay_with_patched_source<DB>(
+    context: &Arc<EngineContext<DB>>,
+    params: Option<Value>,
+    derived_servers: &Mutex<Vec<RpcServerHandle>>,
+) -> Result<Value, RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let (address, path, source) = api::params::replay_with_patched_source(params.as_ref())?;
+
+    if !context.artifacts.contains_key(&address) {
+        return Err(RpcError {
+            code: error_codes::INVALID_ADDRESS,
+            message: format!("No source code available for contract {address}"),
+            data: None,
+        });
+    }
+
+    let patched_path = path.clone();
+    let result = serve_derived_timeline(context, derived_servers, move |original| {
+        let engine = Engine::new(original.engine_config.clone().with_quiet(true));
+        tokio::runtime::Handle::current().block_on(engine.replay_with_patched_source(
+            original,
+            address,
+            Path::new(&patched_path),
+            source,
+        ))
+    })
+    .await?;
+
+    debug!("Replayed transaction with patched {} of {}", path, address);
+    Ok(result)
+}
+
+/// Derive a timeline from the current one, serve it with a new debug server, and compare
+/// the two timelines.
+async fn serve_derived_timeline<DB, F>(
+    context: &Arc<EngineContext<DB>>,
+    derived_servers: &Mutex<Vec<RpcServerHandle>>,
+    derive: F,
+) -> Result<Value, RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+    F: FnOnce(&EngineContext<DB>) -> eyre::Result<EngineContext<DB>> + Send + 'static,
+{
+    let (endpoint, diff) = serve_timeline(context, derived_servers, move |original| {
+        let derived = derive(original)?;
+        let diff = diff_timelines(original, &derived)?;
+        Ok((derived, diff))
+    })
+    .await?;
+
+    let port = endpoint.port().unwrap_or_default();
+    let socket = endpoint.unix_socket().map(Into::into);
+    serde_json::to_value(WhatIfResult { port, socket, diff }).map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to serialize what-if result: {e}"),
+        data: None,
+    })
+}
+
+/// Derive a timeline from the current one and serve it with a new debug server.
+///
+/// Deriving a timeline re-executes the transaction, so it runs off the async workers. The
+/// derived server is started from there as well, since its handlers may start servers
+/// themselves. It lives as long as the server of the current timeline.
+///
+/// Returns the endpoint of the new server along with whatever else `derive` computed.
+pub(super) async fn serve_timeline<DB, F, T>(
+    context: &Arc<EngineContext<DB>>,
+    derived_servers: &Mutex<Vec<RpcServerHandle>>,
+    derive: F,
+) -> Result<(RpcEndpoint, T), RpcError>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+    F: FnOnce(&EngineContext<DB>) -> eyre::Result<(EngineContext<DB>, T)> + Send + 'static,
+    T: Send + 'static,
+{
+    let original = context.clone();
+    let (handle, extra) = tokio::task::spawn_blocking(move || {
+        let (mut derived, extra) = derive(&original)?;
+        derived.engine_config.rpc_server = original.engine_config.rpc_server.derived();
+        let handle = tokio::runtime::Handle::current().block_on(start_debug_server(derived))?;
+        Ok::<_, eyre::Error>((handle, extra))
+    })
+    .await
+    .map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Re-execution panicked: {e}"),
+        data: None,
+    })?
+    .map_err(|e| RpcError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to derive timeline: {e}"),
+        data: None,
+    })?;
+
+    let endpoint = handle.endpoint.clone();
+    derived_servers.lock().unwrap_or_else(|e| e.into_inner()).push(handle);
+    debug!("Derived timeline served on {}", endpoint);
+
+    Ok((endpoint, extra))
+}
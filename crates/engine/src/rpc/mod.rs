@@ -26,6 +26,7 @@
 //! The RPC system consists of several key components:
 //!
 //! - **Server** ([`server`]) - HTTP/WebSocket server handling client connections
+//! - **Auth** ([`auth`]) - Bearer token authentication of clients
 //! - **Methods** ([`methods`]) - RPC method implementations organized by functionality
 //! - **Types** ([`types`]) - Request/response data structures and protocol types
 //! - **Utils** ([`utils`]) - Common utilities for RPC operations
@@ -73,11 +74,16 @@
 //! The RPC server supports both HTTP POST requests and WebSocket connections
 //! for real-time debugging. All methods follow the JSON-RPC 2.0 specification
 //! with structured request/response formats defined in the [`types`] module.
+//! Batch requests and notifications are accepted on both transports, and WebSocket
+//! clients additionally receive server-pushed notifications, such as the
+//! `edb_progress` notifications of long-running operations.
 
+pub mod auth;
 pub mod methods;
 pub mod server;
 pub mod types;
 pub mod utils;
 
+pub use auth::*;
 pub use server::*;
 pub use types::*;
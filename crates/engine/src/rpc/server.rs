@@ -39,47 +39,257 @@
 //! # Endpoints
 //!
 //! - `POST /` - Main JSON-RPC endpoint for debugging methods
+//! - `GET /ws` - WebSocket endpoint serving the same methods, which also pushes
+//!   notifications (e.g., `edb_progress`) to the client
 //! - `GET /health` - Health check endpoint returning server status
+//!
+//! Both JSON-RPC endpoints accept batch requests (arrays of requests) and notifications
+//! (requests without an id, which get no response).
+//!
+//! # Access Control
+//!
+//! The server binds to `127.0.0.1` unless [`RpcServerConfig`] says otherwise, and can
+//! listen on a Unix domain socket instead of TCP, so file permissions decide who may
+//! connect. When configured with a token, the JSON-RPC endpoints require it as a bearer
+//! token (see [`super::auth`]).
 
+use super::auth::require_token;
 use super::methods::MethodHandler;
-use super::types::{RpcError, RpcRequest, RpcResponse};
+use super::types::{error_codes, RpcError, RpcId, RpcNotification, RpcRequest, RpcResponse};
 use super::utils::get_default_rpc_port;
 use crate::EngineContext;
 use axum::{
-    extract::{Json as JsonExtract, State},
-    response::Json as JsonResponse,
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Json as JsonResponse, Response},
     routing::{get, post},
     Router,
 };
 use eyre::Result;
+use futures::{future, stream, SinkExt, StreamExt};
 use revm::database::CacheDB;
 use revm::{Database, DatabaseCommit, DatabaseRef};
-use std::net::SocketAddr;
+use serde_json::Value;
+use std::fmt;
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::oneshot;
-use tracing::{error, info, warn};
+use tokio::sync::{broadcast, mpsc, oneshot, Semaphore};
+use tracing::{debug, error, info, warn};
+
+/// Requests handled at once by a server, over HTTP and WebSocket and including the
+/// elements of batches; further ones wait for a slot. It also bounds the messages of a
+/// WebSocket connection pending at once, beyond which the connection is not read from.
+pub const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// Id of the next WebSocket connection, to which the progress of its requests is sent
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Future returned by [`MethodDispatch::dispatch`].
+pub type DispatchFuture<'a> = Pin<Box<dyn Future<Output = Result<Value, RpcError>> + Send + 'a>>;
+
+/// Database-independent access to the RPC methods of a debug server.
+///
+/// This lets in-process front-ends (e.g., the DAP adapter) call the same methods as
+/// JSON-RPC clients, without going through HTTP.
+pub trait MethodDispatch: Send + Sync {
+    /// Handle an RPC method call, see [`MethodHandler::handle_method`]
+    fn dispatch<'a>(&'a self, method: &'a str, params: Option<Value>) -> DispatchFuture<'a>;
+
+    /// Handle an RPC request, see [`MethodHandler::handle_request`]
+    fn dispatch_request(&self, request: RpcRequest) -> DispatchFuture<'_> {
+        Box::pin(async move { self.dispatch(&request.method, request.params).await })
+    }
+
+    /// Subscribe to the notifications pushed by the methods, if they push any
+    fn subscribe(&self) -> Option<broadcast::Receiver<RpcNotification>> {
+        None
+    }
+}
+
+impl<DB> MethodDispatch for MethodHandler<DB>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    fn dispatch<'a>(&'a self, method: &'a str, params: Option<Value>) -> DispatchFuture<'a> {
+        Box::pin(self.handle_method(method, params))
+    }
+
+    fn dispatch_request(&self, request: RpcRequest) -> DispatchFuture<'_> {
+        Box::pin(self.handle_request(request))
+    }
+
+    fn subscribe(&self) -> Option<broadcast::Receiver<RpcNotification>> {
+        Some(Self::subscribe(self))
+    }
+}
+
+/// Where and how a debug RPC server accepts clients.
+#[derive(Clone)]
+pub struct RpcServerConfig {
+    /// Address to bind the TCP listener to
+    pub host: IpAddr,
+    /// Port to listen on, found automatically (starting from 3000) when unset
+    pub port: Option<u16>,
+    /// Listen on this Unix domain socket instead of TCP
+    pub unix_socket: Option<PathBuf>,
+    /// Token clients must present as a bearer token, if any
+    pub auth_token: Option<String>,
+}
+
+impl Default for RpcServerConfig {
+    fn default() -> Self {
+        Self {
+            host: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port: None,
+            unix_socket: None,
+            auth_token: None,
+        }
+    }
+}
+
+impl fmt::Debug for RpcServerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RpcServerConfig")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("unix_socket", &self.unix_socket)
+            .field("auth_token", &self.auth_token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl RpcServerConfig {
+    /// Set the address to bind the TCP listener to
+    pub fn with_host(mut self, host: IpAddr) -> Self {
+        self.host = host;
+        self
+    }
+
+    /// Set the port to listen on
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Listen on a Unix domain socket instead of TCP
+    pub fn with_unix_socket(mut self, path: PathBuf) -> Self {
+        self.unix_socket = Some(path);
+        self
+    }
+
+    /// Require clients to present a bearer token
+    pub fn with_auth_token(mut self, token: String) -> Self {
+        self.auth_token = Some(token);
+        self
+    }
+
+    /// Configuration of the servers derived from this one (e.g., what-if timelines and
+    /// hosted sessions): same host and token, on a free port, or on a sibling socket of
+    /// the Unix domain socket of this one, so that they are as protected as it is.
+    pub fn derived(&self) -> Self {
+        Self {
+            host: self.host,
+            port: None,
+            unix_socket: self.unix_socket.as_deref().map(sibling_socket),
+            auth_token: self.auth_token.clone(),
+        }
+    }
+}
+
+/// A new socket path in the directory of `path`, e.g. `edb-3.sock` for `edb.sock`
+fn sibling_socket(path: &Path) -> PathBuf {
+    static NEXT_SIBLING: AtomicU64 = AtomicU64::new(1);
+
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let mut name = format!("{stem}-{}", NEXT_SIBLING.fetch_add(1, Ordering::Relaxed));
+    if let Some(extension) = path.extension() {
+        name = format!("{name}.{}", extension.to_string_lossy());
+    }
+    path.with_file_name(name)
+}
+
+/// Endpoint a debug RPC server listens on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpcEndpoint {
+    /// TCP socket address
+    Tcp(SocketAddr),
+    /// Path of a Unix domain socket
+    Unix(PathBuf),
+}
+
+impl RpcEndpoint {
+    /// Port of the endpoint, unless it is a Unix domain socket
+    pub fn port(&self) -> Option<u16> {
+        match self {
+            Self::Tcp(addr) => Some(addr.port()),
+            Self::Unix(_) => None,
+        }
+    }
+
+    /// Path of the endpoint, if it is a Unix domain socket
+    pub fn unix_socket(&self) -> Option<&Path> {
+        match self {
+            Self::Tcp(_) => None,
+            Self::Unix(path) => Some(path),
+        }
+    }
+}
+
+impl fmt::Display for RpcEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "http://{addr}"),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
 
 /// Handle to control a running RPC server.
 ///
 /// This handle provides access to server information and allows for graceful shutdown.
 /// The server runs in a background task and can be monitored and controlled through this handle.
-#[derive(Debug)]
 pub struct RpcServerHandle {
-    /// Address the server is listening on
-    pub addr: SocketAddr,
+    /// Endpoint the server is listening on
+    pub endpoint: RpcEndpoint,
     /// Shutdown signal sender (consumed when shutting down)
     shutdown_tx: oneshot::Sender<()>,
+    /// Methods served by the server, for in-process callers
+    methods: Arc<dyn MethodDispatch>,
+}
+
+impl fmt::Debug for RpcServerHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RpcServerHandle").field("endpoint", &self.endpoint).finish_non_exhaustive()
+    }
 }
 
 impl RpcServerHandle {
-    /// Get the server address
-    pub fn addr(&self) -> SocketAddr {
-        self.addr
+    /// Get the server address, unless it listens on a Unix domain socket
+    pub fn addr(&self) -> Option<SocketAddr> {
+        match &self.endpoint {
+            RpcEndpoint::Tcp(addr) => Some(*addr),
+            RpcEndpoint::Unix(_) => None,
+        }
+    }
+
+    /// Get the port number, unless the server listens on a Unix domain socket
+    pub fn port(&self) -> Option<u16> {
+        self.addr().map(|addr| addr.port())
     }
 
-    /// Get the port number
-    pub fn port(&self) -> u16 {
-        self.addr.port()
+    /// Get the methods served by the server, to call them without going through HTTP
+    pub fn methods(&self) -> Arc<dyn MethodDispatch> {
+        self.methods.clone()
     }
 
     /// Gracefully shutdown the RPC server
@@ -104,6 +314,8 @@ where
 {
     /// The debug RPC server instance (shared across request handlers)
     server: Arc<DebugRpcServer<DB>>,
+    /// Slots of the requests handled at once, shared by both transports
+    requests: Arc<Semaphore>,
 }
 
 /// Main debug RPC server providing JSON-RPC debugging API.
@@ -140,26 +352,34 @@ where
         Self { context, method_handler }
     }
 
-    /// Start the RPC server
+    /// Start the RPC server, as configured by the engine configuration of its context
     pub async fn start(self) -> Result<RpcServerHandle> {
-        let port = get_default_rpc_port()?;
+        let config = self.context.engine_config.rpc_server.clone();
+        if let Some(path) = config.unix_socket {
+            return self.start_on_unix_socket(path).await;
+        }
+
+        let port = match config.port {
+            Some(port) => port,
+            None => get_default_rpc_port()?,
+        };
         self.start_on_port(port).await
     }
 
     /// Start the RPC server on a specific port using standard multi-threaded pattern
     ///
     /// This method creates the Axum server with Send+Sync state, leveraging
-    /// the now thread-safe EngineContext.
+    /// the now thread-safe EngineContext. The server binds to the configured host.
     pub async fn start_on_port(self, port: u16) -> Result<RpcServerHandle> {
-        // Create the Axum app with the server as state
-        let app = Router::new()
-            .route("/", post(handle_rpc_request))
-            .route("/health", get(health_check))
-            .with_state(RpcState { server: Arc::new(self) });
+        let methods: Arc<dyn MethodDispatch> = self.method_handler.clone();
+        let method_handler = self.method_handler.clone();
+
+        let addr = SocketAddr::new(self.context.engine_config.rpc_server.host, port);
+        let app = self.into_router();
 
-        let addr = SocketAddr::from(([127, 0, 0, 1], port));
         let listener = tokio::net::TcpListener::bind(addr).await?;
         let actual_addr = listener.local_addr()?;
+        method_handler.set_port(actual_addr.port());
 
         let (shutdown_tx, shutdown_rx) = oneshot::channel();
 
@@ -175,23 +395,106 @@ where
 
         info!("Debug RPC server started on {}", actual_addr);
 
-        Ok(RpcServerHandle { addr: actual_addr, shutdown_tx })
+        Ok(RpcServerHandle { endpoint: RpcEndpoint::Tcp(actual_addr), shutdown_tx, methods })
     }
 
-    /// Handle an RPC request (called from worker thread)
-    async fn handle_request(&self, request: RpcRequest) -> RpcResponse {
-        let id = request.id.clone();
+    /// Start the RPC server on a Unix domain socket, only accessible to the current user.
+    ///
+    /// A stale socket at `path` is replaced, but any other file there is an error. The
+    /// socket file is removed when the server shuts down.
+    #[cfg(unix)]
+    pub async fn start_on_unix_socket(self, path: PathBuf) -> Result<RpcServerHandle> {
+        use hyper_util::{rt::TokioIo, service::TowerToHyperService};
+        use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+        match std::fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.file_type().is_socket() => {}
+            Ok(_) => eyre::bail!("{} exists and is not a socket", path.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
 
-        // Dispatch to method handler
-        match self.method_handler.handle_method(&request.method, request.params).await {
-            Ok(result) => {
-                RpcResponse { jsonrpc: "2.0".to_string(), result: Some(result), error: None, id }
+        let methods: Arc<dyn MethodDispatch> = self.method_handler.clone();
+        let app = self.into_router();
+
+        // Bind in a private (0700) directory next to `path` and only move the socket in
+        // place once restricted, so that nobody else can connect in between
+        let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty());
+        let staging = tempfile::Builder::new()
+            .prefix(".edb-rpc-")
+            .permissions(std::fs::Permissions::from_mode(0o700))
+            .tempdir_in(parent.unwrap_or(Path::new(".")))?;
+        let staged = staging.path().join("rpc.sock");
+        let listener = tokio::net::UnixListener::bind(&staged)?;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+        std::fs::rename(&staged, &path)?;
+
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+
+        let socket_path = path.clone();
+        tokio::spawn(async move {
+            loop {
+                let stream = tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => stream,
+                        Err(e) => {
+                            error!("Failed to accept RPC connection: {}", e);
+                            continue;
+                        }
+                    },
+                    _ = &mut shutdown_rx => break,
+                };
+
+                let service = TowerToHyperService::new(app.clone());
+                tokio::spawn(async move {
+                    if let Err(e) = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .with_upgrades()
+                        .await
+                    {
+                        debug!("RPC connection closed with error: {}", e);
+                    }
+                });
             }
-            Err(err) => {
-                error!(target: "rpc", "Error handling RPC request: {:?}", err);
-                RpcResponse { jsonrpc: "2.0".to_string(), result: None, error: Some(err), id }
+
+            if let Err(e) = std::fs::remove_file(&socket_path) {
+                warn!("Failed to remove RPC socket {}: {}", socket_path.display(), e);
             }
-        }
+        });
+
+        info!("Debug RPC server started on {}", path.display());
+
+        Ok(RpcServerHandle { endpoint: RpcEndpoint::Unix(path), shutdown_tx, methods })
+    }
+
+    /// Unix domain sockets are not available on this platform
+    #[cfg(not(unix))]
+    pub async fn start_on_unix_socket(self, path: PathBuf) -> Result<RpcServerHandle> {
+        Err(eyre::eyre!(
+            "Cannot listen on {}: Unix domain sockets are not supported on this platform",
+            path.display()
+        ))
+    }
+
+    /// Build the Axum app serving this server, requiring the configured token if any
+    fn into_router(self) -> Router {
+        let auth_token = self.context.engine_config.rpc_server.auth_token.clone();
+
+        let router =
+            Router::new().route("/", post(handle_rpc_request)).route("/ws", get(handle_ws_upgrade));
+        // The health check stays open, so it is added after the authentication layer
+        let router = match auth_token {
+            Some(token) => router.route_layer(middleware::from_fn_with_state(
+                Arc::<str>::from(token),
+                require_token,
+            )),
+            None => router,
+        };
+
+        let requests = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
+        router
+            .route("/health", get(health_check))
+            .with_state(RpcState { server: Arc::new(self), requests })
     }
 
     /// Get total snapshot count (stateless)
@@ -217,33 +520,216 @@ where
     }
 }
 
-/// Handle RPC requests directly with the thread-safe server
-async fn handle_rpc_request<DB>(
-    State(state): State<RpcState<DB>>,
-    JsonExtract(request): JsonExtract<RpcRequest>,
-) -> JsonResponse<RpcResponse>
+/// Handle HTTP JSON-RPC requests, including batches
+async fn handle_rpc_request<DB>(State(state): State<RpcState<DB>>, body: String) -> Response
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    match handle_payload(state.server.method_handler.as_ref(), &state.requests, &body, None).await {
+        Some(response) => JsonResponse(response).into_response(),
+        // Only notifications were sent
+        None => StatusCode::NO_CONTENT.into_response(),
+    }
+}
+
+/// Upgrade a connection to a WebSocket JSON-RPC session
+async fn handle_ws_upgrade<DB>(ws: WebSocketUpgrade, State(state): State<RpcState<DB>>) -> Response
 where
     DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
     <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
     <DB as Database>::Error: Clone + Send + Sync,
 {
+    let methods = state.server.method_handler.clone();
+    let notifications = methods.subscribe();
+    ws.on_upgrade(move |socket| serve_websocket(socket, methods, state.requests, notifications))
+}
+
+/// Serve JSON-RPC messages over a WebSocket, pushing the notifications addressed to the
+/// connection as they come.
+///
+/// Up to [`MAX_CONCURRENT_REQUESTS`] messages are handled concurrently, so a long-running
+/// request does not hold up the others; their responses are sent as they complete. Their
+/// requests take their slots from `requests`, shared with the other connections.
+async fn serve_websocket(
+    socket: WebSocket,
+    methods: Arc<dyn MethodDispatch>,
+    requests: Arc<Semaphore>,
+    mut notifications: broadcast::Receiver<RpcNotification>,
+) {
+    let connection_id = NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed);
+    let (mut sender, mut receiver) = socket.split();
+    let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<String>();
+
+    // Responses and notifications share the socket
+    let writer = tokio::spawn(async move {
+        while let Some(text) = outgoing_rx.recv().await {
+            if sender.send(Message::Text(text)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let notification_tx = outgoing_tx.clone();
+    let forwarder = tokio::spawn(async move {
+        loop {
+            match notifications.recv().await {
+                Ok(notification) => {
+                    if notification.connection_id.is_some_and(|id| id != connection_id) {
+                        continue;
+                    }
+                    let Ok(text) = serde_json::to_string(&notification) else { continue };
+                    if notification_tx.send(text).is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    warn!("WebSocket client missed {} notifications", missed);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+
+    let pending = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
+    while let Some(Ok(message)) = receiver.next().await {
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Binary(data) => String::from_utf8_lossy(&data).into_owned(),
+            Message::Close(_) => break,
+            // Pings are answered by axum
+            _ => continue,
+        };
+
+        // Stop reading from the client while too many of its messages are pending
+        let Ok(permit) = pending.clone().acquire_owned().await else { break };
+        let methods = methods.clone();
+        let requests = requests.clone();
+        let outgoing_tx = outgoing_tx.clone();
+        tokio::spawn(async move {
+            if let Some(response) =
+                handle_payload(methods.as_ref(), &requests, &text, Some(connection_id)).await
+            {
+                let _ = outgoing_tx.send(response.to_string());
+            }
+            drop(permit);
+        });
+    }
+
+    debug!("WebSocket client disconnected");
+    forwarder.abort();
+    writer.abort();
+}
+
+/// Handle a JSON-RPC payload: a request, a notification or a batch of them, received on
+/// WebSocket connection `connection_id` if any. Each request is handled once it gets a
+/// slot from `requests`.
+///
+/// Returns the response to send back, or `None` if the payload only held notifications.
+async fn handle_payload(
+    methods: &dyn MethodDispatch,
+    requests: &Semaphore,
+    payload: &str,
+    connection_id: Option<u64>,
+) -> Option<Value> {
+    let payload: Value = match serde_json::from_str(payload) {
+        Ok(payload) => payload,
+        Err(e) => {
+            return Some(error_response(
+                None,
+                error_codes::PARSE_ERROR,
+                format!("Parse error: {e}"),
+            ))
+        }
+    };
+
+    match payload {
+        Value::Array(batch) if batch.is_empty() => Some(error_response(
+            None,
+            error_codes::INVALID_REQUEST,
+            "Invalid Request - empty batch".to_string(),
+        )),
+        Value::Array(batch) => {
+            let responses: Vec<Value> = stream::iter(batch)
+                .map(|request| handle_single(methods, requests, request, connection_id))
+                .buffered(MAX_CONCURRENT_REQUESTS)
+                .filter_map(future::ready)
+                .collect()
+                .await;
+            (!responses.is_empty()).then_some(Value::Array(responses))
+        }
+        request => handle_single(methods, requests, request, connection_id).await,
+    }
+}
+
+/// Handle a single JSON-RPC request once it gets a slot from `requests`, returning its
+/// response unless it is a notification
+async fn handle_single(
+    methods: &dyn MethodDispatch,
+    requests: &Semaphore,
+    request: Value,
+    connection_id: Option<u64>,
+) -> Option<Value> {
+    let request: RpcRequest = match serde_json::from_value(request.clone()) {
+        Ok(request) => RpcRequest { connection_id, ..request },
+        Err(e) => {
+            let id = request.get("id").and_then(|id| serde_json::from_value(id.clone()).ok());
+            return Some(error_response(
+                id,
+                error_codes::INVALID_REQUEST,
+                format!("Invalid Request - {e}"),
+            ));
+        }
+    };
+
     // Validate JSON-RPC version
     if request.jsonrpc != "2.0" {
-        return JsonResponse(RpcResponse {
-            jsonrpc: "2.0".to_string(),
-            result: None,
-            error: Some(RpcError {
-                code: -32600,
-                message: "Invalid Request - JSON-RPC version must be 2.0".to_string(),
-                data: None,
-            }),
-            id: request.id.clone(),
-        });
+        return Some(error_response(
+            request.id,
+            error_codes::INVALID_REQUEST,
+            "Invalid Request - JSON-RPC version must be 2.0".to_string(),
+        ));
+    }
+
+    let id = request.id.clone();
+    let result = match requests.acquire().await {
+        Ok(_permit) => methods.dispatch_request(request).await,
+        Err(_) => Err(RpcError {
+            code: error_codes::INTERNAL_ERROR,
+            message: "Server is shutting down".to_string(),
+            data: None,
+        }),
+    };
+    if let Err(err) = &result {
+        error!(target: "rpc", "Error handling RPC request: {:?}", err);
     }
 
-    // Handle request directly (no channel proxy needed)
-    let response = state.server.handle_request(request).await;
-    JsonResponse(response)
+    // Notifications get no response, even on errors
+    let id = id?;
+    let response = match result {
+        Ok(result) => RpcResponse {
+            jsonrpc: "2.0".to_string(),
+            result: Some(result),
+            error: None,
+            id: Some(id),
+        },
+        Err(err) => {
+            RpcResponse { jsonrpc: "2.0".to_string(), result: None, error: Some(err), id: Some(id) }
+        }
+    };
+    serde_json::to_value(response).ok()
+}
+
+/// Build an error response
+fn error_response(id: Option<RpcId>, code: i32, message: String) -> Value {
+    serde_json::to_value(RpcResponse {
+        jsonrpc: "2.0".to_string(),
+        result: None,
+        error: Some(RpcError { code, message, data: None }),
+        id,
+    })
+    .unwrap_or(Value::Null)
 }
 
 /// Health check endpoint
@@ -256,7 +742,8 @@ async fn health_check() -> JsonResponse<serde_json::Value> {
     }))
 }
 
-/// Create and start a debug RPC server with auto-port detection
+/// Create and start a debug RPC server, as configured by the engine configuration of
+/// the context (by default on localhost with auto-port detection)
 pub async fn start_debug_server<DB>(context: EngineContext<DB>) -> Result<RpcServerHandle>
 where
     DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
@@ -266,3 +753,185 @@ where
     let server = DebugRpcServer::new(context);
     server.start().await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Echoes the params of `echo` and fails every other method
+    struct EchoMethods;
+
+    impl MethodDispatch for EchoMethods {
+        fn dispatch<'a>(&'a self, method: &'a str, params: Option<Value>) -> DispatchFuture<'a> {
+            Box::pin(async move {
+                match method {
+                    "echo" => Ok(params.unwrap_or(Value::Null)),
+                    _ => Err(RpcError {
+                        code: error_codes::METHOD_NOT_FOUND,
+                        message: format!("Method '{method}' not found"),
+                        data: None,
+                    }),
+                }
+            })
+        }
+    }
+
+    fn slots() -> Semaphore {
+        Semaphore::new(MAX_CONCURRENT_REQUESTS)
+    }
+
+    /// Sleeps in `sleep`, recording how many calls run at once
+    #[derive(Default)]
+    struct SleepMethods {
+        running: AtomicU64,
+        max_running: AtomicU64,
+    }
+
+    impl MethodDispatch for SleepMethods {
+        fn dispatch<'a>(&'a self, _method: &'a str, _params: Option<Value>) -> DispatchFuture<'a> {
+            Box::pin(async move {
+                let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+                self.max_running.fetch_max(running, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                self.running.fetch_sub(1, Ordering::SeqCst);
+                Ok(Value::Null)
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_single_request() {
+        let response = handle_payload(
+            &EchoMethods,
+            &slots(),
+            r#"{"jsonrpc":"2.0","method":"echo","params":[1],"id":7}"#,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response, json!({ "jsonrpc": "2.0", "result": [1], "id": 7 }));
+
+        // Notifications get no response
+        assert!(handle_payload(
+            &EchoMethods,
+            &slots(),
+            r#"{"jsonrpc":"2.0","method":"echo"}"#,
+            None
+        )
+        .await
+        .is_none());
+
+        let response = handle_payload(&EchoMethods, &slots(), "{", None).await.unwrap();
+        assert_eq!(response["error"]["code"], error_codes::PARSE_ERROR);
+        assert_eq!(response["id"], Value::Null);
+
+        let response = handle_payload(
+            &EchoMethods,
+            &slots(),
+            r#"{"jsonrpc":"1.0","method":"echo","id":"a"}"#,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response["error"]["code"], error_codes::INVALID_REQUEST);
+        assert_eq!(response["id"], "a");
+    }
+
+    #[tokio::test]
+    async fn test_handle_batch_request() {
+        let batch = json!([
+            { "jsonrpc": "2.0", "method": "echo", "params": ["a"], "id": 1 },
+            { "jsonrpc": "2.0", "method": "echo", "params": ["b"] },
+            { "jsonrpc": "2.0", "method": "missing", "id": 2 },
+            { "method": "echo", "id": 3 },
+        ]);
+        let response =
+            handle_payload(&EchoMethods, &slots(), &batch.to_string(), None).await.unwrap();
+        let responses = response.as_array().unwrap();
+
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["result"], json!(["a"]));
+        assert_eq!(responses[1]["error"]["code"], error_codes::METHOD_NOT_FOUND);
+        assert_eq!(responses[2]["error"]["code"], error_codes::INVALID_REQUEST);
+        assert_eq!(responses[2]["id"], 3);
+
+        let response = handle_payload(&EchoMethods, &slots(), "[]", None).await.unwrap();
+        assert_eq!(response["error"]["code"], error_codes::INVALID_REQUEST);
+
+        let notifications = json!([{ "jsonrpc": "2.0", "method": "echo" }]);
+        assert!(handle_payload(&EchoMethods, &slots(), &notifications.to_string(), None)
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_are_bounded() {
+        let methods = SleepMethods::default();
+        let requests = slots();
+        let batch: Vec<_> = (0..3 * MAX_CONCURRENT_REQUESTS)
+            .map(|id| json!({ "jsonrpc": "2.0", "method": "sleep", "id": id }))
+            .collect();
+        let batch = Value::Array(batch).to_string();
+        let single = json!({ "jsonrpc": "2.0", "method": "sleep", "id": 0 }).to_string();
+
+        // A batch and single requests, e.g., over HTTP and WebSocket, share the slots
+        let payloads = [&batch, &batch, &single, &single];
+        let responses = future::join_all(
+            payloads.map(|payload| handle_payload(&methods, &requests, payload, None)),
+        )
+        .await;
+
+        assert_eq!(responses[0].as_ref().unwrap().as_array().unwrap().len(), 24);
+        assert!(responses.iter().all(Option::is_some));
+        let max_running = methods.max_running.load(Ordering::SeqCst);
+        assert_eq!(max_running, MAX_CONCURRENT_REQUESTS as u64);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_unix_socket_file() {
+        use crate::test_utils::prepare_test_context;
+        use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("edb.sock");
+
+        // A file which is not a socket is left alone
+        std::fs::write(&path, "data").unwrap();
+        let server = DebugRpcServer::new(prepare_test_context().await);
+        let err = server.start_on_unix_socket(path.clone()).await.err().unwrap();
+        assert!(err.to_string().contains("is not a socket"), "{err}");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "data");
+        std::fs::remove_file(&path).unwrap();
+
+        // A stale socket is replaced, and the socket is private
+        let _stale = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        let server = DebugRpcServer::new(prepare_test_context().await);
+        let handle = server.start_on_unix_socket(path.clone()).await.unwrap();
+        let metadata = std::fs::symlink_metadata(&path).unwrap();
+        assert!(metadata.file_type().is_socket());
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        handle.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_derived_config() {
+        let config = RpcServerConfig::default().with_port(3000).with_auth_token("t".to_string());
+        let derived = config.derived();
+        assert_eq!((derived.port, derived.unix_socket), (None, None));
+        assert_eq!(derived.auth_token.as_deref(), Some("t"));
+
+        // Derived servers of a socket server stay off TCP
+        let config = RpcServerConfig::default().with_unix_socket("/tmp/edb.sock".into());
+        let first = config.derived().unix_socket.unwrap();
+        let second = config.derived().unix_socket.unwrap();
+        assert_ne!(first, second);
+        for path in [first, second] {
+            assert_eq!(path.parent(), Some(Path::new("/tmp")));
+            assert!(path.file_name().unwrap().to_string_lossy().starts_with("edb-"));
+            assert_eq!(path.extension().unwrap(), "sock");
+        }
+    }
+}
@@ -26,6 +26,7 @@
 //! - [`RpcResponse`] - Outgoing JSON-RPC response with result or error
 //! - [`RpcError`] - Structured error information following JSON-RPC error format
 //! - [`RpcId`] - Request/response identifier (string or number)
+//! - [`RpcNotification`] - Server-pushed notification, e.g. [`OperationProgress`]
 //!
 //! # Debugging Types
 //!
@@ -53,8 +54,16 @@ pub struct RpcRequest {
     pub method: String,
     /// Optional method parameters as JSON value
     pub params: Option<serde_json::Value>,
-    /// Request identifier for matching with response
-    pub id: RpcId,
+    /// Session to run the method in, the server's own transaction if omitted
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Request identifier for matching with response (`None` for notifications, which
+    /// get no response)
+    #[serde(default)]
+    pub id: Option<RpcId>,
+    /// WebSocket connection the request was received on, which receives its progress
+    #[serde(skip)]
+    pub connection_id: Option<u64>,
 }
 
 /// JSON-RPC 2.0 response structure.
@@ -71,8 +80,8 @@ pub struct RpcResponse {
     /// Error information (omitted if method succeeded)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<RpcError>,
-    /// Request identifier matching the original request
-    pub id: RpcId,
+    /// Request identifier matching the original request (`null` if it could not be read)
+    pub id: Option<RpcId>,
 }
 
 /// JSON-RPC 2.0 error structure.
@@ -90,6 +99,12 @@ pub struct RpcError {
     pub data: Option<serde_json::Value>,
 }
 
+impl From<edb_common::api::ParamsError> for RpcError {
+    fn from(error: edb_common::api::ParamsError) -> Self {
+        Self { code: error_codes::INVALID_PARAMS, message: error.to_string(), data: None }
+    }
+}
+
 /// JSON-RPC request/response identifier.
 ///
 /// Can be either a string or number as per JSON-RPC 2.0 specification.
@@ -103,6 +118,74 @@ pub enum RpcId {
     String(String),
 }
 
+/// JSON-RPC 2.0 notification pushed by the server.
+///
+/// Notifications are only delivered over WebSocket connections; they carry no id and
+/// expect no response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcNotification {
+    /// JSON-RPC version (always "2.0")
+    pub jsonrpc: String,
+    /// Notification name (e.g., "edb_progress")
+    pub method: String,
+    /// Notification payload
+    pub params: serde_json::Value,
+    /// WebSocket connection the notification is addressed to, every one if `None`
+    #[serde(skip)]
+    pub connection_id: Option<u64>,
+}
+
+impl RpcNotification {
+    /// Create a notification for every client
+    pub fn new(method: impl Into<String>, params: serde_json::Value) -> Self {
+        Self { jsonrpc: "2.0".to_string(), method: method.into(), params, connection_id: None }
+    }
+
+    /// Address the notification to a single WebSocket connection
+    pub fn to_connection(mut self, connection_id: u64) -> Self {
+        self.connection_id = Some(connection_id);
+        self
+    }
+}
+
+/// Payload of `edb_progress` notifications, sent to the WebSocket connection that
+/// requested a long-running operation (e.g., a snapshot search or a what-if
+/// re-execution) when it starts, periodically while it runs, and when it ends.
+///
+/// Notifications relayed from the sessions hosted by a server also carry a `session_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationProgress {
+    /// Server-wide identifier of the operation, shared by its notifications
+    pub token: u64,
+    /// RPC method running the operation
+    pub operation: String,
+    /// Id of the request running the operation, if known
+    pub request_id: Option<RpcId>,
+    /// Current state of the operation
+    pub state: OperationState,
+    /// Stage the operation is in, for operations going through several (e.g., the
+    /// forking and analysis of a new session)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage: Option<String>,
+    /// Time the operation has taken so far, once it started
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u64>,
+}
+
+/// State of a long-running operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationState {
+    /// The operation started
+    Started,
+    /// The operation is still running, possibly in a new stage
+    Running,
+    /// The operation succeeded
+    Finished,
+    /// The operation failed
+    Failed,
+}
+
 /// Debugging breakpoint configuration.
 ///
 /// Represents a breakpoint that can pause execution at specific locations.
@@ -159,33 +242,5 @@ pub enum LocationType {
 /// JSON-RPC error codes for consistent error reporting.
 ///
 /// Includes both standard JSON-RPC 2.0 error codes and EDB-specific error codes
-/// for debugging-related failures.
-pub mod error_codes {
-    // Standard JSON-RPC 2.0 error codes
-
-    /// Parse error - Invalid JSON was received by the server
-    pub const PARSE_ERROR: i32 = -32700;
-    /// Invalid request - The JSON sent is not a valid request object
-    pub const INVALID_REQUEST: i32 = -32600;
-    /// Method not found - The method does not exist or is not available
-    pub const METHOD_NOT_FOUND: i32 = -32601;
-    /// Invalid params - Invalid method parameter(s)
-    pub const INVALID_PARAMS: i32 = -32602;
-    /// Internal error - Internal JSON-RPC error
-    pub const INTERNAL_ERROR: i32 = -32603;
-
-    // EDB-specific error codes (starting from -33000)
-
-    /// Snapshot index is out of bounds
-    pub const SNAPSHOT_OUT_OF_BOUNDS: i32 = -33001;
-    /// Invalid or malformed address
-    pub const INVALID_ADDRESS: i32 = -33002;
-    /// Trace entry not found for the given ID
-    pub const TRACE_ENTRY_NOT_FOUND: i32 = -33003;
-    /// Contract code not found at the given address
-    pub const CODE_NOT_FOUND: i32 = -33004;
-    /// User-defined snapshot ID not found
-    pub const USID_NOT_FOUND: i32 = -33005;
-    /// Expression evaluation failed
-    pub const EVAL_FAILED: i32 = -33006;
-}
+/// for debugging-related failures. They are defined with the API, in [`edb_common::api`].
+pub use edb_common::api::error_codes;
@@ -18,7 +18,7 @@
 //!
 //! This module provides common utilities needed by the RPC server including:
 //! - Port discovery and availability checking
-//! - Socket address parsing with sensible defaults
+//! - Socket and bind address parsing with sensible defaults
 //! - Error handling helpers for JSON-RPC responses
 //! - Convenience functions for common error types
 //!
@@ -33,7 +33,7 @@
 //! responses with standard error codes and descriptive messages.
 
 use eyre::{eyre, Result};
-use std::net::{SocketAddr, TcpListener};
+use std::net::{IpAddr, SocketAddr, TcpListener};
 use tracing::{debug, info};
 
 /// Find an available port starting from a base port
@@ -78,6 +78,19 @@ pub fn parse_socket_addr(addr_str: Option<&str>, default_port: u16) -> Result<So
     }
 }
 
+/// Parse a bind address, either an IP address or an IP address with a port
+/// (e.g., `0.0.0.0`, `127.0.0.1:3000` or `[::1]:3000`)
+pub fn parse_bind_addr(addr: &str) -> Result<(IpAddr, Option<u16>)> {
+    if let Ok(socket_addr) = addr.parse::<SocketAddr>() {
+        return Ok((socket_addr.ip(), Some(socket_addr.port())));
+    }
+    addr.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+        .map(|ip| (ip, None))
+        .map_err(|e| eyre!("Invalid bind address '{}': {}", addr, e))
+}
+
 /// Convert error to RPC error format
 pub fn to_rpc_error(
     code: i32,
@@ -128,6 +141,20 @@ mod tests {
         assert_eq!(addr.ip().to_string(), "127.0.0.1");
     }
 
+    #[test]
+    fn test_bind_addr_parsing() {
+        let (ip, port) = parse_bind_addr("0.0.0.0").unwrap();
+        assert_eq!(ip.to_string(), "0.0.0.0");
+        assert_eq!(port, None);
+
+        let (ip, port) = parse_bind_addr("[::1]:3000").unwrap();
+        assert_eq!(ip.to_string(), "::1");
+        assert_eq!(port, Some(3000));
+
+        assert_eq!(parse_bind_addr("[::1]").unwrap().0.to_string(), "::1");
+        assert!(parse_bind_addr("localhost:3000").is_err());
+    }
+
     #[test]
     fn test_error_helpers() {
         let err = internal_error("test message");
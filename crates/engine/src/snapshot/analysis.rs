@@ -304,6 +304,11 @@ where
                 });
             }
 
+            // Record the internal calls leading to this step, e.g., to evaluate expressions
+            // in the frames of the callers
+            let callers = internal_callers(&stack, &snapshots);
+            snapshots[i].1.set_internal_callers(callers);
+
             // Step 1: try to update the current function entry
             let stack_entry = stack.last_mut().ok_or_else(|| {
                 eyre::eyre!("Call stack is empty at step 1 (which is impossible)")
@@ -427,6 +432,12 @@ where
             }
         }
 
+        // The call stack of the last step is the one left by the second to last step
+        let callers = internal_callers(&stack, &snapshots);
+        if let Some((_, last_snapshot)) = snapshots.last_mut() {
+            last_snapshot.set_internal_callers(callers);
+        }
+
         while let Some(CallStackEntry { callsite: Some(Callsite { id, .. }), .. }) = stack.pop() {
             debug!("Add snapshot as a hole: {}", snapshots[id].1.id());
             holed_snapshots.insert(snapshots[id].1.id());
@@ -436,6 +447,23 @@ where
     }
 }
 
+/// Snapshot ids of the callsites in the call stack, outermost first
+fn internal_callers<DB>(
+    stack: &[CallStackEntry],
+    snapshots: &[&mut (ExecutionFrameId, Snapshot<DB>)],
+) -> Vec<usize>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone,
+    <CacheDB<DB> as Database>::Error: Clone,
+    <DB as Database>::Error: Clone,
+{
+    stack
+        .iter()
+        .filter_map(|entry| entry.callsite.as_ref())
+        .map(|callsite| snapshots[callsite.id].1.id())
+        .collect()
+}
+
 #[derive(Debug)]
 struct CallStackEntry {
     func_info: FunctionInfo,
@@ -512,3 +540,26 @@ impl FunctionInfo {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::prepare_test_context;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_internal_callers() {
+        let mut context = prepare_test_context().await;
+        let snapshots: Vec<_> = context.snapshots.iter_mut().collect();
+
+        // Callsites are indices into the analyzed snapshots, reported as snapshot ids
+        let entry = |callsite: Option<usize>| CallStackEntry {
+            func_info: FunctionInfo::Unknown,
+            callsite: callsite.map(|id| Callsite { id, callees: 1 }),
+            return_after_callsite: false,
+        };
+        let stack = [entry(None), entry(Some(3)), entry(Some(7))];
+        let expected = vec![snapshots[3].1.id(), snapshots[7].1.id()];
+        assert_eq!(internal_callers(&stack, &snapshots), expected);
+        assert!(internal_callers(&[entry(None)], &snapshots).is_empty());
+    }
+}
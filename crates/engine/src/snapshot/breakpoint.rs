@@ -0,0 +1,654 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Server-side breakpoint search over the snapshot timeline.
+//!
+//! Breakpoints are matched against snapshots as follows:
+//!
+//! - **Opcode locations** match opcode snapshots of the same bytecode address and PC.
+//! - **Source locations** match hook snapshots of the same bytecode address whose step
+//!   starts on the given line of the given file.
+//! - **Symbolic locations** match any code:
+//!   - function entries match the entry step of a function with the given name, contract
+//!     and parameter types (looked up in the analysis function table), or, in code
+//!     without source, the first snapshot of a call whose selector matches the signature.
+//!     An inherited function belongs to both its base contract and the deployed contract,
+//!     and struct parameters are written in their ABI tuple form;
+//!   - events match `LOG1`-`LOG4` opcodes with the given first topic, or `emit` steps of
+//!     an event with the given signature;
+//!   - calls and creations match the first snapshot of the call (creation) frame;
+//!   - self-destructs match `SELFDESTRUCT` opcodes, or the last snapshot of a
+//!     self-destructing call without source;
+//!   - reverts match the last snapshot of every call that reverts (or halts).
+//! - **Conditions** are evaluated with the [`ExpressionEvaluator`]. A breakpoint with a
+//!   location stops whenever the location is reached and the condition holds. A
+//!   condition-only (data) breakpoint stops when its condition becomes true, i.e., it
+//!   holds at a snapshot but not at the one right before it.
+//!
+//! Conditions that fail to evaluate (e.g., a variable out of scope) are considered false.
+//!
+//! A breakpoint with an ignore count does not stop at its first hits, counted in timeline
+//! order over the whole transaction (regardless of the search direction).
+//!
+//! Logpoints are matched the same way but never stop execution. Instead, the whole
+//! timeline is scanned once and every hit logs its message, whose `{expr}` placeholders
+//! are evaluated at the hit snapshot.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use alloy_dyn_abi::{DynSolType, DynSolValue};
+use alloy_primitives::{keccak256, Address, Selector, B256};
+use edb_common::types::{
+    parse_log_format, Breakpoint, BreakpointHit, BreakpointLocation, CallResult, CallType,
+    LogSegment, LogpointEntry, SearchDirection, SolValueFormatter, SolValueFormatterContext,
+    TraceEntry,
+};
+use eyre::{bail, Result};
+use foundry_compilers::artifacts::{Expression, FunctionDefinition, Statement};
+use revm::{
+    bytecode::opcode::{LOG1, LOG4, SELFDESTRUCT},
+    database::CacheDB,
+    Database, DatabaseCommit, DatabaseRef,
+};
+
+use crate::{
+    analysis::{dyn_sol_type, StepVariant, UserDefinedTypeRef},
+    EngineContext, ExpressionEvaluator, SnapshotDetail, USID,
+};
+
+/// Find the nearest snapshot in `direction` from `snapshot_id` at which any of the
+/// given breakpoints is hit. The starting snapshot itself is never reported.
+///
+/// # Returns
+/// The hit snapshot with all breakpoints hit there, or `None` if no breakpoint is hit
+/// until the end (or the beginning) of the timeline.
+///
+/// # Errors
+/// Returns an error if the snapshot does not exist.
+pub fn find_breakpoint_hit<DB>(
+    context: &Arc<EngineContext<DB>>,
+    breakpoints: &[Breakpoint],
+    snapshot_id: usize,
+    direction: SearchDirection,
+) -> Result<Option<BreakpointHit>>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    if snapshot_id >= context.snapshots.len() {
+        bail!("Snapshot with id {snapshot_id} not found");
+    }
+
+    let mut matcher = BreakpointMatcher::new(context);
+
+    // Hits ignored by breakpoints with an ignore count
+    let mut ignored: HashMap<usize, HashSet<usize>> = HashMap::new();
+    for (index, breakpoint) in breakpoints.iter().enumerate() {
+        if breakpoint.ignore_count > 0 && !breakpoint.is_logpoint() {
+            let hits = (0..context.snapshots.len()).filter(|&id| matcher.is_hit(breakpoint, id));
+            ignored.insert(index, hits.take(breakpoint.ignore_count).collect());
+        }
+    }
+
+    let ids: Box<dyn Iterator<Item = usize>> = match direction {
+        SearchDirection::Forward => Box::new(snapshot_id + 1..context.snapshots.len()),
+        SearchDirection::Backward => Box::new((0..snapshot_id).rev()),
+    };
+
+    for id in ids {
+        let hits: Vec<usize> = (0..breakpoints.len())
+            .filter(|&index| {
+                !breakpoints[index].is_logpoint()
+                    && !ignored.get(&index).is_some_and(|ignored| ignored.contains(&id))
+                    && matcher.is_hit(&breakpoints[index], id)
+            })
+            .collect();
+        if !hits.is_empty() {
+            return Ok(Some(BreakpointHit { snapshot_id: id, breakpoints: hits }));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Scan the whole timeline for hits of the given breakpoints.
+///
+/// # Returns
+/// For each breakpoint, the snapshots at which it is hit in timeline order. Ignore counts
+/// are not applied, and logpoints are counted like any other breakpoint.
+pub fn collect_breakpoint_hits<DB>(
+    context: &Arc<EngineContext<DB>>,
+    breakpoints: &[Breakpoint],
+) -> Vec<Vec<usize>>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let mut matcher = BreakpointMatcher::new(context);
+    breakpoints
+        .iter()
+        .map(|breakpoint| {
+            (0..context.snapshots.len()).filter(|&id| matcher.is_hit(breakpoint, id)).collect()
+        })
+        .collect()
+}
+
+/// Scan the whole timeline for hits of the given logpoints.
+///
+/// # Returns
+/// The logged messages in timeline order. Logpoints hit at the same snapshot log in
+/// the order they are given. Placeholders that fail to evaluate are logged as
+/// `<error: ...>`. Breakpoints without a log format are ignored.
+///
+/// # Errors
+/// Returns an error if a log format is invalid.
+pub fn collect_logpoint_log<DB>(
+    context: &Arc<EngineContext<DB>>,
+    logpoints: &[Breakpoint],
+) -> Result<Vec<LogpointEntry>>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let formats = logpoints
+        .iter()
+        .map(|logpoint| logpoint.log.as_deref().map(parse_log_format).transpose())
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut matcher = BreakpointMatcher::new(context);
+    let ctx = SolValueFormatterContext::new();
+
+    let mut log = Vec::new();
+    for id in 0..context.snapshots.len() {
+        for (index, logpoint) in logpoints.iter().enumerate() {
+            let Some(segments) = &formats[index] else { continue };
+            if !matcher.is_hit(logpoint, id) {
+                continue;
+            }
+
+            let message = segments
+                .iter()
+                .map(|segment| match segment {
+                    LogSegment::Text(text) => text.clone(),
+                    LogSegment::Expr(expr) => match matcher.evaluator.eval(expr, id) {
+                        Ok(value) => value.format_value(&ctx),
+                        Err(e) => format!("<error: {e}>"),
+                    },
+                })
+                .collect();
+            log.push(LogpointEntry { snapshot_id: id, logpoint: index, message });
+        }
+    }
+
+    Ok(log)
+}
+
+struct BreakpointMatcher<'a, DB>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    context: &'a Arc<EngineContext<DB>>,
+    evaluator: ExpressionEvaluator,
+    /// Source file and line of each step, keyed by bytecode address and USID
+    lines: HashMap<(Address, USID), Option<(PathBuf, usize)>>,
+    /// Evaluated conditions, keyed by expression and snapshot
+    conditions: HashMap<(String, usize), bool>,
+    /// Function entered at each step (contract, name, canonical parameters, selector),
+    /// keyed by bytecode address and USID
+    entries: HashMap<(Address, USID), Option<FunctionSymbol>>,
+    /// Topics of the events emitted at each step, keyed by bytecode address and USID
+    emits: HashMap<(Address, USID), Vec<B256>>,
+    /// Last snapshot of each trace entry
+    last_snapshots: Option<HashMap<usize, usize>>,
+}
+
+/// A function, as matched by function entry locations
+struct FunctionSymbol {
+    /// The contract defining the function and, for inherited functions, the deployed
+    /// contract inheriting it
+    contracts: Vec<String>,
+    name: String,
+    params: Vec<String>,
+    selector: Option<Selector>,
+}
+
+impl<'a, DB> BreakpointMatcher<'a, DB>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    fn new(context: &'a Arc<EngineContext<DB>>) -> Self {
+        Self {
+            context,
+            evaluator: ExpressionEvaluator::new_edb(context.clone()),
+            lines: HashMap::new(),
+            conditions: HashMap::new(),
+            entries: HashMap::new(),
+            emits: HashMap::new(),
+            last_snapshots: None,
+        }
+    }
+
+    fn is_hit(&mut self, breakpoint: &Breakpoint, snapshot_id: usize) -> bool {
+        match (&breakpoint.loc, &breakpoint.condition) {
+            (Some(loc), condition) => {
+                self.at_location(loc, snapshot_id)
+                    && condition.as_ref().is_none_or(|expr| self.condition_holds(expr, snapshot_id))
+            }
+            (None, Some(expr)) => {
+                self.condition_holds(expr, snapshot_id)
+                    && (snapshot_id == 0 || !self.condition_holds(expr, snapshot_id - 1))
+            }
+            (None, None) => false,
+        }
+    }
+
+    fn at_location(&mut self, loc: &BreakpointLocation, snapshot_id: usize) -> bool {
+        let (_, snapshot) = &self.context.snapshots[snapshot_id];
+        if loc.bytecode_address().is_some_and(|address| snapshot.bytecode_address() != address) {
+            return false;
+        }
+
+        match (loc, snapshot.detail()) {
+            (BreakpointLocation::Opcode { pc, .. }, SnapshotDetail::Opcode(detail)) => {
+                detail.pc == *pc
+            }
+            (
+                BreakpointLocation::Source { file_path, line_number, .. },
+                SnapshotDetail::Hook(detail),
+            ) => self
+                .source_line(detail.bytecode_address, detail.usid)
+                .is_some_and(|(path, line)| path == file_path && line == line_number),
+            (
+                BreakpointLocation::Function { contract, name, params },
+                SnapshotDetail::Hook(detail),
+            ) => {
+                self.function_entered(detail.bytecode_address, detail.usid).is_some_and(|symbol| {
+                    symbol.name == *name
+                        && contract.as_ref().is_none_or(|c| symbol.contracts.contains(c))
+                        && params.as_ref().is_none_or(|params| match symbol.selector {
+                            Some(selector) => selector == signature_selector(name, params),
+                            None => symbol.params == *params,
+                        })
+                })
+            }
+            (
+                BreakpointLocation::Function { contract, name, params },
+                SnapshotDetail::Opcode(_),
+            ) => {
+                // Without source, only the selector of an external call can be matched
+                let Some(params) = params else { return false };
+                self.entered_call(snapshot_id).is_some_and(|entry| {
+                    matches!(entry.call_type, CallType::Call(_))
+                        && entry.input.get(..4) == Some(&signature_selector(name, params)[..])
+                        && contract.as_ref().is_none_or(|c| entry.target_label.as_ref() == Some(c))
+                })
+            }
+            (BreakpointLocation::Event { topic0, .. }, SnapshotDetail::Opcode(detail)) => {
+                (LOG1..=LOG4).contains(&detail.opcode)
+                    && detail.stack.len() >= 3
+                    && B256::from(detail.stack[detail.stack.len() - 3]) == *topic0
+            }
+            (BreakpointLocation::Event { topic0, .. }, SnapshotDetail::Hook(detail)) => {
+                self.emitted_events(detail.bytecode_address, detail.usid).contains(topic0)
+            }
+            (BreakpointLocation::Call { target, selector }, _) => {
+                self.entered_call(snapshot_id).is_some_and(|entry| {
+                    matches!(entry.call_type, CallType::Call(_))
+                        && target.is_none_or(|target| entry.target == target)
+                        && selector
+                            .is_none_or(|selector| entry.input.get(..4) == Some(&selector[..]))
+                })
+            }
+            (BreakpointLocation::Create, _) => self
+                .entered_call(snapshot_id)
+                .is_some_and(|entry| matches!(entry.call_type, CallType::Create(_))),
+            (BreakpointLocation::SelfDestruct, SnapshotDetail::Opcode(detail)) => {
+                detail.opcode == SELFDESTRUCT
+            }
+            (BreakpointLocation::SelfDestruct, SnapshotDetail::Hook(_)) => {
+                self.exited_call(snapshot_id).is_some_and(|entry| entry.self_destruct.is_some())
+            }
+            (BreakpointLocation::Revert, _) => self.exited_call(snapshot_id).is_some_and(|entry| {
+                matches!(entry.result, Some(CallResult::Revert { .. } | CallResult::Error { .. }))
+            }),
+            _ => false,
+        }
+    }
+
+    /// The trace entry whose first snapshot is `snapshot_id`
+    fn entered_call(&self, snapshot_id: usize) -> Option<&'a TraceEntry> {
+        let (frame_id, _) = &self.context.snapshots[snapshot_id];
+        let entry = self.context.trace.get(frame_id.trace_entry_id())?;
+        (entry.first_snapshot_id == Some(snapshot_id)).then_some(entry)
+    }
+
+    /// The trace entry whose last snapshot is `snapshot_id`
+    fn exited_call(&mut self, snapshot_id: usize) -> Option<&'a TraceEntry> {
+        let context = self.context;
+        let last_snapshots = self.last_snapshots.get_or_insert_with(|| {
+            let mut last_snapshots = HashMap::new();
+            for (id, (frame_id, _)) in context.snapshots.iter().enumerate() {
+                last_snapshots.insert(frame_id.trace_entry_id(), id);
+            }
+            last_snapshots
+        });
+
+        let (frame_id, _) = &context.snapshots[snapshot_id];
+        let entry_id = frame_id.trace_entry_id();
+        (last_snapshots.get(&entry_id) == Some(&snapshot_id))
+            .then(|| context.trace.get(entry_id))
+            .flatten()
+    }
+
+    /// The function whose entry is the given step
+    fn function_entered(&mut self, address: Address, usid: USID) -> Option<&FunctionSymbol> {
+        let context = self.context;
+        self.entries
+            .entry((address, usid))
+            .or_insert_with(|| {
+                let analysis = context.analysis_results.get(&address)?;
+                let step = analysis.usid_to_step.get(&usid)?;
+                let ufid = step.function_entry()?;
+                let StepVariant::FunctionEntry(definition) = step.variant() else { return None };
+                let function = analysis.ufid_to_function.get(&ufid)?;
+
+                let base = function.contract();
+                let mut contracts: Vec<String> = base.iter().map(|c| c.name().clone()).collect();
+                if let (Some(base), Some(artifact)) = (&base, context.artifacts.get(&address)) {
+                    let deployed = artifact.contract_name();
+                    let inherits = analysis.ucid_to_contract.values().any(|contract| {
+                        contract.name() == deployed
+                            && contract
+                                .definition()
+                                .linearized_base_contracts
+                                .contains(&base.definition().id)
+                    });
+                    if inherits && base.name() != deployed {
+                        contracts.push(deployed.to_string());
+                    }
+                }
+
+                Some(FunctionSymbol {
+                    contracts,
+                    name: function.name(),
+                    params: function_params(definition, &analysis.user_defined_types),
+                    selector: definition
+                        .function_selector
+                        .as_ref()
+                        .and_then(|selector| selector.parse::<Selector>().ok()),
+                })
+            })
+            .as_ref()
+    }
+
+    /// Topics of the events emitted by the given step
+    fn emitted_events(&mut self, address: Address, usid: USID) -> &Vec<B256> {
+        let context = self.context;
+        self.emits.entry((address, usid)).or_insert_with(|| {
+            let Some(step) = context.get_step(address, usid) else {
+                return Vec::new();
+            };
+
+            let statements = match step.variant() {
+                StepVariant::Statement(statement) => std::slice::from_ref(statement),
+                StepVariant::Statements(statements) => statements.as_slice(),
+                _ => &[],
+            };
+            statements
+                .iter()
+                .filter_map(|statement| match statement {
+                    Statement::EmitStatement(emit) => event_topic(&emit.event_call.expression),
+                    _ => None,
+                })
+                .collect()
+        })
+    }
+
+    /// Source file and line (1-based) at which a step starts
+    fn source_line(&mut self, address: Address, usid: USID) -> Option<&(PathBuf, usize)> {
+        let context = self.context;
+        self.lines
+            .entry((address, usid))
+            .or_insert_with(|| context.get_step_line(address, usid))
+            .as_ref()
+    }
+
+    fn condition_holds(&mut self, expr: &str, snapshot_id: usize) -> bool {
+        let evaluator = &self.evaluator;
+        *self.conditions.entry((expr.to_string(), snapshot_id)).or_insert_with(|| {
+            matches!(evaluator.eval(expr, snapshot_id), Ok(DynSolValue::Bool(true)))
+        })
+    }
+}
+
+/// Canonical parameter types of a function definition. Types are resolved from their
+/// type names when possible, so that structs and user-defined value types are written in
+/// their ABI form.
+fn function_params(
+    definition: &FunctionDefinition,
+    user_defined_types: &HashMap<usize, UserDefinedTypeRef>,
+) -> Vec<String> {
+    definition
+        .parameters
+        .parameters
+        .iter()
+        .map(|param| {
+            param
+                .type_name
+                .as_ref()
+                .and_then(|type_name| dyn_sol_type(user_defined_types, type_name))
+                .map(|ty| abi_type(ty).sol_type_name().into_owned())
+                .unwrap_or_else(|| {
+                    canonical_type(param.type_descriptions.type_string.as_deref().unwrap_or(""))
+                })
+        })
+        .collect()
+}
+
+/// ABI form of a type, with structs written as tuples
+fn abi_type(ty: DynSolType) -> DynSolType {
+    match ty {
+        DynSolType::CustomStruct { tuple, .. } | DynSolType::Tuple(tuple) => {
+            DynSolType::Tuple(tuple.into_iter().map(abi_type).collect())
+        }
+        DynSolType::Array(inner) => DynSolType::Array(Box::new(abi_type(*inner))),
+        DynSolType::FixedArray(inner, len) => {
+            DynSolType::FixedArray(Box::new(abi_type(*inner)), len)
+        }
+        ty => ty,
+    }
+}
+
+/// First topic of the event referenced by an `emit` statement. The signature is derived
+/// from the type of the event reference, e.g., `function (address,uint256)`.
+fn event_topic(expression: &Expression) -> Option<B256> {
+    let (name, type_string) = match expression {
+        Expression::Identifier(identifier) => {
+            (&identifier.name, identifier.type_descriptions.type_string.as_ref()?)
+        }
+        Expression::MemberAccess(member) => {
+            (&member.member_name, member.type_descriptions.type_string.as_ref()?)
+        }
+        _ => return None,
+    };
+
+    let params = type_string.strip_prefix("function ")?;
+    let params = params.get(params.find('(')? + 1..params.rfind(')')?)?;
+    let params = split_params(params).into_iter().map(|param| canonical_type(&param));
+    Some(keccak256(format!("{name}({})", params.collect::<Vec<_>>().join(","))))
+}
+
+/// Split a comma-separated type list at the top level
+fn split_params(params: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in params.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(params[start..i].to_string());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if !params.trim().is_empty() {
+        parts.push(params[start..].to_string());
+    }
+    parts
+}
+
+/// Canonical ABI type of a Solidity type string (e.g., `uint256[] memory` is `uint256[]`,
+/// `contract IERC20` is `address`, `enum Kind` is `uint8`). Structs cannot be resolved
+/// from the type string alone, see [`function_params`].
+fn canonical_type(type_string: &str) -> String {
+    let mut ty = type_string.trim();
+    for location in [" storage pointer", " storage ref", " memory", " calldata", " storage"] {
+        ty = ty.strip_suffix(location).unwrap_or(ty);
+    }
+
+    let (base, dims) = match ty.find('[') {
+        Some(pos) => (&ty[..pos], &ty[pos..]),
+        None => (ty, ""),
+    };
+    let base = if base.starts_with("contract ") || base == "address payable" {
+        "address"
+    } else if base.starts_with("enum ") {
+        "uint8"
+    } else {
+        base
+    };
+    format!("{base}{dims}")
+}
+
+/// Selector of a function signature
+fn signature_selector(name: &str, params: &[String]) -> Selector {
+    Selector::from_slice(&keccak256(format!("{name}({})", params.join(",")))[..4])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        ping_topic, prepare_test_context, CALLEE_CONTRACT, CALLEE_LOG_PC, CALLER_CONTRACT,
+    };
+
+    #[test]
+    fn test_canonical_type() {
+        assert_eq!(canonical_type("uint256[] memory"), "uint256[]");
+        assert_eq!(canonical_type("contract IERC20"), "address");
+        assert_eq!(canonical_type("enum Vault.Kind[2] calldata"), "uint8[2]");
+        assert_eq!(canonical_type("address payable"), "address");
+        assert_eq!(canonical_type("bytes storage ref"), "bytes");
+    }
+
+    #[test]
+    fn test_abi_type() {
+        let position = DynSolType::CustomStruct {
+            name: "Position".to_string(),
+            prop_names: vec!["owner".to_string(), "amounts".to_string()],
+            tuple: vec![DynSolType::Address, DynSolType::Array(Box::new(DynSolType::Uint(256)))],
+        };
+        let ty = DynSolType::FixedArray(Box::new(position), 2);
+        assert_eq!(abi_type(ty).sol_type_name(), "(address,uint256[])[2]");
+    }
+
+    #[test]
+    fn test_split_params() {
+        assert_eq!(
+            split_params("address,(uint256,bool)[],bytes"),
+            vec!["address", "(uint256,bool)[]", "bytes"]
+        );
+        assert!(split_params("").is_empty());
+        assert_eq!(
+            signature_selector("transfer", &["address".to_string(), "uint256".to_string()]),
+            Selector::from([0xa9, 0x05, 0x9c, 0xbb])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_breakpoint_matching() {
+        let context = Arc::new(prepare_test_context().await);
+        let hits = |loc: BreakpointLocation, condition: Option<&str>| {
+            let breakpoint = Breakpoint::new(Some(loc), condition.map(str::to_string));
+            collect_breakpoint_hits(&context, &[breakpoint]).remove(0)
+        };
+        let address = |id: usize| context.snapshots[id].1.bytecode_address();
+
+        // The LOG1 of the callee, by PC and by event
+        let log = hits(
+            BreakpointLocation::Opcode { bytecode_address: CALLEE_CONTRACT, pc: CALLEE_LOG_PC },
+            None,
+        );
+        assert_eq!(log.len(), 1);
+        let event = BreakpointLocation::Event { signature: None, topic0: ping_topic() };
+        assert_eq!(hits(event, None), log);
+
+        // The callee is entered once, and reverts at its last snapshot
+        let callee = BreakpointLocation::Call { target: Some(CALLEE_CONTRACT), selector: None };
+        let call = hits(callee.clone(), None);
+        let revert = hits(BreakpointLocation::Revert, None);
+        assert_eq!((call.len(), revert.len()), (1, 1));
+        assert!(call[0] < log[0] && log[0] < revert[0]);
+        assert_eq!((address(call[0]), address(revert[0])), (CALLEE_CONTRACT, CALLEE_CONTRACT));
+        assert_eq!(address(revert[0] + 1), CALLER_CONTRACT);
+
+        // Both calls, unless the condition does not hold
+        let any_call = BreakpointLocation::Call { target: None, selector: None };
+        assert_eq!(hits(any_call.clone(), None), vec![0, call[0]]);
+        assert_eq!(hits(any_call, Some("1 == 2")), Vec::<usize>::new());
+        assert!(hits(BreakpointLocation::Create, None).is_empty());
+
+        // Searches skip the starting snapshot, in either direction
+        let breakpoints = [
+            Breakpoint::new(Some(BreakpointLocation::Revert), None),
+            Breakpoint::new(Some(callee), None),
+        ];
+        let forward =
+            |id| find_breakpoint_hit(&context, &breakpoints, id, SearchDirection::Forward).unwrap();
+        assert_eq!(forward(0), Some(BreakpointHit { snapshot_id: call[0], breakpoints: vec![1] }));
+        assert_eq!(
+            forward(call[0]),
+            Some(BreakpointHit { snapshot_id: revert[0], breakpoints: vec![0] })
+        );
+        assert_eq!(forward(revert[0]), None);
+        let last = context.snapshots.len() - 1;
+        let backward =
+            find_breakpoint_hit(&context, &breakpoints, last, SearchDirection::Backward).unwrap();
+        assert_eq!(backward.map(|hit| hit.snapshot_id), Some(revert[0]));
+
+        // Ignored hits are counted over the whole timeline
+        let mut ignored = breakpoints[0].clone();
+        ignored.ignore_count = 1;
+        assert_eq!(
+            find_breakpoint_hit(&context, &[ignored], 0, SearchDirection::Forward).unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,164 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Value history over the snapshot timeline.
+//!
+//! The history of a value is collected by evaluating an expression (e.g., a local or
+//! state variable, or any watch expression) with the [`ExpressionEvaluator`] at every
+//! hook snapshot within a [`HistoryScope`], keeping only the snapshots where the value
+//! changes. Snapshots at which the expression cannot be evaluated (e.g., a local
+//! variable out of scope) are skipped.
+
+use std::sync::Arc;
+
+use edb_common::types::{HistoryScope, ValueChange, ValueHistory};
+use eyre::{bail, Result};
+use revm::{database::CacheDB, Database, DatabaseCommit, DatabaseRef};
+
+use crate::{EngineContext, ExpressionEvaluator, SnapshotDetail};
+
+/// Collect every change of the value of `expr` within `scope`.
+///
+/// # Returns
+/// The first observed value followed by every change, in timeline order.
+///
+/// # Errors
+/// Returns an error if the scope refers to a non-existent call or snapshot, or if the
+/// expression cannot be evaluated at any hook snapshot within the scope.
+pub fn collect_value_history<DB>(
+    context: &Arc<EngineContext<DB>>,
+    expr: &str,
+    scope: HistoryScope,
+) -> Result<ValueHistory>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    match scope {
+        HistoryScope::Frame { trace_entry_id } if trace_entry_id >= context.trace.len() => {
+            bail!("Trace entry with id {trace_entry_id} not found")
+        }
+        HistoryScope::Range { start, end } if start > end || end >= context.snapshots.len() => {
+            bail!("Invalid snapshot range {start}..={end}")
+        }
+        _ => {}
+    }
+
+    let hook_ids: Vec<usize> = context
+        .snapshots
+        .iter()
+        .enumerate()
+        .filter(|(id, (frame_id, snapshot))| snapshot.is_hook() && scope.contains(*id, *frame_id))
+        .map(|(id, _)| id)
+        .collect();
+    if hook_ids.is_empty() {
+        bail!("No source-level step in {scope}");
+    }
+
+    let evaluator = ExpressionEvaluator::new_edb(context.clone());
+    let mut last_error = None;
+    let (observed, changes) = collect_changes(hook_ids, |id| match evaluator.eval(expr, id) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            last_error = Some(e);
+            None
+        }
+    });
+
+    if observed == 0 {
+        match last_error {
+            Some(e) => bail!("Cannot evaluate {expr} in {scope}: {e}"),
+            None => bail!("Cannot evaluate {expr} in {scope}"),
+        }
+    }
+
+    let changes = changes
+        .into_iter()
+        .map(|(snapshot_id, value)| {
+            let (frame_id, snapshot) = &context.snapshots[snapshot_id];
+            let mut change = ValueChange {
+                snapshot_id,
+                frame_id: *frame_id,
+                bytecode_address: snapshot.bytecode_address(),
+                path: None,
+                offset: None,
+                length: None,
+                line: None,
+                value: value.into(),
+            };
+
+            if let SnapshotDetail::Hook(detail) = snapshot.detail() {
+                if let Some(source) = context.get_step_source(detail.bytecode_address, detail.usid)
+                {
+                    change.path = Some(source.path);
+                    change.offset = source.offset;
+                    change.length = source.length;
+                }
+                change.line = context
+                    .get_step_line(detail.bytecode_address, detail.usid)
+                    .map(|(_, line)| line);
+            }
+
+            change
+        })
+        .collect();
+
+    Ok(ValueHistory { observed, changes })
+}
+
+/// Observe a value at each of the given snapshots, and keep the first observation and
+/// every observation that differs from the previous one.
+///
+/// # Returns
+/// The number of snapshots at which the value is observable, and the kept observations.
+fn collect_changes<T: PartialEq>(
+    ids: impl IntoIterator<Item = usize>,
+    mut observe: impl FnMut(usize) -> Option<T>,
+) -> (usize, Vec<(usize, T)>) {
+    let mut observed = 0;
+    let mut changes: Vec<(usize, T)> = Vec::new();
+
+    for id in ids {
+        let Some(value) = observe(id) else { continue };
+        observed += 1;
+        if changes.last().is_none_or(|(_, last)| *last != value) {
+            changes.push((id, value));
+        }
+    }
+
+    (observed, changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_changes() {
+        let values = [Some(1), Some(1), None, Some(2), Some(2), Some(1), None];
+        let (observed, changes) = collect_changes(0..values.len(), |id| values[id]);
+        assert_eq!(observed, 5);
+        assert_eq!(changes, vec![(0, 1), (3, 2), (5, 1)]);
+    }
+
+    #[test]
+    fn test_collect_changes_unobservable() {
+        let (observed, changes) = collect_changes(0..3, |_| None::<u8>);
+        assert_eq!(observed, 0);
+        assert!(changes.is_empty());
+    }
+}
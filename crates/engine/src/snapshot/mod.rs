@@ -29,10 +29,20 @@
 //! of execution state across the entire transaction.
 
 mod analysis;
+mod breakpoint;
+mod history;
 mod pretty_print;
+mod provenance;
+mod query;
+mod watchpoint;
 
 use alloy_primitives::Address;
 pub use analysis::SnapshotAnalysis;
+pub use breakpoint::{collect_breakpoint_hits, collect_logpoint_log, find_breakpoint_hit};
+pub use history::collect_value_history;
+pub use provenance::trace_value_origin;
+pub use query::find_snapshots;
+pub use watchpoint::{find_next_watchpoint_hit, find_prev_watchpoint_hit};
 
 use std::{
     ops::{Deref, DerefMut},
@@ -64,6 +74,11 @@ where
     next_id: Option<usize>,
     prev_id: Option<usize>,
 
+    /// Snapshots of the steps making the internal calls (within the same frame) that lead
+    /// to this snapshot, outermost first
+    #[serde(default)]
+    internal_callers: Vec<usize>,
+
     /// Detail of the snapshot
     detail: SnapshotDetail<DB>,
 }
@@ -95,12 +110,26 @@ where
 {
     /// Create an opcode snapshot
     pub fn new_opcode(id: usize, frame_id: ExecutionFrameId, detail: OpcodeSnapshot<DB>) -> Self {
-        Self { id, frame_id, next_id: None, prev_id: None, detail: SnapshotDetail::Opcode(detail) }
+        Self {
+            id,
+            frame_id,
+            next_id: None,
+            prev_id: None,
+            internal_callers: Vec::new(),
+            detail: SnapshotDetail::Opcode(detail),
+        }
     }
 
     /// Create a hook snapshot
     pub fn new_hook(id: usize, frame_id: ExecutionFrameId, detail: HookSnapshot<DB>) -> Self {
-        Self { id, frame_id, next_id: None, prev_id: None, detail: SnapshotDetail::Hook(detail) }
+        Self {
+            id,
+            frame_id,
+            next_id: None,
+            prev_id: None,
+            internal_callers: Vec::new(),
+            detail: SnapshotDetail::Hook(detail),
+        }
     }
 
     /// Set the id of the next snapshot
@@ -123,6 +152,17 @@ where
         self.prev_id
     }
 
+    /// Set the snapshots of the steps making the internal calls that lead to this snapshot
+    pub fn set_internal_callers(&mut self, callers: Vec<usize>) {
+        self.internal_callers = callers;
+    }
+
+    /// Get the snapshots of the steps making the internal calls that lead to this snapshot,
+    /// outermost first
+    pub fn internal_callers(&self) -> &[usize] {
+        &self.internal_callers
+    }
+
     /// Get the snapshot id
     pub fn id(&self) -> usize {
         self.id
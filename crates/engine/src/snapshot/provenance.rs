@@ -0,0 +1,776 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Backward data-flow tracing over the snapshot timeline.
+//!
+//! Given a value at some snapshot (a stack item, a memory or calldata range, or a
+//! storage slot), this module walks the timeline backwards to find the instruction
+//! that produced it. Along the way it follows the value through:
+//!
+//! - **Stack**: DUP/SWAP shuffling and MLOAD/SLOAD/TLOAD/CALLDATALOAD reads
+//! - **Memory**: MSTORE, MCOPY, CALLDATACOPY, RETURNDATACOPY and call return buffers
+//! - **Calldata**: into the caller's memory at the call site
+//! - **Returndata**: into the callee's memory at its RETURN/REVERT
+//! - **Storage**: to the last write, located by diffing snapshot databases
+//!
+//! Opcode snapshots carry the full machine state and are traced precisely. Hook
+//! snapshots only exist for instrumented (source-level) frames and do not expose the
+//! stack or memory, so tracing stops there and reports the source step as the origin.
+
+use std::sync::Arc;
+
+use alloy_primitives::U256;
+use edb_common::types::{ValueLocation, ValueOrigin, ValueOriginKind, ValueOriginStep};
+use eyre::{bail, eyre, Result};
+use revm::{
+    bytecode::{opcode::*, OpCode},
+    database::CacheDB,
+    Database, DatabaseCommit, DatabaseRef,
+};
+
+use crate::{EngineContext, OpcodeSnapshot, SnapshotDetail};
+
+/// Maximum number of hops recorded before giving up
+const MAX_ORIGIN_STEPS: usize = 256;
+
+/// Maximum size of a traced memory, calldata or returndata range (32 words)
+const MAX_TRACED_RANGE: usize = 32 * 32;
+
+/// Trace a value at `snapshot_id` backwards to the instruction that produced it.
+///
+/// # Arguments
+/// * `context` - The engine context holding snapshots, trace and analysis results
+/// * `snapshot_id` - Snapshot at which the value is observed (state before its instruction)
+/// * `location` - Where the value lives at that snapshot
+///
+/// # Returns
+/// The chain of instructions that moved the value, most recent first, and the kind of
+/// its origin. Tracing that cannot reach an origin ends with [`ValueOriginKind::Unresolved`].
+///
+/// # Errors
+/// Returns an error if the snapshot does not exist, if the location is not observable
+/// at it (e.g. a stack item at a hook snapshot), or if a byte range is larger than
+/// 32 words or ends past the address space.
+pub fn trace_value_origin<DB>(
+    context: &EngineContext<DB>,
+    snapshot_id: usize,
+    location: ValueLocation,
+) -> Result<ValueOrigin>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    ValueTracer { context }.trace(snapshot_id, location)
+}
+
+/// Outcome of following a value one hop backwards
+enum Hop {
+    /// Keep tracing the value at the given snapshot and location
+    Continue(usize, ValueLocation),
+    /// The origin has been reached
+    Done(ValueOriginKind),
+}
+
+struct ValueTracer<'a, DB>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    context: &'a EngineContext<DB>,
+}
+
+impl<'a, DB> ValueTracer<'a, DB>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    fn trace(&self, snapshot_id: usize, location: ValueLocation) -> Result<ValueOrigin> {
+        let Some((_, snapshot)) = self.context.snapshots.get(snapshot_id) else {
+            bail!("Snapshot with id {snapshot_id} not found");
+        };
+
+        match (snapshot.detail(), location) {
+            (
+                SnapshotDetail::Hook(_),
+                ValueLocation::Stack { .. } | ValueLocation::Memory { .. },
+            ) => {
+                bail!("Stack and memory are only available at opcode snapshots")
+            }
+            (SnapshotDetail::Opcode(detail), ValueLocation::Stack { depth })
+                if depth >= detail.stack.len() =>
+            {
+                bail!("Stack depth {depth} out of bounds (stack size {})", detail.stack.len())
+            }
+            _ => {}
+        }
+
+        if let ValueLocation::Memory { offset, size }
+        | ValueLocation::Calldata { offset, size }
+        | ValueLocation::Returndata { offset, size } = location
+        {
+            if size > MAX_TRACED_RANGE {
+                bail!("Cannot trace {size} bytes, at most {MAX_TRACED_RANGE} bytes at a time");
+            }
+            if offset.checked_add(size).is_none() {
+                bail!("Range {location} exceeds the address space");
+            }
+        }
+
+        let mut steps = Vec::new();
+        let mut cursor = (snapshot_id, location);
+        let kind = loop {
+            if steps.len() >= MAX_ORIGIN_STEPS {
+                break ValueOriginKind::Unresolved(format!(
+                    "Gave up after {MAX_ORIGIN_STEPS} steps"
+                ));
+            }
+
+            let (id, loc) = cursor;
+            let hop = match loc {
+                ValueLocation::Stack { depth } => self.trace_stack(id, depth, &mut steps)?,
+                ValueLocation::Memory { offset, size } => {
+                    self.trace_memory(id, offset, size, &mut steps)?
+                }
+                ValueLocation::Calldata { offset, size } => {
+                    self.trace_calldata(id, offset, size, &mut steps)?
+                }
+                ValueLocation::Returndata { offset, size } => match self.latest_call_before(id) {
+                    Some(call_id) => self.trace_returndata(call_id, offset, size, &mut steps)?,
+                    None => Hop::Done(ValueOriginKind::Unresolved(
+                        "No call precedes this snapshot in the current frame".to_string(),
+                    )),
+                },
+                ValueLocation::Storage { .. } => self.trace_storage(id, loc, &mut steps)?,
+                ValueLocation::TransientStorage { .. } => {
+                    self.trace_transient_storage(id, loc, &mut steps)?
+                }
+            };
+
+            match hop {
+                Hop::Continue(id, loc) => cursor = (id, loc),
+                Hop::Done(kind) => break kind,
+            }
+        };
+
+        Ok(ValueOrigin { snapshot_id, location, steps, kind })
+    }
+
+    /// Follow a stack item back to the instruction that pushed it.
+    fn trace_stack(
+        &self,
+        snapshot_id: usize,
+        mut depth: usize,
+        steps: &mut Vec<ValueOriginStep>,
+    ) -> Result<Hop> {
+        let mut cursor = snapshot_id;
+        loop {
+            let Some(prev) = self.prev_in_entry(cursor) else {
+                return Ok(Hop::Done(ValueOriginKind::Unresolved(
+                    "No earlier instruction in this call frame".to_string(),
+                )));
+            };
+
+            let Some(snapshot) = self.opcode_snapshot(prev) else {
+                steps.push(self.step(prev, ValueLocation::Stack { depth }, None)?);
+                return Ok(Hop::Done(ValueOriginKind::SourceStep));
+            };
+
+            let op = snapshot.opcode;
+            let Some((inputs, outputs)) = OpCode::new(op).map(|op| op.input_output()) else {
+                return Ok(Hop::Done(ValueOriginKind::Unresolved(format!(
+                    "Invalid opcode {op:#04x} at snapshot {prev}"
+                ))));
+            };
+
+            // Pure stack shuffling only moves the value around
+            match op {
+                DUP1..=DUP16 => {
+                    let n = (op - DUP1) as usize;
+                    depth = if depth == 0 { n } else { depth - 1 };
+                    cursor = prev;
+                    continue;
+                }
+                SWAP1..=SWAP16 => {
+                    let n = (op - SWAP1 + 1) as usize;
+                    if depth == 0 {
+                        depth = n;
+                    } else if depth == n {
+                        depth = 0;
+                    }
+                    cursor = prev;
+                    continue;
+                }
+                _ => {}
+            }
+
+            // The item was below everything this instruction touched
+            if depth >= outputs as usize {
+                depth = depth - outputs as usize + inputs as usize;
+                cursor = prev;
+                continue;
+            }
+
+            let written = ValueLocation::Stack { depth };
+            let read_from = match op {
+                MLOAD => {
+                    Some(ValueLocation::Memory { offset: stack_usize(snapshot, 0)?, size: 32 })
+                }
+                CALLDATALOAD => {
+                    Some(ValueLocation::Calldata { offset: stack_usize(snapshot, 0)?, size: 32 })
+                }
+                SLOAD => Some(ValueLocation::Storage {
+                    address: snapshot.target_address,
+                    slot: stack_item(snapshot, 0)?,
+                }),
+                TLOAD => Some(ValueLocation::TransientStorage {
+                    address: snapshot.target_address,
+                    slot: stack_item(snapshot, 0)?,
+                }),
+                _ => None,
+            };
+            steps.push(self.step(prev, written, read_from)?);
+
+            if let Some(read_from) = read_from {
+                return Ok(Hop::Continue(prev, read_from));
+            }
+
+            let kind = match op {
+                PUSH0..=PUSH32 => ValueOriginKind::Constant,
+                CALL | CALLCODE | DELEGATECALL | STATICCALL | CREATE | CREATE2 => {
+                    ValueOriginKind::CallOutcome
+                }
+                ADDRESS | BALANCE | ORIGIN | CALLER | CALLVALUE | CALLDATASIZE | CODESIZE
+                | GASPRICE | EXTCODESIZE | RETURNDATASIZE | EXTCODEHASH | BLOCKHASH | COINBASE
+                | TIMESTAMP | NUMBER | DIFFICULTY | GASLIMIT | CHAINID | SELFBALANCE | BASEFEE
+                | BLOBHASH | BLOBBASEFEE | PC | MSIZE | GAS => ValueOriginKind::Environment,
+                _ => ValueOriginKind::Computation,
+            };
+            return Ok(Hop::Done(kind));
+        }
+    }
+
+    /// Follow a memory range back to the instruction that last wrote it.
+    fn trace_memory(
+        &self,
+        snapshot_id: usize,
+        offset: usize,
+        size: usize,
+        steps: &mut Vec<ValueOriginStep>,
+    ) -> Result<Hop> {
+        let written = ValueLocation::Memory { offset, size };
+        let Some(snapshot) = self.opcode_snapshot(snapshot_id) else {
+            bail!("Memory is only available at opcode snapshots");
+        };
+        let mut later = read_padded(&snapshot.memory, offset, size);
+
+        let mut cursor = snapshot_id;
+        let (writer_id, writer) = loop {
+            let Some(prev) = self.prev_in_entry(cursor) else {
+                // Memory starts zeroed in every call frame
+                return Ok(Hop::Done(ValueOriginKind::Initial));
+            };
+
+            let Some(snapshot) = self.opcode_snapshot(prev) else {
+                steps.push(self.step(prev, written, None)?);
+                return Ok(Hop::Done(ValueOriginKind::SourceStep));
+            };
+
+            let earlier = read_padded(&snapshot.memory, offset, size);
+            if earlier != later {
+                break (prev, snapshot);
+            }
+            later = earlier;
+            cursor = prev;
+        };
+
+        let partial = |name: &str| {
+            Hop::Done(ValueOriginKind::Unresolved(format!(
+                "Memory range is only partially written by {name} at snapshot {writer_id}"
+            )))
+        };
+
+        let hop = match writer.opcode {
+            MSTORE | MSTORE8 => {
+                let width = if writer.opcode == MSTORE { 32 } else { 1 };
+                if !contains(stack_usize(writer, 0)?, width, offset, size) {
+                    steps.push(self.step(writer_id, written, None)?);
+                    return Ok(partial(OpCode::name_by_op(writer.opcode)));
+                }
+                let read_from = ValueLocation::Stack { depth: 1 };
+                steps.push(self.step(writer_id, written, Some(read_from))?);
+                Hop::Continue(writer_id, read_from)
+            }
+            MCOPY | CALLDATACOPY | RETURNDATACOPY => {
+                let dst = stack_usize(writer, 0)?;
+                let src = stack_usize(writer, 1)?;
+                let len = stack_usize(writer, 2)?;
+                if !contains(dst, len, offset, size) {
+                    steps.push(self.step(writer_id, written, None)?);
+                    return Ok(partial(OpCode::name_by_op(writer.opcode)));
+                }
+
+                let Some(offset) = src.checked_add(offset - dst) else {
+                    steps.push(self.step(writer_id, written, None)?);
+                    return Ok(Hop::Done(ValueOriginKind::Unresolved(format!(
+                        "{} source offset exceeds the address space",
+                        OpCode::name_by_op(writer.opcode)
+                    ))));
+                };
+                match writer.opcode {
+                    MCOPY => {
+                        let read_from = ValueLocation::Memory { offset, size };
+                        steps.push(self.step(writer_id, written, Some(read_from))?);
+                        Hop::Continue(writer_id, read_from)
+                    }
+                    CALLDATACOPY => {
+                        let read_from = ValueLocation::Calldata { offset, size };
+                        steps.push(self.step(writer_id, written, Some(read_from))?);
+                        Hop::Continue(writer_id, read_from)
+                    }
+                    _ => {
+                        let read_from = ValueLocation::Returndata { offset, size };
+                        steps.push(self.step(writer_id, written, Some(read_from))?);
+                        match self.latest_call_before(writer_id) {
+                            Some(call_id) => self.trace_returndata(call_id, offset, size, steps)?,
+                            None => Hop::Done(ValueOriginKind::Unresolved(
+                                "No call precedes RETURNDATACOPY in the current frame".to_string(),
+                            )),
+                        }
+                    }
+                }
+            }
+            CODECOPY | EXTCODECOPY => {
+                steps.push(self.step(writer_id, written, None)?);
+                Hop::Done(ValueOriginKind::Constant)
+            }
+            CALL | CALLCODE | DELEGATECALL | STATICCALL => {
+                // The return buffer is the last pair of call arguments
+                let ret_index = if matches!(writer.opcode, CALL | CALLCODE) { 5 } else { 4 };
+                let ret_offset = stack_usize(writer, ret_index)?;
+                let ret_size = stack_usize(writer, ret_index + 1)?;
+                if !contains(ret_offset, ret_size, offset, size) {
+                    steps.push(self.step(writer_id, written, None)?);
+                    return Ok(partial(OpCode::name_by_op(writer.opcode)));
+                }
+
+                let read_from = ValueLocation::Returndata { offset: offset - ret_offset, size };
+                steps.push(self.step(writer_id, written, Some(read_from))?);
+                self.trace_returndata(writer_id, offset - ret_offset, size, steps)?
+            }
+            op => {
+                steps.push(self.step(writer_id, written, None)?);
+                Hop::Done(ValueOriginKind::Unresolved(format!(
+                    "Memory written by unsupported opcode {}",
+                    OpCode::name_by_op(op)
+                )))
+            }
+        };
+
+        Ok(hop)
+    }
+
+    /// Follow a calldata range of the current frame to the caller's memory.
+    fn trace_calldata(
+        &self,
+        snapshot_id: usize,
+        offset: usize,
+        size: usize,
+        steps: &mut Vec<ValueOriginStep>,
+    ) -> Result<Hop> {
+        let entry_id = self.entry_id(snapshot_id);
+        let entry = self
+            .context
+            .trace
+            .get(entry_id)
+            .ok_or_else(|| eyre!("Trace entry with id {entry_id} not found"))?;
+
+        let Some(parent_id) = entry.parent_id else {
+            return Ok(Hop::Done(ValueOriginKind::TransactionInput));
+        };
+
+        // The call site is the last snapshot of the parent before this frame starts
+        let first_id = entry.first_snapshot_id.unwrap_or(snapshot_id);
+        let Some(call_id) = (0..first_id).rev().find(|id| self.entry_id(*id) == parent_id) else {
+            return Ok(Hop::Done(ValueOriginKind::Unresolved(
+                "Call site not found in the parent frame".to_string(),
+            )));
+        };
+
+        let written = ValueLocation::Calldata { offset, size };
+        let Some(call) = self.opcode_snapshot(call_id) else {
+            steps.push(self.step(call_id, written, None)?);
+            return Ok(Hop::Done(ValueOriginKind::SourceStep));
+        };
+
+        let args_index = match call.opcode {
+            CALL | CALLCODE => 3,
+            DELEGATECALL | STATICCALL => 2,
+            op => {
+                steps.push(self.step(call_id, written, None)?);
+                return Ok(Hop::Done(ValueOriginKind::Unresolved(format!(
+                    "Frame was entered through {}, which passes no calldata",
+                    OpCode::name_by_op(op)
+                ))));
+            }
+        };
+        let args_offset = stack_usize(call, args_index)?;
+        let args_size = stack_usize(call, args_index + 1)?;
+        if offset.checked_add(size).is_none_or(|end| end > args_size) {
+            steps.push(self.step(call_id, written, None)?);
+            return Ok(Hop::Done(ValueOriginKind::Unresolved(
+                "Calldata range exceeds the call input".to_string(),
+            )));
+        }
+
+        let Some(offset) = args_offset.checked_add(offset) else {
+            steps.push(self.step(call_id, written, None)?);
+            return Ok(Hop::Done(ValueOriginKind::Unresolved(
+                "Call input offset exceeds the address space".to_string(),
+            )));
+        };
+        let read_from = ValueLocation::Memory { offset, size };
+        steps.push(self.step(call_id, written, Some(read_from))?);
+        Ok(Hop::Continue(call_id, read_from))
+    }
+
+    /// Follow returndata of the call at `call_id` into the callee's memory.
+    fn trace_returndata(
+        &self,
+        call_id: usize,
+        offset: usize,
+        size: usize,
+        steps: &mut Vec<ValueOriginStep>,
+    ) -> Result<Hop> {
+        let caller_entry = self.entry_id(call_id);
+        let callee_entry = match self.context.snapshots.get(call_id + 1) {
+            Some((frame_id, _))
+                if self.context.is_parent_trace(caller_entry, frame_id.trace_entry_id()) =>
+            {
+                frame_id.trace_entry_id()
+            }
+            // Precompiles and accounts without code produce no snapshots
+            _ => return Ok(Hop::Done(ValueOriginKind::CallOutcome)),
+        };
+
+        let Some(return_id) = (call_id + 1..self.context.snapshots.len())
+            .take_while(|id| self.entry_id(*id) != caller_entry)
+            .filter(|id| self.entry_id(*id) == callee_entry)
+            .last()
+        else {
+            return Ok(Hop::Done(ValueOriginKind::CallOutcome));
+        };
+
+        let written = ValueLocation::Returndata { offset, size };
+        let Some(ret) = self.opcode_snapshot(return_id) else {
+            steps.push(self.step(return_id, written, None)?);
+            return Ok(Hop::Done(ValueOriginKind::SourceStep));
+        };
+
+        if !matches!(ret.opcode, RETURN | REVERT) {
+            steps.push(self.step(return_id, written, None)?);
+            return Ok(Hop::Done(ValueOriginKind::Unresolved(format!(
+                "Callee ended with {}, which returns no data",
+                OpCode::name_by_op(ret.opcode)
+            ))));
+        }
+
+        let Some(offset) = stack_usize(ret, 0)?.checked_add(offset) else {
+            steps.push(self.step(return_id, written, None)?);
+            return Ok(Hop::Done(ValueOriginKind::Unresolved(
+                "Return data offset exceeds the address space".to_string(),
+            )));
+        };
+        let read_from = ValueLocation::Memory { offset, size };
+        steps.push(self.step(return_id, written, Some(read_from))?);
+        Ok(Hop::Continue(return_id, read_from))
+    }
+
+    /// Find the last write to a storage slot by diffing snapshot databases.
+    fn trace_storage(
+        &self,
+        snapshot_id: usize,
+        written: ValueLocation,
+        steps: &mut Vec<ValueOriginStep>,
+    ) -> Result<Hop> {
+        let ValueLocation::Storage { address, slot } = written else {
+            bail!("Expected a storage location, got {written}");
+        };
+
+        let read = |db: &CacheDB<DB>| {
+            db.storage_ref(address, slot)
+                .map_err(|e| eyre!("Failed to retrieve storage at {address} for slot {slot}: {e}"))
+        };
+
+        let mut later_db = self.context.snapshots[snapshot_id].1.db();
+        let mut later = read(&later_db)?;
+        let mut writer_id = None;
+        for id in (0..snapshot_id).rev() {
+            let db = self.context.snapshots[id].1.db();
+            if Arc::ptr_eq(&db, &later_db) {
+                continue;
+            }
+
+            let earlier = read(&db)?;
+            if earlier != later {
+                writer_id = Some(id);
+                break;
+            }
+            later = earlier;
+            later_db = db;
+        }
+
+        let Some(writer_id) = writer_id else {
+            return Ok(Hop::Done(ValueOriginKind::Initial));
+        };
+
+        let Some(writer) = self.opcode_snapshot(writer_id) else {
+            steps.push(self.step(writer_id, written, None)?);
+            return Ok(Hop::Done(ValueOriginKind::SourceStep));
+        };
+
+        if writer.opcode == SSTORE
+            && writer.target_address == address
+            && stack_item(writer, 0)? == slot
+        {
+            let read_from = ValueLocation::Stack { depth: 1 };
+            steps.push(self.step(writer_id, written, Some(read_from))?);
+            return Ok(Hop::Continue(writer_id, read_from));
+        }
+
+        steps.push(self.step(writer_id, written, None)?);
+        Ok(Hop::Done(ValueOriginKind::Unresolved(format!(
+            "Storage changed across {} (e.g. by a reverted call)",
+            OpCode::name_by_op(writer.opcode)
+        ))))
+    }
+
+    /// Find the last TSTORE to a transient storage slot.
+    ///
+    /// Transient storage is only recorded at opcode snapshots, so writes made inside
+    /// instrumented frames are not visible here.
+    fn trace_transient_storage(
+        &self,
+        snapshot_id: usize,
+        written: ValueLocation,
+        steps: &mut Vec<ValueOriginStep>,
+    ) -> Result<Hop> {
+        let ValueLocation::TransientStorage { address, slot } = written else {
+            bail!("Expected a transient storage location, got {written}");
+        };
+
+        for id in (0..snapshot_id).rev() {
+            let Some(snapshot) = self.opcode_snapshot(id) else { continue };
+            if snapshot.opcode == TSTORE
+                && snapshot.target_address == address
+                && stack_item(snapshot, 0)? == slot
+            {
+                let read_from = ValueLocation::Stack { depth: 1 };
+                steps.push(self.step(id, written, Some(read_from))?);
+                return Ok(Hop::Continue(id, read_from));
+            }
+        }
+
+        Ok(Hop::Done(ValueOriginKind::Initial))
+    }
+
+    /// Trace entry that a snapshot belongs to
+    fn entry_id(&self, snapshot_id: usize) -> usize {
+        self.context.snapshots[snapshot_id].0.trace_entry_id()
+    }
+
+    /// Previous snapshot executed in the same call frame, skipping nested calls
+    fn prev_in_entry(&self, snapshot_id: usize) -> Option<usize> {
+        let entry_id = self.entry_id(snapshot_id);
+        (0..snapshot_id).rev().find(|id| self.entry_id(*id) == entry_id)
+    }
+
+    /// Latest call instruction before a snapshot in the same call frame
+    fn latest_call_before(&self, snapshot_id: usize) -> Option<usize> {
+        let mut cursor = snapshot_id;
+        while let Some(prev) = self.prev_in_entry(cursor) {
+            let snapshot = self.opcode_snapshot(prev)?;
+            if matches!(
+                snapshot.opcode,
+                CALL | CALLCODE | DELEGATECALL | STATICCALL | CREATE | CREATE2
+            ) {
+                return Some(prev);
+            }
+            cursor = prev;
+        }
+        None
+    }
+
+    fn opcode_snapshot(&self, snapshot_id: usize) -> Option<&'a OpcodeSnapshot<DB>> {
+        match self.context.snapshots.get(snapshot_id)?.1.detail() {
+            SnapshotDetail::Opcode(snapshot) => Some(snapshot),
+            SnapshotDetail::Hook(_) => None,
+        }
+    }
+
+    /// Build a step for the given snapshot, resolving its source location for hook snapshots
+    fn step(
+        &self,
+        snapshot_id: usize,
+        written: ValueLocation,
+        read_from: Option<ValueLocation>,
+    ) -> Result<ValueOriginStep> {
+        let (frame_id, snapshot) = &self.context.snapshots[snapshot_id];
+
+        let mut step = ValueOriginStep {
+            snapshot_id,
+            frame_id: *frame_id,
+            bytecode_address: snapshot.bytecode_address(),
+            pc: None,
+            opcode: None,
+            path: None,
+            offset: None,
+            length: None,
+            line: None,
+            written,
+            read_from,
+        };
+
+        match snapshot.detail() {
+            SnapshotDetail::Opcode(detail) => {
+                step.pc = Some(detail.pc);
+                step.opcode = Some(detail.opcode);
+            }
+            SnapshotDetail::Hook(detail) => {
+                if let Some(source) =
+                    self.context.get_step_source(detail.bytecode_address, detail.usid)
+                {
+                    step.path = Some(source.path);
+                    step.offset = source.offset;
+                    step.length = source.length;
+                }
+                step.line = self
+                    .context
+                    .get_step_line(detail.bytecode_address, detail.usid)
+                    .map(|(_, line)| line);
+            }
+        }
+
+        Ok(step)
+    }
+}
+
+/// Stack item at the given depth from the top
+fn stack_item<DB>(snapshot: &OpcodeSnapshot<DB>, depth: usize) -> Result<U256>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone,
+    <CacheDB<DB> as Database>::Error: Clone,
+    <DB as Database>::Error: Clone,
+{
+    snapshot
+        .stack
+        .len()
+        .checked_sub(depth + 1)
+        .map(|index| snapshot.stack[index])
+        .ok_or_else(|| eyre!("Stack underflow at pc {}", snapshot.pc))
+}
+
+/// Stack item at the given depth from the top, as a memory offset or size
+fn stack_usize<DB>(snapshot: &OpcodeSnapshot<DB>, depth: usize) -> Result<usize>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone,
+    <CacheDB<DB> as Database>::Error: Clone,
+    <DB as Database>::Error: Clone,
+{
+    let value = stack_item(snapshot, depth)?;
+    usize::try_from(value).map_err(|_| eyre!("Stack value {value} is not a valid offset"))
+}
+
+/// Read a memory range, treating bytes past the end as zero
+fn read_padded(memory: &[u8], offset: usize, size: usize) -> Vec<u8> {
+    let mut data = vec![0u8; size];
+    if offset < memory.len() {
+        let end = memory.len().min(offset.saturating_add(size));
+        data[..end - offset].copy_from_slice(&memory[offset..end]);
+    }
+    data
+}
+
+/// Whether `[start, start + len)` fully covers `[offset, offset + size)`
+fn contains(start: usize, len: usize, offset: usize, size: usize) -> bool {
+    offset >= start && offset.saturating_add(size) <= start.saturating_add(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        prepare_test_context, CALLEE_CONTRACT, CALLER_CONTRACT, CALLER_MSTORE_PC,
+    };
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_trace_through_call() {
+        let context = prepare_test_context().await;
+        let address = |id: usize| context.snapshots[id].1.bytecode_address();
+        let entry =
+            (0..context.snapshots.len()).find(|id| address(*id) == CALLEE_CONTRACT).unwrap();
+        let call = entry - 1;
+
+        // The gas forwarded to the callee was read from the environment
+        let origin = trace_value_origin(&context, call, ValueLocation::Stack { depth: 0 }).unwrap();
+        assert_eq!(origin.kind, ValueOriginKind::Environment);
+        assert_eq!(origin.steps[0].opcode, Some(GAS));
+
+        // The memory passed as call input was written by MSTORE from a pushed constant
+        let memory = ValueLocation::Memory { offset: 0, size: 32 };
+        let origin = trace_value_origin(&context, call, memory).unwrap();
+        assert_eq!(origin.kind, ValueOriginKind::Constant);
+        assert_eq!(origin.steps[0].pc, Some(CALLER_MSTORE_PC));
+        assert_eq!(origin.steps[0].read_from, Some(ValueLocation::Stack { depth: 1 }));
+        assert_eq!(origin.origin_step().unwrap().opcode, Some(PUSH1));
+
+        // The callee's calldata is followed back into the caller's memory
+        let calldata = ValueLocation::Calldata { offset: 0, size: 32 };
+        let origin = trace_value_origin(&context, entry, calldata).unwrap();
+        assert_eq!(origin.kind, ValueOriginKind::Constant);
+        assert_eq!(origin.steps[0].snapshot_id, call);
+        assert_eq!(origin.steps[0].read_from, Some(memory));
+        assert_eq!(origin.steps[1].bytecode_address, CALLER_CONTRACT);
+        assert_eq!(origin.steps[1].pc, Some(CALLER_MSTORE_PC));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reject_large_ranges() {
+        let context = prepare_test_context().await;
+        let huge = ValueLocation::Memory { offset: 0, size: 1 << 50 };
+        assert!(trace_value_origin(&context, 1, huge).is_err());
+        let overflowing = ValueLocation::Calldata { offset: usize::MAX, size: 32 };
+        assert!(trace_value_origin(&context, 1, overflowing).is_err());
+    }
+
+    #[test]
+    fn test_read_padded() {
+        let memory = [1u8, 2, 3, 4];
+        assert_eq!(read_padded(&memory, 1, 2), vec![2, 3]);
+        assert_eq!(read_padded(&memory, 2, 4), vec![3, 4, 0, 0]);
+        assert_eq!(read_padded(&memory, 8, 2), vec![0, 0]);
+    }
+
+    #[test]
+    fn test_contains() {
+        assert!(contains(0x80, 32, 0x80, 32));
+        assert!(contains(0x80, 32, 0x90, 16));
+        assert!(!contains(0x80, 32, 0x90, 32));
+        assert!(!contains(0x80, 32, 0x60, 32));
+    }
+}
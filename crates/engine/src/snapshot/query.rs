@@ -0,0 +1,192 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Snapshot queries over the timeline.
+//!
+//! A query returns every snapshot at which a boolean expression holds. Snapshots are
+//! first narrowed down by a [`SnapshotFilter`], which is cheap to check, and the
+//! expression is then evaluated with the [`ExpressionEvaluator`] at the remaining
+//! snapshots in parallel. Each worker thread uses its own evaluator.
+//!
+//! Snapshots at which the expression fails to evaluate (e.g., a variable out of scope)
+//! are considered not to match, unless it fails at every one of them.
+
+use std::sync::Arc;
+
+use alloy_dyn_abi::DynSolValue;
+use edb_common::types::{SnapshotFilter, SnapshotKind};
+use eyre::{bail, Result};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use revm::{database::CacheDB, Database, DatabaseCommit, DatabaseRef};
+
+use crate::{EngineContext, ExpressionEvaluator, Snapshot, SnapshotDetail};
+
+/// Find every snapshot passing `filter` at which `expr` evaluates to `true`.
+///
+/// # Returns
+/// The matching snapshot IDs in timeline order.
+///
+/// # Errors
+/// Returns an error if the filter names a function without the address of its code, if
+/// the expression does not evaluate to a boolean, or if it cannot be evaluated at any of
+/// the filtered snapshots.
+pub fn find_snapshots<DB>(
+    context: &Arc<EngineContext<DB>>,
+    expr: &str,
+    filter: &SnapshotFilter,
+) -> Result<Vec<usize>>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    if filter.function.is_some() && filter.address.is_none() {
+        bail!("Filtering by function requires the address of its code, as UFIDs are only unique within a contract");
+    }
+
+    let candidates: Vec<usize> = context
+        .snapshots
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, snapshot))| passes_filter(context, snapshot, filter))
+        .map(|(id, _)| id)
+        .collect();
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let results: Vec<(usize, Result<DynSolValue>)> = candidates
+        .into_par_iter()
+        .map_init(
+            || ExpressionEvaluator::new_edb(context.clone()),
+            |evaluator, id| (id, evaluator.eval(expr, id)),
+        )
+        .collect();
+
+    select_matches(expr, results)
+}
+
+/// Returns whether a snapshot passes all conditions of the filter.
+fn passes_filter<DB>(
+    context: &EngineContext<DB>,
+    snapshot: &Snapshot<DB>,
+    filter: &SnapshotFilter,
+) -> bool
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    if let Some(address) = filter.address {
+        if snapshot.target_address() != address && snapshot.bytecode_address() != address {
+            return false;
+        }
+    }
+
+    match (filter.kind, snapshot.is_hook()) {
+        (Some(SnapshotKind::Opcode), true) | (Some(SnapshotKind::Hook), false) => return false,
+        _ => {}
+    }
+
+    if !filter.needs_source() {
+        return true;
+    }
+
+    // Functions and source files are only known for source-level steps
+    let SnapshotDetail::Hook(detail) = snapshot.detail() else { return false };
+    let Some(step) = context.get_step(detail.bytecode_address, detail.usid) else { return false };
+
+    // UFIDs are scoped by the code they are analyzed from
+    if let Some(function) = filter.function {
+        if filter.address != Some(detail.bytecode_address) || u64::from(step.ufid()) != function {
+            return false;
+        }
+    }
+
+    match &filter.path {
+        Some(path) => context
+            .get_step_source(detail.bytecode_address, detail.usid)
+            .is_some_and(|source| source.path.ends_with(path)),
+        None => true,
+    }
+}
+
+/// Select the snapshots at which the expression holds.
+///
+/// A non-boolean value is reported right away, while evaluation failures are only
+/// reported if the expression could not be evaluated anywhere.
+fn select_matches(expr: &str, results: Vec<(usize, Result<DynSolValue>)>) -> Result<Vec<usize>> {
+    let mut matches = Vec::new();
+    let mut evaluated = false;
+    let mut last_error = None;
+
+    for (id, result) in results {
+        match result {
+            Ok(DynSolValue::Bool(holds)) => {
+                evaluated = true;
+                if holds {
+                    matches.push(id);
+                }
+            }
+            Ok(_) => bail!("{expr} does not evaluate to a boolean"),
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    match last_error {
+        Some(e) if !evaluated => bail!("Cannot evaluate {expr}: {e}"),
+        _ => Ok(matches),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use eyre::eyre;
+
+    use crate::test_utils::{prepare_test_context, CALLER_CONTRACT};
+
+    #[test]
+    fn test_select_matches() {
+        let results = vec![
+            (1, Ok(DynSolValue::Bool(true))),
+            (3, Err(eyre!("out of scope"))),
+            (4, Ok(DynSolValue::Bool(false))),
+            (7, Ok(DynSolValue::Bool(true))),
+        ];
+        assert_eq!(select_matches("x", results).unwrap(), vec![1, 7]);
+
+        // Failing everywhere is an error, rather than no match
+        let results = vec![(1, Err(eyre!("out of scope"))), (2, Err(eyre!("out of scope")))];
+        assert!(select_matches("x", results).is_err());
+
+        let results =
+            vec![(1, Ok(DynSolValue::Bool(true))), (2, Ok(DynSolValue::Uint(U256::from(1), 256)))];
+        assert!(select_matches("x", results).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_function_filter_needs_address() {
+        let context = Arc::new(prepare_test_context().await);
+        let filter = SnapshotFilter { function: Some(1), ..Default::default() };
+        assert!(find_snapshots(&context, "true", &filter).is_err());
+
+        // The fixture contracts have no source, so none of their steps is in a function
+        let filter = SnapshotFilter { address: Some(CALLER_CONTRACT), ..filter };
+        assert_eq!(find_snapshots(&context, "true", &filter).unwrap(), Vec::<usize>::new());
+    }
+}
@@ -0,0 +1,290 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Watchpoint resolution over the snapshot timeline.
+//!
+//! A watchpoint fires whenever the watched state differs between two consecutive
+//! snapshots at which it can be observed:
+//!
+//! - **Storage slots** are observed at every snapshot through its database. Consecutive
+//!   snapshots sharing the same database are skipped, so only steps that modified state
+//!   (SSTOREs, calls, reverts, or instrumented source steps) are actually compared.
+//! - **Transient storage slots** are observed at opcode snapshots only, which are the
+//!   only ones recording transient storage. TSTOREs made inside instrumented frames are
+//!   therefore detected at the next opcode snapshot.
+//! - **State variables** are observed at hook snapshots of the owning contract, whose
+//!   state variables have been pre-evaluated when the snapshots were finalized.
+//!
+//! Since no expression has to be evaluated, scanning a long transaction is cheap
+//! compared to a conditional breakpoint.
+
+use std::sync::Arc;
+
+use alloy_dyn_abi::DynSolValue;
+use alloy_primitives::U256;
+use edb_common::types::{EdbSolValue, Watchpoint, WatchpointHit};
+use eyre::{bail, eyre, Result};
+use revm::{database::CacheDB, Database, DatabaseCommit, DatabaseRef};
+
+use crate::{EngineContext, SnapshotDetail};
+
+/// Find the first change of the watched state after `snapshot_id`.
+///
+/// # Returns
+/// The change whose new value is first observed after `snapshot_id`, or `None` if the
+/// watched state does not change until the end of the timeline.
+///
+/// # Errors
+/// Returns an error if the snapshot does not exist, or if a state variable watchpoint
+/// refers to a variable that is never recorded for its address.
+pub fn find_next_watchpoint_hit<DB>(
+    context: &EngineContext<DB>,
+    watchpoint: &Watchpoint,
+    snapshot_id: usize,
+) -> Result<Option<WatchpointHit>>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let mut observer = WatchpointObserver::new(context, watchpoint, snapshot_id)?;
+    let change = next_change(snapshot_id, context.snapshots.len(), |id| observer.observe(id))?;
+    Ok(change.map(Into::into))
+}
+
+/// Find the last change of the watched state before `snapshot_id`.
+///
+/// # Returns
+/// The latest change whose new value becomes visible before `snapshot_id`, or `None`
+/// if the watched state never changes before it.
+///
+/// # Errors
+/// Same as [`find_next_watchpoint_hit`].
+pub fn find_prev_watchpoint_hit<DB>(
+    context: &EngineContext<DB>,
+    watchpoint: &Watchpoint,
+    snapshot_id: usize,
+) -> Result<Option<WatchpointHit>>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let mut observer = WatchpointObserver::new(context, watchpoint, snapshot_id)?;
+    let change = prev_change(snapshot_id, |id| observer.observe(id))?;
+    Ok(change.map(Into::into))
+}
+
+/// Reads the watched state at individual snapshots
+struct WatchpointObserver<'a, DB>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    context: &'a EngineContext<DB>,
+    watchpoint: &'a Watchpoint,
+    /// Last database read for a storage watchpoint, with the slot value it holds
+    last_db: Option<(Arc<CacheDB<DB>>, U256)>,
+}
+
+impl<'a, DB> WatchpointObserver<'a, DB>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    fn new(
+        context: &'a EngineContext<DB>,
+        watchpoint: &'a Watchpoint,
+        snapshot_id: usize,
+    ) -> Result<Self> {
+        if snapshot_id >= context.snapshots.len() {
+            bail!("Snapshot with id {snapshot_id} not found");
+        }
+
+        if let Watchpoint::StateVariable { address, name } = watchpoint {
+            let recorded = context.snapshots.iter().any(|(_, snapshot)| match snapshot.detail() {
+                SnapshotDetail::Hook(detail) => {
+                    detail.target_address == *address && detail.state_variables.contains_key(name)
+                }
+                SnapshotDetail::Opcode(_) => false,
+            });
+            if !recorded {
+                bail!(
+                    "State variable {name} is not recorded for {address} (only source-level \
+                     contracts and variables without parameters can be watched)"
+                );
+            }
+        }
+
+        Ok(Self { context, watchpoint, last_db: None })
+    }
+
+    /// Observe the watched state at a snapshot, `None` if it is not observable there.
+    fn observe(&mut self, snapshot_id: usize) -> Result<Option<EdbSolValue>> {
+        let (_, snapshot) = &self.context.snapshots[snapshot_id];
+
+        match self.watchpoint {
+            Watchpoint::Storage { address, slot } => {
+                let db = snapshot.db();
+                let value = match &self.last_db {
+                    Some((last_db, value)) if Arc::ptr_eq(last_db, &db) => *value,
+                    _ => {
+                        let value = db.storage_ref(*address, *slot).map_err(|e| {
+                            eyre!("Failed to retrieve storage at {address} for slot {slot}: {e}")
+                        })?;
+                        self.last_db = Some((db, value));
+                        value
+                    }
+                };
+                Ok(Some(DynSolValue::Uint(value, 256).into()))
+            }
+            Watchpoint::TransientStorage { address, slot } => match snapshot.detail() {
+                SnapshotDetail::Opcode(detail) => {
+                    let value = detail
+                        .transient_storage
+                        .get(&(*address, *slot))
+                        .copied()
+                        .unwrap_or_default();
+                    Ok(Some(DynSolValue::Uint(value, 256).into()))
+                }
+                SnapshotDetail::Hook(_) => Ok(None),
+            },
+            Watchpoint::StateVariable { address, name } => match snapshot.detail() {
+                SnapshotDetail::Hook(detail) if detail.target_address == *address => Ok(detail
+                    .state_variables
+                    .get(name)
+                    .and_then(|value| value.as_ref())
+                    .map(|value| (**value).clone())),
+                _ => Ok(None),
+            },
+        }
+    }
+}
+
+/// Find the first change observed after `from`.
+///
+/// The value at `from` is compared against the last observation at or before it, so a
+/// change that becomes visible right after `from` is reported.
+fn next_change<T: PartialEq>(
+    from: usize,
+    len: usize,
+    mut observe: impl FnMut(usize) -> Result<Option<T>>,
+) -> Result<Option<Change<T>>> {
+    let mut last = None;
+    for id in (0..=from).rev() {
+        if let Some(value) = observe(id)? {
+            last = Some((id, value));
+            break;
+        }
+    }
+
+    for id in from + 1..len {
+        let Some(value) = observe(id)? else { continue };
+        if let Some((prev_id, old)) = last.take() {
+            if old != value {
+                return Ok(Some(Change {
+                    snapshot_id: id,
+                    prev_snapshot_id: prev_id,
+                    old,
+                    new: value,
+                }));
+            }
+        }
+        last = Some((id, value));
+    }
+
+    Ok(None)
+}
+
+/// Find the last change observed before `from`.
+fn prev_change<T: PartialEq>(
+    from: usize,
+    mut observe: impl FnMut(usize) -> Result<Option<T>>,
+) -> Result<Option<Change<T>>> {
+    let mut later = None;
+    for id in (0..from).rev() {
+        let Some(value) = observe(id)? else { continue };
+        if let Some((later_id, new)) = later.take() {
+            if new != value {
+                return Ok(Some(Change {
+                    snapshot_id: later_id,
+                    prev_snapshot_id: id,
+                    old: value,
+                    new,
+                }));
+            }
+        }
+        later = Some((id, value));
+    }
+
+    Ok(None)
+}
+
+/// A change between two consecutive observations
+#[derive(Debug, PartialEq)]
+struct Change<T> {
+    snapshot_id: usize,
+    prev_snapshot_id: usize,
+    old: T,
+    new: T,
+}
+
+impl From<Change<EdbSolValue>> for WatchpointHit {
+    fn from(change: Change<EdbSolValue>) -> Self {
+        Self {
+            snapshot_id: change.snapshot_id,
+            prev_snapshot_id: change.prev_snapshot_id,
+            old_value: change.old,
+            new_value: change.new,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observe(values: &[Option<u32>]) -> impl FnMut(usize) -> Result<Option<u32>> + '_ {
+        move |id| Ok(values[id])
+    }
+
+    #[test]
+    fn test_next_change() {
+        let values = [Some(1), None, Some(1), Some(2), None, Some(3)];
+
+        let change = next_change(0, values.len(), observe(&values)).unwrap().unwrap();
+        assert_eq!(change, Change { snapshot_id: 3, prev_snapshot_id: 2, old: 1, new: 2 });
+
+        // The baseline is the last observation at or before the starting snapshot
+        let change = next_change(4, values.len(), observe(&values)).unwrap().unwrap();
+        assert_eq!(change, Change { snapshot_id: 5, prev_snapshot_id: 3, old: 2, new: 3 });
+
+        assert!(next_change(5, values.len(), observe(&values)).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_prev_change() {
+        let values = [Some(1), None, Some(1), Some(2), None, Some(3)];
+
+        let change = prev_change(5, observe(&values)).unwrap().unwrap();
+        assert_eq!(change, Change { snapshot_id: 3, prev_snapshot_id: 2, old: 1, new: 2 });
+
+        // A change at the starting snapshot itself is not reported
+        assert!(prev_change(3, observe(&values)).unwrap().is_none());
+    }
+}
@@ -0,0 +1,123 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Test fixtures of the engine.
+//!
+//! [`prepare_test_context`] runs the preparation pipeline on a small transaction over an
+//! in-memory database, without network access. No contract has source code, so the
+//! timeline only has opcode snapshots:
+//!
+//! 1. [`SENDER`] calls [`CALLER_CONTRACT`], which stores `42` in slot `0` and at memory
+//!    offset `0`, and calls [`CALLEE_CONTRACT`] with that memory word as calldata
+//! 2. [`CALLEE_CONTRACT`] emits `Ping()` and reverts
+//! 3. [`CALLER_CONTRACT`] ignores the failure and stops
+
+use std::collections::HashMap;
+
+use alloy_primitives::{address, keccak256, Address, Bytes, TxKind, B256, U256};
+use edb_common::{ForkInfo, ForkResult};
+use revm::{
+    context::TxEnv,
+    database::{CacheDB, EmptyDB},
+    primitives::hardfork::SpecId,
+    state::{AccountInfo, Bytecode},
+    Context, MainContext,
+};
+
+use crate::{Engine, EngineConfig, EngineContext};
+
+/// Database of the test transaction
+pub(crate) type TestDB = CacheDB<EmptyDB>;
+
+/// Sender of the test transaction
+pub(crate) const SENDER: Address = address!("0x00000000000000000000000000000000000000cc");
+
+/// Contract called by the test transaction
+pub(crate) const CALLER_CONTRACT: Address = address!("0x00000000000000000000000000000000000000aa");
+
+/// Contract called by [`CALLER_CONTRACT`], which emits `Ping()` and reverts
+pub(crate) const CALLEE_CONTRACT: Address = address!("0x00000000000000000000000000000000000000bb");
+
+/// PC of the `MSTORE` opcode of [`CALLER_CONTRACT`]
+pub(crate) const CALLER_MSTORE_PC: usize = 9;
+
+/// PC of the `LOG1` opcode of [`CALLEE_CONTRACT`]
+pub(crate) const CALLEE_LOG_PC: usize = 37;
+
+/// First topic of the `Ping()` event
+pub(crate) fn ping_topic() -> B256 {
+    keccak256("Ping()")
+}
+
+/// Runtime code of [`CALLER_CONTRACT`]
+fn caller_code() -> Bytes {
+    let mut code = vec![0x60, 0x2a, 0x60, 0x00, 0x55]; // SSTORE(0, 42)
+    code.extend([0x60, 0x2a, 0x60, 0x00, 0x52]); // MSTORE(0, 42)
+    code.extend([0x60, 0x00, 0x60, 0x00]); // retSize, retOffset
+    code.extend([0x60, 0x20, 0x60, 0x00, 0x60, 0x00]); // argsSize, argsOffset, value
+    code.push(0x73); // PUSH20 callee
+    code.extend(CALLEE_CONTRACT.as_slice());
+    code.extend([0x5a, 0xf1, 0x50, 0x00]); // GAS, CALL, POP, STOP
+    code.into()
+}
+
+/// Runtime code of [`CALLEE_CONTRACT`]
+fn callee_code() -> Bytes {
+    let mut code = vec![0x7f]; // PUSH32 topic
+    code.extend(ping_topic().as_slice());
+    code.extend([0x60, 0x00, 0x60, 0x00, 0xa1]); // LOG1(0, 0, topic)
+    code.extend([0x60, 0x00, 0x60, 0x00, 0xfd]); // REVERT(0, 0)
+    code.into()
+}
+
+/// Prepare the context of the test transaction, as [`Engine::prepare`] would
+pub(crate) async fn prepare_test_context() -> EngineContext<TestDB> {
+    let mut db = CacheDB::new(CacheDB::new(EmptyDB::default()));
+    db.insert_account_info(SENDER, AccountInfo::from_balance(U256::from(10).pow(U256::from(18))));
+    for (address, code) in [(CALLER_CONTRACT, caller_code()), (CALLEE_CONTRACT, callee_code())] {
+        db.insert_account_info(address, AccountInfo::default().with_code(Bytecode::new_raw(code)));
+    }
+
+    let context = Context::mainnet().with_db(db).modify_cfg_chained(|cfg| {
+        cfg.chain_id = 1;
+        cfg.spec = SpecId::CANCUN;
+    });
+    let tx = TxEnv {
+        caller: SENDER,
+        kind: TxKind::Call(CALLER_CONTRACT),
+        gas_limit: 1_000_000,
+        chain_id: Some(1),
+        ..Default::default()
+    };
+    let fork_result = ForkResult {
+        fork_info: ForkInfo {
+            block_number: 1,
+            block_hash: B256::ZERO,
+            timestamp: 1,
+            chain_id: 1,
+            spec_id: SpecId::CANCUN,
+        },
+        context,
+        target_tx_env: tx,
+        target_tx_hash: B256::with_last_byte(1),
+    };
+
+    let engine = Engine::new(EngineConfig::default());
+    let replay_result = engine
+        .replay_and_collect_trace(fork_result.context.clone(), fork_result.target_tx_env.clone())
+        .unwrap();
+    engine.prepare_with_artifacts(fork_result, replay_result, HashMap::new()).await.unwrap()
+}
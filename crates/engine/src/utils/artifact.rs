@@ -35,9 +35,18 @@
 //! - **Code Tweaking**: Supporting bytecode replacement through recompilation
 //! - **Analysis Engine**: Providing source code and ABI data for instrumentation
 
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
 use alloy_primitives::Bytes;
+use eyre::{bail, Result};
 use foundry_block_explorers::contract::Metadata;
-use foundry_compilers::artifacts::{CompilerOutput, Contract, SolcInput};
+use foundry_compilers::{
+    artifacts::{CompilerOutput, Contract, SolcInput},
+    solc::Solc,
+};
 use serde::{Deserialize, Serialize};
 use tracing::error;
 
@@ -106,4 +115,62 @@ impl Artifact {
 
         hooks
     }
+
+    /// Find the source file of the compilation input that a (possibly local) path refers
+    /// to. The path matches a source file if it is the same path, if it ends with the
+    /// source path, or if the file names are the same, as long as the match is unique.
+    pub fn find_source_path(&self, path: &Path) -> Result<PathBuf> {
+        let sources: Vec<&PathBuf> = self.input.sources.keys().collect();
+        if let Some(source) = sources.iter().find(|source| source.as_path() == path) {
+            return Ok((*source).clone());
+        }
+
+        let candidates: Vec<&PathBuf> = sources
+            .iter()
+            .copied()
+            .filter(|source| path.ends_with(source) || source.ends_with(path))
+            .collect();
+        let candidates = if candidates.is_empty() {
+            sources.into_iter().filter(|source| source.file_name() == path.file_name()).collect()
+        } else {
+            candidates
+        };
+
+        match candidates.as_slice() {
+            [source] => Ok((*source).clone()),
+            [] => bail!("No source file of {} matches {}", self.contract_name(), path.display()),
+            _ => bail!(
+                "Ambiguous source file {}, candidates: {}",
+                path.display(),
+                candidates.iter().map(|c| c.display().to_string()).collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+
+    /// Replace the content of a source file and recompile the artifact.
+    ///
+    /// The patched artifact keeps the metadata (e.g., compiler settings and constructor
+    /// arguments) of the original one.
+    pub fn patch_source(&self, path: &Path, content: String) -> Result<Self> {
+        let path = self.find_source_path(path)?;
+
+        let mut input = self.input.clone();
+        if let Some(source) = input.sources.get_mut(&path) {
+            source.content = Arc::new(content);
+        }
+
+        let compiler = Solc::find_or_install(&self.meta.compiler_version()?)?;
+        let output = compiler.compile_exact(&input)?;
+        let errors: Vec<_> = output
+            .errors
+            .iter()
+            .filter(|e| e.is_error())
+            .map(|e| e.formatted_message.clone().unwrap_or_else(|| e.message.clone()))
+            .collect();
+        if !errors.is_empty() {
+            bail!("Failed to compile patched {}:\n{}", path.display(), errors.join("\n"));
+        }
+
+        Ok(Self { meta: self.meta.clone(), input, output })
+    }
 }
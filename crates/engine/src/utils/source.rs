@@ -93,6 +93,24 @@ pub fn slice_source_location(src: &SourceLocation, start: usize, length: usize)
     }
 }
 
+/// Convert a byte offset in a source to a 1-based line and column.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `offset` - The byte offset, e.g. the start of a source location
+///
+/// # Returns
+///
+/// The line and column of the offset, or `None` if the offset is out of the source or
+/// not at a character boundary.
+pub fn offset_to_line_column(source: &str, offset: usize) -> Option<(usize, usize)> {
+    let before = source.get(..offset)?;
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    Some((line, column))
+}
+
 /// Convert the visibility to a string.
 ///
 /// # Arguments
@@ -475,6 +493,17 @@ mod tests {
     use super::*;
     use semver::VersionReq;
 
+    #[test]
+    fn test_offset_to_line_column() {
+        let source = "contract A {\n    uint x;\n}\n";
+        assert_eq!(offset_to_line_column(source, 0), Some((1, 1)));
+        assert_eq!(offset_to_line_column(source, 9), Some((1, 10)));
+        assert_eq!(offset_to_line_column(source, 13), Some((2, 1)));
+        assert_eq!(offset_to_line_column(source, 22), Some((2, 10)));
+        assert_eq!(offset_to_line_column(source, source.len()), Some((4, 1)));
+        assert_eq!(offset_to_line_column(source, source.len() + 1), None);
+    }
+
     #[test]
     fn test_abi_encode_available_exact_versions() {
         // Test exact versions that should return true (>= 0.4.24, so no versions < 0.4.24 allowed)
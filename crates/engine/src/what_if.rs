@@ -0,0 +1,437 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! What-if analysis: edit the state at a snapshot and re-execute the rest of the
+//! transaction.
+//!
+//! A what-if re-execution replays the instrumented transaction from the state right
+//! before it, and applies the [`StateEdit`]s when the replay reaches the edited
+//! snapshot. The replay follows the same workflow as the original collection:
+//!
+//! 1. **Tracing**: Replay with the edits to collect the derived call trace
+//! 2. **Collection**: Replay again with the edits to collect opcode and hook snapshots
+//! 3. **Packing**: Build a new [`EngineContext`] from the derived trace and snapshots
+//!
+//! The derived timeline shares the prefix of the original one, and can be debugged (and
+//! edited again) like the original one. [`diff_timelines`] summarizes how it differs.
+//!
+//! Local variables are edited at hook snapshots. Their types come from the analysis of
+//! the snapshot's step, and the [`StateEditInspector`] locates them on the stack or in
+//! memory during the replay. Only locals of value types, `bytes` and `string` can be
+//! edited.
+
+use std::collections::{BTreeSet, HashSet};
+
+use alloy_dyn_abi::DynSolType;
+use alloy_primitives::{Address, Bytes};
+use edb_common::{
+    relax_evm_constraints,
+    types::{StateEdit, StorageChange, TimelineDiff},
+    EdbContext,
+};
+use eyre::{bail, eyre, Result};
+use foundry_compilers::artifacts::{Contract, StorageLocation};
+use revm::{
+    database::CacheDB,
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    Context, Database, DatabaseCommit, DatabaseRef, InspectEvm, Inspector, MainBuilder,
+    MainContext,
+};
+use tracing::{debug, info};
+
+use crate::{
+    analysis::dyn_sol_type, CallTracer, EngineContext, HookSnapshotInspector, LocalEdit,
+    OpcodeSnapshotInspector, Snapshot, SnapshotAnalysis, SnapshotDetail, Snapshots,
+    StateEditInspector, USID,
+};
+
+/// Edit the state at a snapshot and re-execute the rest of the transaction.
+///
+/// # Arguments
+///
+/// * `context` - The timeline to derive from
+/// * `snapshot_id` - The snapshot at which the edits are applied
+/// * `edits` - The state edits to apply, after the changes of the session overlay
+///
+/// # Returns
+///
+/// Returns the derived timeline as a finalized [`EngineContext`].
+///
+/// # Errors
+///
+/// Returns an error if the snapshot does not exist, if stack or memory edits are applied
+/// at a hook snapshot, if local variables are edited at an opcode snapshot or are not
+/// accessible, or if any edit cannot be applied during the re-execution.
+pub fn re_execute_with_edits<DB>(
+    context: &EngineContext<DB>,
+    snapshot_id: usize,
+    edits: &[StateEdit],
+) -> Result<EngineContext<DB>>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let Some((frame_id, snapshot)) = context.snapshots.get(snapshot_id) else {
+        bail!("Snapshot with id {snapshot_id} not found");
+    };
+
+    // Changes of the session overlay come first, so that the edits can override them
+    let overlay = context.overlay();
+    let edits = [overlay.state_edits(), edits.to_vec()].concat();
+    let edits = edits.as_slice();
+    if edits.is_empty() {
+        bail!("No state edit to apply");
+    }
+    if snapshot.is_hook() && edits.iter().any(|edit| edit.is_interpreter_edit()) {
+        bail!("Stack and memory edits are only supported at opcode snapshots");
+    }
+    let locals = resolve_locals(context, snapshot_id, snapshot, edits)?;
+
+    let ordinal = context.snapshots[..snapshot_id].iter().filter(|(id, _)| id == frame_id).count();
+    let at_hook = snapshot.is_hook();
+
+    let mut block = context.block.clone();
+    overlay.apply_to_block(&mut block);
+    let mut ctx: EdbContext<DB> = Context::mainnet()
+        .with_db(CacheDB::clone(&context.pre_tx_db))
+        .with_cfg(context.cfg.clone())
+        .with_block(block.clone());
+    let mut tx = context.tx.clone();
+    relax_evm_constraints(&mut ctx, &mut tx);
+
+    // Step 1: Replay with the edits to collect the derived call trace
+    info!("Re-executing transaction with {} state edits at snapshot {}", edits.len(), snapshot_id);
+    let mut tracer = CallTracer::new();
+    let mut editor = StateEditInspector::new(*frame_id, ordinal, at_hook, edits);
+    editor.with_creation_hooks(creation_hooks(context))?;
+    editor.with_locals(locals.clone());
+    {
+        let mut evm = ctx.clone().build_mainnet_with_inspector((&mut tracer, &mut editor));
+        evm.inspect_one_tx(tx.clone())
+            .map_err(|e| eyre!("Failed to re-execute the target transaction: {:?}", e))?;
+    }
+    editor.into_result()?;
+    let trace = tracer.into_replay_result().execution_trace;
+
+    // Step 2: Replay again with the edits to collect snapshots
+    let mut recorder = EditingRecorder {
+        editor: StateEditInspector::new(*frame_id, ordinal, at_hook, edits),
+        recorder: OpcodeSnapshotInspector::new(&ctx, &trace),
+        hooks: HookSnapshotInspector::new(&trace, &context.analysis_results),
+        address: snapshot.bytecode_address(),
+        locals: &locals,
+    };
+    recorder.editor.with_locals(locals.clone());
+    recorder.recorder.with_excluded_addresses(context.artifacts.keys().copied().collect());
+    recorder.hooks.with_creation_hooks(creation_hooks(context))?;
+    {
+        let mut evm = ctx.build_mainnet_with_inspector(&mut recorder);
+        evm.inspect_one_tx(tx)
+            .map_err(|e| eyre!("Failed to re-execute the target transaction: {:?}", e))?;
+    }
+    let EditingRecorder { editor, recorder, hooks, .. } = recorder;
+    editor.into_result()?;
+
+    // Step 3: Pack the derived timeline
+    let mut snapshots = Snapshots::merge(recorder.into_snapshots(), hooks.into_snapshots());
+    snapshots.analyze(&trace, &context.analysis_results)?;
+    debug!("Derived timeline has {} snapshots", snapshots.len());
+
+    EngineContext::build(
+        context.fork_info.clone(),
+        context.cfg.clone(),
+        block,
+        context.tx.clone(),
+        context.tx_hash,
+        snapshots,
+        context.artifacts.clone(),
+        context.recompiled_artifacts.clone(),
+        context.analysis_results.clone(),
+        trace,
+        context.pre_tx_db.clone(),
+        context.pre_tx_original_db.clone(),
+        context.engine_config.clone(),
+    )
+}
+
+/// Compare a derived timeline against the timeline it is derived from.
+///
+/// Storage is compared at the last snapshot of each timeline, for every slot that is
+/// cached by either of them.
+pub fn diff_timelines<DB>(
+    original: &EngineContext<DB>,
+    derived: &EngineContext<DB>,
+) -> Result<TimelineDiff>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let steps = |context: &EngineContext<DB>| -> Vec<_> {
+        context
+            .snapshots
+            .iter()
+            .map(|(frame_id, snapshot)| (*frame_id, step_of(snapshot)))
+            .collect()
+    };
+    let divergence = first_divergence(&steps(original), &steps(derived));
+
+    let mut storage_changes = Vec::new();
+    if let (Some((_, original_last)), Some((_, derived_last))) =
+        (original.snapshots.last(), derived.snapshots.last())
+    {
+        let (original_db, derived_db) = (original_last.db(), derived_last.db());
+
+        let slots: BTreeSet<_> = [&original_db, &derived_db]
+            .into_iter()
+            .flat_map(|db| {
+                db.cache.accounts.iter().flat_map(|(address, account)| {
+                    account.storage.keys().map(move |slot| (*address, *slot))
+                })
+            })
+            .collect();
+
+        for (address, slot) in slots {
+            let read = |db: &CacheDB<DB>| {
+                db.storage_ref(address, slot).map_err(|e| {
+                    eyre!("Failed to retrieve storage at {address} for slot {slot}: {e}")
+                })
+            };
+            let (original_value, derived_value) = (read(&original_db)?, read(&derived_db)?);
+            if original_value != derived_value {
+                storage_changes.push(StorageChange {
+                    address,
+                    slot,
+                    original: original_value,
+                    derived: derived_value,
+                });
+            }
+        }
+    }
+
+    Ok(TimelineDiff {
+        original_snapshot_count: original.snapshots.len(),
+        derived_snapshot_count: derived.snapshots.len(),
+        divergence,
+        original_result: original.trace.first().and_then(|entry| entry.result.clone()),
+        derived_result: derived.trace.first().and_then(|entry| entry.result.clone()),
+        storage_changes,
+    })
+}
+
+/// Records opcode and hook snapshots while applying state edits. The recorded state is
+/// refreshed right after the edits, since they are not made by any executed opcode, and
+/// the edited local variables take their new values.
+struct EditingRecorder<'a, DB>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone,
+    <CacheDB<DB> as Database>::Error: Clone,
+    <DB as Database>::Error: Clone,
+{
+    editor: StateEditInspector<'a>,
+    recorder: OpcodeSnapshotInspector<'a, DB>,
+    hooks: HookSnapshotInspector<'a, DB>,
+    /// Bytecode address of the edited snapshot
+    address: Address,
+    /// Edited local variables
+    locals: &'a [LocalEdit],
+}
+
+impl<'a, DB> Inspector<EdbContext<DB>> for EditingRecorder<'a, DB>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone,
+    <CacheDB<DB> as Database>::Error: Clone,
+    <DB as Database>::Error: Clone,
+{
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EdbContext<DB>) {
+        let applied = self.editor.is_applied();
+        self.editor.step(interp, context);
+        if !applied && self.editor.is_applied() {
+            self.recorder.refresh_state(context);
+            for local in self.locals {
+                self.hooks.set_variable_value(self.address, local.uvid, local.value.clone());
+            }
+        }
+        self.recorder.step(interp, context);
+        self.hooks.step(interp, context);
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, context: &mut EdbContext<DB>) {
+        self.recorder.step_end(interp, context);
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EdbContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.editor.call(context, inputs);
+        self.recorder.call(context, inputs).or_else(|| self.hooks.call(context, inputs))
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EdbContext<DB>,
+        inputs: &CallInputs,
+        outcome: &mut CallOutcome,
+    ) {
+        self.editor.call_end(context, inputs, outcome);
+        self.recorder.call_end(context, inputs, outcome);
+        self.hooks.call_end(context, inputs, outcome);
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EdbContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.editor.create(context, inputs);
+        self.recorder.create(context, inputs).or_else(|| self.hooks.create(context, inputs))
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EdbContext<DB>,
+        inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        self.editor.create_end(context, inputs, outcome);
+        self.recorder.create_end(context, inputs, outcome);
+        self.hooks.create_end(context, inputs, outcome);
+    }
+}
+
+/// Resolve the local variable edits against the variables accessible at a hook snapshot
+fn resolve_locals<DB>(
+    context: &EngineContext<DB>,
+    snapshot_id: usize,
+    snapshot: &Snapshot<DB>,
+    edits: &[StateEdit],
+) -> Result<Vec<LocalEdit>>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let mut locals = Vec::new();
+    for edit in edits {
+        let StateEdit::Local { name, value } = edit else { continue };
+        let Some(usid) = snapshot.usid() else {
+            bail!("Local variables can only be edited at source-level snapshots");
+        };
+        let address = snapshot.bytecode_address();
+        let Some(analysis) = context.analysis_results.get(&address) else {
+            bail!("No analysis found for {address}");
+        };
+        let Some(step) = analysis.usid_to_step.get(&usid) else {
+            bail!("No step found for snapshot {snapshot_id}");
+        };
+        let step = step.read();
+        let Some(variable) = step.accessible_variables.iter().find(|variable| {
+            let declaration = variable.declaration();
+            !declaration.state_variable && declaration.name == *name
+        }) else {
+            bail!("No local variable {name} at snapshot {snapshot_id}");
+        };
+
+        match variable.declaration().storage_location {
+            StorageLocation::Calldata => bail!("{name} points to calldata: edit the calldata"),
+            StorageLocation::Storage => bail!("{name} points to storage: edit the storage"),
+            _ => {}
+        }
+        let Some(ty) = variable
+            .type_name()
+            .and_then(|type_name| dyn_sol_type(&analysis.user_defined_types, type_name))
+        else {
+            bail!("Type of {name} is not supported");
+        };
+        let in_memory = match ty {
+            DynSolType::Bytes | DynSolType::String => true,
+            DynSolType::Bool
+            | DynSolType::Int(_)
+            | DynSolType::Uint(_)
+            | DynSolType::FixedBytes(_)
+            | DynSolType::Address
+            | DynSolType::Function => false,
+            _ => {
+                bail!("Only locals of value types, bytes and string can be edited, {name} is {ty}")
+            }
+        };
+        let value = ty.coerce_str(value).map_err(|e| eyre!("Invalid {ty} value {value}: {e}"))?;
+
+        locals.push(LocalEdit { name: name.clone(), uvid: variable.id(), in_memory, value });
+    }
+    Ok(locals)
+}
+
+/// Creation hooks of the source-available contracts deployed by the transaction
+fn creation_hooks<DB>(context: &EngineContext<DB>) -> Vec<(&Contract, &Contract, &Bytes)>
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone + Send + Sync + 'static,
+    <CacheDB<DB> as Database>::Error: Clone + Send + Sync,
+    <DB as Database>::Error: Clone + Send + Sync,
+{
+    let deployed: HashSet<_> =
+        context.trace.iter().filter(|entry| entry.created_contract).map(|e| e.target).collect();
+
+    deployed
+        .into_iter()
+        .filter_map(|address| {
+            Some((context.artifacts.get(&address)?, context.recompiled_artifacts.get(&address)?))
+        })
+        .flat_map(|(artifact, recompiled)| artifact.find_creation_hooks(recompiled))
+        .collect()
+}
+
+/// The code location executed at a snapshot: the PC for opcode snapshots, or the USID
+/// for hook snapshots
+fn step_of<DB>(snapshot: &Snapshot<DB>) -> (Address, Option<usize>, Option<USID>)
+where
+    DB: Database + DatabaseCommit + DatabaseRef + Clone,
+    <CacheDB<DB> as Database>::Error: Clone,
+    <DB as Database>::Error: Clone,
+{
+    let pc = match snapshot.detail() {
+        SnapshotDetail::Opcode(detail) => Some(detail.pc),
+        SnapshotDetail::Hook(_) => None,
+    };
+    (snapshot.bytecode_address(), pc, snapshot.usid())
+}
+
+/// Index of the first position at which two sequences differ, `None` if they are equal
+fn first_divergence<T: PartialEq>(a: &[T], b: &[T]) -> Option<usize> {
+    a.iter().zip(b).position(|(x, y)| x != y).or_else(|| {
+        if a.len() != b.len() {
+            Some(a.len().min(b.len()))
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_divergence() {
+        assert_eq!(first_divergence(&[1, 2, 3], &[1, 2, 3]), None);
+        assert_eq!(first_divergence(&[1, 2, 3], &[1, 5, 3]), Some(1));
+        assert_eq!(first_divergence(&[1, 2, 3], &[1, 2]), Some(2));
+        assert_eq!(first_divergence::<u8>(&[], &[]), None);
+    }
+}
@@ -77,6 +77,8 @@ async fn main() -> Result<()> {
         refresh_interval: std::time::Duration::from_millis(args.refresh_interval),
         data_fetch_interval: std::time::Duration::from_millis(args.data_fetch_interval),
         enable_mouse: args.mouse,
+        // Read from the environment rather than the command line, which other users can see
+        auth_token: std::env::var(edb_common::types::RPC_TOKEN_ENV).ok(),
     };
 
     tracing::info!("Starting EDB TUI");
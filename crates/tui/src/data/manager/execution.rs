@@ -34,7 +34,11 @@ use std::{
 use tokio::sync::RwLock;
 use tracing::{debug, error};
 
-use edb_common::types::{Breakpoint, BreakpointLocation, Code, SnapshotInfo, Trace};
+use edb_common::types::{
+    Breakpoint, BreakpointHit, BreakpointLocation, Code, HistoryScope, LogpointEntry,
+    NestedCallTimeline, SearchDirection, SnapshotFilter, SnapshotInfo, StateEdit, Trace,
+    ValueHistory, ValueLocation, ValueOrigin, Watchpoint, WatchpointHit, WhatIfResult,
+};
 
 use crate::{
     data::manager::core::{
@@ -43,6 +47,9 @@ use crate::{
     RpcClient,
 };
 
+/// Result of searching a watchpoint hit, with resolution errors kept for display
+pub type WatchpointResult = core::result::Result<Option<WatchpointHit>, String>;
+
 #[derive(Debug, Clone)]
 pub struct ExecutionState {
     snapshot_count: usize,
@@ -50,9 +57,28 @@ pub struct ExecutionState {
     code: FetchCache<Address, Code>,
     next_call: FetchCache<usize, usize>,
     prev_call: FetchCache<usize, usize>,
+    call_stack: FetchCache<usize, Vec<usize>>,
     storage: FetchCache<(usize, U256), U256>,
     storage_diff: FetchCache<usize, HashMap<U256, (U256, U256)>>,
+    value_origin: FetchCache<(usize, ValueLocation), core::result::Result<ValueOrigin, String>>,
+    value_history: FetchCache<(String, HistoryScope), core::result::Result<ValueHistory, String>>,
+    snapshot_matches:
+        FetchCache<(String, SnapshotFilter), core::result::Result<Vec<usize>, String>>,
+    next_watchpoint_hit: FetchCache<(usize, Watchpoint), WatchpointResult>,
+    prev_watchpoint_hit: FetchCache<(usize, Watchpoint), WatchpointResult>,
+    breakpoint_hit: FetchCache<(usize, SearchDirection, Vec<Breakpoint>), Option<BreakpointHit>>,
+    logpoint_log: FetchCache<Vec<Breakpoint>, Vec<LogpointEntry>>,
+    breakpoint_hits: FetchCache<Vec<Breakpoint>, Vec<Vec<usize>>>,
+    what_if: FetchCache<(usize, Vec<StateEdit>), core::result::Result<WhatIfResult, String>>,
+    patched_replay:
+        FetchCache<(Address, String, String), core::result::Result<WhatIfResult, String>>,
+    nested_call: FetchCache<
+        (usize, String, Option<usize>),
+        core::result::Result<NestedCallTimeline, String>,
+    >,
     trace_data: Trace,
+    /// Number of overlay changes the cached results are computed under
+    overlay_version: usize,
 }
 
 impl ManagerStateTr for ExecutionState {
@@ -65,13 +91,41 @@ impl ManagerStateTr for ExecutionState {
             code: FetchCache::new(),
             next_call: FetchCache::new(),
             prev_call: FetchCache::new(),
+            call_stack: FetchCache::new(),
             storage: FetchCache::new(),
             storage_diff: FetchCache::new(),
+            value_origin: FetchCache::new(),
+            value_history: FetchCache::new(),
+            snapshot_matches: FetchCache::new(),
+            next_watchpoint_hit: FetchCache::new(),
+            prev_watchpoint_hit: FetchCache::new(),
+            breakpoint_hit: FetchCache::new(),
+            logpoint_log: FetchCache::new(),
+            breakpoint_hits: FetchCache::new(),
+            what_if: FetchCache::new(),
+            patched_replay: FetchCache::new(),
+            nested_call: FetchCache::new(),
             trace_data,
+            overlay_version: 0,
         })
     }
 
     fn update(&mut self, other: &Self) {
+        // Results depending on evaluations are discarded along with the core's after an
+        // overlay change
+        if self.overlay_version != other.overlay_version {
+            self.overlay_version = other.overlay_version;
+            self.value_history = other.value_history.clone();
+            self.snapshot_matches = other.snapshot_matches.clone();
+            self.next_watchpoint_hit = other.next_watchpoint_hit.clone();
+            self.prev_watchpoint_hit = other.prev_watchpoint_hit.clone();
+            self.breakpoint_hit = other.breakpoint_hit.clone();
+            self.logpoint_log = other.logpoint_log.clone();
+            self.breakpoint_hits = other.breakpoint_hits.clone();
+            self.what_if = other.what_if.clone();
+            self.nested_call = other.nested_call.clone();
+        }
+
         if self.snapshot_info.need_update(&other.snapshot_info) {
             self.snapshot_info.update(&other.snapshot_info);
         }
@@ -88,6 +142,10 @@ impl ManagerStateTr for ExecutionState {
             self.prev_call.update(&other.prev_call);
         }
 
+        if self.call_stack.need_update(&other.call_stack) {
+            self.call_stack.update(&other.call_stack);
+        }
+
         if self.storage.need_update(&other.storage) {
             self.storage.update(&other.storage);
         }
@@ -95,18 +153,77 @@ impl ManagerStateTr for ExecutionState {
         if self.storage_diff.need_update(&other.storage_diff) {
             self.storage_diff.update(&other.storage_diff);
         }
+
+        if self.value_origin.need_update(&other.value_origin) {
+            self.value_origin.update(&other.value_origin);
+        }
+
+        if self.value_history.need_update(&other.value_history) {
+            self.value_history.update(&other.value_history);
+        }
+
+        if self.snapshot_matches.need_update(&other.snapshot_matches) {
+            self.snapshot_matches.update(&other.snapshot_matches);
+        }
+
+        if self.next_watchpoint_hit.need_update(&other.next_watchpoint_hit) {
+            self.next_watchpoint_hit.update(&other.next_watchpoint_hit);
+        }
+
+        if self.prev_watchpoint_hit.need_update(&other.prev_watchpoint_hit) {
+            self.prev_watchpoint_hit.update(&other.prev_watchpoint_hit);
+        }
+
+        if self.breakpoint_hit.need_update(&other.breakpoint_hit) {
+            self.breakpoint_hit.update(&other.breakpoint_hit);
+        }
+
+        if self.logpoint_log.need_update(&other.logpoint_log) {
+            self.logpoint_log.update(&other.logpoint_log);
+        }
+
+        if self.breakpoint_hits.need_update(&other.breakpoint_hits) {
+            self.breakpoint_hits.update(&other.breakpoint_hits);
+        }
+
+        if self.what_if.need_update(&other.what_if) {
+            self.what_if.update(&other.what_if);
+        }
+
+        if self.patched_replay.need_update(&other.patched_replay) {
+            self.patched_replay.update(&other.patched_replay);
+        }
+
+        if self.nested_call.need_update(&other.nested_call) {
+            self.nested_call.update(&other.nested_call);
+        }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ExecutionRequest {
     SnapshotInfo(usize),
     Code(usize),
     CodeByAddress(Address),
     NextCall(usize),
     PrevCall(usize),
+    CallStack(usize),
     Storage(usize, U256),
     StorageDiff(usize),
+    ValueOrigin(usize, ValueLocation),
+    ValueHistory(String, HistoryScope),
+    SnapshotMatches(String, SnapshotFilter),
+    NextWatchpointHit(usize, Watchpoint),
+    PrevWatchpointHit(usize, Watchpoint),
+    BreakpointHit(usize, SearchDirection, Vec<Breakpoint>),
+    LogpointLog(Vec<Breakpoint>),
+    BreakpointHits(Vec<Breakpoint>),
+    WhatIf(usize, Vec<StateEdit>),
+    PatchedReplay(Address, String, String),
+    NestedCall(usize, String, Option<usize>),
+    /// Discard the results depending on evaluations, computed before the given number of
+    /// overlay changes
+    OverlayChanged(usize),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -114,6 +231,10 @@ pub enum ExecutionStatus {
     Normal,
     WaitNextCall(usize),
     WaitPrevCall(usize),
+    WaitNextWatchpoint(usize),
+    WaitPrevWatchpoint(usize),
+    WaitContinue(usize),
+    WaitReverseContinue(usize),
 }
 
 impl ExecutionStatus {
@@ -129,6 +250,22 @@ impl ManagerRequestTr<ExecutionState> for ExecutionRequest {
         state: &mut ExecutionState,
     ) -> Result<()> {
         match self {
+            Self::OverlayChanged(version) => {
+                if state.overlay_version >= version {
+                    return Ok(());
+                }
+
+                state.value_history.clear();
+                state.snapshot_matches.clear();
+                state.next_watchpoint_hit.clear();
+                state.prev_watchpoint_hit.clear();
+                state.breakpoint_hit.clear();
+                state.logpoint_log.clear();
+                state.breakpoint_hits.clear();
+                state.what_if.clear();
+                state.nested_call.clear();
+                state.overlay_version = version;
+            }
             Self::SnapshotInfo(id) => {
                 if state.snapshot_info.contains_key(&id) {
                     return Ok(());
@@ -170,6 +307,14 @@ impl ManagerRequestTr<ExecutionState> for ExecutionRequest {
                 let code = rpc_client.get_code_by_address(address).await?;
                 state.code.insert(address, Some(code));
             }
+            Self::CallStack(id) => {
+                if state.call_stack.contains_key(&id) {
+                    return Ok(());
+                }
+
+                let frames = rpc_client.get_call_stack(id).await?;
+                state.call_stack.insert(id, Some(frames));
+            }
             Self::NextCall(id) => {
                 if state.next_call.contains_key(&id) {
                     return Ok(());
@@ -216,6 +361,111 @@ impl ManagerRequestTr<ExecutionState> for ExecutionRequest {
                 let diff = rpc_client.get_storage_diff(id).await?;
                 state.storage_diff.insert(id, Some(diff));
             }
+            Self::ValueOrigin(id, location) => {
+                if state.value_origin.contains_key(&(id, location)) {
+                    return Ok(());
+                }
+
+                let origin = rpc_client.trace_value_origin(id, location).await?;
+                state.value_origin.insert((id, location), Some(origin));
+            }
+            Self::ValueHistory(expr, scope) => {
+                let key = (expr, scope);
+                if state.value_history.contains_key(&key) {
+                    return Ok(());
+                }
+
+                let history = rpc_client.get_value_history(&key.0, scope).await?;
+                state.value_history.insert(key, Some(history));
+            }
+            Self::SnapshotMatches(expr, filter) => {
+                let key = (expr, filter);
+                if state.snapshot_matches.contains_key(&key) {
+                    return Ok(());
+                }
+
+                let matches = rpc_client.find_snapshots(&key.0, &key.1).await?;
+                state.snapshot_matches.insert(key, Some(matches));
+            }
+            Self::NextWatchpointHit(id, watchpoint) => {
+                let key = (id, watchpoint);
+                if state.next_watchpoint_hit.contains_key(&key) {
+                    return Ok(());
+                }
+
+                let hit = rpc_client.get_next_watchpoint_hit(key.0, &key.1).await?;
+                state.next_watchpoint_hit.insert(key, Some(hit));
+            }
+            Self::PrevWatchpointHit(id, watchpoint) => {
+                let key = (id, watchpoint);
+                if state.prev_watchpoint_hit.contains_key(&key) {
+                    return Ok(());
+                }
+
+                let hit = rpc_client.get_prev_watchpoint_hit(key.0, &key.1).await?;
+                state.prev_watchpoint_hit.insert(key, Some(hit));
+            }
+            Self::BreakpointHit(id, direction, breakpoints) => {
+                let key = (id, direction, breakpoints);
+                if state.breakpoint_hit.contains_key(&key) {
+                    return Ok(());
+                }
+
+                let hit = rpc_client.find_breakpoint_hit(&key.2, id, direction).await?;
+                state.breakpoint_hit.insert(key, Some(hit));
+            }
+            Self::LogpointLog(logpoints) => {
+                if state.logpoint_log.contains_key(&logpoints) {
+                    return Ok(());
+                }
+
+                let log = rpc_client.collect_logpoints(&logpoints).await?;
+                state.logpoint_log.insert(logpoints, Some(log));
+            }
+            Self::BreakpointHits(breakpoints) => {
+                if state.breakpoint_hits.contains_key(&breakpoints) {
+                    return Ok(());
+                }
+
+                let hits = rpc_client.count_breakpoint_hits(&breakpoints).await?;
+                state.breakpoint_hits.insert(breakpoints, Some(hits));
+            }
+            Self::WhatIf(id, edits) => {
+                let key = (id, edits);
+                if state.what_if.contains_key(&key) {
+                    return Ok(());
+                }
+
+                // Keep failures, since edits that cannot be applied are user errors
+                let result = rpc_client.what_if(id, &key.1).await.map_err(|e| e.to_string());
+                state.what_if.insert(key, Some(result));
+            }
+            Self::PatchedReplay(address, path, source) => {
+                let key = (address, path, source);
+                if state.patched_replay.contains_key(&key) {
+                    return Ok(());
+                }
+
+                // Keep failures, since patches that do not compile are user errors
+                let result = rpc_client
+                    .replay_with_patched_source(address, &key.1, &key.2)
+                    .await
+                    .map_err(|e| e.to_string());
+                state.patched_replay.insert(key, Some(result));
+            }
+            Self::NestedCall(id, expr, call_index) => {
+                let key = (id, expr, call_index);
+                if state.nested_call.contains_key(&key) {
+                    return Ok(());
+                }
+
+                // Keep failures, since expressions that make no calls are user errors
+                let result = rpc_client
+                    .step_into_call(id, &key.1, call_index)
+                    .await
+                    .unwrap_or_else(|e| Err(e.to_string()));
+                state.nested_call.insert(key, Some(result));
+            }
         }
 
         Ok(())
@@ -264,6 +514,15 @@ pub struct ExecutionManager {
     breakpoint_set: HashSet<Breakpoint>,
     breakpoints: Vec<(Breakpoint, bool)>, // bool indicates enabled/disabled
 
+    // Watchpoints
+    watchpoints: Vec<Watchpoint>,
+
+    // Frame of the call stack selected at a snapshot, see `get_selected_frame`
+    selected_frame: Option<(usize, usize)>,
+
+    // Number of overlay changes requested to be synchronized, see `sync_overlay_version`
+    requested_overlay_version: usize,
+
     /// State
     state: ExecutionState,
 
@@ -299,6 +558,9 @@ impl ExecutionManager {
             display_snapshot: 0,
             breakpoints: Vec::new(),
             breakpoint_set: HashSet::new(),
+            watchpoints: Vec::new(),
+            selected_frame: None,
+            requested_overlay_version: 0,
         };
 
         let _ = mgr.goto_snapshot(0);
@@ -306,6 +568,16 @@ impl ExecutionManager {
         mgr
     }
 
+    /// Discard the results depending on evaluations (value histories, snapshot searches,
+    /// watchpoint and breakpoint hits, what-if runs and nested calls) once the session
+    /// overlay has changed `version` times.
+    pub fn sync_overlay_version(&mut self, version: usize) {
+        if self.requested_overlay_version < version {
+            self.requested_overlay_version = version;
+            self.new_fetching_request(ExecutionRequest::OverlayChanged(version));
+        }
+    }
+
     pub fn get_sanitized_id(&self, id: usize) -> usize {
         id.max(0).min(self.state.snapshot_count - 1)
     }
@@ -342,6 +614,312 @@ impl ExecutionManager {
         }
     }
 
+    pub fn get_value_origin(
+        &mut self,
+        id: usize,
+        location: ValueLocation,
+    ) -> Option<&core::result::Result<ValueOrigin, String>> {
+        let _ = self.pull_from_core();
+
+        let id = self.get_sanitized_id(id);
+        if !self.state.value_origin.contains_key(&(id, location)) {
+            debug!("Value origin not found in cache, fetching...");
+            self.new_fetching_request(ExecutionRequest::ValueOrigin(id, location));
+            return None;
+        }
+
+        match self.state.value_origin.get(&(id, location)) {
+            Some(origin) => origin.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn get_value_history(
+        &mut self,
+        expr: &str,
+        scope: HistoryScope,
+    ) -> Option<&core::result::Result<ValueHistory, String>> {
+        let _ = self.pull_from_core();
+
+        let key = (expr.to_string(), scope);
+        if !self.state.value_history.contains_key(&key) {
+            debug!("Value history not found in cache, fetching...");
+            self.new_fetching_request(ExecutionRequest::ValueHistory(key.0, key.1));
+            return None;
+        }
+
+        match self.state.value_history.get(&key) {
+            Some(history) => history.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn get_snapshot_matches(
+        &mut self,
+        expr: &str,
+        filter: &SnapshotFilter,
+    ) -> Option<&core::result::Result<Vec<usize>, String>> {
+        let _ = self.pull_from_core();
+
+        let key = (expr.to_string(), filter.clone());
+        if !self.state.snapshot_matches.contains_key(&key) {
+            debug!("Snapshot matches not found in cache, fetching...");
+            self.new_fetching_request(ExecutionRequest::SnapshotMatches(key.0, key.1));
+            return None;
+        }
+
+        match self.state.snapshot_matches.get(&key) {
+            Some(matches) => matches.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn get_next_watchpoint_hit(
+        &mut self,
+        id: usize,
+        watchpoint: &Watchpoint,
+    ) -> Option<&WatchpointResult> {
+        let _ = self.pull_from_core();
+
+        let key = (self.get_sanitized_id(id), watchpoint.clone());
+        if !self.state.next_watchpoint_hit.contains_key(&key) {
+            debug!("Next watchpoint hit not found in cache, fetching...");
+            self.new_fetching_request(ExecutionRequest::NextWatchpointHit(key.0, key.1));
+            return None;
+        }
+
+        match self.state.next_watchpoint_hit.get(&key) {
+            Some(hit) => hit.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn get_prev_watchpoint_hit(
+        &mut self,
+        id: usize,
+        watchpoint: &Watchpoint,
+    ) -> Option<&WatchpointResult> {
+        let _ = self.pull_from_core();
+
+        let key = (self.get_sanitized_id(id), watchpoint.clone());
+        if !self.state.prev_watchpoint_hit.contains_key(&key) {
+            debug!("Prev watchpoint hit not found in cache, fetching...");
+            self.new_fetching_request(ExecutionRequest::PrevWatchpointHit(key.0, key.1));
+            return None;
+        }
+
+        match self.state.prev_watchpoint_hit.get(&key) {
+            Some(hit) => hit.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Find the nearest snapshot from `id` at which any enabled breakpoint is hit
+    pub fn get_breakpoint_hit(
+        &mut self,
+        id: usize,
+        direction: SearchDirection,
+    ) -> Option<&Option<BreakpointHit>> {
+        let _ = self.pull_from_core();
+
+        let breakpoints: Vec<Breakpoint> = self
+            .breakpoints
+            .iter()
+            .filter(|(bp, enabled)| *enabled && !bp.is_logpoint())
+            .map(|(bp, _)| bp.clone())
+            .collect();
+        let key = (self.get_sanitized_id(id), direction, breakpoints);
+        if !self.state.breakpoint_hit.contains_key(&key) {
+            debug!("Breakpoint hit not found in cache, fetching...");
+            self.new_fetching_request(ExecutionRequest::BreakpointHit(key.0, key.1, key.2));
+            return None;
+        }
+
+        match self.state.breakpoint_hit.get(&key) {
+            Some(hit) => hit.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Collect the log of all enabled logpoints over the whole timeline. Each entry is
+    /// returned with the id of the logpoint that logged it.
+    pub fn get_logpoint_log(&mut self) -> Option<Vec<(usize, LogpointEntry)>> {
+        let _ = self.pull_from_core();
+
+        let logpoints: Vec<Breakpoint> = self
+            .breakpoints
+            .iter()
+            .filter(|(bp, enabled)| *enabled && bp.is_logpoint())
+            .map(|(bp, _)| bp.clone())
+            .collect();
+        if logpoints.is_empty() {
+            return Some(Vec::new());
+        }
+
+        if !self.state.logpoint_log.contains_key(&logpoints) {
+            debug!("Logpoint log not found in cache, fetching...");
+            self.new_fetching_request(ExecutionRequest::LogpointLog(logpoints));
+            return None;
+        }
+
+        let log = self.state.logpoint_log.get(&logpoints)?.as_ref()?;
+        Some(
+            log.iter()
+                .filter_map(|entry| {
+                    let bp = logpoints.get(entry.logpoint)?;
+                    let (id, _) = self.find_breakpoints(bp, false).into_iter().next()?;
+                    Some((id, entry.clone()))
+                })
+                .collect(),
+        )
+    }
+
+    /// Hit counts of all breakpoints (in list order) over the whole timeline, as the
+    /// number of hits up to snapshot `id` and the total number of hits
+    pub fn get_breakpoint_hit_counts(&mut self, id: usize) -> Option<Vec<(usize, usize)>> {
+        let _ = self.pull_from_core();
+
+        // Hits do not depend on how they are handled, so neither should the cache key
+        let breakpoints: Vec<Breakpoint> = self
+            .breakpoints
+            .iter()
+            .map(|(bp, _)| Breakpoint { ignore_count: 0, temporary: false, ..bp.clone() })
+            .collect();
+        if breakpoints.is_empty() {
+            return Some(Vec::new());
+        }
+
+        if !self.state.breakpoint_hits.contains_key(&breakpoints) {
+            debug!("Breakpoint hits not found in cache, fetching...");
+            self.new_fetching_request(ExecutionRequest::BreakpointHits(breakpoints));
+            return None;
+        }
+
+        let hits = self.state.breakpoint_hits.get(&breakpoints)?.as_ref()?;
+        Some(hits.iter().map(|hits| (hits.partition_point(|&hit| hit <= id), hits.len())).collect())
+    }
+
+    /// Re-execute the transaction with state edits applied at snapshot `id`
+    pub fn get_what_if(
+        &mut self,
+        id: usize,
+        edits: &[StateEdit],
+    ) -> Option<&core::result::Result<WhatIfResult, String>> {
+        let _ = self.pull_from_core();
+
+        let key = (self.get_sanitized_id(id), edits.to_vec());
+        if !self.state.what_if.contains_key(&key) {
+            debug!("What-if result not found in cache, fetching...");
+            self.new_fetching_request(ExecutionRequest::WhatIf(key.0, key.1));
+            return None;
+        }
+
+        match self.state.what_if.get(&key) {
+            Some(result) => result.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Replay the transaction with the patched source code of a contract
+    pub fn get_patched_replay(
+        &mut self,
+        address: Address,
+        path: &str,
+        source: &str,
+    ) -> Option<&core::result::Result<WhatIfResult, String>> {
+        let _ = self.pull_from_core();
+
+        let key = (address, path.to_string(), source.to_string());
+        if !self.state.patched_replay.contains_key(&key) {
+            debug!("Patched replay not found in cache, fetching...");
+            self.new_fetching_request(ExecutionRequest::PatchedReplay(key.0, key.1, key.2));
+            return None;
+        }
+
+        match self.state.patched_replay.get(&key) {
+            Some(result) => result.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Debug a call made by an expression on snapshot `id` as a nested timeline
+    pub fn get_nested_call(
+        &mut self,
+        id: usize,
+        expr: &str,
+        call_index: Option<usize>,
+    ) -> Option<&core::result::Result<NestedCallTimeline, String>> {
+        let _ = self.pull_from_core();
+
+        let key = (self.get_sanitized_id(id), expr.to_string(), call_index);
+        if !self.state.nested_call.contains_key(&key) {
+            debug!("Nested call timeline not found in cache, fetching...");
+            self.new_fetching_request(ExecutionRequest::NestedCall(key.0, key.1, key.2));
+            return None;
+        }
+
+        match self.state.nested_call.get(&key) {
+            Some(result) => result.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Snapshot at which continue / reverse-continue from `id` stops: the nearest
+    /// breakpoint hit, or the end (beginning) of the timeline if there is none.
+    pub fn get_continue_target(&mut self, id: usize, direction: SearchDirection) -> Option<usize> {
+        let last_id = self.state.snapshot_count - 1;
+        let hit = self.get_breakpoint_hit(id, direction)?;
+        Some(match (hit, direction) {
+            (Some(hit), _) => hit.snapshot_id,
+            (None, SearchDirection::Forward) => last_id,
+            (None, SearchDirection::Backward) => 0,
+        })
+    }
+
+    /// Find the nearest hit among all watchpoints, searching forward or backward from `id`.
+    ///
+    /// Returns `None` while any watchpoint is still being resolved, and `Some(None)` if no
+    /// watchpoint changes in that direction. Watchpoints that fail to resolve are skipped.
+    pub fn get_nearest_watchpoint_hit(
+        &mut self,
+        id: usize,
+        forward: bool,
+    ) -> Option<Option<(usize, WatchpointHit)>> {
+        let mut nearest: Option<(usize, WatchpointHit)> = None;
+        let mut pending = false;
+
+        for (idx, watchpoint) in self.watchpoints.clone().into_iter().enumerate() {
+            let result = if forward {
+                self.get_next_watchpoint_hit(id, &watchpoint)
+            } else {
+                self.get_prev_watchpoint_hit(id, &watchpoint)
+            };
+
+            match result {
+                None => pending = true,
+                Some(Ok(Some(hit))) => {
+                    let closer = nearest.as_ref().is_none_or(|(_, best)| {
+                        if forward {
+                            hit.snapshot_id < best.snapshot_id
+                        } else {
+                            hit.snapshot_id > best.snapshot_id
+                        }
+                    });
+                    if closer {
+                        nearest = Some((idx + 1, hit.clone()));
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+
+        if pending {
+            None
+        } else {
+            Some(nearest)
+        }
+    }
+
     pub fn get_snapshot_info(&mut self, id: usize) -> Option<&SnapshotInfo> {
         let _ = self.pull_from_core();
 
@@ -396,6 +974,57 @@ impl ExecutionManager {
         }
     }
 
+    /// Get the call stack at snapshot `id`, as the snapshots of its frames (innermost first)
+    pub fn get_call_stack(&mut self, id: usize) -> Option<&Vec<usize>> {
+        let _ = self.pull_from_core();
+
+        let id = self.get_sanitized_id(id);
+        if !self.state.call_stack.contains_key(&id) {
+            debug!("Call stack not found in cache, fetching...");
+            self.new_fetching_request(ExecutionRequest::CallStack(id));
+            return None;
+        }
+
+        match self.state.call_stack.get(&id) {
+            Some(frames) => frames.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Selected frame of the call stack at the current snapshot. Moving to another
+    /// snapshot selects its own frame (frame 0) again.
+    pub fn get_selected_frame(&mut self) -> usize {
+        let current = self.get_current_snapshot();
+        match self.selected_frame {
+            Some((id, frame)) if id == current => frame,
+            _ => 0,
+        }
+    }
+
+    /// Select a frame of the call stack at the current snapshot, displaying its snapshot
+    pub fn select_frame(&mut self, frame: usize) -> Result<()> {
+        let current = self.get_current_snapshot();
+        let frame_snapshot = self
+            .get_call_stack(current)
+            .and_then(|frames| frames.get(frame).copied())
+            .ok_or_else(|| eyre::eyre!("Frame {} not found at snapshot {}", frame, current))?;
+
+        self.selected_frame = Some((current, frame));
+        self.display_snapshot(frame_snapshot)
+    }
+
+    /// Snapshot of the selected frame, at which expressions are evaluated
+    pub fn get_frame_snapshot(&mut self) -> usize {
+        let current = self.get_current_snapshot();
+        match self.get_selected_frame() {
+            0 => current,
+            frame => self
+                .get_call_stack(current)
+                .and_then(|frames| frames.get(frame).copied())
+                .unwrap_or(current),
+        }
+    }
+
     pub fn get_prev_call(&mut self, id: usize) -> Option<usize> {
         let _ = self.pull_from_core();
 
@@ -547,6 +1176,66 @@ impl ExecutionManager {
                 // Any other execution request will be rejected
                 false
             }
+            ExecutionStatus::WaitNextWatchpoint(src_id) => {
+                // There is a pending execution request, for which we should wait
+                // and should not update current_snapshot
+                if let Some(hit) = self.get_nearest_watchpoint_hit(src_id, true) {
+                    self.execution_status = ExecutionStatus::Normal;
+
+                    // We stay where we are if no watchpoint changes
+                    if let Some((_, hit)) = hit {
+                        let _ = self.goto_snapshot(hit.snapshot_id);
+                        let _ = self.display_snapshot(hit.snapshot_id);
+                    }
+                }
+
+                // Any other execution request will be rejected
+                false
+            }
+            ExecutionStatus::WaitPrevWatchpoint(src_id) => {
+                // There is a pending execution request, for which we should wait
+                // and should not update current_snapshot
+                if let Some(hit) = self.get_nearest_watchpoint_hit(src_id, false) {
+                    self.execution_status = ExecutionStatus::Normal;
+
+                    // We stay where we are if no watchpoint changes
+                    if let Some((_, hit)) = hit {
+                        let _ = self.goto_snapshot(hit.snapshot_id);
+                        let _ = self.display_snapshot(hit.snapshot_id);
+                    }
+                }
+
+                // Any other execution request will be rejected
+                false
+            }
+            ExecutionStatus::WaitContinue(src_id) => {
+                // There is a pending execution request, for which we should wait
+                // and should not update current_snapshot
+                if let Some(target) = self.get_continue_target(src_id, SearchDirection::Forward) {
+                    self.execution_status = ExecutionStatus::Normal;
+
+                    // We will override the current snapshot
+                    let _ = self.goto_snapshot(target);
+                    let _ = self.display_snapshot(target);
+                }
+
+                // Any other execution request will be rejected
+                false
+            }
+            ExecutionStatus::WaitReverseContinue(src_id) => {
+                // There is a pending execution request, for which we should wait
+                // and should not update current_snapshot
+                if let Some(target) = self.get_continue_target(src_id, SearchDirection::Backward) {
+                    self.execution_status = ExecutionStatus::Normal;
+
+                    // We will override the current snapshot
+                    let _ = self.goto_snapshot(target);
+                    let _ = self.display_snapshot(target);
+                }
+
+                // Any other execution request will be rejected
+                false
+            }
             ExecutionStatus::Normal => true,
         }
     }
@@ -652,6 +1341,74 @@ impl ExecutionManager {
         Ok(())
     }
 
+    pub fn continue_execution(&mut self) -> Result<()> {
+        if !self.check_pending_request() {
+            // There is a pending request, we should not update current_snapshot
+            return Ok(());
+        }
+
+        if let Some(target) =
+            self.get_continue_target(self.current_snapshot, SearchDirection::Forward)
+        {
+            self.goto(target)?;
+        } else {
+            self.execution_status = ExecutionStatus::WaitContinue(self.current_snapshot);
+        }
+
+        Ok(())
+    }
+
+    pub fn reverse_continue(&mut self) -> Result<()> {
+        if !self.check_pending_request() {
+            // There is a pending request, we should not update current_snapshot
+            return Ok(());
+        }
+
+        if let Some(target) =
+            self.get_continue_target(self.current_snapshot, SearchDirection::Backward)
+        {
+            self.goto(target)?;
+        } else {
+            self.execution_status = ExecutionStatus::WaitReverseContinue(self.current_snapshot);
+        }
+
+        Ok(())
+    }
+
+    pub fn next_watchpoint(&mut self) -> Result<()> {
+        if !self.check_pending_request() {
+            // There is a pending request, we should not update current_snapshot
+            return Ok(());
+        }
+
+        match self.get_nearest_watchpoint_hit(self.current_snapshot, true) {
+            Some(Some((_, hit))) => self.goto(hit.snapshot_id)?,
+            Some(None) => {}
+            None => {
+                self.execution_status = ExecutionStatus::WaitNextWatchpoint(self.current_snapshot)
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn prev_watchpoint(&mut self) -> Result<()> {
+        if !self.check_pending_request() {
+            // There is a pending request, we should not update current_snapshot
+            return Ok(());
+        }
+
+        match self.get_nearest_watchpoint_hit(self.current_snapshot, false) {
+            Some(Some((_, hit))) => self.goto(hit.snapshot_id)?,
+            Some(None) => {}
+            None => {
+                self.execution_status = ExecutionStatus::WaitPrevWatchpoint(self.current_snapshot)
+            }
+        }
+
+        Ok(())
+    }
+
     /////////////////////////////////////////////
     // Breakpoint management
     /////////////////////////////////////////////
@@ -663,7 +1420,10 @@ impl ExecutionManager {
         }
 
         let loc = bp.loc.as_ref()?;
-        let bytecode_address = loc.bytecode_address();
+        let Some(bytecode_address) = loc.bytecode_address() else {
+            // Symbolic locations are resolved by the engine against any code
+            return Some(bp);
+        };
         let code = self.get_code_by_bytecode_address(bytecode_address)?;
 
         match (loc, code) {
@@ -732,6 +1492,16 @@ impl ExecutionManager {
         }
     }
 
+    /// Add a breakpoint saved by an earlier session, which was validated when first added
+    pub fn restore_breakpoint(&mut self, bp: Breakpoint, enabled: bool) -> bool {
+        if self.breakpoint_set.insert(bp.clone()) {
+            self.breakpoints.push((bp, enabled));
+            true
+        } else {
+            false
+        }
+    }
+
     pub fn update_breakpoint_condition(&mut self, id: usize, expr: String) -> Result<()> {
         // When we try to add a breakpoint, we check whether there is a pending request
         if !self.check_pending_request() {
@@ -751,6 +1521,38 @@ impl ExecutionManager {
         Ok(())
     }
 
+    pub fn update_breakpoint_ignore_count(&mut self, id: usize, count: usize) -> Result<()> {
+        if id == 0 || id > self.breakpoints.len() {
+            bail!("Invalid breakpoint id {}", id);
+        }
+
+        let bp = &mut self.breakpoints[id - 1].0;
+        self.breakpoint_set.remove(bp);
+        bp.ignore_count = count;
+        self.breakpoint_set.insert(bp.clone());
+        Ok(())
+    }
+
+    /// Remove the temporary breakpoints among the given ones, once they have stopped
+    /// execution. Returns the ids of the removed breakpoints.
+    pub fn remove_temporary_breakpoints(&mut self, ids: &[usize]) -> Vec<usize> {
+        let mut removed: Vec<usize> = ids
+            .iter()
+            .copied()
+            .filter(|id| {
+                self.breakpoints.get(id.wrapping_sub(1)).is_some_and(|(bp, _)| bp.temporary)
+            })
+            .collect();
+        removed.sort_unstable();
+        removed.dedup();
+
+        // Remove from the back, so that the remaining ids stay valid
+        for id in removed.iter().rev() {
+            let _ = self.remove_breakpoint(*id);
+        }
+        removed
+    }
+
     pub fn enable_breakpoint(&mut self, id: usize) -> Result<()> {
         if id == 0 || id > self.breakpoints.len() {
             bail!("Breakpoint id {id} out of bounds");
@@ -807,10 +1609,61 @@ impl ExecutionManager {
         matching_ids
     }
 
-    /// Return the breakpoints that are hit at the current snapshot
-    pub fn get_hit_breakpoints(&mut self, _snaphost_id: usize) -> Vec<usize> {
-        // TODO
-        vec![]
+    /// Return the (enabled) breakpoints that are hit at the given snapshot, as found
+    /// by previous continue / reverse-continue searches
+    pub fn get_hit_breakpoints(&mut self, snapshot_id: usize) -> Vec<usize> {
+        let _ = self.pull_from_core();
+
+        let mut hit_ids = Vec::new();
+        for ((_, _, breakpoints), hit) in &self.state.breakpoint_hit {
+            let Some(Some(hit)) = hit else { continue };
+            if hit.snapshot_id != snapshot_id {
+                continue;
+            }
+
+            for bp in hit.breakpoints.iter().filter_map(|index| breakpoints.get(*index)) {
+                hit_ids.extend(
+                    self.find_breakpoints(bp, false)
+                        .into_iter()
+                        .filter(|(_, enabled)| *enabled)
+                        .map(|(id, _)| id),
+                );
+            }
+        }
+
+        hit_ids.sort_unstable();
+        hit_ids.dedup();
+        hit_ids
+    }
+}
+
+// Watchpoint management
+impl ExecutionManager {
+    pub fn list_watchpoints(&self) -> impl Iterator<Item = (usize, &Watchpoint)> {
+        self.watchpoints.iter().enumerate().map(|(i, wp)| (i + 1, wp))
+    }
+
+    pub fn add_watchpoint(&mut self, wp: Watchpoint) -> Result<bool> {
+        if self.watchpoints.contains(&wp) {
+            Ok(false)
+        } else {
+            self.watchpoints.push(wp);
+            Ok(true)
+        }
+    }
+
+    pub fn remove_watchpoint(&mut self, id: usize) -> Result<()> {
+        if id == 0 || id > self.watchpoints.len() {
+            bail!("Watchpoint id {id} out of bounds");
+        }
+
+        self.watchpoints.remove(id - 1);
+        Ok(())
+    }
+
+    pub fn clear_watchpoints(&mut self) -> Result<()> {
+        self.watchpoints.clear();
+        Ok(())
     }
 }
 
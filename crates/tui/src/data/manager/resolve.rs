@@ -34,7 +34,8 @@ use alloy_dyn_abi::{DynSolValue, EventExt, FunctionExt, JsonAbiExt};
 use alloy_json_abi::JsonAbi;
 use alloy_primitives::{hex, Address, Bytes, LogData, Selector, U256};
 use edb_common::types::{
-    CallableAbiInfo, EdbSolValue, SolValueFormatter, SolValueFormatterContext as FormatCtx,
+    CallableAbiInfo, EdbSolValue, EvalReport, SolValueFormatter,
+    SolValueFormatterContext as FormatCtx,
 };
 use eyre::Result;
 use std::{collections::HashSet, ops::Deref, sync::Arc};
@@ -52,6 +53,11 @@ pub struct ResolverState {
     callable_abi: FetchCache<Address, Vec<CallableAbiInfo>>,
     constructor_args: FetchCache<Address, Bytes>,
     expr_value: FetchCache<(usize, String), core::result::Result<EdbSolValue, String>>,
+    expr_report: FetchCache<(usize, String), EvalReport>,
+    /// Reports of the expressions changing the session overlay, keyed by submission order
+    mutation_report: FetchCache<usize, EvalReport>,
+    /// Number of overlay changes made so far; evaluations cached before a change are stale
+    overlay_version: usize,
 }
 
 impl ManagerStateTr for ResolverState {
@@ -60,6 +66,13 @@ impl ManagerStateTr for ResolverState {
     }
 
     fn update(&mut self, other: &Self) {
+        // Cached evaluations are discarded along with the core's after an overlay change
+        if self.overlay_version != other.overlay_version {
+            self.overlay_version = other.overlay_version;
+            self.expr_value = other.expr_value.clone();
+            self.expr_report = other.expr_report.clone();
+        }
+
         if self.contract_abi.need_update(&other.contract_abi) {
             self.contract_abi.update(&other.contract_abi);
         }
@@ -75,6 +88,14 @@ impl ManagerStateTr for ResolverState {
         if self.expr_value.need_update(&other.expr_value) {
             self.expr_value.update(&other.expr_value);
         }
+
+        if self.expr_report.need_update(&other.expr_report) {
+            self.expr_report.update(&other.expr_report);
+        }
+
+        if self.mutation_report.need_update(&other.mutation_report) {
+            self.mutation_report.update(&other.mutation_report);
+        }
     }
 }
 
@@ -91,6 +112,12 @@ pub enum ResolverRequest {
 
     /// Evaluate expression on snapshot
     ExprOnSnapshot(usize, String),
+
+    /// Evaluate expression on snapshot, reporting the contract calls it makes
+    ExprReportOnSnapshot(usize, String),
+
+    /// Evaluate an expression changing the session overlay on snapshot, exactly once
+    MutationOnSnapshot(usize, usize, String),
 }
 
 impl ManagerRequestTr<ResolverState> for ResolverRequest {
@@ -124,6 +151,28 @@ impl ManagerRequestTr<ResolverState> for ResolverRequest {
                 let value = rpc_client.eval_on_snapshot(snapshot_id, &expr).await?;
                 state.expr_value.insert((snapshot_id, expr), Some(value));
             }
+            Self::ExprReportOnSnapshot(snapshot_id, expr) => {
+                if state.expr_report.has_cached(&(snapshot_id, expr.clone())) {
+                    return Ok(());
+                }
+                let report = rpc_client.eval_on_snapshot_with_calls(snapshot_id, &expr).await?;
+                state.expr_report.insert((snapshot_id, expr), Some(report));
+            }
+            Self::MutationOnSnapshot(seq, snapshot_id, expr) => {
+                if state.mutation_report.has_cached(&seq) {
+                    return Ok(());
+                }
+                // The overlay may have changed even if the request failed, so the error is
+                // reported rather than retried
+                let report = rpc_client
+                    .eval_on_snapshot_with_calls(snapshot_id, &expr)
+                    .await
+                    .unwrap_or_else(|e| EvalReport { value: Err(e.to_string()), calls: vec![] });
+                state.expr_value.clear();
+                state.expr_report.clear();
+                state.overlay_version += 1;
+                state.mutation_report.insert(seq, Some(report));
+            }
         }
         Ok(())
     }
@@ -152,6 +201,8 @@ pub struct Resolver {
     /// Pending requests
     pending_requests: HashSet<ResolverRequest>,
     state: ResolverState,
+    /// Number of expressions changing the session overlay submitted so far
+    mutation_count: usize,
 
     core: Arc<RwLock<ManagerCore<ResolverState, ResolverRequest>>>,
 }
@@ -186,6 +237,7 @@ impl Resolver {
         Self {
             pending_requests: HashSet::new(),
             state: core.clone().read().await.state.clone(),
+            mutation_count: 0,
             core,
         }
     }
@@ -259,6 +311,54 @@ impl Resolver {
         }
     }
 
+    /// Evaluate an expression on a specific snapshot, along with the contract calls it makes
+    pub fn eval_report_on_snapshot(
+        &mut self,
+        snapshot_id: usize,
+        expr: &str,
+    ) -> Option<&EvalReport> {
+        let _ = self.pull_from_core(); // Try to update cache
+        let expr_key = remove_whitespace(expr);
+
+        if !self.state.expr_report.contains_key(&(snapshot_id, expr_key.clone())) {
+            debug!("Expression report not found in cache, fetching...");
+            self.new_fetching_request(ResolverRequest::ExprReportOnSnapshot(snapshot_id, expr_key));
+            return None;
+        }
+
+        match self.state.expr_report.get(&(snapshot_id, expr_key)) {
+            Some(report) => report.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Submit an expression changing the session overlay (see
+    /// [`edb_common::mutates_overlay`]), to be evaluated exactly once on a snapshot.
+    ///
+    /// Returns the submission number, by which the report is looked up.
+    pub fn submit_mutation(&mut self, snapshot_id: usize, expr: &str) -> usize {
+        let seq = self.mutation_count;
+        self.mutation_count += 1;
+        self.new_fetching_request(ResolverRequest::MutationOnSnapshot(
+            seq,
+            snapshot_id,
+            remove_whitespace(expr),
+        ));
+        seq
+    }
+
+    /// Report of a submitted expression changing the session overlay
+    pub fn get_mutation_report(&mut self, seq: usize) -> Option<&EvalReport> {
+        let _ = self.pull_from_core(); // Try to update cache
+
+        self.state.mutation_report.get(&seq).and_then(Option::as_ref)
+    }
+
+    /// Number of overlay changes made so far
+    pub fn overlay_version(&self) -> usize {
+        self.state.overlay_version
+    }
+
     /// Resolve function return
     pub fn resolve_function_return(
         &mut self,
@@ -22,6 +22,7 @@
 //! - `ExecutionManager`: Manages trace and snapshot data with cached state
 //! - `Resolver`: Handles ABI resolution and address labeling with cached lookups
 //! - `Theme`: Direct theme configuration without async wrapping
+//! - `SessionStore`: Persists breakpoints and watch expressions across sessions
 //!
 //! # Architecture
 //!
@@ -58,6 +59,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 pub mod manager;
+pub mod persist;
 pub mod theme;
 pub mod watcher;
 
@@ -68,12 +70,27 @@ use crate::{
             execution::{ExecutionManager, ExecutionRequest, ExecutionState},
             resolve::{Resolver, ResolverRequest, ResolverState},
         },
+        persist::SessionStore,
         theme::Theme,
         watcher::Watcher,
     },
     RpcClient,
 };
 
+/// Request to switch the timeline debugged by the TUI, carried out by the TUI runner
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimelineSwitch {
+    /// Debug the nested timeline served on `port`, described by `label`
+    Enter {
+        /// Port of the debug server serving the nested timeline
+        port: u16,
+        /// Description of the nested timeline, e.g., the call it debugs
+        label: String,
+    },
+    /// Return to the timeline the current one is nested in
+    Leave,
+}
+
 /// Central data manager containing all state managers
 ///
 /// This structure is passed as a mutable reference to all app functions,
@@ -87,6 +104,12 @@ pub struct DataManager {
     pub theme: Theme,
     /// Expression watcher (no Arc/RwLock needed)
     pub watcher: Watcher,
+    /// Persistence of breakpoints and watch expressions across sessions
+    pub session: SessionStore,
+    /// Labels of the nested timelines leading to the current one, outermost first
+    pub nesting: Vec<String>,
+    /// Pending timeline switch requested by a panel
+    pub timeline_switch: Option<TimelineSwitch>,
 }
 
 impl DataManager {
@@ -101,6 +124,9 @@ impl DataManager {
             resolver: Resolver::new(resolver_core).await,
             theme: Theme::default(),
             watcher: Watcher::default(),
+            session: SessionStore::default(),
+            nesting: Vec::new(),
+            timeline_switch: None,
         })
     }
 
@@ -115,10 +141,14 @@ impl DataManager {
 
     /// Pull processed data from cores back to managers
     ///
-    /// This updates the cached state in managers with data processed by cores
+    /// This updates the cached state in managers with data processed by cores,
+    /// discards results made stale by overlay changes, and keeps the persisted
+    /// breakpoints and watch expressions in sync
     pub fn process_core_updates(&mut self) -> Result<()> {
         self.execution.pull_from_core()?;
         self.resolver.pull_from_core()?;
+        self.execution.sync_overlay_version(self.resolver.overlay_version());
+        self.session.sync(&mut self.execution, &mut self.watcher);
         Ok(())
     }
 
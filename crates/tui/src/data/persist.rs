@@ -0,0 +1,270 @@
+// EDB - Ethereum Debugger
+// Copyright (C) 2024 Zhuo Zhang and Wuqi Zhang
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Persistence of breakpoints and watch expressions across debug sessions
+//!
+//! Everything is saved under `<cache_root>/session`, with one file per contract named after
+//! the hash of its bytecode. Source breakpoints keep their source path and line, and opcode
+//! breakpoints their program counter, but not the address they were set at, so they come
+//! back whenever a later session runs the same code, at whatever address it is deployed.
+//! Watch expressions and symbolic breakpoints belong to the contract that was being
+//! debugged when they were added.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    env,
+};
+
+use alloy_primitives::{keccak256, Address, B256};
+use edb_common::{
+    normalize_expression,
+    types::{Breakpoint, BreakpointLocation},
+    Cache, CachePath, EdbCache, EdbCachePath,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::data::{manager::execution::ExecutionManager, watcher::Watcher};
+
+/// Breakpoints and watch expressions saved for one contract
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistedState {
+    /// Breakpoints with whether they are enabled, without bytecode addresses
+    pub breakpoints: Vec<(Breakpoint, bool)>,
+    /// Watch expressions
+    pub watches: Vec<String>,
+}
+
+/// Store that keeps breakpoints and watch expressions in sync with the cache directory
+#[derive(Debug)]
+pub struct SessionStore {
+    /// Cache of persisted states, labeled by code hash (`None` if there is no cache dir)
+    cache: Option<EdbCache<PersistedState>>,
+    /// Code hash of every bytecode address in the trace
+    code_hashes: HashMap<Address, B256>,
+    /// Contract each watch expression and symbolic breakpoint belongs to
+    origins: HashMap<String, B256>,
+    /// States last written to the cache
+    saved: BTreeMap<B256, PersistedState>,
+    /// Whether the states of earlier sessions have been restored
+    restored: bool,
+}
+
+impl Default for SessionStore {
+    fn default() -> Self {
+        let cache_dir = EdbCachePath::new(env::var("EDB_CACHE_DIR").ok()).session_cache_dir();
+        let cache = EdbCache::new(cache_dir, None).unwrap_or_else(|e| {
+            warn!("Breakpoints and watches will not be persisted: {e}");
+            None
+        });
+
+        Self {
+            cache,
+            code_hashes: HashMap::new(),
+            origins: HashMap::new(),
+            saved: BTreeMap::new(),
+            restored: false,
+        }
+    }
+}
+
+impl SessionStore {
+    /// Restore the states of earlier sessions on the first call, and save the contracts
+    /// whose breakpoints or watch expressions changed on the following ones
+    pub fn sync(&mut self, execution: &mut ExecutionManager, watcher: &mut Watcher) {
+        if self.cache.is_none() {
+            return;
+        }
+
+        if !self.restored {
+            self.restore(execution, watcher);
+            self.restored = true;
+            self.saved = self.collect(execution, watcher);
+            return;
+        }
+
+        let states = self.collect(execution, watcher);
+        let empty = PersistedState::default();
+        let code_hashes: BTreeSet<B256> = states.keys().chain(self.saved.keys()).copied().collect();
+        for code_hash in code_hashes {
+            let state = states.get(&code_hash).unwrap_or(&empty);
+            if self.saved.get(&code_hash).unwrap_or(&empty) == state {
+                continue;
+            }
+
+            debug!("Saving breakpoints and watches of contract {code_hash}");
+            if let Err(e) = self.cache.save_cache(code_hash.to_string(), state) {
+                warn!("Failed to save breakpoints and watches of contract {code_hash}: {e}");
+            }
+        }
+        self.saved = states;
+    }
+
+    /// Restore the breakpoints and watch expressions of every contract in the trace
+    fn restore(&mut self, execution: &mut ExecutionManager, watcher: &mut Watcher) {
+        for entry in execution.get_trace().iter() {
+            let Some(bytecode) = &entry.bytecode else {
+                continue;
+            };
+
+            // A created contract runs its init code first, so prefer the runtime code
+            let code_hash = keccak256(bytecode);
+            if entry.created_contract {
+                self.code_hashes.entry(entry.code_address).or_insert(code_hash);
+            } else {
+                self.code_hashes.insert(entry.code_address, code_hash);
+            }
+        }
+
+        let mut addresses: BTreeMap<B256, Vec<Address>> = BTreeMap::new();
+        for (address, code_hash) in &self.code_hashes {
+            addresses.entry(*code_hash).or_default().push(*address);
+        }
+
+        for (code_hash, addresses) in addresses {
+            let Some(state) = self.cache.load_cache(code_hash.to_string()) else {
+                continue;
+            };
+            debug!("Restoring breakpoints and watches of contract {code_hash}");
+
+            for (bp, enabled) in state.breakpoints {
+                if bp.loc.as_ref().is_some_and(|loc| loc.bytecode_address().is_some()) {
+                    for address in &addresses {
+                        execution.restore_breakpoint(relocate(&bp, *address), enabled);
+                    }
+                } else {
+                    self.origins.entry(bp.to_string()).or_insert(code_hash);
+                    execution.restore_breakpoint(bp, enabled);
+                }
+            }
+
+            for expr in state.watches {
+                self.origins.entry(watch_key(&expr)).or_insert(code_hash);
+                watcher.add_expression(expr);
+            }
+        }
+    }
+
+    /// Group the current breakpoints and watch expressions by contract
+    fn collect(
+        &mut self,
+        execution: &mut ExecutionManager,
+        watcher: &Watcher,
+    ) -> BTreeMap<B256, PersistedState> {
+        // Items added in this session belong to the contract being debugged
+        let current = execution
+            .get_current_bytecode_address()
+            .and_then(|address| self.code_hashes.get(&address).copied());
+
+        let mut states: BTreeMap<B256, PersistedState> = BTreeMap::new();
+        for (_, bp, enabled) in execution.list_breakpoints() {
+            if bp.temporary {
+                continue;
+            }
+
+            let code_hash = match bp.loc.as_ref().and_then(|loc| loc.bytecode_address()) {
+                Some(address) => self.code_hashes.get(&address).copied(),
+                None => origin(&mut self.origins, bp.to_string(), current),
+            };
+            let Some(code_hash) = code_hash else {
+                continue;
+            };
+
+            // The same code may run at several addresses
+            let entry = (relocate(bp, Address::ZERO), enabled);
+            let state = states.entry(code_hash).or_default();
+            if !state.breakpoints.contains(&entry) {
+                state.breakpoints.push(entry);
+            }
+        }
+
+        for (_, expr) in watcher.list_expressions() {
+            if let Some(code_hash) = origin(&mut self.origins, watch_key(expr), current) {
+                states.entry(code_hash).or_default().watches.push(expr.clone());
+            }
+        }
+
+        states
+    }
+}
+
+/// Contract an item belongs to, which is the current one for items not seen before
+fn origin(origins: &mut HashMap<String, B256>, key: String, current: Option<B256>) -> Option<B256> {
+    match origins.get(&key) {
+        Some(code_hash) => Some(*code_hash),
+        None => {
+            let code_hash = current?;
+            origins.insert(key, code_hash);
+            Some(code_hash)
+        }
+    }
+}
+
+/// Key of a watch expression among the origins
+fn watch_key(expr: &str) -> String {
+    format!("watch:{}", normalize_expression(expr))
+}
+
+/// Move a breakpoint to the bytecode at `address`
+fn relocate(bp: &Breakpoint, address: Address) -> Breakpoint {
+    let loc = bp.loc.as_ref().map(|loc| match loc {
+        BreakpointLocation::Source { file_path, line_number, .. } => BreakpointLocation::Source {
+            bytecode_address: address,
+            file_path: file_path.clone(),
+            line_number: *line_number,
+        },
+        BreakpointLocation::Opcode { pc, .. } => {
+            BreakpointLocation::Opcode { bytecode_address: address, pc: *pc }
+        }
+        loc => loc.clone(),
+    });
+    Breakpoint { loc, ..bp.clone() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn test_relocate_breakpoint() {
+        let bp: Breakpoint =
+            "@0x1111111111111111111111111111111111111111:42 if $x > 1".parse().unwrap();
+        let moved = relocate(&bp, address!("2222222222222222222222222222222222222222"));
+        assert_eq!(
+            moved.loc,
+            Some(BreakpointLocation::Opcode {
+                bytecode_address: address!("2222222222222222222222222222222222222222"),
+                pc: 42
+            })
+        );
+        assert_eq!(moved.condition, bp.condition);
+
+        // Symbolic locations are not tied to any address
+        let bp: Breakpoint = "@Vault.withdraw".parse().unwrap();
+        assert_eq!(relocate(&bp, Address::ZERO), bp);
+    }
+
+    #[test]
+    fn test_origin_defaults_to_current_contract() {
+        let mut origins = HashMap::new();
+        let (a, b) = (B256::with_last_byte(1), B256::with_last_byte(2));
+
+        assert_eq!(origin(&mut origins, "x".to_string(), None), None);
+        assert_eq!(origin(&mut origins, "x".to_string(), Some(a)), Some(a));
+        assert_eq!(origin(&mut origins, "x".to_string(), Some(b)), Some(a));
+    }
+}
@@ -16,7 +16,10 @@
 
 use std::collections::HashSet;
 
-use edb_common::normalize_expression;
+use edb_common::{
+    normalize_expression,
+    types::{HistoryScope, SnapshotFilter},
+};
 
 /// Watcher for monitoring user-defined expressions
 #[derive(Debug, Clone, Default)]
@@ -25,6 +28,10 @@ pub struct Watcher {
     expressions: Vec<String>,
     /// Keys of the expressions in the current state
     expression_keys: HashSet<String>,
+    /// Expression whose value history is shown, with the scanned part of the timeline
+    history: Option<(String, HistoryScope)>,
+    /// Expression of the last snapshot query, with its filter
+    query: Option<(String, SnapshotFilter)>,
 }
 
 impl Watcher {
@@ -67,4 +74,34 @@ impl Watcher {
     pub fn clear(&mut self) {
         self.expressions.clear();
     }
+
+    /// Show the value history of an expression
+    pub fn set_history(&mut self, expr: String, scope: HistoryScope) {
+        self.history = Some((expr, scope));
+    }
+
+    /// Get the expression whose value history is shown
+    pub fn history(&self) -> Option<&(String, HistoryScope)> {
+        self.history.as_ref()
+    }
+
+    /// Stop showing the value history
+    pub fn clear_history(&mut self) {
+        self.history = None;
+    }
+
+    /// Remember the last snapshot query, whose matches are navigated
+    pub fn set_query(&mut self, expr: String, filter: SnapshotFilter) {
+        self.query = Some((expr, filter));
+    }
+
+    /// Get the last snapshot query
+    pub fn query(&self) -> Option<&(String, SnapshotFilter)> {
+        self.query.as_ref()
+    }
+
+    /// Forget the last snapshot query
+    pub fn clear_query(&mut self) {
+        self.query = None;
+    }
 }
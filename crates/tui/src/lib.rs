@@ -48,10 +48,10 @@ use eyre::{bail, Result};
 use futures::{FutureExt, StreamExt};
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::{io, sync::Arc, time::Duration};
-use tokio::{select, time::interval};
+use tokio::{select, task::JoinHandle, time::interval};
 use tracing::{debug, error, info, warn};
 
-use crate::data::DataManager;
+use crate::data::{DataManager, TimelineSwitch};
 
 /// Configuration for the TUI
 #[derive(Debug, Clone)]
@@ -64,6 +64,8 @@ pub struct TuiConfig {
     pub data_fetch_interval: Duration,
     /// Enable mouse support
     pub enable_mouse: bool,
+    /// Bearer token required by the RPC server, if any
+    pub auth_token: Option<String>,
 }
 
 impl Default for TuiConfig {
@@ -73,6 +75,7 @@ impl Default for TuiConfig {
             refresh_interval: Duration::from_millis(50),
             data_fetch_interval: Duration::from_millis(200),
             enable_mouse: false,
+            auth_token: None,
         }
     }
 }
@@ -105,7 +108,8 @@ impl Tui {
         let terminal = Terminal::new(backend)?;
 
         // Create RPC client
-        let rpc_client = Arc::new(RpcClient::new(&config.rpc_url).await?);
+        let rpc_client =
+            Arc::new(RpcClient::new(&config.rpc_url, config.auth_token.as_deref()).await?);
 
         // Create app with layout manager
         let layout_config = LayoutConfig { enable_mouse: config.enable_mouse };
@@ -121,33 +125,11 @@ impl Tui {
         // Create DataManager
         let mut data_manager = crate::data::DataManager::new(self.app.rpc_client.clone()).await?;
 
-        // Get cores for background processing
-        let exec_core = data_manager.get_execution_core();
-        let resolver_core = data_manager.get_resolver_core();
+        // Spawn background tasks for core processing
+        let mut core_tasks = CoreTasks::spawn(&data_manager, self.config.data_fetch_interval);
 
-        // Spawn background task for execution core processing
-        let exec_handle = tokio::spawn(async move {
-            let mut interval = tokio::time::interval(self.config.data_fetch_interval);
-            loop {
-                interval.tick().await;
-                let mut core = exec_core.write().await;
-                if let Err(e) = core.process_pending_requests().await {
-                    error!("Error processing execution requests: {}", e);
-                }
-            }
-        });
-
-        // Spawn background task for resolver core processing
-        let resolver_handle = tokio::spawn(async move {
-            let mut interval = tokio::time::interval(self.config.data_fetch_interval);
-            loop {
-                interval.tick().await;
-                let mut core = resolver_core.write().await;
-                if let Err(e) = core.process_pending_requests().await {
-                    error!("Error processing resolver requests: {}", e);
-                }
-            }
-        });
+        // Timelines the current one is nested in, innermost last
+        let mut parents: Vec<ParentTimeline> = Vec::new();
 
         let mut event_stream = EventStream::new();
         let mut ticker = interval(self.config.refresh_interval);
@@ -241,16 +223,76 @@ impl Tui {
                 info!("App requested exit");
                 break Ok(());
             }
+
+            // Check if app wants to debug another timeline
+            if let Some(switch) = data_manager.timeline_switch.take() {
+                if let Err(e) = self
+                    .switch_timeline(switch, &mut data_manager, &mut core_tasks, &mut parents)
+                    .await
+                {
+                    error!("Failed to switch timeline: {}", e);
+                }
+            }
         };
 
         // Abort background tasks
-        exec_handle.abort();
-        resolver_handle.abort();
+        core_tasks.abort();
+        for parent in parents {
+            parent.core_tasks.abort();
+        }
 
         info!("TUI event loop ended");
         result
     }
 
+    // Switch to a nested timeline, or back to the parent timeline
+    async fn switch_timeline(
+        &mut self,
+        switch: TimelineSwitch,
+        data_manager: &mut DataManager,
+        core_tasks: &mut CoreTasks,
+        parents: &mut Vec<ParentTimeline>,
+    ) -> Result<()> {
+        match switch {
+            TimelineSwitch::Enter { port, label } => {
+                info!("Entering nested timeline on port {}: {}", port, label);
+
+                // Derived servers listen on the same host, and require the same token
+                let mut url = reqwest::Url::parse(&self.config.rpc_url)?;
+                url.set_port(Some(port)).map_err(|_| eyre::eyre!("Invalid RPC URL: {url}"))?;
+                let rpc_client = Arc::new(
+                    RpcClient::new(url.as_str(), self.config.auth_token.as_deref()).await?,
+                );
+                let layout_config = LayoutConfig { enable_mouse: self.config.enable_mouse };
+                let app = App::new(rpc_client.clone(), layout_config).await?;
+                let mut nested = DataManager::new(rpc_client).await?;
+                nested.theme = data_manager.theme.clone();
+                nested.nesting = data_manager.nesting.clone();
+                nested.nesting.push(label);
+
+                let nested_tasks = CoreTasks::spawn(&nested, self.config.data_fetch_interval);
+                parents.push(ParentTimeline {
+                    app: std::mem::replace(&mut self.app, app),
+                    data_manager: std::mem::replace(data_manager, nested),
+                    core_tasks: std::mem::replace(core_tasks, nested_tasks),
+                });
+            }
+            TimelineSwitch::Leave => {
+                let Some(parent) = parents.pop() else {
+                    warn!("Not in a nested timeline");
+                    return Ok(());
+                };
+                info!("Leaving nested timeline: {:?}", data_manager.nesting.last());
+
+                std::mem::replace(core_tasks, parent.core_tasks).abort();
+                self.app = parent.app;
+                *data_manager = parent.data_manager;
+            }
+        }
+
+        Ok(())
+    }
+
     // Handle a single resize event
     fn handle_resize(&mut self, width: u16, height: u16) {
         debug!("Terminal resized: {}x{}", width, height);
@@ -282,6 +324,59 @@ impl Tui {
     }
 }
 
+/// Background tasks processing the pending requests of the cores of a [`DataManager`]
+struct CoreTasks {
+    exec_handle: JoinHandle<()>,
+    resolver_handle: JoinHandle<()>,
+}
+
+impl CoreTasks {
+    /// Spawn the background tasks, processing pending requests every `interval`
+    fn spawn(data_manager: &DataManager, interval: Duration) -> Self {
+        let exec_core = data_manager.get_execution_core();
+        let resolver_core = data_manager.get_resolver_core();
+
+        // Spawn background task for execution core processing
+        let exec_handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                let mut core = exec_core.write().await;
+                if let Err(e) = core.process_pending_requests().await {
+                    error!("Error processing execution requests: {}", e);
+                }
+            }
+        });
+
+        // Spawn background task for resolver core processing
+        let resolver_handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                let mut core = resolver_core.write().await;
+                if let Err(e) = core.process_pending_requests().await {
+                    error!("Error processing resolver requests: {}", e);
+                }
+            }
+        });
+
+        Self { exec_handle, resolver_handle }
+    }
+
+    /// Abort the background tasks
+    fn abort(self) {
+        self.exec_handle.abort();
+        self.resolver_handle.abort();
+    }
+}
+
+/// A timeline suspended while debugging a timeline nested in it
+struct ParentTimeline {
+    app: App,
+    data_manager: DataManager,
+    core_tasks: CoreTasks,
+}
+
 impl Drop for Tui {
     fn drop(&mut self) {
         // Restore terminal state
@@ -25,18 +25,19 @@ use crate::ui::borders::BorderPresets;
 use crate::ui::colors::ColorScheme;
 use crate::ui::status::StatusBar;
 use crate::ui::syntax::{SyntaxHighlighter, SyntaxType};
+use alloy_dyn_abi::DynSolValue;
 use alloy_primitives::{Address, Bytes, U256};
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
 use edb_common::types::{
-    Breakpoint, EdbSolValue, HookSnapshotInfoDetail, OpcodeSnapshotInfoDetail, SnapshotInfoDetail,
-    SolValueFormatterContext,
+    Breakpoint, EdbSolValue, HookSnapshotInfoDetail, LogpointEntry, OpcodeSnapshotInfoDetail,
+    SnapshotInfoDetail, SolValueFormatterContext, ValueChange,
 };
 use eyre::Result;
 use ratatui::{
     layout::Rect,
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{List, ListItem, Paragraph},
+    widgets::{Block, List, ListItem, Paragraph, Sparkline},
     Frame,
 };
 use revm::state::TransientStorage;
@@ -79,6 +80,10 @@ pub enum DisplayMode {
     Storage,
     /// Show transient storage state (for opcode snapshots)
     TransientStorage,
+    /// Show the change history of a value (for both opcode and hooked snapshots)
+    History,
+    /// Show the messages logged by logpoints (for both opcode and hooked snapshots)
+    Log,
 }
 
 /// Represents a variable entry for display
@@ -99,6 +104,33 @@ struct BreakpointEntry {
     breakpoint: Breakpoint,
     enabled: bool,
     is_hit: bool,
+    /// Hits up to the current snapshot and over the whole timeline, while counted
+    hits: Option<(usize, usize)>,
+}
+
+impl BreakpointEntry {
+    /// Hit count (`?` while pending), ignore count and temporary flag, e.g.
+    /// ` (hit 1/3, ignore 2, temp)`
+    fn counts_text(&self) -> String {
+        let mut counts = Vec::new();
+        match self.hits {
+            Some((so_far, total)) => counts.push(format!("hit {so_far}/{total}")),
+            // Hits are still being counted, the panel is re-rendered once they arrive
+            None => counts.push("hit ?".to_string()),
+        }
+        if self.breakpoint.ignore_count > 0 {
+            counts.push(format!("ignore {}", self.breakpoint.ignore_count));
+        }
+        if self.breakpoint.temporary {
+            counts.push("temp".to_string());
+        }
+
+        if counts.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", counts.join(", "))
+        }
+    }
 }
 
 impl cmp::PartialOrd for VariableEntry {
@@ -138,6 +170,8 @@ impl DisplayMode {
             Self::CallData => "Call Data",
             Self::Storage => "Storage",
             Self::TransientStorage => "Transient Storage",
+            Self::History => "History",
+            Self::Log => "Log",
         }
     }
 }
@@ -238,6 +272,14 @@ pub struct DisplayPanel {
     expressions: Vec<VariableEntry>,
     /// Expressions that are toggled to multi-line (persisted by expression)
     multi_line_expressions: HashSet<String>,
+    /// Changes of the value whose history is shown
+    history_changes: Vec<ValueChange>,
+    /// Summary of the value history, or why it is not available
+    history_message: String,
+    /// Messages logged by logpoints, with the id of the logpoint
+    log_entries: Vec<(usize, LogpointEntry)>,
+    /// Summary of the logpoint log, or why it is not available
+    log_message: String,
     /// Cached display line count for storage mode
     storage_display_lines: usize,
     /// Cached display line count for transient storage mode
@@ -275,6 +317,10 @@ impl DisplayPanel {
             multi_line_expressions: HashSet::new(),
             breakpoints: Vec::new(),
             hit_breakpoints: Vec::new(),
+            history_changes: Vec::new(),
+            history_message: String::new(),
+            log_entries: Vec::new(),
+            log_message: String::new(),
             storage_display_lines: 0,
             tstorage_display_lines: 0,
             syntax_highlighter: SyntaxHighlighter::new(),
@@ -295,6 +341,12 @@ impl DisplayPanel {
         // Update breakpoints data
         self.update_breakpoints_data(dm, current_snapshot);
 
+        // Update value history data
+        self.update_history_data(dm);
+
+        // Update logpoint log data
+        self.update_log_data(dm);
+
         // Check if snapshot changed
         if self.current_execution_snapshot == Some(current_snapshot) {
             return Some(());
@@ -315,6 +367,8 @@ impl DisplayPanel {
                     DisplayMode::TransientStorage,
                     DisplayMode::Expressions,
                     DisplayMode::Breakpoints,
+                    DisplayMode::History,
+                    DisplayMode::Log,
                 ];
 
                 // Update opcode-specific data
@@ -329,7 +383,12 @@ impl DisplayPanel {
             }
             SnapshotInfoDetail::Hook(hook_detail) => {
                 self.is_opcode_snapshot = false;
-                self.available_modes = vec![DisplayMode::Variables, DisplayMode::Breakpoints];
+                self.available_modes = vec![
+                    DisplayMode::Variables,
+                    DisplayMode::Breakpoints,
+                    DisplayMode::History,
+                    DisplayMode::Log,
+                ];
 
                 // Update hook-specific data
                 self.update_hook_data(hook_detail);
@@ -597,6 +656,8 @@ impl DisplayPanel {
                 self.calculate_expressions_max_width(dm)
             }
             DisplayMode::Breakpoints => self.calculate_breakpoints_max_width(dm),
+            DisplayMode::History => self.calculate_history_max_width(dm),
+            DisplayMode::Log => self.calculate_log_max_width(dm),
         };
     }
 
@@ -796,6 +857,7 @@ impl DisplayPanel {
     fn update_breakpoints_data(&mut self, dm: &mut DataManager, current_snapshot: usize) {
         // Get hit breakpoints for current snapshot
         self.hit_breakpoints = dm.execution.get_hit_breakpoints(current_snapshot);
+        let hit_counts = dm.execution.get_breakpoint_hit_counts(current_snapshot);
 
         // Get all breakpoints from execution manager
         let breakpoint_data: Vec<(usize, &Breakpoint, bool)> =
@@ -810,10 +872,133 @@ impl DisplayPanel {
                 breakpoint: breakpoint.clone(),
                 enabled,
                 is_hit,
+                hits: hit_counts.as_ref().and_then(|counts| counts.get(id - 1).copied()),
             });
         }
     }
 
+    /// Update the value history shown in history mode
+    fn update_history_data(&mut self, dm: &mut DataManager) {
+        self.history_changes.clear();
+
+        let Some((expr, scope)) = dm.watcher.history().cloned() else {
+            self.history_message =
+                "No value history (use 'changes $<expr>' in the terminal)".to_string();
+            return;
+        };
+
+        self.history_message = match dm.execution.get_value_history(&expr, scope) {
+            Some(Ok(history)) => {
+                self.history_changes = history.changes.clone();
+                format!(
+                    "{expr} in {scope}: {} changes over {} steps",
+                    history.changes.len().saturating_sub(1),
+                    history.observed
+                )
+            }
+            Some(Err(e)) => format!("Cannot collect history of {expr}: {e}"),
+            None => format!("Collecting history of {expr}..."),
+        };
+    }
+
+    /// Format a value change for history mode
+    fn format_history_entry(
+        &self,
+        change: &ValueChange,
+        is_current: bool,
+        dm: &mut DataManager,
+    ) -> Vec<Span<'static>> {
+        let marker = if is_current { "▶ " } else { "  " };
+        let position = match (&change.path, change.line) {
+            (Some(path), Some(line)) => format!("{}:{line}", path.display()),
+            (Some(path), None) => path.display().to_string(),
+            _ => change.bytecode_address.to_string(),
+        };
+
+        let value = dm.resolver.resolve_sol_value(&change.value, None);
+        let mut spans = vec![
+            Span::styled(marker, Style::default().fg(dm.theme.accent_color)),
+            Span::styled(
+                format!("Step {:>6}", change.snapshot_id),
+                Style::default().fg(dm.theme.line_number),
+            ),
+            Span::raw(format!("  {position}  ")),
+        ];
+        spans.extend(self.highlight_solidity_value(value, dm));
+        spans
+    }
+
+    /// Calculate the maximum width for history display
+    fn calculate_history_max_width(&self, dm: &mut DataManager) -> usize {
+        let entries_width = self
+            .history_changes
+            .iter()
+            .map(|change| {
+                self.format_history_entry(change, false, dm)
+                    .iter()
+                    .map(|span| span.content.chars().count())
+                    .sum::<usize>()
+            })
+            .max()
+            .unwrap_or(0);
+        entries_width.max(self.history_message.len())
+    }
+
+    /// Update the logpoint log shown in log mode
+    fn update_log_data(&mut self, dm: &mut DataManager) {
+        self.log_entries.clear();
+
+        if !dm.execution.list_breakpoints().any(|(_, bp, enabled)| enabled && bp.is_logpoint()) {
+            self.log_message =
+                "No logpoints (use 'break add @<loc> log <format>' in the terminal)".to_string();
+            return;
+        }
+
+        self.log_message = match dm.execution.get_logpoint_log() {
+            Some(log) => {
+                self.log_entries = log;
+                format!("{} messages logged", self.log_entries.len())
+            }
+            None => "Collecting the logpoint log...".to_string(),
+        };
+    }
+
+    /// Format a logged message for log mode
+    fn format_log_entry(
+        &self,
+        logpoint_id: usize,
+        entry: &LogpointEntry,
+        is_current: bool,
+        dm: &mut DataManager,
+    ) -> Vec<Span<'static>> {
+        let marker = if is_current { "▶ " } else { "  " };
+        vec![
+            Span::styled(marker, Style::default().fg(dm.theme.accent_color)),
+            Span::styled(
+                format!("Step {:>6}", entry.snapshot_id),
+                Style::default().fg(dm.theme.line_number),
+            ),
+            Span::styled(format!("  #{logpoint_id}  "), Style::default().fg(dm.theme.info_color)),
+            Span::raw(entry.message.clone()),
+        ]
+    }
+
+    /// Calculate the maximum width for log display
+    fn calculate_log_max_width(&self, dm: &mut DataManager) -> usize {
+        let entries_width = self
+            .log_entries
+            .iter()
+            .map(|(id, entry)| {
+                self.format_log_entry(*id, entry, false, dm)
+                    .iter()
+                    .map(|span| span.content.chars().count())
+                    .sum::<usize>()
+            })
+            .max()
+            .unwrap_or(0);
+        entries_width.max(self.log_message.len())
+    }
+
     /// Calculate the maximum width for variables display
     fn calculate_variables_max_width(&self, dm: &mut DataManager) -> usize {
         let mut max_width = 0;
@@ -955,8 +1140,12 @@ impl DisplayPanel {
 
             // Location display
             if let Some(loc) = &entry.breakpoint.loc {
-                width +=
-                    loc.display(dm.resolver.resolve_address_label(loc.bytecode_address())).len();
+                width += loc
+                    .display(
+                        loc.bytecode_address()
+                            .and_then(|address| dm.resolver.resolve_address_label(address)),
+                    )
+                    .len();
             } else {
                 width += 12; // "no location"
             }
@@ -966,6 +1155,13 @@ impl DisplayPanel {
                 width += format!(" if {condition}").len();
             }
 
+            // Log format (if a logpoint)
+            if let Some(log) = &entry.breakpoint.log {
+                width += format!(" log {log}").len();
+            }
+
+            width += entry.counts_text().len();
+
             max_width = max_width.max(width);
         }
 
@@ -1011,7 +1207,10 @@ impl DisplayPanel {
 
         if let Some(loc) = &entry.breakpoint.loc {
             spans.push(Span::styled(
-                loc.display(dm.resolver.resolve_address_label(loc.bytecode_address())),
+                loc.display(
+                    loc.bytecode_address()
+                        .and_then(|address| dm.resolver.resolve_address_label(address)),
+                ),
                 location_style,
             ));
         } else {
@@ -1034,6 +1233,18 @@ impl DisplayPanel {
             spans.push(Span::styled(condition.to_string(), condition_style));
         }
 
+        // Log format (if a logpoint)
+        if let Some(log) = &entry.breakpoint.log {
+            spans.push(Span::styled(" log ", base_style));
+            spans.push(Span::styled(log.to_string(), Style::default().fg(dm.theme.accent_color)));
+        }
+
+        // Hit count, ignore count and temporary flag
+        let counts = entry.counts_text();
+        if !counts.is_empty() {
+            spans.push(Span::styled(counts, Style::default().fg(dm.theme.comment_color)));
+        }
+
         spans
     }
 
@@ -1171,6 +1382,8 @@ impl DisplayPanel {
             DisplayMode::Variables => self.calculate_variables_display_lines(dm),
             DisplayMode::Expressions => self.calculate_expressions_display_lines(dm),
             DisplayMode::Breakpoints => self.breakpoints.len(),
+            DisplayMode::History => self.history_changes.len(),
+            DisplayMode::Log => self.log_entries.len(),
         }
     }
 
@@ -1895,6 +2108,141 @@ impl DisplayPanel {
         self.render_status_and_help(frame, area, dm);
     }
 
+    /// Render value history display (for both opcode and hooked snapshots)
+    fn render_history(&mut self, frame: &mut Frame<'_>, area: Rect, dm: &mut DataManager) {
+        let block = BorderPresets::display(
+            self.focused,
+            self.title(dm),
+            dm.theme.focused_border,
+            dm.theme.unfocused_border,
+        );
+
+        if self.history_changes.is_empty() {
+            let paragraph = Paragraph::new(self.history_message.clone()).block(block);
+            frame.render_widget(paragraph, area);
+            return;
+        }
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        // Summary line, followed by a sparkline of numeric values
+        let sparkline = history_sparkline(&self.history_changes);
+        let header_height = if sparkline.is_some() { 4 } else { 1 }.min(inner.height);
+        let summary_area = Rect { height: header_height.min(1), ..inner };
+        frame.render_widget(
+            Paragraph::new(self.history_message.clone())
+                .style(Style::default().fg(dm.theme.accent_color)),
+            summary_area,
+        );
+        if let Some(data) = sparkline {
+            let sparkline_area =
+                Rect { y: inner.y + 1, height: header_height.saturating_sub(1), ..inner };
+            frame.render_widget(
+                Sparkline::default().data(&data).style(Style::default().fg(dm.theme.info_color)),
+                sparkline_area,
+            );
+        }
+
+        // The change list takes the rest of the panel
+        self.context_height = self.context_height.saturating_sub(header_height as usize);
+        let list_area = Rect {
+            y: inner.y + header_height,
+            height: inner.height.saturating_sub(header_height),
+            ..inner
+        };
+
+        // The change in effect at the current snapshot
+        let current_snapshot = dm.execution.get_current_snapshot();
+        let current_index =
+            self.history_changes.iter().rposition(|change| change.snapshot_id <= current_snapshot);
+
+        let changes = self.history_changes.clone();
+        let items: Vec<ListItem<'_>> = changes
+            .iter()
+            .enumerate()
+            .skip(self.scroll_offset)
+            .take(self.context_height)
+            .map(|(display_idx, change)| {
+                let is_selected = display_idx == self.selected_index;
+                let formatted_spans =
+                    self.format_history_entry(change, current_index == Some(display_idx), dm);
+                let formatted_line = self.apply_horizontal_offset(Line::from(formatted_spans));
+
+                let style = if is_selected && self.focused {
+                    Style::default().bg(dm.theme.selection_bg)
+                } else {
+                    Style::default()
+                };
+
+                ListItem::new(formatted_line).style(style)
+            })
+            .collect();
+
+        frame.render_widget(List::new(items).block(Block::default()), list_area);
+        self.render_status_and_help(frame, area, dm);
+    }
+
+    /// Render logpoint log display (for both opcode and hooked snapshots)
+    fn render_log(&mut self, frame: &mut Frame<'_>, area: Rect, dm: &mut DataManager) {
+        let block = BorderPresets::display(
+            self.focused,
+            self.title(dm),
+            dm.theme.focused_border,
+            dm.theme.unfocused_border,
+        );
+
+        if self.log_entries.is_empty() {
+            let paragraph = Paragraph::new(self.log_message.clone()).block(block);
+            frame.render_widget(paragraph, area);
+            return;
+        }
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        // Summary line, followed by the messages
+        let summary_area = Rect { height: inner.height.min(1), ..inner };
+        frame.render_widget(
+            Paragraph::new(self.log_message.clone())
+                .style(Style::default().fg(dm.theme.accent_color)),
+            summary_area,
+        );
+
+        self.context_height = self.context_height.saturating_sub(1);
+        let list_area = Rect { y: inner.y + 1, height: inner.height.saturating_sub(1), ..inner };
+
+        // The latest message logged up to the current snapshot
+        let current_snapshot = dm.execution.get_current_snapshot();
+        let current_index =
+            self.log_entries.iter().rposition(|(_, entry)| entry.snapshot_id <= current_snapshot);
+
+        let entries = self.log_entries.clone();
+        let items: Vec<ListItem<'_>> = entries
+            .iter()
+            .enumerate()
+            .skip(self.scroll_offset)
+            .take(self.context_height)
+            .map(|(display_idx, (id, entry))| {
+                let is_selected = display_idx == self.selected_index;
+                let formatted_spans =
+                    self.format_log_entry(*id, entry, current_index == Some(display_idx), dm);
+                let formatted_line = self.apply_horizontal_offset(Line::from(formatted_spans));
+
+                let style = if is_selected && self.focused {
+                    Style::default().bg(dm.theme.selection_bg)
+                } else {
+                    Style::default()
+                };
+
+                ListItem::new(formatted_line).style(style)
+            })
+            .collect();
+
+        frame.render_widget(List::new(items).block(Block::default()), list_area);
+        self.render_status_and_help(frame, area, dm);
+    }
+
     /// Render status and help text
     fn render_status_and_help(&self, frame: &mut Frame<'_>, area: Rect, dm: &mut DataManager) {
         if !self.focused || area.height <= 10 {
@@ -1958,6 +2306,9 @@ impl DisplayPanel {
                 "Vim-like Navigation • s/S: Switch mode • Enter: Toggle enable/disable • ?: Help"
                     .to_string()
             }
+            DisplayMode::History | DisplayMode::Log => {
+                "Vim-like Navigation • s/S: Switch mode • Enter: Go to step • ?: Help".to_string()
+            }
             _ => "Vim-like Navigation • s/S: Switch mode • ?: Help".to_string(),
         };
 
@@ -1988,6 +2339,8 @@ impl PanelTr for DisplayPanel {
             DisplayMode::Variables => self.variables.len(),
             DisplayMode::Expressions => self.expressions.len(),
             DisplayMode::Breakpoints => self.breakpoints.len(),
+            DisplayMode::History => self.history_changes.len(),
+            DisplayMode::Log => self.log_entries.len(),
         };
 
         let snapshot_type = if self.is_opcode_snapshot { "Opcode" } else { "Hook" };
@@ -2022,6 +2375,8 @@ impl PanelTr for DisplayPanel {
             DisplayMode::Variables => self.render_variables(frame, area, dm),
             DisplayMode::Expressions => self.render_expressions(frame, area, dm),
             DisplayMode::Breakpoints => self.render_breakpoints(frame, area, dm),
+            DisplayMode::History => self.render_history(frame, area, dm),
+            DisplayMode::Log => self.render_log(frame, area, dm),
         }
     }
 
@@ -2094,6 +2449,18 @@ impl PanelTr for DisplayPanel {
                         self.toggle_breakpoint(dm);
                         Ok(EventResponse::Handled)
                     }
+                    DisplayMode::History => {
+                        if let Some(change) = self.history_changes.get(self.selected_index) {
+                            dm.execution.goto(change.snapshot_id)?;
+                        }
+                        Ok(EventResponse::Handled)
+                    }
+                    DisplayMode::Log => {
+                        if let Some((_, entry)) = self.log_entries.get(self.selected_index) {
+                            dm.execution.goto(entry.snapshot_id)?;
+                        }
+                        Ok(EventResponse::Handled)
+                    }
                     _ => Ok(EventResponse::NotHandled),
                 }
             }
@@ -2137,6 +2504,30 @@ impl PanelTr for DisplayPanel {
 }
 
 // Helper functions
+/// Sparkline data of a value history, scaled between the smallest and largest value.
+/// Returns `None` unless all values are numeric or boolean.
+fn history_sparkline(changes: &[ValueChange]) -> Option<Vec<u64>> {
+    let values = changes
+        .iter()
+        .map(|change| match &change.value.0 {
+            DynSolValue::Uint(value, _) => Some(f64::from(*value)),
+            DynSolValue::Int(value, _) => {
+                let (sign, abs) = value.into_sign_and_abs();
+                Some(if sign.is_negative() { -f64::from(abs) } else { f64::from(abs) })
+            }
+            DynSolValue::Bool(value) => Some(if *value { 1.0 } else { 0.0 }),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = if max > min { max - min } else { 1.0 };
+
+    // Keep the smallest value visible as a bar
+    Some(values.iter().map(|value| 1 + ((value - min) / range * 99.0) as u64).collect())
+}
+
 /// Format bytes as hex with ASCII decode (like xxd)
 fn format_bytes_with_decode<'a>(
     bytes: &'a [u8],
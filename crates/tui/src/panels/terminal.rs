@@ -19,7 +19,7 @@
 //! This panel provides a command-line interface for debugging commands.
 
 use super::{EventResponse, PanelTr, PanelType};
-use crate::data::DataManager;
+use crate::data::{DataManager, TimelineSwitch};
 use crate::panels::utils;
 use crate::ui::borders::BorderPresets;
 use crate::ui::icons::Icons;
@@ -29,10 +29,12 @@ use crate::{Spinner, SpinnerStyles};
 use alloy_dyn_abi::DynSolValue;
 use alloy_primitives::{Address, U256};
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
-use edb_common::normalize_expression;
 use edb_common::types::{
-    Breakpoint, BreakpointLocation, Code, SnapshotInfoDetail, SolValueFormatterContext,
+    Breakpoint, BreakpointLocation, CallResult, Code, EvalReport, HistoryScope, SearchDirection,
+    SnapshotFilter, SnapshotInfoDetail, SnapshotKind, SolValueFormatterContext, StateEdit,
+    ValueLocation, ValueOriginKind, Watchpoint, WhatIfResult,
 };
+use edb_common::{mutates_overlay, normalize_expression};
 use eyre::{bail, eyre, Result};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
@@ -41,6 +43,7 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, Paragraph},
     Frame,
 };
+use revm::bytecode::OpCode;
 use std::collections::VecDeque;
 use std::str::FromStr;
 use std::time::Instant;
@@ -83,6 +86,23 @@ enum TerminalMode {
     Vim,
 }
 
+/// Error of a command that still produced some output, shown before the error
+#[derive(Debug)]
+struct PartialOutput {
+    /// Output produced before the failure
+    output: String,
+    /// Error message
+    error: String,
+}
+
+impl std::fmt::Display for PartialOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl std::error::Error for PartialOutput {}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum PendingCommand {
     /// Step forward in execution
@@ -93,6 +113,10 @@ enum PendingCommand {
     NextCall(usize),
     /// Goto previous call in execution
     PrevCall(usize),
+    /// Continue to the next breakpoint hit
+    Continue(usize),
+    /// Continue backward to the previous breakpoint hit
+    ReverseContinue(usize),
     /// Step forward without going into callees
     StepForwardNoCallees(usize),
     /// Step backward without going into callees
@@ -115,6 +139,30 @@ enum PendingCommand {
     ShowAddress(usize),
     /// Evaluate Solidity expression
     EvalExpr(usize, String),
+    /// Evaluate Solidity expression changing the session overlay, by submission number
+    EvalMutation(usize, String),
+    /// Trace a value back to its origin
+    TraceOrigin(usize, ValueLocation),
+    /// Goto next change of any watchpoint
+    NextWatchpoint(usize),
+    /// Goto previous change of any watchpoint
+    PrevWatchpoint(usize),
+    /// Re-execute with state edits applied at a snapshot
+    WhatIf(usize, Vec<StateEdit>),
+    /// Replay with the patched source code (path, content) of a contract
+    PatchedReplay(Address, String, String),
+    /// Collect the change history of an expression
+    ValueHistory(String, HistoryScope),
+    /// Find the snapshots at which an expression holds
+    FindSnapshots(String, SnapshotFilter),
+    /// Debug a call (by index, the last one by default) made by an expression
+    StepInto(usize, String, Option<usize>),
+    /// Show the call stack at a snapshot
+    Backtrace(usize),
+    /// Select a frame of the call stack at a snapshot
+    SelectFrame(usize, usize),
+    /// List the breakpoints with their hit counts up to a snapshot
+    ListBreakpoints(usize),
 }
 
 impl PendingCommand {
@@ -137,6 +185,17 @@ impl PendingCommand {
                     dm.execution.get_snapshot_info(id)?;
                     dm.execution.get_code(id)?;
                 }
+                PendingCommand::Continue(src_id) => {
+                    let id = dm.execution.get_continue_target(*src_id, SearchDirection::Forward)?;
+                    dm.execution.get_snapshot_info(id)?;
+                    dm.execution.get_code(id)?;
+                }
+                PendingCommand::ReverseContinue(src_id) => {
+                    let id =
+                        dm.execution.get_continue_target(*src_id, SearchDirection::Backward)?;
+                    dm.execution.get_snapshot_info(id)?;
+                    dm.execution.get_code(id)?;
+                }
                 PendingCommand::StepForwardNoCallees(src_id) => {
                     let id = dm.execution.get_snapshot_info(*src_id)?.next_id;
                     dm.execution.get_snapshot_info(id)?;
@@ -177,7 +236,52 @@ impl PendingCommand {
                     dm.resolver.eval_on_snapshot(*id, &expr)?;
                 }
                 PendingCommand::EvalExpr(id, expr) => {
-                    dm.resolver.eval_on_snapshot(*id, expr)?;
+                    dm.resolver.eval_report_on_snapshot(*id, expr)?;
+                }
+                PendingCommand::EvalMutation(seq, _) => {
+                    dm.resolver.get_mutation_report(*seq)?;
+                }
+                PendingCommand::TraceOrigin(id, location) => {
+                    if let Ok(origin) = dm.execution.get_value_origin(*id, *location)?.clone() {
+                        // Source code is needed to resolve line numbers of source steps
+                        for step in origin.steps.iter().filter(|step| step.path.is_some()) {
+                            dm.execution.get_code_by_bytecode_address(step.bytecode_address)?;
+                        }
+                    }
+                }
+                PendingCommand::NextWatchpoint(src_id) | PendingCommand::PrevWatchpoint(src_id) => {
+                    let forward = matches!(cmd, PendingCommand::NextWatchpoint(_));
+                    if let Some((_, hit)) =
+                        dm.execution.get_nearest_watchpoint_hit(*src_id, forward)?
+                    {
+                        dm.execution.get_snapshot_info(hit.snapshot_id)?;
+                        dm.execution.get_code(hit.snapshot_id)?;
+                    }
+                }
+                PendingCommand::WhatIf(id, edits) => {
+                    dm.execution.get_what_if(*id, edits)?;
+                }
+                PendingCommand::PatchedReplay(address, path, source) => {
+                    dm.execution.get_patched_replay(*address, path, source)?;
+                }
+                PendingCommand::ValueHistory(expr, scope) => {
+                    dm.execution.get_value_history(expr, *scope)?;
+                }
+                PendingCommand::FindSnapshots(expr, filter) => {
+                    dm.execution.get_snapshot_matches(expr, filter)?;
+                }
+                PendingCommand::StepInto(id, expr, call_index) => {
+                    dm.execution.get_nested_call(*id, expr, *call_index)?;
+                }
+                PendingCommand::ListBreakpoints(id) => {
+                    dm.execution.get_breakpoint_hit_counts(*id)?;
+                }
+                PendingCommand::Backtrace(id) | PendingCommand::SelectFrame(id, _) => {
+                    // Source code is needed to resolve line numbers of the frames
+                    for frame_id in dm.execution.get_call_stack(*id)?.clone() {
+                        dm.execution.get_snapshot_info(frame_id)?;
+                        dm.execution.get_code(frame_id)?;
+                    }
                 }
             }
             Some(())
@@ -186,6 +290,72 @@ impl PendingCommand {
         inner(self, dm).is_none()
     }
 
+    /// Describe the value of an expression, after the calls it made
+    fn format_eval_report(dm: &mut DataManager, expr: &str, report: EvalReport) -> Result<String> {
+        let mut lines = Vec::new();
+        for call in &report.calls {
+            let options =
+                if call.options.is_empty() { String::new() } else { call.options.to_string() };
+            let status = match &call.revert_reason {
+                Some(reason) => format!("✗ reverted: {reason}"),
+                None => "✓".to_string(),
+            };
+            let callee = dm.resolver.resolve_sol_value(&DynSolValue::Address(call.to), None);
+            lines.push(format!(
+                "  ↳ {}.{}{} {} (gas used: {})",
+                callee, call.function, options, status, call.gas_used
+            ));
+            for log in &call.logs {
+                let event =
+                    dm.resolver.resolve_event(&log.data, Some(log.address)).unwrap_or_else(|| {
+                        format!("Unknown event ({} bytes data)", log.data.data.len())
+                    });
+                lines.push(format!("      · [EVENT] {event}"));
+            }
+        }
+
+        // The calls made before a failure are still worth listing
+        let value =
+            report.value.map_err(|error| PartialOutput { output: lines.join("\n"), error })?;
+        let ctx = SolValueFormatterContext::new().with_ty(true).multi_line(true);
+        lines.insert(0, format!("{} = {}", expr, dm.resolver.resolve_sol_value(&value, Some(ctx))));
+        Ok(lines.join("\n"))
+    }
+
+    /// Describe a frame of the call stack by its snapshot and position
+    fn format_frame(dm: &mut DataManager, index: usize, frame_id: usize) -> Result<String> {
+        let info = dm
+            .execution
+            .get_snapshot_info(frame_id)
+            .ok_or(eyre!("No snapshot info found"))?
+            .clone();
+        let position = match info.detail() {
+            SnapshotInfoDetail::Opcode(detail) => {
+                format!("pc {} {}", detail.pc, OpCode::name_by_op(detail.opcode))
+            }
+            SnapshotInfoDetail::Hook(detail) => {
+                let line = match dm.execution.get_code(frame_id) {
+                    Some(Code::Source(code)) => code.sources.get(&detail.path).map(|source| {
+                        // Count in bytes, as the offset may not be at a character boundary
+                        let end = detail.offset.min(source.len());
+                        source.as_bytes()[..end].iter().filter(|&&b| b == b'\n').count() + 1
+                    }),
+                    _ => None,
+                };
+                match line {
+                    Some(line) => format!("{}:{line}", detail.path.display()),
+                    None => format!("{}", detail.path.display()),
+                }
+            }
+        };
+
+        let address = dm
+            .resolver
+            .resolve_address_label(info.bytecode_address)
+            .unwrap_or_else(|| info.bytecode_address.to_string());
+        Ok(format!("#{index:<3} Step {frame_id:>6} | {address} {position}"))
+    }
+
     /// Output when command is finished
     fn output_finished(&self, dm: &mut DataManager) -> Result<String> {
         let id = dm.execution.get_current_snapshot();
@@ -202,6 +372,39 @@ impl PendingCommand {
                     dm.execution.get_prev_call(*src_id).ok_or(eyre!("No previous call found"))?;
                 Ok(format!("Goto Previous Call at Step {prev_id}"))
             }
+            Self::Continue(src_id) | Self::ReverseContinue(src_id) => {
+                let direction = if matches!(self, Self::Continue(_)) {
+                    SearchDirection::Forward
+                } else {
+                    SearchDirection::Backward
+                };
+                let target = dm
+                    .execution
+                    .get_continue_target(*src_id, direction)
+                    .ok_or(eyre!("No breakpoint hit found"))?;
+
+                let hit_ids = dm.execution.get_hit_breakpoints(target);
+                if hit_ids.is_empty() {
+                    let end = match direction {
+                        SearchDirection::Forward => "end",
+                        SearchDirection::Backward => "beginning",
+                    };
+                    Ok(format!("No breakpoint hit, reached the {end} at Step {target}"))
+                } else {
+                    let ids = hit_ids.iter().map(|id| format!("#{id}")).collect::<Vec<_>>();
+                    let mut output = format!("Breakpoint {} hit at Step {target}", ids.join(", "));
+
+                    let removed = dm.execution.remove_temporary_breakpoints(&hit_ids);
+                    if !removed.is_empty() {
+                        let ids = removed.iter().map(|id| format!("#{id}")).collect::<Vec<_>>();
+                        output.push_str(&format!(
+                            " (temporary breakpoint {} deleted)",
+                            ids.join(", ")
+                        ));
+                    }
+                    Ok(output)
+                }
+            }
             Self::StepForwardNoCallees(src_id) => {
                 let next_id = dm
                     .execution
@@ -373,18 +576,343 @@ impl PendingCommand {
                     info.target_address, info.bytecode_address
                 ))
             }
-            Self::EvalExpr(_, expr) => {
-                let value =
-                    dm.resolver.eval_on_snapshot(id, expr).ok_or(eyre!("No value found"))?.clone();
+            Self::EvalExpr(eval_id, expr) => {
+                let report = dm
+                    .resolver
+                    .eval_report_on_snapshot(*eval_id, expr)
+                    .ok_or(eyre!("No value found"))?
+                    .clone();
+                Self::format_eval_report(dm, expr, report)
+            }
+            Self::EvalMutation(seq, expr) => {
+                let report =
+                    dm.resolver.get_mutation_report(*seq).ok_or(eyre!("No value found"))?.clone();
+                Self::format_eval_report(dm, expr, report)
+            }
+            Self::TraceOrigin(id, location) => {
+                let origin = dm
+                    .execution
+                    .get_value_origin(*id, *location)
+                    .ok_or(eyre!("No value origin found"))?
+                    .clone()
+                    .map_err(|e| eyre!(e))?;
+
+                let kind = match &origin.kind {
+                    ValueOriginKind::Constant => "constant in bytecode".to_string(),
+                    ValueOriginKind::Computation => "computed by the instruction".to_string(),
+                    ValueOriginKind::Environment => "read from the environment".to_string(),
+                    ValueOriginKind::CallOutcome => "outcome of a call".to_string(),
+                    ValueOriginKind::TransactionInput => "transaction input".to_string(),
+                    ValueOriginKind::Initial => "present before it was traced".to_string(),
+                    ValueOriginKind::SourceStep => "written by a source-level step".to_string(),
+                    ValueOriginKind::Unresolved(reason) => format!("unresolved ({reason})"),
+                };
+
+                let mut lines = vec![format!("Origin of {location} at Step {id}: {kind}")];
+                for step in &origin.steps {
+                    let position = if let (Some(pc), Some(op)) = (step.pc, step.opcode) {
+                        format!("pc {pc} {}", OpCode::name_by_op(op))
+                    } else if let Some(path) = &step.path {
+                        match step.line {
+                            Some(line) => format!("{}:{line}", path.display()),
+                            None => format!("{}", path.display()),
+                        }
+                    } else {
+                        "unknown position".to_string()
+                    };
+
+                    let address = dm
+                        .resolver
+                        .resolve_address_label(step.bytecode_address)
+                        .unwrap_or_else(|| step.bytecode_address.to_string());
+                    let flow = match &step.read_from {
+                        Some(read_from) => format!("{} <- {read_from}", step.written),
+                        None => format!("{}", step.written),
+                    };
+                    lines.push(format!(
+                        "  Step {:>6} | {address} {position} | {flow}",
+                        step.snapshot_id
+                    ));
+                }
+
+                Ok(lines.join("\n"))
+            }
+            Self::NextWatchpoint(src_id) | Self::PrevWatchpoint(src_id) => {
+                let forward = matches!(self, Self::NextWatchpoint(_));
+                let watchpoints: Vec<_> =
+                    dm.execution.list_watchpoints().map(|(i, wp)| (i, wp.clone())).collect();
+
+                let mut lines = Vec::new();
+                for (wp_id, wp) in &watchpoints {
+                    let result = if forward {
+                        dm.execution.get_next_watchpoint_hit(*src_id, wp)
+                    } else {
+                        dm.execution.get_prev_watchpoint_hit(*src_id, wp)
+                    };
+                    if let Some(Err(e)) = result {
+                        lines.push(format!("Watchpoint #{wp_id} ({wp}) cannot be resolved: {e}"));
+                    }
+                }
+
+                let nearest = dm
+                    .execution
+                    .get_nearest_watchpoint_hit(*src_id, forward)
+                    .ok_or(eyre!("No watchpoint hit found"))?;
+                match nearest {
+                    Some((wp_id, hit)) => {
+                        let wp = &watchpoints[wp_id - 1].1;
+                        let old = dm.resolver.resolve_sol_value(&hit.old_value, None);
+                        let new = dm.resolver.resolve_sol_value(&hit.new_value, None);
+                        lines.push(format!(
+                            "Watchpoint #{wp_id} hit at Step {}: {wp}",
+                            hit.snapshot_id
+                        ));
+                        lines
+                            .push(format!("  Old value: {old} (at Step {})", hit.prev_snapshot_id));
+                        lines.push(format!("  New value: {new}"));
+                    }
+                    None => {
+                        let direction = if forward { "after" } else { "before" };
+                        lines.push(format!("No watchpoint changes {direction} Step {src_id}"));
+                    }
+                }
+
+                Ok(lines.join("\n"))
+            }
+            Self::WhatIf(id, edits) => {
+                let result = dm
+                    .execution
+                    .get_what_if(*id, edits)
+                    .ok_or(eyre!("No what-if result found"))?
+                    .clone()
+                    .map_err(|e| eyre!(e))?;
+                Ok(format_what_if_result(
+                    &format!("Re-executed with {} state edits at Step {id}", edits.len()),
+                    format!("what-if at Step {id}"),
+                    &result,
+                    dm,
+                ))
+            }
+            Self::PatchedReplay(address, path, source) => {
+                let result = dm
+                    .execution
+                    .get_patched_replay(*address, path, source)
+                    .ok_or(eyre!("No patched replay result found"))?
+                    .clone()
+                    .map_err(|e| eyre!(e))?;
+
+                Ok(format_what_if_result(
+                    &format!("Replayed with patched {path} of {address}"),
+                    format!("patched {path}"),
+                    &result,
+                    dm,
+                ))
+            }
+            Self::ValueHistory(expr, scope) => {
+                let history = dm
+                    .execution
+                    .get_value_history(expr, *scope)
+                    .ok_or(eyre!("No value history found"))?
+                    .clone()
+                    .map_err(|e| eyre!(e))?;
+
+                let mut lines = vec![format!(
+                    "{expr} in {scope}: {} changes over {} steps",
+                    history.changes.len().saturating_sub(1),
+                    history.observed
+                )];
+                for change in &history.changes {
+                    lines.push(format!(
+                        "  Step {:>6} | {}",
+                        change.snapshot_id,
+                        dm.resolver.resolve_sol_value(&change.value, None)
+                    ));
+                }
+                lines.push("See the History mode of the display panel for details".to_string());
+
+                Ok(lines.join("\n"))
+            }
+            Self::FindSnapshots(expr, filter) => {
+                let matches = dm
+                    .execution
+                    .get_snapshot_matches(expr, filter)
+                    .ok_or(eyre!("No snapshot matches found"))?
+                    .clone()
+                    .map_err(|e| eyre!(e))?;
+
+                if matches.is_empty() {
+                    return Ok(format!("No snapshot in {filter} where {expr} holds"));
+                }
+
+                let mut lines =
+                    vec![format!("{} snapshots in {filter} where {expr} holds:", matches.len())];
+                for chunk in matches.chunks(10).take(5) {
+                    let ids: Vec<_> = chunk.iter().map(|id| format!("{id:>6}")).collect();
+                    lines.push(format!("  {}", ids.join(" ")));
+                }
+                if matches.len() > 50 {
+                    lines.push(format!("  ... and {} more", matches.len() - 50));
+                }
+                lines.push("Use 'find next' / 'find prev' to go to the matches".to_string());
 
-                let ctx = SolValueFormatterContext::new().with_ty(true).multi_line(true);
+                Ok(lines.join("\n"))
+            }
+            Self::Backtrace(id) => {
+                let frames =
+                    dm.execution.get_call_stack(*id).ok_or(eyre!("No call stack found"))?.clone();
+                let selected = dm.execution.get_selected_frame();
 
-                value
-                    .map(|v| format!("{} = {}", expr, dm.resolver.resolve_sol_value(&v, Some(ctx))))
-                    .map_err(|e| eyre!(e))
+                let mut lines = vec![format!("Call stack at Step {id} (innermost first):")];
+                for (index, frame_id) in frames.into_iter().enumerate() {
+                    let marker = if index == selected { "→" } else { " " };
+                    lines.push(format!("{marker} {}", Self::format_frame(dm, index, frame_id)?));
+                }
+                Ok(lines.join("\n"))
             }
+            Self::ListBreakpoints(id) => {
+                let hit_counts = dm.execution.get_breakpoint_hit_counts(*id);
+                let breakpoints: Vec<_> = dm
+                    .execution
+                    .list_breakpoints()
+                    .map(|(id, bp, enabled)| (id, bp.clone(), enabled))
+                    .collect();
+                if breakpoints.is_empty() {
+                    return Ok("No breakpoints set".to_string());
+                }
+
+                let mut lines = vec!["Breakpoints:".to_string()];
+                for (id, bp, enabled) in breakpoints {
+                    let status = if enabled { "enabled" } else { "disabled" };
+                    let loc_desc = bp
+                        .loc
+                        .as_ref()
+                        .map(|loc| {
+                            loc.display(
+                                loc.bytecode_address()
+                                    .and_then(|address| dm.resolver.resolve_address_label(address)),
+                            )
+                        })
+                        .unwrap_or_else(|| "no location".to_string());
+
+                    let mut line_str = format!("  #{id}: {loc_desc}");
+                    if let Some(code) = &bp.condition {
+                        line_str.push_str(&format!(" if {code}"));
+                    }
+                    if let Some(log) = &bp.log {
+                        line_str.push_str(&format!(" log {log}"));
+                    }
+                    line_str.push_str(&format!(" [{status}]"));
+                    if let Some((so_far, total)) =
+                        hit_counts.as_ref().and_then(|counts| counts.get(id - 1))
+                    {
+                        line_str.push_str(&format!(" hit {so_far}/{total}"));
+                    }
+                    if bp.ignore_count > 0 {
+                        line_str.push_str(&format!(", ignore {}", bp.ignore_count));
+                    }
+                    if bp.temporary {
+                        line_str.push_str(", temporary");
+                    }
+                    lines.push(line_str);
+                }
+                Ok(lines.join("\n"))
+            }
+            Self::SelectFrame(id, frame) => {
+                let frame_id = dm
+                    .execution
+                    .get_call_stack(*id)
+                    .ok_or(eyre!("No call stack found"))?
+                    .get(*frame)
+                    .copied()
+                    .ok_or(eyre!("No frame #{frame} at Step {id}, see `bt` for the call stack"))?;
+
+                dm.execution.select_frame(*frame)?;
+                Self::format_frame(dm, *frame, frame_id)
+            }
+            Self::StepInto(id, expr, call_index) => {
+                let nested = dm
+                    .execution
+                    .get_nested_call(*id, expr, *call_index)
+                    .ok_or(eyre!("No nested call timeline found"))?
+                    .clone()
+                    .map_err(|e| eyre!(e))?;
+
+                let callee =
+                    dm.resolver.resolve_sol_value(&DynSolValue::Address(nested.call.to), None);
+                let label = format!("{callee}.{}", nested.call.function);
+                let mut lines = vec![format!("Stepping into {label} at Step {id}")];
+                if !nested.call.options.is_empty() {
+                    lines.push(format!("  Options: {}", nested.call.options));
+                }
+                match &nested.call.revert_reason {
+                    Some(reason) => lines.push(format!("  Result:  reverted: {reason}")),
+                    None => lines.push("  Result:  success".to_string()),
+                }
+                lines.push(format!(
+                    "  Nested timeline: edb-tui --url http://localhost:{}",
+                    nested.port
+                ));
+                lines.push("Use 'return' to come back to this timeline".to_string());
+
+                dm.timeline_switch = Some(TimelineSwitch::Enter { port: nested.port, label });
+                Ok(lines.join("\n"))
+            }
+        }
+    }
+}
+
+/// Format the comparison of a derived timeline against the current one, and switch to it
+fn format_what_if_result(
+    title: &str,
+    label: String,
+    result: &WhatIfResult,
+    dm: &mut DataManager,
+) -> String {
+    let diff = &result.diff;
+
+    let format_result = |result: &Option<CallResult>| match result {
+        Some(CallResult::Success { .. }) => "success".to_string(),
+        Some(CallResult::Revert { output, .. }) => format!("revert ({output})"),
+        Some(CallResult::Error { result, .. }) => format!("error ({result:?})"),
+        None => "unknown".to_string(),
+    };
+
+    let mut lines = vec![
+        title.to_string(),
+        format!(
+            "  Snapshots:        {} -> {}",
+            diff.original_snapshot_count, diff.derived_snapshot_count
+        ),
+    ];
+    match diff.divergence {
+        Some(step) => lines.push(format!("  Diverges at:      Step {step}")),
+        None => lines.push("  Diverges at:      never (same steps)".to_string()),
+    }
+    lines.push(format!(
+        "  Result:           {} -> {}",
+        format_result(&diff.original_result),
+        format_result(&diff.derived_result)
+    ));
+
+    if diff.storage_changes.is_empty() {
+        lines.push("  No storage changes at the end of the transaction".to_string());
+    } else {
+        lines.push("  Storage changes at the end of the transaction:".to_string());
+        for change in &diff.storage_changes {
+            let address = dm
+                .resolver
+                .resolve_address_label(change.address)
+                .unwrap_or_else(|| change.address.to_string());
+            lines.push(format!(
+                "    {address}[{:#x}]: {:#x} -> {:#x}",
+                change.slot, change.original, change.derived
+            ));
         }
     }
+    lines.push("Use 'return' to come back to this timeline".to_string());
+
+    dm.timeline_switch = Some(TimelineSwitch::Enter { port: result.port, label });
+    lines.join("\n")
 }
 
 /// Terminal panel implementation with vim-style navigation
@@ -618,12 +1146,52 @@ impl TerminalPanel {
             cmd if cmd.starts_with("watch") => {
                 self.handle_watch_command(cmd[5..].trim(), dm);
             }
-            cmd if cmd.starts_with('$') => {
-                // Solidity expression evaluation
+            cmd if cmd.starts_with("changes") => {
+                self.handle_changes_command(cmd[7..].trim(), dm);
+            }
+            cmd if cmd.starts_with("find") => {
+                if let Err(e) = self.handle_find_command(cmd[4..].trim(), dm) {
+                    self.add_error(&e.to_string());
+                }
+            }
+            cmd if cmd.starts_with("stepinto") => {
+                if let Err(e) = self.handle_step_into_command(cmd[8..].trim(), dm) {
+                    self.add_error(&e.to_string());
+                }
+            }
+            "return" => match dm.nesting.last() {
+                Some(label) => {
+                    self.add_system(&format!("Returning from {label}..."));
+                    dm.timeline_switch = Some(TimelineSwitch::Leave);
+                }
+                None => self.add_error("Not in a nested timeline, nothing to return from"),
+            },
+            "bt" | "backtrace" => {
                 let id = dm.execution.get_current_snapshot();
+                self.pending_command = Some(PendingCommand::Backtrace(id));
+                self.spinner.start_loading("Fetching call stack...");
+            }
+            cmd if ["frame", "up", "down"]
+                .contains(&cmd.split_whitespace().next().unwrap_or_default()) =>
+            {
+                if let Err(e) = self.handle_frame_command(cmd, dm) {
+                    self.add_error(&e.to_string());
+                }
+            }
+            cmd if cmd.starts_with('$') => {
+                // Solidity expression evaluation, in the selected frame of the call stack
+                let id = dm.execution.get_frame_snapshot();
                 let expr = cmd[1..].trim();
 
-                self.pending_command = Some(PendingCommand::EvalExpr(id, expr.to_string()));
+                // Expressions changing the overlay are evaluated once, never from the cache
+                self.pending_command = Some(if mutates_overlay(expr) {
+                    PendingCommand::EvalMutation(
+                        dm.resolver.submit_mutation(id, expr),
+                        expr.to_string(),
+                    )
+                } else {
+                    PendingCommand::EvalExpr(id, expr.to_string())
+                });
                 self.spinner.start_loading("Fetching evaluation result...");
             }
             cmd => {
@@ -690,6 +1258,18 @@ impl TerminalPanel {
                 self.spinner.start_loading("Stepping to previous function call...");
                 dm.execution.prev_call()?;
             }
+            "continue" | "cont" => {
+                let id = dm.execution.get_current_snapshot();
+                self.pending_command = Some(PendingCommand::Continue(id));
+                self.spinner.start_loading("Continuing to next breakpoint...");
+                dm.execution.continue_execution()?;
+            }
+            "reverse-continue" | "rcont" => {
+                let id = dm.execution.get_current_snapshot();
+                self.pending_command = Some(PendingCommand::ReverseContinue(id));
+                self.spinner.start_loading("Continuing backward to previous breakpoint...");
+                dm.execution.reverse_continue()?;
+            }
             "abi" => {
                 let id = dm.execution.get_current_snapshot();
                 let address = if parts.len() > 1 {
@@ -726,39 +1306,27 @@ impl TerminalPanel {
                     self.add_error(&format!("No snapshot info found for id {id}"));
                 }
             }
+            "tbreak" => {
+                // Shorthand for `break add -t`
+                let command = format!("break add -t {}", parts[1..].join(" "));
+                return self.handle_debug_command(command.trim(), dm);
+            }
             "break" => {
                 if parts.len() < 2 {
                     self.add_output("Usage:");
-                    self.add_output("  break add [@<loc>] [if $<expr>] - Add breakpoint");
-                    self.add_output("        <loc> := <addr>:<path>:<line> (source)");
-                    self.add_output("               | <addr>:<pc>          (opcode)");
-                    self.add_output("  break remove <id>               - Remove breakpoint");
-                    self.add_output("  break enable <id>               - Enable breakpoint");
-                    self.add_output("  break disable <id>              - Disable breakpoint");
-                    self.add_output(
-                        "  break add_expr <id> $<expr>     - Add condition to breakpoint",
-                    );
-                    self.add_output("  break list                      - List all breakpoints");
-                    self.add_output("  break clear                     - Clear all breakpoints");
-                    self.add_output("");
-                    self.add_output("Breakpoint types:");
-                    self.add_output("  • Location only: Stops when execution reaches the location");
-                    self.add_output(
-                        "  • Condition only: Data breakpoint - stops when expression is true",
-                    );
-                    self.add_output(
-                        "  • Both: Stops when location is reached AND condition is true",
-                    );
-                    self.add_output(
-                        "  • Neither: Invalid - breakpoint must have location or condition",
-                    );
+                    self.show_breakpoint_usage();
                     return Ok(());
                 }
 
                 match parts[1] {
                     "add" => {
+                        // `break add -t ...` (or `tbreak ...`) adds a temporary breakpoint
+                        let temporary = parts.get(2) == Some(&"-t");
+                        let parts =
+                            if temporary { [&parts[..2], &parts[3..]].concat() } else { parts };
+
                         // Parse: break add [@<loc>] [if <expr>]
-                        let breakpoint = if parts.len() > 2 {
+                        let mut breakpoint = if parts.len() > 2 {
                             // Join the remaining parts after "break add" into a single string
                             let bp_spec = parts[2..].join(" ");
                             match Breakpoint::from_str(&bp_spec) {
@@ -809,13 +1377,14 @@ impl TerminalPanel {
 
                             Breakpoint::new(loc, None)
                         };
+                        breakpoint.temporary = temporary;
 
                         match dm.execution.add_breakpoint(breakpoint.clone()) {
                             Ok(true) => {
                                 let bp_count = dm.execution.list_breakpoints().count();
-                                self.add_output(&format!(
-                                    "Breakpoint #{bp_count} added: {breakpoint}"
-                                ));
+                                let kind =
+                                    if temporary { "Temporary breakpoint" } else { "Breakpoint" };
+                                self.add_output(&format!("{kind} #{bp_count} added: {breakpoint}"));
                             }
                             Ok(false) => {
                                 self.add_output("Breakpoint already exists at this location");
@@ -902,33 +1471,30 @@ impl TerminalPanel {
                             Err(_) => self.add_error("Invalid breakpoint id"),
                         }
                     }
+                    "ignore" => {
+                        let (Some(id), Some(count)) = (
+                            parts.get(2).and_then(|id| id.parse::<usize>().ok()),
+                            parts.get(3).and_then(|count| count.parse::<usize>().ok()),
+                        ) else {
+                            self.add_error("Usage: break ignore <id> <count>");
+                            return Ok(());
+                        };
+
+                        match dm.execution.update_breakpoint_ignore_count(id, count) {
+                            Ok(()) => self.add_output(&format!(
+                                "Breakpoint #{id} will ignore its first {count} hits"
+                            )),
+                            Err(e) => self.add_error(&format!("Failed to set ignore count: {e}")),
+                        }
+                    }
                     "list" => {
-                        let breakpoints: Vec<_> = dm.execution.list_breakpoints().collect();
-                        if breakpoints.is_empty() {
+                        if dm.execution.list_breakpoints().next().is_none() {
                             self.add_output("No breakpoints set");
                         } else {
-                            self.add_output("Breakpoints:");
-                            for (id, bp, enabled) in breakpoints {
-                                let status = if enabled { "enabled" } else { "disabled" };
-                                let loc_desc = bp
-                                    .loc
-                                    .as_ref()
-                                    .map(|loc| {
-                                        loc.display(
-                                            dm.resolver
-                                                .resolve_address_label(loc.bytecode_address()),
-                                        )
-                                    })
-                                    .unwrap_or_else(|| "no location".to_string());
-
-                                let mut line_str = format!("  #{id}: {loc_desc}");
-                                if let Some(code) = &bp.condition {
-                                    line_str.push_str(&format!(" if {code}"));
-                                }
-                                line_str.push_str(&format!(" [{status}]"));
-
-                                self.add_output(&line_str);
-                            }
+                            // Hit counts are collected over the whole timeline by the engine
+                            let id = dm.execution.get_current_snapshot();
+                            self.pending_command = Some(PendingCommand::ListBreakpoints(id));
+                            self.spinner.start_loading("Counting breakpoint hits...");
                         }
                     }
                     "clear" => match dm.execution.clear_breakpoints() {
@@ -938,36 +1504,94 @@ impl TerminalPanel {
                     _ => {
                         self.add_error(&format!("Unknown break subcommand: {}", parts[1]));
                         self.add_output("Usage:");
-                        self.add_output("  break add [@<loc>] [if $<expr>] - Add breakpoint");
-                        self.add_output("        <loc> := <addr>:<path>:<line> (source)");
-                        self.add_output("               | <addr>:<pc>          (opcode)");
-                        self.add_output("  break remove <id>               - Remove breakpoint");
-                        self.add_output("  break enable <id>               - Enable breakpoint");
-                        self.add_output("  break disable <id>              - Disable breakpoint");
-                        self.add_output(
-                            "  break add_expr <id> $<expr>     - Add condition to breakpoint",
-                        );
-                        self.add_output("  break list                      - List all breakpoints");
-                        self.add_output(
-                            "  break clear                     - Clear all breakpoints",
-                        );
-                        self.add_output("");
-                        self.add_output("Breakpoint types:");
-                        self.add_output(
-                            "  • Location only: Stops when execution reaches the location",
-                        );
-                        self.add_output(
-                            "  • Condition only: Data breakpoint - stops when expression is true",
-                        );
-                        self.add_output(
-                            "  • Both: Stops when location is reached AND condition is true",
-                        );
-                        self.add_output(
-                            "  • Neither: Invalid - breakpoint must have location or condition",
-                        );
+                        self.show_breakpoint_usage();
                     }
                 }
             }
+            "wp" => match parts.get(1).copied() {
+                Some("add") => {
+                    let watchpoint = match self.parse_watchpoint(&parts[2..], dm) {
+                        Ok(wp) => wp,
+                        Err(e) => {
+                            self.add_error(&format!("Failed to parse watchpoint: {e}"));
+                            return Ok(());
+                        }
+                    };
+
+                    match dm.execution.add_watchpoint(watchpoint.clone()) {
+                        Ok(true) => {
+                            let wp_count = dm.execution.list_watchpoints().count();
+                            self.add_output(&format!("Watchpoint #{wp_count} added: {watchpoint}"));
+                        }
+                        Ok(false) => self.add_output("Watchpoint already exists"),
+                        Err(e) => self.add_error(&format!("Failed to add watchpoint: {e}")),
+                    }
+                }
+                Some("remove") => {
+                    if parts.len() != 3 {
+                        self.add_error("Usage: wp remove <id>");
+                        return Ok(());
+                    }
+
+                    match parts[2].parse::<usize>() {
+                        Ok(id) => match dm.execution.remove_watchpoint(id) {
+                            Ok(()) => self.add_output(&format!("Watchpoint #{id} removed")),
+                            Err(e) => self.add_error(&format!("Failed to remove watchpoint: {e}")),
+                        },
+                        Err(_) => self.add_error("Invalid watchpoint id"),
+                    }
+                }
+                Some("list") => {
+                    let watchpoints: Vec<_> = dm.execution.list_watchpoints().collect();
+                    if watchpoints.is_empty() {
+                        self.add_output("No watchpoints set");
+                    } else {
+                        let lines: Vec<_> =
+                            watchpoints.iter().map(|(id, wp)| format!("  #{id}: {wp}")).collect();
+                        self.add_output("Watchpoints:");
+                        for line in lines {
+                            self.add_output(&line);
+                        }
+                    }
+                }
+                Some("clear") => match dm.execution.clear_watchpoints() {
+                    Ok(()) => self.add_output("All watchpoints cleared"),
+                    Err(e) => self.add_error(&format!("Failed to clear watchpoints: {e}")),
+                },
+                Some(dir @ ("next" | "prev")) => {
+                    if dm.execution.list_watchpoints().next().is_none() {
+                        self.add_output("No watchpoints set");
+                        return Ok(());
+                    }
+
+                    let id = dm.execution.get_current_snapshot();
+                    if dir == "next" {
+                        self.pending_command = Some(PendingCommand::NextWatchpoint(id));
+                        self.spinner.start_loading("Continuing to next watchpoint change...");
+                        dm.execution.next_watchpoint()?;
+                    } else {
+                        self.pending_command = Some(PendingCommand::PrevWatchpoint(id));
+                        self.spinner.start_loading("Reversing to previous watchpoint change...");
+                        dm.execution.prev_watchpoint()?;
+                    }
+                }
+                other => {
+                    if let Some(sub) = other {
+                        self.add_error(&format!("Unknown wp subcommand: {sub}"));
+                    }
+                    self.add_output("Usage:");
+                    self.add_output("  wp add sload <slot> [address]  - Watch a storage slot");
+                    self.add_output("  wp add tsload <slot> [address] - Watch a transient slot");
+                    self.add_output("  wp add var <name> [address]    - Watch a state variable");
+                    self.add_output("  wp remove <id>                 - Remove watchpoint");
+                    self.add_output("  wp list                        - List all watchpoints");
+                    self.add_output("  wp clear                       - Clear all watchpoints");
+                    self.add_output("  wp next                        - Continue to next change");
+                    self.add_output(
+                        "  wp prev                        - Reverse to previous change",
+                    );
+                }
+            },
             "address" => {
                 let id = dm.execution.get_current_snapshot();
                 self.pending_command = Some(PendingCommand::ShowAddress(id));
@@ -1006,6 +1630,82 @@ impl TerminalPanel {
                 self.pending_command = Some(PendingCommand::ShowStorage(id, slot));
                 self.spinner.start_loading(&format!("Fetching storage at slot {slot}..."));
             }
+            "origin" => {
+                let id = dm.execution.get_current_snapshot();
+                let location = match self.parse_value_location(&parts[1..], dm) {
+                    Ok(location) => location,
+                    Err(e) => {
+                        self.add_error(&format!("{e}"));
+                        self.add_output("Usage:");
+                        self.add_output("  origin stack [depth]            - Trace a stack item");
+                        self.add_output("  origin memory <offset> <size>   - Trace a memory range");
+                        self.add_output(
+                            "  origin calldata <offset> <size> - Trace a calldata range",
+                        );
+                        self.add_output("  origin sload <slot> [address]   - Trace a storage slot");
+                        self.add_output(
+                            "  origin tsload <slot> [address]  - Trace a transient slot",
+                        );
+                        return Ok(());
+                    }
+                };
+                self.pending_command = Some(PendingCommand::TraceOrigin(id, location));
+                self.spinner.start_loading(&format!("Tracing origin of {location}..."));
+            }
+            "whatif" => {
+                let id = dm.execution.get_current_snapshot();
+                if parts.len() < 2 {
+                    self.add_error("Usage: whatif <edit> [<edit> ...]");
+                    self.add_output("Edits:");
+                    self.add_output(
+                        "  storage[<addr>][<slot>]=<value>   - Overwrite a storage slot",
+                    );
+                    self.add_output(
+                        "  tstorage[<addr>][<slot>]=<value>  - Overwrite a transient slot",
+                    );
+                    self.add_output("  balance[<addr>]=<wei>             - Overwrite a balance");
+                    self.add_output(
+                        "  code[<addr>]=<hex>                - Replace runtime bytecode",
+                    );
+                    self.add_output(
+                        "  calldata=<hex>                    - Replace calldata of the frame",
+                    );
+                    self.add_output("  stack[<depth>]=<value>            - Overwrite a stack item");
+                    self.add_output(
+                        "  memory[<offset>]=<hex>            - Overwrite a memory range",
+                    );
+                    self.add_output(
+                        "  <local>=<value>                   - Overwrite a local variable (at a source step)",
+                    );
+                    return Ok(());
+                }
+                let edits = parts[1..]
+                    .iter()
+                    .map(|edit| StateEdit::from_str(edit))
+                    .collect::<Result<Vec<_>>>()?;
+                self.pending_command = Some(PendingCommand::WhatIf(id, edits));
+                self.spinner.start_loading("Re-executing with state edits...");
+            }
+            "patch" => {
+                let Some(path) = parts.get(1) else {
+                    bail!("Usage: patch <file> [address]");
+                };
+                let source = std::fs::read_to_string(path)
+                    .map_err(|e| eyre!("Failed to read patched source {path}: {e}"))?;
+                let address = match parts.get(2) {
+                    Some(address) => address.parse::<Address>()?,
+                    None => {
+                        let id = dm.execution.get_current_snapshot();
+                        dm.execution
+                            .get_snapshot_info(id)
+                            .ok_or(eyre!("Snapshot info is not loaded yet, please retry"))?
+                            .bytecode_address
+                    }
+                };
+                self.pending_command =
+                    Some(PendingCommand::PatchedReplay(address, path.to_string(), source));
+                self.spinner.start_loading(&format!("Replaying with patched {path}..."));
+            }
             "tsload" => {
                 let id = dm.execution.get_current_snapshot();
                 let slot = if parts.len() > 1 {
@@ -1026,7 +1726,98 @@ impl TerminalPanel {
         Ok(())
     }
 
+    /// Parse the arguments of the `origin` command into a value location
+    fn parse_value_location(&self, args: &[&str], dm: &mut DataManager) -> Result<ValueLocation> {
+        let number = |i: usize| -> Result<usize> {
+            let arg = args.get(i).ok_or(eyre!("Missing argument"))?;
+            let value = arg.parse::<U256>().map_err(|e| eyre!("Invalid number {arg}: {e}"))?;
+            usize::try_from(value).map_err(|_| eyre!("Number {arg} is too large"))
+        };
+        let slot = || -> Result<U256> {
+            let arg = args.get(1).ok_or(eyre!("Missing slot"))?;
+            arg.parse::<U256>().map_err(|e| eyre!("Invalid slot {arg}: {e}"))
+        };
+        let mut address = || -> Result<Address> {
+            match args.get(2) {
+                Some(arg) => arg.parse::<Address>().map_err(|e| eyre!("Invalid address: {e}")),
+                None => dm.execution.get_current_address().ok_or(eyre!("Current address unknown")),
+            }
+        };
+
+        match args.first().copied() {
+            Some("stack") => {
+                Ok(ValueLocation::Stack { depth: if args.len() > 1 { number(1)? } else { 0 } })
+            }
+            Some("memory") => Ok(ValueLocation::Memory { offset: number(1)?, size: number(2)? }),
+            Some("calldata") => {
+                Ok(ValueLocation::Calldata { offset: number(1)?, size: number(2)? })
+            }
+            Some("sload") => Ok(ValueLocation::Storage { slot: slot()?, address: address()? }),
+            Some("tsload") => {
+                Ok(ValueLocation::TransientStorage { slot: slot()?, address: address()? })
+            }
+            Some(other) => bail!("Unknown value location: {other}"),
+            None => bail!("Missing value location"),
+        }
+    }
+
+    /// Parse the arguments of `wp add` into a watchpoint
+    fn parse_watchpoint(&self, args: &[&str], dm: &mut DataManager) -> Result<Watchpoint> {
+        let mut address = || -> Result<Address> {
+            match args.get(2) {
+                Some(arg) => arg.parse::<Address>().map_err(|e| eyre!("Invalid address: {e}")),
+                None => dm.execution.get_current_address().ok_or(eyre!("Current address unknown")),
+            }
+        };
+        let slot = || -> Result<U256> {
+            let arg = args.get(1).ok_or(eyre!("Missing slot"))?;
+            arg.parse::<U256>().map_err(|e| eyre!("Invalid slot {arg}: {e}"))
+        };
+
+        match args.first().copied() {
+            Some("sload") => Ok(Watchpoint::Storage { slot: slot()?, address: address()? }),
+            Some("tsload") => {
+                Ok(Watchpoint::TransientStorage { slot: slot()?, address: address()? })
+            }
+            Some("var") => {
+                let name = args.get(1).ok_or(eyre!("Missing state variable name"))?.to_string();
+                Ok(Watchpoint::StateVariable { name, address: address()? })
+            }
+            Some(_) => Watchpoint::from_str(&args.join(" ")),
+            None => bail!("Missing watchpoint"),
+        }
+    }
+
     /// Show help information
+    /// Show the usage of the breakpoint commands
+    fn show_breakpoint_usage(&mut self) {
+        self.add_output("  break add [@<loc>] [if $<expr>] - Add breakpoint");
+        self.add_output("        <loc> := <addr>:<path>:<line> (source)");
+        self.add_output("               | <addr>:<pc>          (opcode)");
+        self.add_output("               | <Contract>.<fn>[(<types>)]  (function entry)");
+        self.add_output("               | <fn>(<types>)               (function entry)");
+        self.add_output("               | event:<signature|topic0>    (event emission)");
+        self.add_output("               | call[:<addr>][:<selector>]  (external call)");
+        self.add_output("               | create | selfdestruct | revert");
+        self.add_output("  break add @<loc> [if $<expr>] log <format>");
+        self.add_output("                                  - Add logpoint, e.g. i = {i}");
+        self.add_output("  break remove <id>               - Remove breakpoint");
+        self.add_output("  break enable <id>               - Enable breakpoint");
+        self.add_output("  break disable <id>              - Disable breakpoint");
+        self.add_output("  break add_expr <id> $<expr>     - Add condition to breakpoint");
+        self.add_output("  break list                      - List all breakpoints");
+        self.add_output("  break ignore <id> <n>           - Ignore the first n hits");
+        self.add_output("  tbreak [@<loc>] [if $<expr>]    - Add temporary breakpoint");
+        self.add_output("  break clear                     - Clear all breakpoints");
+        self.add_output("");
+        self.add_output("  Types: - Location-only (stops at location)");
+        self.add_output("         - Condition-only (data breakpoint)");
+        self.add_output("         - Both (location AND condition)");
+        self.add_output("         - Neither (invalid)");
+        self.add_output("         - With a log format (logpoint, logs to the");
+        self.add_output("           display panel's Log mode without stopping)");
+    }
+
     fn show_help(&mut self) {
         self.add_output("📋 EDB Terminal Help");
         self.add_output("");
@@ -1046,6 +1837,8 @@ impl TerminalPanel {
         self.add_output("  reverse, rs <count> - Reverse step multiple snapshots");
         self.add_output("  call, c             - Step to next function call");
         self.add_output("  rcall, rc           - Step back from function call");
+        self.add_output("  continue, cont      - Continue to next breakpoint");
+        self.add_output("  reverse-continue, rcont - Continue backward to previous breakpoint");
         self.add_output("");
         self.add_output("🔍 Inspection:");
         self.add_output("  address                 - Show current address");
@@ -1056,29 +1849,71 @@ impl TerminalPanel {
         self.add_output("  sload <slot>            - Show storage at slot");
         self.add_output("  tsload <slot>           - Show transient storage at slot");
         self.add_output("");
+        self.add_output("🧭 Data Flow:");
+        self.add_output("  origin stack [depth]            - Where did a stack item come from");
+        self.add_output("  origin memory <offset> <size>   - Where did a memory range come from");
+        self.add_output("  origin calldata <offset> <size> - Where did a calldata range come from");
+        self.add_output("  origin sload <slot> [address]   - Who last wrote a storage slot");
+        self.add_output("  origin tsload <slot> [address]  - Who last wrote a transient slot");
+        self.add_output("");
+        self.add_output("🧪 What-If:");
+        self.add_output("  whatif <edit> [<edit> ...] - Re-execute with state edits at this step");
+        self.add_output("        <edit> := storage[<addr>][<slot>]=<value> | tstorage[..][..]=..");
+        self.add_output("                | balance[<addr>]=<wei> | code[<addr>]=<hex>");
+        self.add_output("                | calldata=<hex> | stack[<depth>]=<value>");
+        self.add_output("                | memory[<offset>]=<hex> | <local>=<value>");
+        self.add_output("  patch <file> [address]     - Replay with a patched source file of the");
+        self.add_output("                               current (or given) contract");
+        self.add_output("                               (not one deployed by the transaction)");
+        self.add_output("");
+        self.add_output("📌 Watchpoints:");
+        self.add_output("  wp add sload <slot> [address]  - Stop when a storage slot changes");
+        self.add_output("  wp add tsload <slot> [address] - Stop when a transient slot changes");
+        self.add_output("  wp add var <name> [address]    - Stop when a state variable changes");
+        self.add_output("  wp remove <id>                 - Remove watchpoint");
+        self.add_output("  wp list                        - List all watchpoints");
+        self.add_output("  wp clear                       - Clear all watchpoints");
+        self.add_output("  wp next / wp prev              - Go to next/previous change");
+        self.add_output("");
+        self.add_output("📈 Value History:");
+        self.add_output("  changes $<expr>               - Changes of a value in the current call");
+        self.add_output("  changes all $<expr>           - Changes of a value in all steps");
+        self.add_output("  changes <start>..<end> $<expr> - Changes of a value in a step range");
+        self.add_output("  changes clear                 - Clear the value history");
+        self.add_output("");
+        self.add_output("🔎 Find:");
+        self.add_output("  find [<filter> ...] $<expr> - Find the steps where a condition holds");
+        self.add_output("        <filter> := in <addr> [fn <ufid>] | file <path> | hook | opcode");
+        self.add_output("  find next / find prev       - Go to the next/previous match");
+        self.add_output("  find clear                  - Clear the matches");
+        self.add_output("");
         self.add_output("👁️ Watcher:");
         self.add_output("  watch add $<expr>   - Add watch expression");
         self.add_output("  watch remove <id>   - Remove watch expression");
         self.add_output("🔴 Breakpoints:");
-        self.add_output("  break add [@<loc>] [if $<expr>] - Add breakpoint");
-        self.add_output("        <loc> := <addr>:<path>:<line> (source)");
-        self.add_output("               | <addr>:<pc>          (opcode)");
-        self.add_output("  break remove <id>               - Remove breakpoint");
-        self.add_output("  break enable <id>               - Enable breakpoint");
-        self.add_output("  break disable <id>              - Disable breakpoint");
-        self.add_output("  break add_expr <id> $<expr>     - Add condition expression");
-        self.add_output("  break list                      - List all breakpoints");
-        self.add_output("  break clear                     - Clear all breakpoints");
-        self.add_output("");
-        self.add_output("  Types: - Location-only (stops at location)");
-        self.add_output("         - Condition-only (data breakpoint)");
-        self.add_output("         - Both (location AND condition)");
-        self.add_output("         - Neither (invalid)");
+        self.show_breakpoint_usage();
         self.add_output("");
         self.add_output("💻 Solidity expressions (prefix with $):");
         self.add_output("  $<expr>          - Evaluate expression");
+        self.add_output("  $f{from: a, value: v, gas: g}(x)");
+        self.add_output("                   - Call with options, showing reverts and events");
+        self.add_output("  $edb_deal(a, v)  - Set up a hypothetical state seen by later calls and");
+        self.add_output("                     what-if runs (also edb_sstore, edb_etch, edb_warp,");
+        self.add_output("                     edb_roll and edb_prank), cleared by $edb_reset()");
         self.add_output("  $edb_help()      - Show more help on expressions");
         self.add_output("");
+        self.add_output("🪆 Nested Calls:");
+        self.add_output("  stepinto $<expr>      - Debug the call an expression makes in a");
+        self.add_output("                          nested timeline (the outermost by default)");
+        self.add_output("  stepinto #<n> $<expr> - Debug the n-th call (from 0) it makes");
+        self.add_output("  return                - Return to the parent timeline");
+        self.add_output("");
+        self.add_output("🧱 Call Stack:");
+        self.add_output("  bt, backtrace    - Show the call stack at the current step");
+        self.add_output("  frame [N]        - Select frame N (0 is the innermost); `$<expr>`");
+        self.add_output("                     is then evaluated in that frame");
+        self.add_output("  up [n], down [n] - Select an outer / inner frame");
+        self.add_output("");
         self.add_output("⚙️  Other:");
         self.add_output("  help, h          - Show this help");
         self.add_output("  clear, cls       - Clear terminal");
@@ -1122,6 +1957,230 @@ impl TerminalPanel {
         self.add_output("  theme           List available themes");
     }
 
+    /// Handle find command
+    fn handle_find_command(&mut self, args: &str, dm: &mut DataManager) -> Result<()> {
+        match args {
+            "clear" => {
+                dm.watcher.clear_query();
+                self.add_output("Snapshot matches cleared");
+                return Ok(());
+            }
+            "next" | "prev" => {
+                let Some((expr, filter)) = dm.watcher.query().cloned() else {
+                    self.add_error("No previous find, use 'find $<expr>' first");
+                    return Ok(());
+                };
+                let matches = match dm.execution.get_snapshot_matches(&expr, &filter) {
+                    Some(Ok(matches)) => matches.clone(),
+                    Some(Err(e)) => {
+                        self.add_error(&format!("Cannot find snapshots: {e}"));
+                        return Ok(());
+                    }
+                    None => {
+                        self.add_error("Snapshot matches are not loaded yet, please retry");
+                        return Ok(());
+                    }
+                };
+
+                let current = dm.execution.get_current_snapshot();
+                let target = if args == "next" {
+                    matches.iter().position(|&id| id > current)
+                } else {
+                    matches.iter().rposition(|&id| id < current)
+                };
+                let Some(index) = target else {
+                    self.add_output(&format!("No {args} match of {expr}"));
+                    return Ok(());
+                };
+
+                let id = matches[index];
+                self.add_output(&format!("Match {}/{} of {expr}", index + 1, matches.len()));
+                self.pending_command = Some(PendingCommand::Goto(id));
+                self.spinner.start_loading(&format!("Going to snapshot {id}..."));
+                dm.execution.goto(id)?;
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        let Some((filters, expr)) = args.split_once('$') else {
+            self.add_output("Usage:");
+            self.add_output(
+                "  find [<filter> ...] $<expr> - Find the steps where a condition holds",
+            );
+            self.add_output(
+                "        <filter> := in <addr> [fn <ufid>] | file <path> | hook | opcode",
+            );
+            self.add_output("  find next / find prev       - Go to the next/previous match");
+            self.add_output("  find clear                  - Clear the matches");
+            return Ok(());
+        };
+
+        let expr = expr.trim();
+        if expr.is_empty() {
+            self.add_error("Usage: find [<filter> ...] $<expr>");
+            return Ok(());
+        }
+
+        let mut filter = SnapshotFilter::default();
+        let mut words = filters.split_whitespace();
+        while let Some(word) = words.next() {
+            match word {
+                "hook" => filter.kind = Some(SnapshotKind::Hook),
+                "opcode" => filter.kind = Some(SnapshotKind::Opcode),
+                "in" | "fn" | "file" => {
+                    let Some(value) = words.next() else {
+                        self.add_error(&format!("Missing value for filter '{word}'"));
+                        return Ok(());
+                    };
+                    match word {
+                        "in" => {
+                            filter.address = Some(
+                                value
+                                    .parse::<Address>()
+                                    .map_err(|e| eyre!("Invalid address: {}", e))?,
+                            )
+                        }
+                        "fn" => {
+                            filter.function =
+                                Some(value.parse().map_err(|e| eyre!("Invalid UFID: {}", e))?)
+                        }
+                        _ => filter.path = Some(value.into()),
+                    }
+                }
+                _ => {
+                    self.add_error(&format!("Unknown filter '{word}'"));
+                    return Ok(());
+                }
+            }
+        }
+        if filter.function.is_some() && filter.address.is_none() {
+            self.add_error(
+                "Filter 'fn' needs 'in <addr>' too, UFIDs are only unique within a contract",
+            );
+            return Ok(());
+        }
+
+        dm.watcher.set_query(expr.to_string(), filter.clone());
+        self.pending_command = Some(PendingCommand::FindSnapshots(expr.to_string(), filter));
+        self.spinner.start_loading(&format!("Finding snapshots where {expr} holds..."));
+        Ok(())
+    }
+
+    /// Handle stepinto command
+    fn handle_step_into_command(&mut self, args: &str, dm: &mut DataManager) -> Result<()> {
+        let Some((index, expr)) = args.split_once('$') else {
+            self.add_output("Usage:");
+            self.add_output("  stepinto $<expr>      - Debug the call an expression makes");
+            self.add_output("  stepinto #<n> $<expr> - Debug the n-th call (from 0) it makes");
+            return Ok(());
+        };
+
+        let expr = expr.trim();
+        if expr.is_empty() {
+            bail!("Usage: stepinto [#<n>] $<expr>");
+        }
+        let call_index = match index.trim() {
+            "" => None,
+            index => Some(
+                index
+                    .strip_prefix('#')
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .ok_or(eyre!("Invalid call index '{index}', expected #<n>"))?,
+            ),
+        };
+
+        let id = dm.execution.get_current_snapshot();
+        self.pending_command = Some(PendingCommand::StepInto(id, expr.to_string(), call_index));
+        self.spinner.start_loading(&format!("Stepping into {expr}..."));
+        Ok(())
+    }
+
+    /// Handle frame selection commands (`frame [N]`, `up [n]`, `down [n]`)
+    fn handle_frame_command(&mut self, cmd: &str, dm: &mut DataManager) -> Result<()> {
+        let (name, arg) = cmd.split_once(' ').unwrap_or((cmd, ""));
+        let arg = arg.trim();
+        let parse = |default: usize| -> Result<usize> {
+            if arg.is_empty() {
+                Ok(default)
+            } else {
+                arg.parse::<usize>().map_err(|_| eyre!("Invalid frame number '{arg}'"))
+            }
+        };
+
+        let selected = dm.execution.get_selected_frame();
+        let frame = match name {
+            "frame" => parse(selected)?,
+            "up" => selected.saturating_add(parse(1)?),
+            "down" => {
+                selected.checked_sub(parse(1)?).ok_or(eyre!("Already at the innermost frame"))?
+            }
+            _ => bail!("Unknown command: {cmd}"),
+        };
+
+        let id = dm.execution.get_current_snapshot();
+        self.pending_command = Some(PendingCommand::SelectFrame(id, frame));
+        self.spinner.start_loading("Fetching call stack...");
+        Ok(())
+    }
+
+    /// Handle changes command
+    fn handle_changes_command(&mut self, args: &str, dm: &mut DataManager) {
+        if args == "clear" {
+            dm.watcher.clear_history();
+            self.add_output("Value history cleared");
+            return;
+        }
+
+        let Some((scope, expr)) = args.split_once('$') else {
+            self.add_output("Usage:");
+            self.add_output("  changes [all|<start>..<end>] $<expr> - Collect changes of a value");
+            self.add_output("  changes clear                        - Clear the value history");
+            return;
+        };
+
+        let (scope, expr) = (scope.trim(), expr.trim());
+        if expr.is_empty() {
+            self.add_error("Usage: changes [all|<start>..<end>] $<expr>");
+            return;
+        }
+
+        let scope = match scope {
+            "" => {
+                let id = dm.execution.get_current_snapshot();
+                match dm.execution.get_snapshot_info(id) {
+                    Some(info) => {
+                        HistoryScope::Frame { trace_entry_id: info.frame_id().trace_entry_id() }
+                    }
+                    None => {
+                        self.add_error("Snapshot info is not loaded yet, please retry");
+                        return;
+                    }
+                }
+            }
+            "all" => HistoryScope::Range {
+                start: 0,
+                end: dm.execution.get_snapshot_count().saturating_sub(1),
+            },
+            range => {
+                let parsed = range
+                    .split_once("..")
+                    .and_then(|(start, end)| Some((start.parse().ok()?, end.parse().ok()?)));
+                match parsed {
+                    Some((start, end)) => HistoryScope::Range { start, end },
+                    None => {
+                        self.add_error(&format!("Invalid step range: {range}"));
+                        return;
+                    }
+                }
+            }
+        };
+
+        dm.watcher.set_history(expr.to_string(), scope);
+        self.pending_command = Some(PendingCommand::ValueHistory(expr.to_string(), scope));
+        self.spinner.start_loading(&format!("Collecting history of {expr}..."));
+    }
+
     /// Handle watch command
     fn handle_watch_command(&mut self, args: &str, dm: &mut DataManager) {
         let parts: Vec<&str> = args.split_whitespace().collect();
@@ -1145,6 +2204,10 @@ impl TerminalPanel {
                     self.add_error("Watch expression must start with '$'");
                     return;
                 }
+                if mutates_overlay(expr) {
+                    self.add_error("Watch expressions cannot change the overlay (edb_sstore etc.)");
+                    return;
+                }
                 match dm.watcher.add_expression(expr[1..].trim().to_string()) {
                     Some(id) => {
                         self.add_output(&format!("Added watch #{id}: {expr}"));
@@ -1800,6 +2863,11 @@ impl TerminalPanel {
                     }
                 }
                 Err(e) => {
+                    if let Some(partial) = e.downcast_ref::<PartialOutput>() {
+                        for line in partial.output.lines() {
+                            self.add_output(line);
+                        }
+                    }
                     let error_msg = format!("{e}");
                     for line in error_msg.lines() {
                         self.add_error(line);
@@ -2078,7 +3146,12 @@ impl PanelTr for TerminalPanel {
         PanelType::Terminal
     }
 
-    fn title(&self, _dm: &mut DataManager) -> String {
+    fn title(&self, dm: &mut DataManager) -> String {
+        let nesting = match dm.nesting.last() {
+            Some(label) => format!(" (in {label}, depth {})", dm.nesting.len()),
+            None => String::new(),
+        };
+
         let status = if let Some((current, total)) = self.snapshot_info {
             format!(" [{current}/{total}]")
         } else {
@@ -2090,7 +3163,7 @@ impl PanelTr for TerminalPanel {
             TerminalMode::Vim => " - VIM mode",
         };
 
-        format!("{} Debug Terminal{}{}", Icons::PROCESSING, status, mode_info)
+        format!("{} Debug Terminal{}{}{}", Icons::PROCESSING, nesting, status, mode_info)
     }
 
     fn render(&mut self, frame: &mut Frame<'_>, area: Rect, dm: &mut DataManager) {
@@ -26,7 +26,7 @@ use crate::ui::syntax::{SyntaxHighlighter, SyntaxType};
 use alloy_dyn_abi::DynSolValue;
 use alloy_primitives::{hex, Bytes};
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
-use edb_common::types::{CallResult, CallType, Trace, TraceEntry};
+use edb_common::types::{decode_revert_reason, CallResult, CallType, Trace, TraceEntry};
 use eyre::{bail, Result};
 use ratatui::{
     layout::Rect,
@@ -745,7 +745,7 @@ impl TracePanelInner {
                 ])
             }
             Some(CallResult::Revert { output, .. }) => {
-                let revert_text = self.decode_revert_reason(output);
+                let revert_text = decode_revert_reason(output);
                 Line::from(vec![
                     Span::styled(full_indent, Style::default().fg(dm.theme.comment_color)),
                     Span::styled("· ", Style::default().fg(dm.theme.comment_color)),
@@ -766,51 +766,6 @@ impl TracePanelInner {
         }
     }
 
-    /// Decode revert reason from output data
-    fn decode_revert_reason(&self, output: &Bytes) -> String {
-        if output.is_empty() {
-            return "(empty revert)".to_string();
-        }
-
-        // Check if it's a standard Error(string) revert (0x08c379a0)
-        if output.len() >= 4 && output.starts_with(&[0x08, 0xc3, 0x79, 0xa0]) {
-            // Try to decode the string from Error(string) signature
-            if let Ok(DynSolValue::String(reason)) =
-                alloy_dyn_abi::DynSolType::String.abi_decode(&output[4..])
-            {
-                return format!("\"{reason}\"");
-            }
-        }
-
-        // Check if it's a Panic(uint256) revert (0x4e487b71)
-        if output.len() >= 4 && output.starts_with(&[0x4e, 0x48, 0x7b, 0x71]) {
-            if let Ok(DynSolValue::Uint(panic_code, _)) =
-                alloy_dyn_abi::DynSolType::Uint(256).abi_decode(&output[4..])
-            {
-                let panic_reason = match panic_code.to_string().as_str() {
-                    "1" => "assertion failed",
-                    "17" => "arithmetic overflow/underflow",
-                    "18" => "division by zero",
-                    "33" => "enum conversion error",
-                    "34" => "invalid storage byte array access",
-                    "49" => "pop() on empty array",
-                    "50" => "array index out of bounds",
-                    "65" => "memory allocation overflow",
-                    "81" => "zero initialization of invalid type",
-                    _ => "unknown panic",
-                };
-                return format!("Panic({panic_code}: {panic_reason})");
-            }
-        }
-
-        // Fallback: show hex data
-        if output.len() <= 32 {
-            format!("0x{}", hex::encode(output))
-        } else {
-            format!("0x{}...({} bytes)", hex::encode(&output[..8]), output.len())
-        }
-    }
-
     /// Format InstructionResult with context
     fn format_instruction_result(&self, result: InstructionResult, output: &Bytes) -> String {
         match result {
@@ -819,7 +774,7 @@ impl TracePanelInner {
             InstructionResult::SelfDestruct => "selfdestruct".to_string(),
             InstructionResult::Revert => {
                 // This shouldn't happen in Error variant, but handle it
-                self.decode_revert_reason(output)
+                decode_revert_reason(output)
             }
             InstructionResult::CallTooDeep => "call stack too deep".to_string(),
             InstructionResult::OutOfFunds => "insufficient funds".to_string(),
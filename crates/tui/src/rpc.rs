@@ -21,58 +21,50 @@
 use crate::ui::spinner::Spinner;
 use alloy_json_abi::JsonAbi;
 use alloy_primitives::{Address, Bytes, U256};
-use edb_common::types::{CallableAbiInfo, Code, EdbSolValue, SnapshotInfo, Trace};
-use eyre::Result;
-use jsonrpsee::{
-    core::client::ClientT,
-    http_client::{HttpClient, HttpClientBuilder},
+use edb_client::{ApiError, EdbApi, EdbClient};
+use edb_common::types::{
+    Breakpoint, BreakpointHit, CallableAbiInfo, Code, EdbSolValue, EvalReport, HistoryScope,
+    LogpointEntry, NestedCallTimeline, SearchDirection, SnapshotFilter, SnapshotInfo, StateEdit,
+    Trace, ValueHistory, ValueLocation, ValueOrigin, Watchpoint, WatchpointHit, WhatIfResult,
 };
+use eyre::Result;
 use serde_json::Value;
 use std::{
     collections::HashMap,
+    future::Future,
     sync::{Arc, RwLock},
     time::Duration,
 };
 use tracing::{debug, error};
 
-/// Macro for building RPC parameters with any number of arguments
-///
-/// # Examples
-/// ```ignore
-/// let params = rpc_params!(); // Empty params
-/// let params = rpc_params!(42); // Single parameter
-/// let params = rpc_params!("0x123...", true); // Multiple parameters of different types
-/// let params = rpc_params!(snapshot_id, address, is_recompiled); // Variable references
-/// ```
-macro_rules! rpc_params {
-    () => {
-        Vec::<serde_json::Value>::new()
-    };
-    ($($param:expr),+ $(,)?) => {
-        vec![
-            $(serde_json::to_value($param).unwrap_or(serde_json::Value::Null)),+
-        ]
-    };
-}
+/// Timeout of requests re-executing the transaction, which may take minutes
+const REPLAY_REQUEST_TIMEOUT: Duration = Duration::from_secs(600);
+
 /// RPC client for debug server communication
 #[derive(Debug)]
 pub struct RpcClient {
-    client: HttpClient,
+    client: EdbClient,
+    /// Client for requests re-executing the transaction
+    replay_client: EdbClient,
     server_url: String,
     /// Shared spinner state for loading indication
     spinner: Arc<RwLock<Spinner>>,
 }
 
 impl RpcClient {
-    /// Create a new RPC client
-    pub async fn new(server_url: &str) -> Result<Self> {
-        let client = HttpClientBuilder::default()
-            .request_timeout(Duration::from_secs(30))
-            .build(server_url)?;
+    /// Create a new RPC client, authenticating with a bearer token if given
+    pub async fn new(server_url: &str, auth_token: Option<&str>) -> Result<Self> {
+        let mut builder = EdbClient::builder().request_timeout(Duration::from_secs(30));
+        let mut replay_builder = EdbClient::builder().request_timeout(REPLAY_REQUEST_TIMEOUT);
+        if let Some(token) = auth_token {
+            builder = builder.auth_token(token);
+            replay_builder = replay_builder.auth_token(token);
+        }
 
         debug!("Created RPC client for: {}", server_url);
         Ok(Self {
-            client,
+            client: builder.build(server_url)?,
+            replay_client: replay_builder.build(server_url)?,
             server_url: server_url.to_string(),
             spinner: Arc::new(RwLock::new(Spinner::new(None, None))),
         })
@@ -82,12 +74,11 @@ impl RpcClient {
     pub async fn test_connection(server_url: &str) -> Result<()> {
         debug!("Testing connection to: {}", server_url);
 
-        let client = HttpClientBuilder::default()
-            .request_timeout(Duration::from_secs(5))
-            .build(server_url)?;
+        let client =
+            EdbClient::builder().request_timeout(Duration::from_secs(5)).build(server_url)?;
 
-        // Try a simple health check or method call
-        match client.request::<Value, _>("debug.getSnapshotCount", rpc_params!()).await {
+        // Try a simple method call
+        match client.get_snapshot_count().await {
             Ok(_) => {
                 debug!("Connection test successful for: {}", server_url);
                 Ok(())
@@ -99,19 +90,18 @@ impl RpcClient {
         }
     }
 
-    /// Generic method to make RPC requests with automatic spinner management
-    async fn request_with_spinner(
+    /// Make an RPC request with automatic spinner management
+    async fn request_with_spinner<T>(
         &self,
-        method: &str,
-        params: Vec<Value>,
+        request: impl Future<Output = Result<T, ApiError>>,
         operation_name: &str,
-    ) -> Result<Value> {
+    ) -> Result<T> {
         self.start_loading(operation_name);
         debug!("Making RPC request: {}", operation_name);
 
-        let result = match self.client.request(method, params).await {
+        let result = match request.await {
             Ok(result) => {
-                debug!("{} successful: {:?}", operation_name, result);
+                debug!("{} successful", operation_name);
                 Ok(result)
             }
             Err(e) => {
@@ -165,11 +155,7 @@ impl RpcClient {
     pub async fn health_check(&self) -> Result<Value> {
         debug!("Checking server health");
 
-        // Make a simple HTTP GET request to the health endpoint
-        let health_url = format!("{}/health", self.server_url.trim_end_matches('/'));
-
-        let response = reqwest::get(&health_url).await?;
-        let health_data: Value = response.json().await?;
+        let health_data = self.client.health().await?;
 
         debug!("Server health: {:?}", health_data);
         Ok(health_data)
@@ -180,10 +166,7 @@ impl RpcClient {
 impl RpcClient {
     /// Get execution trace
     pub async fn get_trace(&self) -> Result<Trace> {
-        let value = self
-            .request_with_spinner("edb_getTrace", rpc_params!(), "Fetching execution trace")
-            .await?;
-        serde_json::from_value(value).map_err(|e| eyre::eyre!("Failed to parse trace: {}", e))
+        self.request_with_spinner(self.client.get_trace(), "Fetching execution trace").await
     }
 
     /// Get contract abi
@@ -192,138 +175,98 @@ impl RpcClient {
         address: Address,
         recompiled: bool,
     ) -> Result<Option<JsonAbi>> {
-        let value = self
-            .request_with_spinner(
-                "edb_getContractABI",
-                rpc_params!(address, recompiled),
-                &format!("Fetching contract ABI for {address}"),
-            )
-            .await?;
-
-        serde_json::from_value(value)
-            .map_err(|e| eyre::eyre!("Failed to parse contract ABI: {}", e))
+        self.request_with_spinner(
+            self.client.get_contract_abi(address, recompiled),
+            &format!("Fetching contract ABI for {address}"),
+        )
+        .await
     }
 
     /// Get callable abi info
     pub async fn get_callable_abi(&self, address: Address) -> Result<Vec<CallableAbiInfo>> {
-        let value = self
-            .request_with_spinner(
-                "edb_getCallableABI",
-                rpc_params!(address),
-                &format!("Fetching callable ABI for {address}"),
-            )
-            .await?;
-
-        serde_json::from_value(value)
-            .map_err(|e| eyre::eyre!("Failed to parse callable ABI: {}", e))
+        self.request_with_spinner(
+            self.client.get_callable_abi(address),
+            &format!("Fetching callable ABI for {address}"),
+        )
+        .await
     }
 
     /// Get contract constructor arguments
     pub async fn get_constructor_args(&self, address: Address) -> Result<Option<Bytes>> {
-        let value = self
-            .request_with_spinner(
-                "edb_getConstructorArgs",
-                rpc_params!(address),
-                &format!("Fetching contract constructor arguments for {address}"),
-            )
-            .await?;
-
-        serde_json::from_value(value)
-            .map_err(|e| eyre::eyre!("Failed to parse contract constructor arguments: {}", e))
+        self.request_with_spinner(
+            self.client.get_constructor_args(address),
+            &format!("Fetching contract constructor arguments for {address}"),
+        )
+        .await
     }
 
     /// Get total snapshot count
     pub async fn get_snapshot_count(&self) -> Result<usize> {
-        let value = self
-            .request_with_spinner(
-                "edb_getSnapshotCount",
-                rpc_params!(),
-                "Getting total snapshot count",
-            )
-            .await?;
-
-        serde_json::from_value(value)
-            .map_err(|e| eyre::eyre!("Failed to parse snapshot count: {}", e))
+        self.request_with_spinner(self.client.get_snapshot_count(), "Getting total snapshot count")
+            .await
     }
 
     /// Get snapshot information
     pub async fn get_snapshot_info(&self, snapshot_id: usize) -> Result<SnapshotInfo> {
-        let value = self
-            .request_with_spinner(
-                "edb_getSnapshotInfo",
-                rpc_params!(snapshot_id),
-                &format!("Getting info for snapshot {snapshot_id}"),
-            )
-            .await?;
+        self.request_with_spinner(
+            self.client.get_snapshot_info(snapshot_id),
+            &format!("Getting info for snapshot {snapshot_id}"),
+        )
+        .await
+    }
 
-        serde_json::from_value(value)
-            .map_err(|e| eyre::eyre!("Failed to parse snapshot info: {}", e))
+    /// Get the call stack at a snapshot, as the snapshots of its frames (innermost first)
+    pub async fn get_call_stack(&self, snapshot_id: usize) -> Result<Vec<usize>> {
+        self.request_with_spinner(
+            self.client.get_call_stack(snapshot_id),
+            &format!("Getting call stack at snapshot {snapshot_id}"),
+        )
+        .await
     }
 
     /// Get code
     pub async fn get_code(&self, snapshot_id: usize) -> Result<Code> {
-        let value = self
-            .request_with_spinner(
-                "edb_getCode",
-                rpc_params!(snapshot_id),
-                &format!("Getting code for snapshot {snapshot_id}"),
-            )
-            .await?;
-
-        serde_json::from_value(value).map_err(|e| eyre::eyre!("Failed to parse code: {}", e))
+        self.request_with_spinner(
+            self.client.get_code(snapshot_id),
+            &format!("Getting code for snapshot {snapshot_id}"),
+        )
+        .await
     }
 
     /// Get code by address
     pub async fn get_code_by_address(&self, address: Address) -> Result<Code> {
-        let value = self
-            .request_with_spinner(
-                "edb_getCodeByAddress",
-                rpc_params!(address),
-                &format!("Getting code for address {address}"),
-            )
-            .await?;
-
-        serde_json::from_value(value).map_err(|e| eyre::eyre!("Failed to parse code: {}", e))
+        self.request_with_spinner(
+            self.client.get_code_by_address(address),
+            &format!("Getting code for address {address}"),
+        )
+        .await
     }
 
     /// Get next call
     pub async fn get_next_call(&self, snapshot_id: usize) -> Result<usize> {
-        let value = self
-            .request_with_spinner(
-                "edb_getNextCall",
-                rpc_params!(snapshot_id),
-                &format!("Getting next call for snapshot {snapshot_id}"),
-            )
-            .await?;
-
-        serde_json::from_value(value).map_err(|e| eyre::eyre!("Failed to parse next call: {}", e))
+        self.request_with_spinner(
+            self.client.get_next_call(snapshot_id),
+            &format!("Getting next call for snapshot {snapshot_id}"),
+        )
+        .await
     }
 
     /// Get prev call
     pub async fn get_prev_call(&self, snapshot_id: usize) -> Result<usize> {
-        let value = self
-            .request_with_spinner(
-                "edb_getPrevCall",
-                rpc_params!(snapshot_id),
-                &format!("Getting prev call for snapshot {snapshot_id}"),
-            )
-            .await?;
-
-        serde_json::from_value(value).map_err(|e| eyre::eyre!("Failed to parse prev call: {}", e))
+        self.request_with_spinner(
+            self.client.get_prev_call(snapshot_id),
+            &format!("Getting prev call for snapshot {snapshot_id}"),
+        )
+        .await
     }
 
     /// Get storage value at a given slot
     pub async fn get_storage(&self, snapshot_id: usize, slot: U256) -> Result<U256> {
-        let value = self
-            .request_with_spinner(
-                "edb_getStorage",
-                rpc_params!(snapshot_id, slot),
-                &format!("Getting storage for snapshot {snapshot_id} at slot {slot}"),
-            )
-            .await?;
-
-        serde_json::from_value(value)
-            .map_err(|e| eyre::eyre!("Failed to parse storage value: {}", e))
+        self.request_with_spinner(
+            self.client.get_storage(snapshot_id, slot),
+            &format!("Getting storage for snapshot {snapshot_id} at slot {slot}"),
+        )
+        .await
     }
 
     /// Get storage diff
@@ -331,16 +274,11 @@ impl RpcClient {
         &self,
         snapshot_id: usize,
     ) -> Result<HashMap<U256, (U256, U256)>> {
-        let value = self
-            .request_with_spinner(
-                "edb_getStorageDiff",
-                rpc_params!(snapshot_id),
-                &format!("Getting storage diff for snapshot {snapshot_id}"),
-            )
-            .await?;
-
-        serde_json::from_value(value)
-            .map_err(|e| eyre::eyre!("Failed to parse storage diff: {}", e))
+        self.request_with_spinner(
+            self.client.get_storage_diff(snapshot_id),
+            &format!("Getting storage diff for snapshot {snapshot_id}"),
+        )
+        .await
     }
 
     /// Evaluate expression on a given snapshot
@@ -349,51 +287,168 @@ impl RpcClient {
         snapshot_id: usize,
         expr: &str,
     ) -> Result<core::result::Result<EdbSolValue, String>> {
-        let value = self
-            .request_with_spinner(
-                "edb_evalOnSnapshot",
-                rpc_params!(snapshot_id, expr),
-                &format!("Evaluating expression on snapshot {snapshot_id}"),
-            )
-            .await?;
+        self.request_with_spinner(
+            self.client.eval_on_snapshot(snapshot_id, expr, None),
+            &format!("Evaluating expression on snapshot {snapshot_id}"),
+        )
+        .await
+    }
+
+    /// Evaluate expression on a given snapshot, reporting the contract calls it makes
+    pub async fn eval_on_snapshot_with_calls(
+        &self,
+        snapshot_id: usize,
+        expr: &str,
+    ) -> Result<EvalReport> {
+        self.request_with_spinner(
+            self.client.eval_on_snapshot_with_calls(snapshot_id, expr, None),
+            &format!("Evaluating expression on snapshot {snapshot_id}"),
+        )
+        .await
+    }
+
+    /// Collect every change of an expression's value over a call or a range of snapshots
+    pub async fn get_value_history(
+        &self,
+        expr: &str,
+        scope: HistoryScope,
+    ) -> Result<core::result::Result<ValueHistory, String>> {
+        let operation = format!("Collecting history of {expr} in {scope}");
+        self.request_with_spinner(self.client.get_value_history(expr, scope), &operation).await
+    }
+
+    /// Find every snapshot at which a boolean expression holds
+    pub async fn find_snapshots(
+        &self,
+        expr: &str,
+        filter: &SnapshotFilter,
+    ) -> Result<core::result::Result<Vec<usize>, String>> {
+        self.request_with_spinner(
+            self.client.find_snapshots(expr, Some(filter)),
+            &format!("Finding snapshots where {expr} holds"),
+        )
+        .await
+    }
+
+    /// Trace a value on a given snapshot back to its origin
+    pub async fn trace_value_origin(
+        &self,
+        snapshot_id: usize,
+        location: ValueLocation,
+    ) -> Result<core::result::Result<ValueOrigin, String>> {
+        let operation = format!("Tracing origin of {location} on snapshot {snapshot_id}");
+        self.request_with_spinner(self.client.trace_value_origin(snapshot_id, location), &operation)
+            .await
+    }
+
+    /// Find the nearest snapshot at which any of the breakpoints is hit
+    pub async fn find_breakpoint_hit(
+        &self,
+        breakpoints: &[Breakpoint],
+        snapshot_id: usize,
+        direction: SearchDirection,
+    ) -> Result<Option<BreakpointHit>> {
+        let operation = format!("Searching breakpoints {direction:?} from snapshot {snapshot_id}");
+        self.request_with_spinner(
+            self.client.find_breakpoint_hit(breakpoints, snapshot_id, direction),
+            &operation,
+        )
+        .await
+    }
+
+    /// Collect the snapshots at which each breakpoint is hit over the whole timeline
+    pub async fn count_breakpoint_hits(
+        &self,
+        breakpoints: &[Breakpoint],
+    ) -> Result<Vec<Vec<usize>>> {
+        self.request_with_spinner(
+            self.client.count_breakpoint_hits(breakpoints),
+            &format!("Counting the hits of {} breakpoints", breakpoints.len()),
+        )
+        .await
+    }
 
-        serde_json::from_value(value)
-            .map_err(|e| eyre::eyre!("Failed to parse evaluated value: {}", e))
+    /// Collect the messages logged by the logpoints over the whole timeline
+    pub async fn collect_logpoints(&self, logpoints: &[Breakpoint]) -> Result<Vec<LogpointEntry>> {
+        self.request_with_spinner(
+            self.client.collect_logpoints(logpoints),
+            &format!("Collecting the log of {} logpoints", logpoints.len()),
+        )
+        .await
+    }
+
+    /// Apply state edits at a snapshot and re-execute the rest of the transaction
+    pub async fn what_if(&self, snapshot_id: usize, edits: &[StateEdit]) -> Result<WhatIfResult> {
+        self.request_with_spinner(
+            self.replay_client.what_if(snapshot_id, edits),
+            &format!("Re-executing with {} state edits at snapshot {snapshot_id}", edits.len()),
+        )
+        .await
+    }
+
+    /// Replay the transaction with the patched source code of a contract
+    pub async fn replay_with_patched_source(
+        &self,
+        address: Address,
+        path: &str,
+        source: &str,
+    ) -> Result<WhatIfResult> {
+        self.request_with_spinner(
+            self.replay_client.replay_with_patched_source(address, path, source),
+            &format!("Replaying with patched {path} of {address}"),
+        )
+        .await
+    }
+
+    /// Debug a call made by an expression on a given snapshot as a nested timeline
+    pub async fn step_into_call(
+        &self,
+        snapshot_id: usize,
+        expr: &str,
+        call_index: Option<usize>,
+    ) -> Result<core::result::Result<NestedCallTimeline, String>> {
+        self.request_with_spinner(
+            self.replay_client.step_into_call(snapshot_id, expr, call_index),
+            &format!("Stepping into {expr} on snapshot {snapshot_id}"),
+        )
+        .await
+    }
+
+    /// Find the next change of a watchpoint after a given snapshot
+    pub async fn get_next_watchpoint_hit(
+        &self,
+        snapshot_id: usize,
+        watchpoint: &Watchpoint,
+    ) -> Result<core::result::Result<Option<WatchpointHit>, String>> {
+        self.request_with_spinner(
+            self.client.get_next_watchpoint_hit(snapshot_id, watchpoint),
+            &format!("Finding next change of {watchpoint} after snapshot {snapshot_id}"),
+        )
+        .await
+    }
+
+    /// Find the previous change of a watchpoint before a given snapshot
+    pub async fn get_prev_watchpoint_hit(
+        &self,
+        snapshot_id: usize,
+        watchpoint: &Watchpoint,
+    ) -> Result<core::result::Result<Option<WatchpointHit>, String>> {
+        self.request_with_spinner(
+            self.client.get_prev_watchpoint_hit(snapshot_id, watchpoint),
+            &format!("Finding previous change of {watchpoint} before snapshot {snapshot_id}"),
+        )
+        .await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::json;
 
     #[tokio::test]
     async fn test_rpc_client_creation() {
-        let client = RpcClient::new("http://localhost:3000").await;
+        let client = RpcClient::new("http://localhost:3000", None).await;
         // This would fail without a running server, but we can test creation logic
         assert!(client.is_ok() || client.is_err()); // Either is fine for this test
     }
-
-    #[test]
-    fn test_rpc_params_macro() {
-        // Test empty params
-        let empty_params: Vec<Value> = rpc_params!();
-        let expected: Vec<Value> = vec![];
-        assert_eq!(empty_params, expected);
-
-        // Test single parameter
-        let single_param = rpc_params!(42);
-        assert_eq!(single_param, vec![json!(42)]);
-
-        // Test multiple parameters of different types
-        let multi_params = rpc_params!("0x1234567890abcdef", true, 123);
-        assert_eq!(multi_params, vec![json!("0x1234567890abcdef"), json!(true), json!(123)]);
-
-        // Test with variables
-        let address = "0xabcdef1234567890";
-        let recompiled = false;
-        let snapshot_id = 5;
-        let var_params = rpc_params!(address, recompiled, snapshot_id);
-        assert_eq!(var_params, vec![json!(address), json!(recompiled), json!(snapshot_id)]);
-    }
 }